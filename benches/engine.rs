@@ -0,0 +1,178 @@
+//! Benchmarks for window resolution and layout math at the scale of large configurations:
+//! 50 workspaces with 300 windows between them, on a desktop with 300 top-level windows.
+//!
+//! Run them with `cargo bench --bench engine`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use multi_manager::backend::{ShowCommand, WindowBackend};
+use multi_manager::engine::resolve_window;
+use multi_manager::layout::{arrange, clamp_to_work_area, AutoArrange, GridCell, LayoutAlgorithm};
+use multi_manager::workspace::{MatchRule, PositionFlags, Window};
+use std::collections::HashSet;
+use windows::core::Result;
+
+const WORKSPACES: usize = 50;
+const WINDOWS_PER_WORKSPACE: usize = 6;
+const DESKTOP_WINDOWS: usize = 300;
+const WORK_AREAS: [(i32, i32, i32, i32); 2] = [(0, 0, 1920, 1040), (1920, 0, 2560, 1400)];
+
+/// A desktop of simulated windows: window `n` belongs to one of 30 executables and 10
+/// classes, so most entries have several candidates to choose between.
+struct SimulatedBackend;
+
+impl WindowBackend for SimulatedBackend {
+    fn exists(&self, id: usize) -> bool {
+        (1..=DESKTOP_WINDOWS).contains(&id)
+    }
+    fn is_visible(&self, id: usize) -> bool {
+        !id.is_multiple_of(7)
+    }
+    fn is_minimized(&self, _id: usize) -> bool {
+        false
+    }
+    fn is_tool_window(&self, _id: usize) -> bool {
+        false
+    }
+    fn title(&self, id: usize) -> String {
+        format!("Window {}", id)
+    }
+    fn exe(&self, id: usize) -> String {
+        format!("app{}.exe", id % 30)
+    }
+    fn class_name(&self, id: usize) -> String {
+        format!("Class{}", id % 10)
+    }
+    fn process_id(&self, id: usize) -> u32 {
+        id as u32
+    }
+    fn process_start_time(&self, id: usize) -> Option<u64> {
+        Some((DESKTOP_WINDOWS - id) as u64)
+    }
+    fn rect(&self, id: usize) -> Option<(i32, i32, i32, i32)> {
+        Some((id as i32, id as i32, 800, 600))
+    }
+    fn set_rect(
+        &self,
+        _id: usize,
+        _rect: (i32, i32, i32, i32),
+        _flags: &PositionFlags,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn raise(&self, _id: usize) -> Result<()> {
+        Ok(())
+    }
+    fn show(&self, _id: usize, _command: ShowCommand) -> bool {
+        true
+    }
+    fn close(&self, _id: usize) -> Result<()> {
+        Ok(())
+    }
+    fn activate(&self, _id: usize) -> bool {
+        true
+    }
+    fn foreground(&self) -> Option<usize> {
+        Some(1)
+    }
+    fn top_level_windows(&self) -> Vec<usize> {
+        (1..=DESKTOP_WINDOWS).collect()
+    }
+    fn monitors(&self) -> Vec<(i32, i32, i32, i32)> {
+        vec![(0, 0, 1920, 1080), (1920, 0, 2560, 1440)]
+    }
+    fn work_areas(&self) -> Vec<(i32, i32, i32, i32)> {
+        WORK_AREAS.to_vec()
+    }
+    fn taskbar_auto_hide(&self) -> bool {
+        false
+    }
+    fn set_taskbar_auto_hide(&self, _auto_hide: bool) {}
+    fn virtual_desktops(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn current_virtual_desktop(&self) -> Option<usize> {
+        None
+    }
+    fn switch_virtual_desktop(&self, _index: usize) -> bool {
+        false
+    }
+    fn create_virtual_desktop(&self, _name: &str) -> Option<usize> {
+        None
+    }
+    fn move_to_current_virtual_desktop(&self, _id: usize) -> bool {
+        false
+    }
+    fn is_on_current_virtual_desktop(&self, _id: usize) -> bool {
+        true
+    }
+    fn window_virtual_desktop(&self, _id: usize) -> Option<usize> {
+        None
+    }
+}
+
+/// Builds the entries of every workspace, matched by exe and class like captured windows.
+fn entries() -> Vec<Window> {
+    (0..WORKSPACES * WINDOWS_PER_WORKSPACE)
+        .map(|n| Window {
+            title: format!("Window {}", n + 1),
+            capture_title: format!("Window {}", n + 1),
+            exe: format!("app{}.exe", (n + 1) % 30),
+            class_name: format!("Class{}", (n + 1) % 10),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn bench_resolve_window(c: &mut Criterion) {
+    let entries = entries();
+    c.bench_function("resolve_window, 300 entries", |b| {
+        b.iter(|| {
+            for entry in &entries {
+                black_box(resolve_window(
+                    &SimulatedBackend,
+                    entry,
+                    MatchRule::MostRecentlyActive,
+                    &HashSet::new(),
+                ));
+            }
+        })
+    });
+}
+
+fn bench_layout(c: &mut Criterion) {
+    for algorithm in LayoutAlgorithm::ALL {
+        let settings = AutoArrange {
+            algorithm,
+            gap: 8,
+            ..Default::default()
+        };
+        c.bench_function(
+            &format!("arrange {}, 50 workspaces", algorithm.label()),
+            |b| {
+                b.iter(|| {
+                    for _ in 0..WORKSPACES {
+                        black_box(arrange(&settings, WORK_AREAS[0], WINDOWS_PER_WORKSPACE));
+                    }
+                })
+            },
+        );
+    }
+
+    let cell = GridCell {
+        monitor: 1,
+        column: 3,
+        column_span: 4,
+        ..Default::default()
+    };
+    c.bench_function("grid cell and clamp, 300 windows", |b| {
+        b.iter(|| {
+            for n in 0..(WORKSPACES * WINDOWS_PER_WORKSPACE) as i32 {
+                black_box(cell.rect(&WORK_AREAS));
+                black_box(clamp_to_work_area((n * 10, n * 5, 900, 700), &WORK_AREAS));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_resolve_window, bench_layout);
+criterion_main!(benches);
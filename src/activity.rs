@@ -0,0 +1,193 @@
+use crate::error::MultiManagerError;
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// The maximum number of toggles remembered per workspace.
+pub const ACTIVITY_LOG_LIMIT: usize = 20;
+
+/// Which way a workspace was toggled.
+///
+/// # Variants
+/// - `ToTarget`: All windows were home, so they were sent to their target positions.
+/// - `ToHome`: At least one window was away from home, so all were sent home.
+/// - `BringToFront`: The workspace's windows were restored and raised without moving.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ToggleDirection {
+    ToTarget,
+    ToHome,
+    BringToFront,
+}
+
+impl ToggleDirection {
+    /// Returns an arrow showing which way the windows went, for compact displays.
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            ToggleDirection::ToTarget => "➡",
+            ToggleDirection::ToHome => "⬅",
+            ToggleDirection::BringToFront => "⬆",
+        }
+    }
+
+    /// Returns a one-word name for the direction, as substituted for `%DIRECTION%` in
+    /// workspace commands.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToggleDirection::ToTarget => "Target",
+            ToggleDirection::ToHome => "Home",
+            ToggleDirection::BringToFront => "Front",
+        }
+    }
+}
+
+/// What happened to a single window during a toggle.
+///
+/// # Variants
+/// - `Moved`: The window was moved to the given rect.
+/// - `Resisted`: The move was accepted but the window ended up elsewhere, e.g. a fullscreen
+///   game or an app enforcing a minimum size, even after restoring it and moving it again.
+/// - `Raised`: The window was restored and raised in place.
+/// - `Hidden`: The window was hidden at its hidden pseudo-position.
+/// - `Minimized`: The window was minimized by its target action.
+/// - `Closed`: The window was asked to close by its target action, or is still closed.
+/// - `OtherDesktop`: The window is on another virtual desktop and was left there.
+/// - `Failed`: The window could not be moved or raised.
+/// - `NotFound`: The window no longer exists and no replacement matched.
+/// - `Ambiguous`: Several windows matched a stale entry and the user must choose.
+#[derive(Clone, Debug)]
+pub enum WindowOutcome {
+    Moved((i32, i32, i32, i32)),
+    Resisted {
+        requested: (i32, i32, i32, i32),
+        actual: (i32, i32, i32, i32),
+    },
+    Raised,
+    Hidden,
+    Minimized,
+    Closed,
+    OtherDesktop,
+    Failed(MultiManagerError),
+    NotFound,
+    Ambiguous(usize),
+}
+
+impl WindowOutcome {
+    /// Returns whether the window ended up where the toggle intended.
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            WindowOutcome::Moved(_)
+                | WindowOutcome::Raised
+                | WindowOutcome::Hidden
+                | WindowOutcome::Minimized
+                | WindowOutcome::Closed
+                | WindowOutcome::OtherDesktop
+        )
+    }
+
+    /// Returns a short human-readable description for the activity log.
+    pub fn describe(&self) -> String {
+        match self {
+            WindowOutcome::Moved(rect) => format!("moved to {:?}", rect),
+            WindowOutcome::Resisted { requested, actual } => format!(
+                "could not achieve requested rect {:?}; the window is at {:?}",
+                requested, actual
+            ),
+            WindowOutcome::Raised => "raised".to_string(),
+            WindowOutcome::Hidden => "hidden".to_string(),
+            WindowOutcome::Minimized => "minimized".to_string(),
+            WindowOutcome::Closed => "closed".to_string(),
+            WindowOutcome::OtherDesktop => "on another virtual desktop; left there".to_string(),
+            WindowOutcome::Failed(e) => e.to_string(),
+            WindowOutcome::NotFound => "window not found".to_string(),
+            WindowOutcome::Ambiguous(count) => {
+                format!("{} windows matched; choose one in the workspace", count)
+            }
+        }
+    }
+}
+
+/// The result of one toggle for one window.
+///
+/// # Fields
+/// - `title`: The window's title at the time of the toggle.
+/// - `outcome`: What happened to the window.
+#[derive(Clone, Debug)]
+pub struct WindowActivity {
+    pub title: String,
+    pub outcome: WindowOutcome,
+}
+
+/// A single recorded toggle of a workspace.
+///
+/// # Fields
+/// - `triggered_at`: When the toggle started.
+/// - `direction`: Which way the workspace was toggled.
+/// - `windows`: The result for each window, in workspace order.
+#[derive(Clone, Debug)]
+pub struct ActivityEntry {
+    pub triggered_at: DateTime<Local>,
+    pub direction: ToggleDirection,
+    pub windows: Vec<WindowActivity>,
+}
+
+impl ActivityEntry {
+    /// Starts a new entry timestamped now.
+    ///
+    /// # Arguments
+    /// - `direction`: Which way the workspace is being toggled.
+    pub fn new(direction: ToggleDirection) -> Self {
+        ActivityEntry {
+            triggered_at: Local::now(),
+            direction,
+            windows: Vec::new(),
+        }
+    }
+
+    /// Records the outcome for one window.
+    ///
+    /// # Arguments
+    /// - `title`: The window's title.
+    /// - `outcome`: What happened to the window.
+    pub fn push(&mut self, title: &str, outcome: WindowOutcome) {
+        self.windows.push(WindowActivity {
+            title: title.to_string(),
+            outcome,
+        });
+    }
+}
+
+/// A bounded history of a workspace's toggles, oldest entries dropped first.
+///
+/// This is runtime state only and is not saved with the workspace.
+#[derive(Clone, Debug, Default)]
+pub struct ActivityLog {
+    entries: VecDeque<ActivityEntry>,
+}
+
+impl ActivityLog {
+    /// Adds an entry, dropping the oldest one if the log is full.
+    ///
+    /// # Arguments
+    /// - `entry`: The toggle to record.
+    pub fn record(&mut self, entry: ActivityEntry) {
+        if self.entries.len() == ACTIVITY_LOG_LIMIT {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Returns the recorded entries, newest first.
+    pub fn newest_first(&self) -> impl Iterator<Item = &ActivityEntry> {
+        self.entries.iter().rev()
+    }
+
+    /// Returns whether nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every recorded entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,349 @@
+//! The background agent: a process without a window that runs the always-on parts (hotkeys,
+//! gestures, schedules, idle restore, and the monitor and window-close watchers), so the GUI
+//! only needs to run while it is being used.
+//!
+//! The agent listens on a named pipe. A GUI started while an agent is running attaches to it:
+//! it leaves hotkeys and the watchers to the agent and asks it to reload after every save.
+//! Requests are single lines of text, answered with `ok` or `error`.
+
+use log::{info, warn};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::os::windows::io::{AsRawHandle, FromRawHandle};
+use std::time::{Duration, Instant};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_IO_PENDING, ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED, HANDLE,
+};
+use windows::Win32::Storage::FileSystem::{
+    ReadFile, WriteFile, FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_FIRST_PIPE_INSTANCE,
+    FILE_FLAG_OVERLAPPED, PIPE_ACCESS_DUPLEX,
+};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, WaitNamedPipeW, PIPE_READMODE_BYTE,
+    PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::CreateEventW;
+use windows::Win32::System::IO::{
+    CancelIoEx, GetOverlappedResult, GetOverlappedResultEx, OVERLAPPED,
+};
+
+/// The command-line flag that runs the background agent instead of the GUI.
+pub const AGENT_FLAG: &str = "--agent";
+
+/// The pipe the agent listens on, as opened by clients.
+const PIPE_PATH: &str = r"\\.\pipe\multi-manager-agent";
+
+/// The size of the pipe's buffers; requests are a single short line.
+const PIPE_BUFFER_SIZE: u32 = 4096;
+
+/// How long a connected client has to send its request, and to take the answer, before the
+/// agent drops it and serves the next one.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often a client tries again while every instance of the pipe is busy.
+const BUSY_RETRIES: u32 = 5;
+
+/// A request to the agent.
+///
+/// # Variants
+/// - `Ping`: Checks that the agent is running.
+/// - `Reload`: Loads the settings and the given workspace file again, after the GUI saved
+///   them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AgentCommand {
+    Ping,
+    Reload(String),
+}
+
+impl AgentCommand {
+    /// Parses a request line, such as `reload C:\layouts\docked.json`.
+    ///
+    /// # Returns
+    /// - The request, or `None` if the line is not one.
+    pub fn parse(line: &str) -> Option<AgentCommand> {
+        let line = line.trim();
+        match line.split_once(' ') {
+            Some(("reload", path)) if !path.trim().is_empty() => {
+                Some(AgentCommand::Reload(path.trim().to_string()))
+            }
+            None if line == "ping" => Some(AgentCommand::Ping),
+            _ => None,
+        }
+    }
+
+    /// Returns the request as the line sent over the pipe.
+    fn to_line(&self) -> String {
+        match self {
+            AgentCommand::Ping => "ping".to_string(),
+            AgentCommand::Reload(path) => format!("reload {}", path),
+        }
+    }
+}
+
+/// The agent's end of the pipe.
+pub struct AgentServer {
+    pipe: File,
+}
+
+impl AgentServer {
+    /// Creates the agent's pipe.
+    ///
+    /// # Returns
+    /// - The server, or `None` if the pipe could not be created, e.g. because another agent
+    ///   is already running.
+    pub fn bind() -> Option<AgentServer> {
+        let server = create_pipe(FILE_FLAG_FIRST_PIPE_INSTANCE).map(|pipe| AgentServer { pipe });
+        if server.is_none() {
+            warn!("Could not create the agent pipe; is another agent running?");
+        }
+        server
+    }
+
+    /// Answers requests one at a time on the calling thread. Never returns.
+    ///
+    /// # Arguments
+    /// - `on_command`: Handles each request, returning whether it succeeded.
+    ///
+    /// # Example
+    /// ```ignore
+    /// if let Some(server) = AgentServer::bind() {
+    ///     server.serve(|command| { println!("{:?}", command); true });
+    /// }
+    /// ```
+    pub fn serve(self, mut on_command: impl FnMut(AgentCommand) -> bool) -> ! {
+        info!("Agent listening on '{}'.", PIPE_PATH);
+        let mut listening = Some(self.pipe);
+        loop {
+            let Some(current) = listening
+                .take()
+                .or_else(|| create_pipe(FILE_FLAGS_AND_ATTRIBUTES(0)))
+            else {
+                std::thread::sleep(Duration::from_secs(1));
+                continue;
+            };
+            let handle = HANDLE(current.as_raw_handle());
+            if wait_for_io(handle, None, |overlapped| unsafe {
+                ConnectNamedPipe(handle, Some(overlapped))
+            })
+            .is_none()
+            {
+                continue;
+            }
+            // Listen on the next instance before serving this one, so a client never finds
+            // the pipe missing while a request is handled
+            listening = create_pipe(FILE_FLAGS_AND_ATTRIBUTES(0));
+
+            let Some(line) = read_request(handle) else {
+                warn!("Dropping an agent client that sent no request in time.");
+                continue;
+            };
+            let answer = match AgentCommand::parse(&line) {
+                Some(command) => {
+                    info!("Agent request: {:?}", command);
+                    if on_command(command) {
+                        "ok"
+                    } else {
+                        "error"
+                    }
+                }
+                None => {
+                    warn!("Ignoring unknown agent request '{}'.", line.trim());
+                    "error"
+                }
+            };
+            let answer = format!("{}\n", answer);
+            if wait_for_io(handle, Some(REQUEST_TIMEOUT), |overlapped| unsafe {
+                WriteFile(handle, Some(answer.as_bytes()), None, Some(overlapped))
+            })
+            .is_none()
+            {
+                warn!("Dropping an agent client that did not take its answer in time.");
+            }
+        }
+    }
+}
+
+/// Reads a request line from a connected client, giving up after `REQUEST_TIMEOUT` so a
+/// client that never writes can't stall the agent.
+///
+/// # Arguments
+/// - `pipe`: The connected pipe instance, opened for overlapped I/O.
+///
+/// # Returns
+/// - The line, or `None` if the client disconnected, sent too much, or took too long.
+fn read_request(pipe: HANDLE) -> Option<String> {
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 256];
+    while !request.contains(&b'\n') {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        let read = wait_for_io(pipe, Some(remaining), |overlapped| unsafe {
+            ReadFile(pipe, Some(&mut buffer), None, Some(overlapped))
+        })?;
+        if read == 0 || request.len() >= PIPE_BUFFER_SIZE as usize {
+            return None;
+        }
+        request.extend_from_slice(&buffer[..read as usize]);
+    }
+    String::from_utf8(request).ok()
+}
+
+/// Starts an overlapped operation on the pipe and waits for it to finish.
+///
+/// # Arguments
+/// - `pipe`: The pipe instance, opened for overlapped I/O.
+/// - `timeout`: How long to wait, or `None` to wait as long as it takes.
+/// - `start`: Starts the operation with the given `OVERLAPPED`.
+///
+/// # Returns
+/// - The number of bytes transferred, or `None` if the operation failed or timed out, in
+///   which case it is cancelled.
+fn wait_for_io(
+    pipe: HANDLE,
+    timeout: Option<Duration>,
+    start: impl FnOnce(*mut OVERLAPPED) -> windows::core::Result<()>,
+) -> Option<u32> {
+    let event = unsafe { CreateEventW(None, true, false, None) }.ok()?;
+    let mut overlapped = OVERLAPPED {
+        hEvent: event,
+        ..Default::default()
+    };
+    let mut transferred = 0;
+    let done = match start(&mut overlapped) {
+        // A client that connected before `ConnectNamedPipe` was called
+        Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => true,
+        Err(e) if e.code() != ERROR_IO_PENDING.to_hresult() => false,
+        _ => {
+            let millis = timeout.map_or(u32::MAX, |timeout| timeout.as_millis() as u32);
+            let finished = unsafe {
+                GetOverlappedResultEx(pipe, &overlapped, &mut transferred, millis, false)
+            }
+            .is_ok();
+            if !finished {
+                // The buffers must outlive the operation, so wait for the cancellation
+                unsafe {
+                    let _ = CancelIoEx(pipe, Some(&overlapped));
+                    let _ = GetOverlappedResult(pipe, &overlapped, &mut transferred, true);
+                }
+            }
+            finished
+        }
+    };
+    unsafe {
+        let _ = CloseHandle(event);
+    }
+    done.then_some(transferred)
+}
+
+/// Sends a request to the running agent and waits for its answer.
+///
+/// # Arguments
+/// - `command`: The request to send.
+///
+/// # Returns
+/// - `true` if an agent is running and handled the request.
+///
+/// # Example
+/// ```ignore
+/// let attached = send_to_agent(&AgentCommand::Ping);
+/// ```
+pub fn send_to_agent(command: &AgentCommand) -> bool {
+    let Some(mut pipe) = open_agent_pipe() else {
+        return false;
+    };
+    if writeln!(pipe, "{}", command.to_line()).is_err() {
+        return false;
+    }
+    let mut answer = String::new();
+    BufReader::new(pipe).read_line(&mut answer).is_ok() && answer.trim() == "ok"
+}
+
+/// Opens the client end of the agent's pipe, waiting for a free instance while the agent is
+/// busy with another client.
+///
+/// # Returns
+/// - The pipe, or `None` if no agent is running or it stayed busy.
+fn open_agent_pipe() -> Option<File> {
+    for _ in 0..BUSY_RETRIES {
+        match OpenOptions::new().read(true).write(true).open(PIPE_PATH) {
+            Ok(pipe) => return Some(pipe),
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY.0 as i32) => {
+                let millis = REQUEST_TIMEOUT.as_millis() as u32;
+                if !unsafe { WaitNamedPipeW(&HSTRING::from(PIPE_PATH), millis) }.as_bool() {
+                    return None;
+                }
+            }
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// Creates one instance of the agent's pipe, for overlapped I/O and local clients only.
+fn create_pipe(flags: FILE_FLAGS_AND_ATTRIBUTES) -> Option<File> {
+    let handle = unsafe {
+        CreateNamedPipeW(
+            &HSTRING::from(PIPE_PATH),
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_OVERLAPPED | flags,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            PIPE_BUFFER_SIZE,
+            PIPE_BUFFER_SIZE,
+            0,
+            None,
+        )
+    };
+    if handle.is_invalid() {
+        return None;
+    }
+    Some(unsafe { File::from_raw_handle(handle.0) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_parsed() {
+        for (line, command) in [
+            ("ping", AgentCommand::Ping),
+            (" ping\r\n", AgentCommand::Ping),
+            (
+                r"reload C:\layouts\docked.json",
+                AgentCommand::Reload(r"C:\layouts\docked.json".to_string()),
+            ),
+            (
+                "reload  C:\\My Layouts\\work.json \n",
+                AgentCommand::Reload(r"C:\My Layouts\work.json".to_string()),
+            ),
+        ] {
+            assert_eq!(AgentCommand::parse(line), Some(command), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn other_lines_are_rejected() {
+        for line in [
+            "",
+            "reload",
+            "reload   ",
+            "ping now",
+            "PING",
+            "Reload C:\\work.json",
+            "quit",
+        ] {
+            assert_eq!(AgentCommand::parse(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn requests_survive_the_pipe() {
+        for command in [
+            AgentCommand::Ping,
+            AgentCommand::Reload(r"C:\My Layouts\work.json".to_string()),
+        ] {
+            assert_eq!(AgentCommand::parse(&command.to_line()), Some(command));
+        }
+    }
+}
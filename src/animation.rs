@@ -0,0 +1,190 @@
+//! Animated window moves: instead of jumping, a window glides from where it is to its new
+//! position over a short duration.
+
+use crate::window_manager::system_animations_enabled;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long each frame of an animated move is shown.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The longest animation offered in the GUI, in milliseconds.
+pub const MAX_ANIMATION_MS: u32 = 1000;
+
+/// How an animated move speeds up and slows down.
+///
+/// # Variants
+/// - `Linear`: The window moves at a constant speed.
+/// - `EaseOut`: The window starts fast and slows down as it arrives.
+/// - `EaseInOut`: The window speeds up, then slows down.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum Easing {
+    Linear,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Every easing, in the order offered in the GUI.
+    pub const ALL: [Easing; 3] = [Easing::Linear, Easing::EaseOut, Easing::EaseInOut];
+
+    /// Returns the name shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseOut => "Ease out",
+            Easing::EaseInOut => "Ease in-out",
+        }
+    }
+
+    /// Maps the elapsed share of the animation to the share of the distance covered.
+    ///
+    /// # Arguments
+    /// - `t`: The elapsed share, from 0.0 to 1.0.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// How window moves are animated.
+///
+/// # Fields
+/// - `duration_ms`: How long a move takes, in milliseconds. 0 moves windows instantly.
+/// - `easing`: How the move speeds up and slows down.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Animation {
+    pub duration_ms: u32,
+    pub easing: Easing,
+}
+
+impl Animation {
+    /// An animation that moves windows instantly.
+    pub const INSTANT: Animation = Animation {
+        duration_ms: 0,
+        easing: Easing::EaseOut,
+    };
+
+    /// Computes the positions a window passes through on its way, one per frame.
+    ///
+    /// # Arguments
+    /// - `from`: Where the window is now `(x, y, width, height)`.
+    /// - `to`: Where the window is going.
+    ///
+    /// # Returns
+    /// - The intermediate positions, not including `from` and `to`. Empty for an instant move.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::animation::{Animation, Easing};
+    /// let animation = Animation { duration_ms: 48, easing: Easing::Linear };
+    /// let frames = animation.frames((0, 0, 100, 100), (300, 0, 100, 100));
+    /// assert_eq!(frames, vec![(100, 0, 100, 100), (200, 0, 100, 100)]);
+    /// ```
+    pub fn frames(
+        &self,
+        from: (i32, i32, i32, i32),
+        to: (i32, i32, i32, i32),
+    ) -> Vec<(i32, i32, i32, i32)> {
+        let steps = self.duration_ms / FRAME_INTERVAL.as_millis() as u32;
+        if steps < 2 || from == to {
+            return Vec::new();
+        }
+        let lerp = |a: i32, b: i32, t: f64| a + ((b - a) as f64 * t).round() as i32;
+        (1..steps)
+            .map(|step| {
+                let t = self.easing.apply(step as f64 / steps as f64);
+                (
+                    lerp(from.0, to.0, t),
+                    lerp(from.1, to.1, t),
+                    lerp(from.2, to.2, t),
+                    lerp(from.3, to.3, t),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Which animation a workspace's moves use.
+///
+/// # Variants
+/// - `Global`: The animation set in the Settings panel.
+/// - `Instant`: No animation, e.g. for layouts that must be in place right away.
+/// - `Custom`: The workspace's own duration and easing.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum AnimationOverride {
+    #[default]
+    Global,
+    Instant,
+    Custom(Animation),
+}
+
+impl AnimationOverride {
+    /// Returns the animation to use, looking up the global one if not overridden. Moves are
+    /// instant whatever the override while animations are off; see `animations_active`.
+    pub fn resolve(self) -> Animation {
+        if !animations_active() {
+            return Animation::INSTANT;
+        }
+        match self {
+            AnimationOverride::Global => global_animation(),
+            AnimationOverride::Instant => Animation::INSTANT,
+            AnimationOverride::Custom(animation) => animation,
+        }
+    }
+}
+
+/// The animation set in the Settings panel, used by workspaces that don't override it.
+static GLOBAL_ANIMATION: Mutex<Animation> = Mutex::new(Animation::INSTANT);
+
+/// Sets the animation used by workspaces that don't override it.
+///
+/// # Arguments
+/// - `animation`: The duration and easing from the Settings panel.
+pub fn set_global_animation(animation: Animation) {
+    *GLOBAL_ANIMATION.lock().unwrap() = animation;
+}
+
+/// Returns the animation used by workspaces that don't override it.
+pub fn global_animation() -> Animation {
+    *GLOBAL_ANIMATION.lock().unwrap()
+}
+
+/// Whether window moves may be animated at all.
+static ANIMATIONS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether the Windows reduced-motion setting turns animations off.
+static RESPECT_REDUCED_MOTION: AtomicBool = AtomicBool::new(true);
+
+/// Turns every animation on or off, whatever the workspaces' overrides.
+///
+/// # Arguments
+/// - `enabled`: Whether window moves may be animated.
+pub fn set_animations_enabled(enabled: bool) {
+    ANIMATIONS_ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Sets whether animations are turned off while Windows is set to reduce motion.
+///
+/// # Arguments
+/// - `respect`: Whether to follow the Windows animation effects setting.
+pub fn set_respect_reduced_motion(respect: bool) {
+    RESPECT_REDUCED_MOTION.store(respect, Ordering::SeqCst);
+}
+
+/// Returns whether window moves are animated: animations are enabled and, if reduced motion
+/// is respected, Windows' animation effects are on. The Windows setting is read each time, so
+/// changing it takes effect without a restart.
+pub fn animations_active() -> bool {
+    ANIMATIONS_ENABLED.load(Ordering::SeqCst)
+        && (!RESPECT_REDUCED_MOTION.load(Ordering::SeqCst) || system_animations_enabled())
+}
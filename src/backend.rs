@@ -0,0 +1,276 @@
+use crate::virtual_desktop;
+use crate::window_manager::{
+    enumerate_monitors, enumerate_top_level_windows, enumerate_work_areas, get_process_start_time,
+    get_window_class, get_window_exe, get_window_position, get_window_title, is_taskbar_auto_hide,
+    move_window_with_flags, set_taskbar_auto_hide, set_window_pos_flags,
+};
+use crate::workspace::PositionFlags;
+use windows::core::Result;
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// The ways the toggling engine changes a window's show state.
+///
+/// # Variants
+/// - `Restore`: Restores a minimized window and activates it (`SW_RESTORE`).
+/// - `RestoreNoActivate`: Restores a window without activating it (`SW_SHOWNOACTIVATE`).
+/// - `ShowNoActivate`: Shows a hidden window in its current state (`SW_SHOWNA`).
+/// - `MinimizeNoActivate`: Minimizes a window without activating it (`SW_SHOWMINNOACTIVE`).
+/// - `Hide`: Hides a window (`SW_HIDE`).
+/// - `Maximize`: Maximizes a window on its monitor and activates it (`SW_MAXIMIZE`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShowCommand {
+    Restore,
+    RestoreNoActivate,
+    ShowNoActivate,
+    MinimizeNoActivate,
+    Hide,
+    Maximize,
+}
+
+/// The window-system operations the toggling engine depends on.
+///
+/// Windows are identified by their handle stored as `usize`, matching `Window::id`.
+/// `Win32Backend` implements this against the live desktop; other implementations can
+/// simulate windows so the engine can be tested or embedded without touching real ones.
+pub trait WindowBackend {
+    /// Returns whether the handle refers to an existing window.
+    fn exists(&self, id: usize) -> bool;
+
+    /// Returns whether the window is visible.
+    fn is_visible(&self, id: usize) -> bool;
+
+    /// Returns whether the window is minimized.
+    fn is_minimized(&self, id: usize) -> bool;
+
+    /// Returns whether the window is a tool window (`WS_EX_TOOLWINDOW`), such as a floating
+    /// palette or a helper window that has no taskbar button.
+    fn is_tool_window(&self, id: usize) -> bool;
+
+    /// Returns the window's current title.
+    fn title(&self, id: usize) -> String;
+
+    /// Returns the file name of the executable that owns the window.
+    fn exe(&self, id: usize) -> String;
+
+    /// Returns the window's class name.
+    fn class_name(&self, id: usize) -> String;
+
+    /// Returns the ID of the process that owns the window.
+    fn process_id(&self, id: usize) -> u32;
+
+    /// Returns when the window's process was started, as a `FILETIME` value, if it can be read.
+    fn process_start_time(&self, id: usize) -> Option<u64>;
+
+    /// Returns the window's position `(x, y, width, height)`.
+    fn rect(&self, id: usize) -> Option<(i32, i32, i32, i32)>;
+
+    /// Moves and/or resizes the window, honoring its position flags.
+    fn set_rect(&self, id: usize, rect: (i32, i32, i32, i32), flags: &PositionFlags) -> Result<()>;
+
+    /// Raises the window to the top of the z-order without moving, resizing or activating it.
+    fn raise(&self, id: usize) -> Result<()>;
+
+    /// Changes the window's show state. Returns whether the window was previously visible.
+    fn show(&self, id: usize, command: ShowCommand) -> bool;
+
+    /// Asks the window to close, as its close button would, without waiting for it.
+    fn close(&self, id: usize) -> Result<()>;
+
+    /// Brings the window to the foreground. Returns whether it succeeded.
+    fn activate(&self, id: usize) -> bool;
+
+    /// Returns the window that currently has focus, if any.
+    fn foreground(&self) -> Option<usize>;
+
+    /// Returns all top-level windows in z-order, topmost first.
+    fn top_level_windows(&self) -> Vec<usize>;
+
+    /// Returns the bounds `(x, y, width, height)` of every connected monitor.
+    fn monitors(&self) -> Vec<(i32, i32, i32, i32)>;
+
+    /// Returns the work area of every connected monitor, excluding the taskbar, in the
+    /// same order as `monitors`.
+    fn work_areas(&self) -> Vec<(i32, i32, i32, i32)>;
+
+    /// Returns whether the taskbar is set to auto-hide.
+    fn taskbar_auto_hide(&self) -> bool;
+
+    /// Turns the taskbar's auto-hide setting on or off.
+    fn set_taskbar_auto_hide(&self, auto_hide: bool);
+
+    /// Returns the names of the virtual desktops, in Task View order.
+    fn virtual_desktops(&self) -> Vec<String>;
+
+    /// Returns the index of the current virtual desktop, if it can be told.
+    fn current_virtual_desktop(&self) -> Option<usize>;
+
+    /// Switches to a virtual desktop. Returns whether it is now current.
+    fn switch_virtual_desktop(&self, index: usize) -> bool;
+
+    /// Creates a virtual desktop with a name and switches to it. Returns its index.
+    fn create_virtual_desktop(&self, name: &str) -> Option<usize>;
+
+    /// Puts the window on the current virtual desktop. Returns whether it is there now.
+    fn move_to_current_virtual_desktop(&self, id: usize) -> bool;
+
+    /// Returns whether the window is on the current virtual desktop, or pinned to all of them.
+    /// Windows whose desktop can't be told count as on the current one.
+    fn is_on_current_virtual_desktop(&self, id: usize) -> bool;
+
+    /// Returns the index of the virtual desktop the window is on, in Task View order.
+    fn window_virtual_desktop(&self, id: usize) -> Option<usize>;
+}
+
+/// The `WindowBackend` that operates on real windows through the Win32 API.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Win32Backend;
+
+/// Converts a stored window ID back into a handle.
+fn hwnd(id: usize) -> HWND {
+    HWND(id as *mut std::ffi::c_void)
+}
+
+impl WindowBackend for Win32Backend {
+    fn exists(&self, id: usize) -> bool {
+        unsafe { IsWindow(hwnd(id)).as_bool() }
+    }
+
+    fn is_visible(&self, id: usize) -> bool {
+        unsafe { IsWindowVisible(hwnd(id)).as_bool() }
+    }
+
+    fn is_minimized(&self, id: usize) -> bool {
+        unsafe { IsIconic(hwnd(id)).as_bool() }
+    }
+
+    fn is_tool_window(&self, id: usize) -> bool {
+        let ex_style = unsafe { GetWindowLongPtrW(hwnd(id), GWL_EXSTYLE) } as u32;
+        ex_style & WS_EX_TOOLWINDOW.0 != 0
+    }
+
+    fn title(&self, id: usize) -> String {
+        get_window_title(hwnd(id))
+    }
+
+    fn exe(&self, id: usize) -> String {
+        get_window_exe(hwnd(id))
+    }
+
+    fn class_name(&self, id: usize) -> String {
+        get_window_class(hwnd(id))
+    }
+
+    fn process_id(&self, id: usize) -> u32 {
+        let mut process_id = 0u32;
+        unsafe {
+            GetWindowThreadProcessId(hwnd(id), Some(&mut process_id));
+        }
+        process_id
+    }
+
+    fn process_start_time(&self, id: usize) -> Option<u64> {
+        get_process_start_time(hwnd(id))
+    }
+
+    fn rect(&self, id: usize) -> Option<(i32, i32, i32, i32)> {
+        get_window_position(hwnd(id)).ok()
+    }
+
+    fn set_rect(&self, id: usize, rect: (i32, i32, i32, i32), flags: &PositionFlags) -> Result<()> {
+        move_window_with_flags(hwnd(id), rect, set_window_pos_flags(flags))
+    }
+
+    fn raise(&self, id: usize) -> Result<()> {
+        unsafe {
+            SetWindowPos(
+                hwnd(id),
+                HWND_TOP,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        }
+    }
+
+    fn show(&self, id: usize, command: ShowCommand) -> bool {
+        let command = match command {
+            ShowCommand::Restore => SW_RESTORE,
+            ShowCommand::RestoreNoActivate => SW_SHOWNOACTIVATE,
+            ShowCommand::ShowNoActivate => SW_SHOWNA,
+            ShowCommand::MinimizeNoActivate => SW_SHOWMINNOACTIVE,
+            ShowCommand::Hide => SW_HIDE,
+            ShowCommand::Maximize => SW_MAXIMIZE,
+        };
+        unsafe { ShowWindow(hwnd(id), command).as_bool() }
+    }
+
+    fn close(&self, id: usize) -> Result<()> {
+        unsafe { PostMessageW(hwnd(id), WM_CLOSE, WPARAM(0), LPARAM(0)) }
+    }
+
+    fn activate(&self, id: usize) -> bool {
+        unsafe { SetForegroundWindow(hwnd(id)).as_bool() }
+    }
+
+    fn foreground(&self) -> Option<usize> {
+        let foreground = unsafe { GetForegroundWindow() };
+        (!foreground.0.is_null()).then_some(foreground.0 as usize)
+    }
+
+    fn top_level_windows(&self) -> Vec<usize> {
+        enumerate_top_level_windows()
+            .into_iter()
+            .map(|hwnd| hwnd.0 as usize)
+            .collect()
+    }
+
+    fn monitors(&self) -> Vec<(i32, i32, i32, i32)> {
+        enumerate_monitors()
+    }
+
+    fn work_areas(&self) -> Vec<(i32, i32, i32, i32)> {
+        enumerate_work_areas()
+    }
+
+    fn taskbar_auto_hide(&self) -> bool {
+        is_taskbar_auto_hide()
+    }
+
+    fn set_taskbar_auto_hide(&self, auto_hide: bool) {
+        set_taskbar_auto_hide(auto_hide)
+    }
+
+    fn virtual_desktops(&self) -> Vec<String> {
+        virtual_desktop::virtual_desktops()
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    fn current_virtual_desktop(&self) -> Option<usize> {
+        virtual_desktop::current_virtual_desktop()
+    }
+
+    fn switch_virtual_desktop(&self, index: usize) -> bool {
+        virtual_desktop::switch_virtual_desktop(index)
+    }
+
+    fn create_virtual_desktop(&self, name: &str) -> Option<usize> {
+        virtual_desktop::create_virtual_desktop(name)
+    }
+
+    fn move_to_current_virtual_desktop(&self, id: usize) -> bool {
+        virtual_desktop::move_to_current_virtual_desktop(hwnd(id))
+    }
+
+    fn is_on_current_virtual_desktop(&self, id: usize) -> bool {
+        virtual_desktop::is_on_current_virtual_desktop(hwnd(id))
+    }
+
+    fn window_virtual_desktop(&self, id: usize) -> Option<usize> {
+        virtual_desktop::window_virtual_desktop(hwnd(id))
+    }
+}
@@ -0,0 +1,201 @@
+//! CapsLock as an extra modifier, so hotkeys such as "Caps+H" don't clash with the shortcuts
+//! of other programs.
+//!
+//! A low-level keyboard hook swallows CapsLock, so holding it doesn't change the lock state,
+//! and while it is held swallows the other keys pressed too, recording them for
+//! `is_layer_key_down` instead. Ctrl, Alt, Shift, and Win pass through, so they still combine
+//! with the layer. Tapping CapsLock on its own is replayed, so it still toggles the lock.
+
+use log::{info, warn};
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    VIRTUAL_KEY, VK_CAPITAL, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU,
+    VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
+    HC_ACTION, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_QUIT,
+    WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+/// The modifier keys, which pass through the layer so they can be combined with it.
+const MODIFIER_KEYS: [VIRTUAL_KEY; 11] = [
+    VK_CONTROL,
+    VK_LCONTROL,
+    VK_RCONTROL,
+    VK_MENU,
+    VK_LMENU,
+    VK_RMENU,
+    VK_SHIFT,
+    VK_LSHIFT,
+    VK_RSHIFT,
+    VK_LWIN,
+    VK_RWIN,
+];
+
+/// The state shared with the hook procedure, which can't capture anything.
+///
+/// # Fields
+/// - `held`: Whether CapsLock is held.
+/// - `used`: Whether a key was pressed since CapsLock went down, so releasing it is not a tap.
+/// - `keys`: The virtual key codes of the keys held in the layer.
+#[derive(Default)]
+struct LayerState {
+    held: bool,
+    used: bool,
+    keys: HashSet<u32>,
+}
+
+static LAYER_STATE: Mutex<Option<LayerState>> = Mutex::new(None);
+
+/// A running CapsLock layer. Dropping it removes the hook, and CapsLock behaves normally again.
+pub struct CapsLayerHook {
+    thread_id: u32,
+}
+
+impl CapsLayerHook {
+    /// Installs the keyboard hook on a thread of its own.
+    ///
+    /// # Returns
+    /// - The running layer, or `None` if the hook could not be installed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let layer = CapsLayerHook::start();
+    /// ```
+    pub fn start() -> Option<CapsLayerHook> {
+        *LAYER_STATE.lock().unwrap() = Some(LayerState::default());
+
+        let (started, started_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("CapsLock Layer".to_string())
+            .spawn(move || run_hook(started))
+            .ok()?;
+        match started_receiver.recv() {
+            Ok(Some(thread_id)) => {
+                info!("CapsLock layer hook installed.");
+                Some(CapsLayerHook { thread_id })
+            }
+            _ => {
+                LAYER_STATE.lock().unwrap().take();
+                None
+            }
+        }
+    }
+}
+
+impl Drop for CapsLayerHook {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        LAYER_STATE.lock().unwrap().take();
+        info!("CapsLock layer hook removed.");
+    }
+}
+
+/// Checks whether a key is held together with CapsLock.
+///
+/// The layer swallows the key, so it is not seen by `GetAsyncKeyState`; hotkeys with the Caps
+/// modifier ask the layer instead.
+///
+/// # Arguments
+/// - `vk`: The virtual key code of the key.
+///
+/// # Returns
+/// - `true` if the layer is running, CapsLock is held, and the key is down.
+pub fn is_layer_key_down(vk: u32) -> bool {
+    LAYER_STATE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|state| state.held && state.keys.contains(&vk))
+}
+
+/// Installs the hook and runs the message loop the hook needs until `WM_QUIT` is posted.
+fn run_hook(started: Sender<Option<u32>>) {
+    let hook = match unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), None, 0) } {
+        Ok(hook) => hook,
+        Err(e) => {
+            warn!("Failed to install the CapsLock layer hook: {}", e);
+            let _ = started.send(None);
+            return;
+        }
+    };
+    let _ = started.send(Some(unsafe { GetCurrentThreadId() }));
+
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {}
+
+    unsafe {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// The low-level keyboard hook: swallows CapsLock and the keys pressed while it is held,
+/// passing everything else on.
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if (event.flags & LLKHF_INJECTED).0 == 0 {
+            let mut tap = false;
+            let mut swallow = false;
+            if let Some(state) = LAYER_STATE.lock().unwrap().as_mut() {
+                let down = matches!(wparam.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN);
+                let up = matches!(wparam.0 as u32, WM_KEYUP | WM_SYSKEYUP);
+                let vk = event.vkCode;
+                if vk == VK_CAPITAL.0 as u32 {
+                    if down && !state.held {
+                        state.held = true;
+                        state.used = false;
+                    } else if up {
+                        tap = !state.used;
+                        state.held = false;
+                        state.keys.clear();
+                    }
+                    swallow = true;
+                } else if MODIFIER_KEYS.iter().all(|key| key.0 as u32 != vk) {
+                    if down && state.held {
+                        state.keys.insert(vk);
+                        state.used = true;
+                        swallow = true;
+                    } else if up {
+                        swallow = state.keys.remove(&vk);
+                    }
+                }
+            }
+            if tap {
+                // Injected input is only processed once the hook returns
+                thread::spawn(replay_caps_lock);
+            }
+            if swallow {
+                return LRESULT(1);
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Sends the CapsLock tap swallowed by the hook, so the lock still toggles.
+fn replay_caps_lock() {
+    let input = |flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VK_CAPITAL,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let inputs = [input(KEYBD_EVENT_FLAGS(0)), input(KEYEVENTF_KEYUP)];
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
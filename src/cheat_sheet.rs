@@ -0,0 +1,124 @@
+use crate::workspace::Workspace;
+use std::collections::HashMap;
+
+/// The hotkeys of one workspace as listed on the cheat sheet.
+///
+/// # Fields
+/// - `workspace`: The name of the workspace.
+/// - `bindings`: Each hotkey and what it does, e.g. `("Ctrl+Alt+1", "Toggle")`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheatSheetGroup {
+    pub workspace: String,
+    pub bindings: Vec<(String, String)>,
+}
+
+/// Builds the cheat sheet of every active hotkey, grouped by workspace in list order.
+///
+/// Toggle hotkeys are taken from the registered hotkeys, so a hotkey that failed to register
+/// is left out rather than promised. The All to Home, All to Target, Save Layout, and
+/// per-window toggle hotkeys of enabled workspaces are listed after them. Workspaces without
+/// any hotkey are skipped.
+///
+/// # Arguments
+/// - `workspaces`: The workspace list.
+/// - `registered_hotkeys`: The hotkeys registered with the system, keyed by key sequence.
+///
+/// # Returns
+/// - One group per workspace with at least one hotkey.
+///
+/// # Example
+/// ```ignore
+/// for group in cheat_sheet(&workspaces, &registered_hotkeys) {
+///     println!("{}: {} hotkey(s)", group.workspace, group.bindings.len());
+/// }
+/// ```
+pub fn cheat_sheet(
+    workspaces: &[Workspace],
+    registered_hotkeys: &HashMap<String, usize>,
+) -> Vec<CheatSheetGroup> {
+    let mut toggles: Vec<(&String, usize)> = registered_hotkeys
+        .iter()
+        .map(|(hotkey, &index)| (hotkey, index))
+        .collect();
+    toggles.sort();
+
+    workspaces
+        .iter()
+        .enumerate()
+        .filter_map(|(index, workspace)| {
+            let mut bindings: Vec<(String, String)> = toggles
+                .iter()
+                .filter(|&&(_, registered)| registered == index)
+                .map(|&(hotkey, _)| (hotkey.clone(), "Toggle".to_string()))
+                .collect();
+            if !workspace.disabled {
+                for (hotkey, action) in [
+                    (&workspace.home_hotkey, "All to Home"),
+                    (&workspace.target_hotkey, "All to Target"),
+                    (&workspace.save_hotkey, "Save Layout"),
+                ] {
+                    if let Some(hotkey) = hotkey.as_ref().filter(|hotkey| !hotkey.is_empty()) {
+                        bindings.push((hotkey.clone(), action.to_string()));
+                    }
+                }
+                let numbered = workspace.windows.len().min(9);
+                if let (Some(first), Some(last)) = (
+                    workspace.window_digit_hotkey(0).filter(|_| numbered > 0),
+                    workspace.window_digit_hotkey(numbered.saturating_sub(1)),
+                ) {
+                    let hotkey = if numbered == 1 {
+                        first
+                    } else {
+                        format!("{} … {}", first, last)
+                    };
+                    bindings.push((hotkey, "Toggle window by number".to_string()));
+                }
+                for window in &workspace.windows {
+                    if let Some(hotkey) = window.toggle_hotkey.as_ref().filter(|h| !h.is_empty()) {
+                        bindings.push((hotkey.clone(), format!("Toggle '{}'", window.title)));
+                    }
+                }
+            }
+
+            (!bindings.is_empty()).then(|| CheatSheetGroup {
+                workspace: workspace.name.clone(),
+                bindings,
+            })
+        })
+        .collect()
+}
+
+/// Formats a cheat sheet as plain text with aligned columns, for printing or pasting.
+///
+/// # Arguments
+/// - `groups`: The cheat sheet from `cheat_sheet`.
+///
+/// # Returns
+/// - The cheat sheet, one workspace heading followed by its indented hotkeys per group.
+pub fn cheat_sheet_text(groups: &[CheatSheetGroup]) -> String {
+    let width = groups
+        .iter()
+        .flat_map(|group| group.bindings.iter())
+        .map(|(hotkey, _)| hotkey.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::from("Multi Manager Hotkeys\n");
+    if groups.is_empty() {
+        text.push_str("\nNo hotkeys are assigned.\n");
+    }
+    for group in groups {
+        text.push('\n');
+        text.push_str(&group.workspace);
+        text.push('\n');
+        for (hotkey, action) in &group.bindings {
+            text.push_str(&format!(
+                "  {:<width$}  {}\n",
+                hotkey,
+                action,
+                width = width
+            ));
+        }
+    }
+    text
+}
@@ -0,0 +1,188 @@
+//! Reactions to a workspace's windows closing, such as re-tiling the windows that are left or
+//! sending everything home, driven by `EVENT_OBJECT_DESTROY` events. A process exiting closes
+//! its windows, so it is noticed the same way.
+
+use crate::activity::ToggleDirection;
+use crate::validation::PositionKind;
+use crate::workspace::{CloseReaction, Workspace};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetMessageW, PostThreadMessageW, CHILDID_SELF, EVENT_OBJECT_DESTROY, MSG, OBJID_WINDOW,
+    WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS, WM_QUIT,
+};
+
+/// How long to wait after a window closes for others closing with it, e.g. when an app exits,
+/// so the workspace reacts once.
+pub const CLOSE_SETTLE: Duration = Duration::from_millis(500);
+
+/// Where the event hook sends the handles of destroyed windows.
+static CLOSED_SENDER: Mutex<Option<Sender<usize>>> = Mutex::new(None);
+
+/// A running hook for windows being destroyed. Dropping it removes the hook.
+pub struct WindowCloseHook {
+    thread_id: u32,
+}
+
+impl WindowCloseHook {
+    /// Installs the event hook on a thread of its own and starts reporting closed windows.
+    ///
+    /// # Arguments
+    /// - `on_close`: Called on a background thread with the handles of the windows closed
+    ///   together, once they have settled for `CLOSE_SETTLE`.
+    ///
+    /// # Returns
+    /// - The running hook, or `None` if the hook could not be installed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let hook = WindowCloseHook::start(|closed| println!("{} windows closed", closed.len()));
+    /// ```
+    pub fn start(on_close: impl Fn(&[usize]) + Send + 'static) -> Option<WindowCloseHook> {
+        let (sender, receiver) = mpsc::channel();
+        *CLOSED_SENDER.lock().unwrap() = Some(sender);
+
+        thread::Builder::new()
+            .name("Window Close Dispatcher".to_string())
+            .spawn(move || {
+                while let Ok(first) = receiver.recv() {
+                    let mut closed = vec![first];
+                    while let Ok(next) = receiver.recv_timeout(CLOSE_SETTLE) {
+                        closed.push(next);
+                    }
+                    on_close(&closed);
+                }
+            })
+            .ok()?;
+
+        let (started, started_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("Window Close Hook".to_string())
+            .spawn(move || run_hook(started))
+            .ok()?;
+        match started_receiver.recv() {
+            Ok(Some(thread_id)) => {
+                info!("Window close hook installed.");
+                Some(WindowCloseHook { thread_id })
+            }
+            _ => {
+                CLOSED_SENDER.lock().unwrap().take();
+                None
+            }
+        }
+    }
+}
+
+impl Drop for WindowCloseHook {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        // Dropping the sender ends the dispatcher thread
+        CLOSED_SENDER.lock().unwrap().take();
+        info!("Window close hook removed.");
+    }
+}
+
+/// Finds how the workspaces react to windows closing.
+///
+/// A workspace reacts if one of its windows closed, it is enabled, and it was last sent to
+/// its targets; a workspace that is home is left alone.
+///
+/// # Arguments
+/// - `workspaces`: The workspace list.
+/// - `closed`: The handles of the windows that closed.
+///
+/// # Returns
+/// - The index of each reacting workspace and where to move its windows: its targets again
+///   to re-arrange them, or home.
+///
+/// # Example
+/// ```ignore
+/// for (index, position) in close_reactions(&workspaces, &closed) {
+///     let workspace = workspaces[index].runtime_id;
+///     window_ops.send(WindowOp::MoveAll { workspace, position });
+/// }
+/// ```
+pub fn close_reactions(workspaces: &[Workspace], closed: &[usize]) -> Vec<(usize, PositionKind)> {
+    let closed: HashSet<usize> = closed.iter().copied().collect();
+    workspaces
+        .iter()
+        .enumerate()
+        .filter(|(_, workspace)| !workspace.disabled)
+        .filter(|(_, workspace)| workspace.windows.iter().any(|w| closed.contains(&w.id)))
+        .filter(|(_, workspace)| {
+            workspace
+                .activity
+                .newest_first()
+                .map(|entry| entry.direction)
+                .find(|direction| *direction != ToggleDirection::BringToFront)
+                == Some(ToggleDirection::ToTarget)
+        })
+        .filter_map(|(index, workspace)| match workspace.on_window_close {
+            CloseReaction::Nothing => None,
+            CloseReaction::Rearrange if workspace.auto_arrange.is_none() => {
+                warn!(
+                    "Workspace '{}' re-arranges when a window closes but does not auto-arrange; nothing to do.",
+                    workspace.name
+                );
+                None
+            }
+            CloseReaction::Rearrange => Some((index, PositionKind::Target)),
+            CloseReaction::AllHome => Some((index, PositionKind::Home)),
+        })
+        .collect()
+}
+
+/// Installs the hook and runs the message loop the hook needs until `WM_QUIT` is posted.
+fn run_hook(started: Sender<Option<u32>>) {
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_OBJECT_DESTROY,
+            EVENT_OBJECT_DESTROY,
+            None,
+            Some(destroy_proc),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    if hook.is_invalid() {
+        warn!("Failed to install the window close hook.");
+        let _ = started.send(None);
+        return;
+    }
+    let _ = started.send(Some(unsafe { GetCurrentThreadId() }));
+
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {}
+
+    unsafe {
+        let _ = UnhookWinEvent(hook);
+    }
+}
+
+/// The event hook: passes on the handles of destroyed windows, ignoring their parts.
+unsafe extern "system" fn destroy_proc(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    object: i32,
+    child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    if object != OBJID_WINDOW.0 || child != CHILDID_SELF as i32 || hwnd.is_invalid() {
+        return;
+    }
+    if let Some(sender) = CLOSED_SENDER.lock().unwrap().as_ref() {
+        let _ = sender.send(hwnd.0 as usize);
+    }
+}
@@ -0,0 +1,135 @@
+//! Game controller button combinations as workspace triggers, read with XInput, for couch and
+//! HTPC setups where a keyboard isn't handy.
+
+use std::time::{Duration, Instant};
+use windows::Win32::UI::Input::XboxController::{
+    XInputGetState, XINPUT_GAMEPAD_A, XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_BACK,
+    XINPUT_GAMEPAD_DPAD_DOWN, XINPUT_GAMEPAD_DPAD_LEFT, XINPUT_GAMEPAD_DPAD_RIGHT,
+    XINPUT_GAMEPAD_DPAD_UP, XINPUT_GAMEPAD_LEFT_SHOULDER, XINPUT_GAMEPAD_LEFT_THUMB,
+    XINPUT_GAMEPAD_RIGHT_SHOULDER, XINPUT_GAMEPAD_RIGHT_THUMB, XINPUT_GAMEPAD_START,
+    XINPUT_GAMEPAD_TRIGGER_THRESHOLD, XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XINPUT_STATE,
+    XUSER_MAX_COUNT,
+};
+
+/// The bit used for the left trigger, which XInput reports as an analog value.
+const LEFT_TRIGGER: u32 = 1 << 16;
+
+/// The bit used for the right trigger.
+const RIGHT_TRIGGER: u32 = 1 << 17;
+
+/// How often controller slots that were empty are checked again. XInput is slow to query
+/// for controllers that are not connected, so they are not checked on every poll.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The button names accepted in a combination, with their bits.
+pub const CONTROLLER_BUTTONS: [(&str, u32); 16] = [
+    ("A", XINPUT_GAMEPAD_A.0 as u32),
+    ("B", XINPUT_GAMEPAD_B.0 as u32),
+    ("X", XINPUT_GAMEPAD_X.0 as u32),
+    ("Y", XINPUT_GAMEPAD_Y.0 as u32),
+    ("LB", XINPUT_GAMEPAD_LEFT_SHOULDER.0 as u32),
+    ("RB", XINPUT_GAMEPAD_RIGHT_SHOULDER.0 as u32),
+    ("LT", LEFT_TRIGGER),
+    ("RT", RIGHT_TRIGGER),
+    ("Back", XINPUT_GAMEPAD_BACK.0 as u32),
+    ("Start", XINPUT_GAMEPAD_START.0 as u32),
+    ("LS", XINPUT_GAMEPAD_LEFT_THUMB.0 as u32),
+    ("RS", XINPUT_GAMEPAD_RIGHT_THUMB.0 as u32),
+    ("Up", XINPUT_GAMEPAD_DPAD_UP.0 as u32),
+    ("Down", XINPUT_GAMEPAD_DPAD_DOWN.0 as u32),
+    ("Left", XINPUT_GAMEPAD_DPAD_LEFT.0 as u32),
+    ("Right", XINPUT_GAMEPAD_DPAD_RIGHT.0 as u32),
+];
+
+/// Parses a controller button combination, such as "LB+RB+A".
+///
+/// Button names are matched case-insensitively; see `CONTROLLER_BUTTONS`.
+///
+/// # Arguments
+/// - `combo`: The button names joined by `+`.
+///
+/// # Returns
+/// - The buttons as a bit mask, or `None` if a name is not a button.
+///
+/// # Example
+/// ```
+/// # use multi_manager::controller::parse_controller_combo;
+/// assert!(parse_controller_combo("LB+RB+A").is_some());
+/// assert!(parse_controller_combo("LB+Q").is_none());
+/// ```
+pub fn parse_controller_combo(combo: &str) -> Option<u32> {
+    combo.split('+').try_fold(0, |mask, name| {
+        CONTROLLER_BUTTONS
+            .iter()
+            .find(|(button, _)| button.eq_ignore_ascii_case(name.trim()))
+            .map(|&(_, bit)| mask | bit)
+    })
+}
+
+/// Reads the buttons held on up to four XInput controllers, remembering the previous poll so
+/// a combination triggers once when it is pressed, not for as long as it is held.
+#[derive(Debug, Default)]
+pub struct ControllerPoller {
+    held: [u32; XUSER_MAX_COUNT as usize],
+    previous: [u32; XUSER_MAX_COUNT as usize],
+    connected: [bool; XUSER_MAX_COUNT as usize],
+    probed_at: Option<Instant>,
+}
+
+impl ControllerPoller {
+    /// Creates a poller that has not read any controller yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the buttons held on each connected controller.
+    pub fn poll(&mut self) {
+        let probe = self
+            .probed_at
+            .is_none_or(|at| at.elapsed() >= RECONNECT_INTERVAL);
+        if probe {
+            self.probed_at = Some(Instant::now());
+        }
+
+        for index in 0..XUSER_MAX_COUNT as usize {
+            self.previous[index] = self.held[index];
+            if !self.connected[index] && !probe {
+                continue;
+            }
+
+            let mut state = XINPUT_STATE::default();
+            self.connected[index] = unsafe { XInputGetState(index as u32, &mut state) } == 0;
+            self.held[index] = if self.connected[index] {
+                let gamepad = state.Gamepad;
+                let threshold = XINPUT_GAMEPAD_TRIGGER_THRESHOLD.0 as u8;
+                let mut held = gamepad.wButtons.0 as u32;
+                if gamepad.bLeftTrigger > threshold {
+                    held |= LEFT_TRIGGER;
+                }
+                if gamepad.bRightTrigger > threshold {
+                    held |= RIGHT_TRIGGER;
+                }
+                held
+            } else {
+                0
+            };
+        }
+    }
+
+    /// Checks whether a combination was completed on any controller by the last poll.
+    ///
+    /// # Arguments
+    /// - `combo`: The buttons, from `parse_controller_combo`.
+    ///
+    /// # Returns
+    /// - `true` if every button of the combination is held now and they weren't all held at
+    ///   the previous poll.
+    pub fn just_pressed(&self, combo: u32) -> bool {
+        combo != 0
+            && self
+                .held
+                .iter()
+                .zip(&self.previous)
+                .any(|(&held, &previous)| held & combo == combo && previous & combo != combo)
+    }
+}
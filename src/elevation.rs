@@ -0,0 +1,123 @@
+//! Relaunching the application as administrator, so windows of elevated programs can be
+//! moved. Windows doesn't let a normal process move or resize windows of an elevated one.
+//!
+//! The workspaces in memory, including unsaved edits, are handed to the elevated instance in
+//! a temporary file named on its command line, which it loads in place of the workspace file
+//! and then deletes.
+
+use crate::error::MultiManagerError;
+use crate::paths::PORTABLE_FLAG;
+use crate::workspace::{save_workspaces, Workspace};
+use log::{info, warn};
+use std::env;
+use std::ffi::c_void;
+use std::fs;
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+/// The command-line flag followed by the handoff file and the workspace file it was saved
+/// from, passed to the elevated instance.
+pub const HANDOFF_FLAG: &str = "--handoff";
+
+/// The name of the handoff file in the temporary folder.
+const HANDOFF_FILE: &str = "multi-manager-handoff.json";
+
+/// Checks whether this process runs with administrator rights.
+///
+/// # Returns
+/// - `true` if the process token is elevated; `false` if not or if it can't be queried.
+pub fn is_elevated() -> bool {
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut size = 0;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut c_void),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        let _ = CloseHandle(token);
+        queried.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Starts an elevated instance of the application that takes over the given workspaces.
+///
+/// Windows asks for consent first. The caller should stop handling hotkeys and exit once
+/// this succeeds, so the new instance can register them.
+///
+/// # Arguments
+/// - `workspaces`: The workspaces to hand over, including unsaved edits.
+/// - `workspaces_path`: The workspace file the new instance saves to.
+///
+/// # Returns
+/// - `Ok(())` if the elevated instance was started.
+/// - `Err(Relaunch)` if the handoff file could not be written or the relaunch was refused,
+///   e.g. when consent was not given.
+///
+/// # Example
+/// ```ignore
+/// if relaunch_elevated(&workspaces, "workspaces.json").is_ok() {
+///     std::process::exit(0);
+/// }
+/// ```
+pub fn relaunch_elevated(
+    workspaces: &[Workspace],
+    workspaces_path: &str,
+) -> Result<(), MultiManagerError> {
+    let relaunch_error = |message: String| MultiManagerError::Relaunch { message };
+
+    let exe = env::current_exe().map_err(|e| relaunch_error(e.to_string()))?;
+    let handoff = env::temp_dir().join(HANDOFF_FILE);
+    let handoff = handoff.to_string_lossy();
+    save_workspaces(workspaces, &handoff).map_err(|e| relaunch_error(e.to_string()))?;
+
+    let mut parameters = format!("{} \"{}\" \"{}\"", HANDOFF_FLAG, handoff, workspaces_path);
+    if env::args().any(|arg| arg == PORTABLE_FLAG) {
+        parameters = format!("{} {}", PORTABLE_FLAG, parameters);
+    }
+
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            w!("runas"),
+            &HSTRING::from(exe.as_os_str()),
+            &HSTRING::from(parameters),
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    // Values up to 32 are error codes
+    if result.0 as usize <= 32 {
+        let _ = fs::remove_file(&*handoff);
+        return Err(relaunch_error(format!(
+            "Windows refused to start it elevated (code {})",
+            result.0 as usize
+        )));
+    }
+
+    info!(
+        "Relaunched as administrator; handing over {} workspace(s).",
+        workspaces.len()
+    );
+    Ok(())
+}
+
+/// Deletes a handoff file once the elevated instance has loaded it.
+///
+/// # Arguments
+/// - `path`: The handoff file named on the command line.
+pub fn remove_handoff(path: &str) {
+    if let Err(e) = fs::remove_file(path) {
+        warn!("Failed to delete the handoff file '{}': {}", path, e);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::error::MultiManagerError;
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use windows::core::{HSTRING, PWSTR};
+use windows::Win32::Foundation::ERROR_NOT_FOUND;
+use windows::Win32::Security::Credentials::*;
+use windows::Win32::Security::Cryptography::*;
+
+/// The first bytes of an encrypted workspaces file. Plain files start with `[`.
+const ENCRYPTED_HEADER: &[u8] = b"MMENC1\n";
+
+/// The Credential Manager entry holding the encryption key.
+const CREDENTIAL_TARGET: &str = "multi-manager/workspaces-key";
+
+/// The length of the AES-256 key.
+const KEY_LEN: usize = 32;
+
+/// The length of the AES-GCM nonce stored after the header.
+const NONCE_LEN: usize = 12;
+
+/// The length of the AES-GCM authentication tag stored after the nonce.
+const TAG_LEN: usize = 16;
+
+/// Whether workspace files are encrypted when saved. Reading detects encryption on its own.
+static ENCRYPT_ON_SAVE: AtomicBool = AtomicBool::new(false);
+
+/// Turns encryption of saved workspace files on or off.
+///
+/// # Arguments
+/// - `enabled`: Whether subsequent saves are encrypted.
+pub fn set_encryption_enabled(enabled: bool) {
+    ENCRYPT_ON_SAVE.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether saved workspace files are encrypted.
+pub fn encryption_enabled() -> bool {
+    ENCRYPT_ON_SAVE.load(Ordering::SeqCst)
+}
+
+/// Checks whether file contents were written by `encrypt`.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_HEADER)
+}
+
+/// Encrypts data with AES-256-GCM using the key held in Windows Credential Manager.
+///
+/// The key is created and stored under the current user on first use.
+///
+/// # Arguments
+/// - `plaintext`: The data to encrypt.
+///
+/// # Returns
+/// - The header, nonce, authentication tag, and ciphertext.
+/// - `Err(Encryption)` if the key or the cipher is unavailable.
+///
+/// # Example
+/// ```ignore
+/// let data = encrypt(json.as_bytes())?;
+/// assert_eq!(decrypt(&data)?, json.as_bytes());
+/// ```
+pub fn encrypt(plaintext: &[u8]) -> Result<Vec<u8>, MultiManagerError> {
+    let key = load_or_create_key()?;
+    let mut nonce = [0u8; NONCE_LEN];
+    random_bytes(&mut nonce)?;
+
+    let mut tag = [0u8; TAG_LEN];
+    let ciphertext =
+        aes_gcm(&key, &nonce, plaintext, &mut tag, true).map_err(|e| encryption_error(&e))?;
+
+    let mut data =
+        Vec::with_capacity(ENCRYPTED_HEADER.len() + NONCE_LEN + TAG_LEN + ciphertext.len());
+    data.extend_from_slice(ENCRYPTED_HEADER);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&tag);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypts data produced by `encrypt`.
+///
+/// # Arguments
+/// - `data`: The encrypted file contents, including the header.
+///
+/// # Returns
+/// - The original plaintext.
+/// - `Err(Encryption)` if the data is truncated, was tampered with, or was encrypted with a
+///   key other than the one in this user's Credential Manager.
+pub fn decrypt(data: &[u8]) -> Result<Vec<u8>, MultiManagerError> {
+    let body = data
+        .strip_prefix(ENCRYPTED_HEADER)
+        .filter(|body| body.len() >= NONCE_LEN + TAG_LEN)
+        .ok_or_else(|| MultiManagerError::Encryption {
+            message: "the encrypted file is truncated".to_string(),
+        })?;
+    let (nonce, rest) = body.split_at(NONCE_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+
+    let key = load_or_create_key()?;
+    let mut tag: [u8; TAG_LEN] = tag.try_into().unwrap_or_default();
+    aes_gcm(&key, nonce, ciphertext, &mut tag, false).map_err(|e| MultiManagerError::Encryption {
+        message: format!(
+            "{} (the file may have been encrypted on another machine or account)",
+            e.message()
+        ),
+    })
+}
+
+/// Converts a failed Win32 call into an encryption error.
+fn encryption_error(error: &windows::core::Error) -> MultiManagerError {
+    MultiManagerError::Encryption {
+        message: error.message(),
+    }
+}
+
+/// Fills a buffer from the system's cryptographic random number generator.
+fn random_bytes(buffer: &mut [u8]) -> Result<(), MultiManagerError> {
+    unsafe {
+        BCryptGenRandom(None, buffer, BCRYPT_USE_SYSTEM_PREFERRED_RNG)
+            .ok()
+            .map_err(|e| encryption_error(&e))
+    }
+}
+
+/// Reads the encryption key from Credential Manager, creating it if there is none.
+fn load_or_create_key() -> Result<Vec<u8>, MultiManagerError> {
+    let target = HSTRING::from(CREDENTIAL_TARGET);
+
+    unsafe {
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        match CredReadW(&target, CRED_TYPE_GENERIC, 0, &mut credential) {
+            Ok(()) => {
+                let key = std::slice::from_raw_parts(
+                    (*credential).CredentialBlob,
+                    (*credential).CredentialBlobSize as usize,
+                )
+                .to_vec();
+                CredFree(credential as *const core::ffi::c_void);
+
+                if key.len() == KEY_LEN {
+                    Ok(key)
+                } else {
+                    Err(MultiManagerError::Encryption {
+                        message: format!(
+                            "the key stored as '{}' in Credential Manager is invalid",
+                            CREDENTIAL_TARGET
+                        ),
+                    })
+                }
+            }
+            Err(e) if e.code() == ERROR_NOT_FOUND.to_hresult() => {
+                let mut key = vec![0u8; KEY_LEN];
+                random_bytes(&mut key)?;
+
+                let mut target_name: Vec<u16> =
+                    CREDENTIAL_TARGET.encode_utf16().chain(Some(0)).collect();
+                let mut user_name: Vec<u16> =
+                    "multi-manager".encode_utf16().chain(Some(0)).collect();
+                let credential = CREDENTIALW {
+                    Type: CRED_TYPE_GENERIC,
+                    TargetName: PWSTR(target_name.as_mut_ptr()),
+                    CredentialBlobSize: KEY_LEN as u32,
+                    CredentialBlob: key.as_mut_ptr(),
+                    Persist: CRED_PERSIST_LOCAL_MACHINE,
+                    UserName: PWSTR(user_name.as_mut_ptr()),
+                    ..Default::default()
+                };
+                CredWriteW(&credential, 0).map_err(|e| encryption_error(&e))?;
+                info!("Created a workspace encryption key in Credential Manager.");
+                Ok(key)
+            }
+            Err(e) => Err(encryption_error(&e)),
+        }
+    }
+}
+
+/// A BCrypt algorithm provider that is closed when dropped.
+struct Algorithm(BCRYPT_ALG_HANDLE);
+
+impl Drop for Algorithm {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = BCryptCloseAlgorithmProvider(self.0, 0);
+        }
+    }
+}
+
+/// A BCrypt key that is destroyed when dropped.
+struct Key(BCRYPT_KEY_HANDLE);
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = BCryptDestroyKey(self.0);
+        }
+    }
+}
+
+/// Encrypts or decrypts with AES-GCM.
+///
+/// When encrypting, the authentication tag is written to `tag`; when decrypting, it is
+/// verified against `tag`.
+fn aes_gcm(
+    key: &[u8],
+    nonce: &[u8],
+    input: &[u8],
+    tag: &mut [u8; TAG_LEN],
+    encrypt: bool,
+) -> windows::core::Result<Vec<u8>> {
+    unsafe {
+        let mut algorithm = Algorithm(BCRYPT_ALG_HANDLE::default());
+        BCryptOpenAlgorithmProvider(
+            &mut algorithm.0,
+            BCRYPT_AES_ALGORITHM,
+            None,
+            BCRYPT_OPEN_ALGORITHM_PROVIDER_FLAGS(0),
+        )
+        .ok()?;
+
+        let mode: Vec<u8> = BCRYPT_CHAIN_MODE_GCM
+            .as_wide()
+            .iter()
+            .chain(Some(&0))
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        BCryptSetProperty(algorithm.0.into(), BCRYPT_CHAINING_MODE, &mode, 0).ok()?;
+
+        let mut key_handle = Key(BCRYPT_KEY_HANDLE::default());
+        BCryptGenerateSymmetricKey(algorithm.0, &mut key_handle.0, None, key, 0).ok()?;
+
+        let info = BCRYPT_AUTHENTICATED_CIPHER_MODE_INFO {
+            cbSize: std::mem::size_of::<BCRYPT_AUTHENTICATED_CIPHER_MODE_INFO>() as u32,
+            dwInfoVersion: BCRYPT_AUTHENTICATED_CIPHER_MODE_INFO_VERSION,
+            pbNonce: nonce.as_ptr() as *mut u8,
+            cbNonce: nonce.len() as u32,
+            pbTag: tag.as_mut_ptr(),
+            cbTag: TAG_LEN as u32,
+            ..Default::default()
+        };
+        let padding_info = Some(&info as *const _ as *const core::ffi::c_void);
+
+        // GCM is a stream mode, so the output is exactly as long as the input
+        let mut output = vec![0u8; input.len()];
+        let mut written = 0u32;
+        if encrypt {
+            BCryptEncrypt(
+                key_handle.0,
+                Some(input),
+                padding_info,
+                None,
+                Some(&mut output),
+                &mut written,
+                BCRYPT_FLAGS(0),
+            )
+            .ok()?;
+        } else {
+            BCryptDecrypt(
+                key_handle.0,
+                Some(input),
+                padding_info,
+                None,
+                Some(&mut output),
+                &mut written,
+                BCRYPT_FLAGS(0),
+            )
+            .ok()?;
+        }
+        output.truncate(written as usize);
+        Ok(output)
+    }
+}
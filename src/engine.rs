@@ -0,0 +1,1531 @@
+use crate::activity::{ActivityEntry, ToggleDirection, WindowOutcome};
+use crate::animation::{Animation, FRAME_INTERVAL};
+use crate::backend::{ShowCommand, WindowBackend};
+use crate::error::MultiManagerError;
+use crate::hooks::{run_hook, PRE_COMMAND_TIMEOUT};
+use crate::layout::{arrange, clamp_to_work_area};
+use crate::validation::PositionKind;
+use crate::window_browser::{is_capturable_window, is_shell_window};
+use crate::workspace::{
+    ExclusiveAction, FocusBehavior, HidePosition, InstanceOrder, MatchRule, OtherDesktopAction,
+    PositionFlags, SoloState, TaskbarAutoHide, Window, WindowAction, Workspace, WorkspaceMode,
+};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+use windows::core::Result;
+
+/// How long to wait after changing the taskbar's auto-hide setting for the work areas to follow.
+const TASKBAR_SETTLE: Duration = Duration::from_millis(200);
+
+/// Checks if all valid windows in a workspace are at their home positions.
+///
+/// Home positions come from the window's home grid cell, or else the monitor profile matching
+/// the connected displays, if any.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `workspace`: The workspace whose windows are being checked.
+///
+/// # Returns
+/// - `true` if all valid windows are at their home positions.
+/// - `false` otherwise.
+pub fn are_all_windows_at_home(backend: &impl WindowBackend, workspace: &Workspace) -> bool {
+    let profile = workspace.active_profile(&backend.monitors());
+    let work_areas = backend.work_areas();
+    workspace
+        .windows
+        .iter()
+        .filter(|w| w.valid)
+        .all(|w| is_window_home(backend, workspace, w, profile, &work_areas))
+}
+
+/// Checks if one window of a workspace is at its home position, as `are_all_windows_at_home`
+/// does for every window, e.g. to toggle just that window.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `workspace`: The workspace the window belongs to.
+/// - `index`: The index of the window in the workspace.
+///
+/// # Returns
+/// - `true` if the window is at home, or doesn't exist.
+pub fn is_window_at_home(
+    backend: &impl WindowBackend,
+    workspace: &Workspace,
+    index: usize,
+) -> bool {
+    let profile = workspace.active_profile(&backend.monitors());
+    let work_areas = backend.work_areas();
+    workspace
+        .windows
+        .get(index)
+        .is_none_or(|w| is_window_home(backend, workspace, w, profile, &work_areas))
+}
+
+/// Checks if a window is at its home position under a monitor profile.
+fn is_window_home(
+    backend: &impl WindowBackend,
+    workspace: &Workspace,
+    w: &Window,
+    profile: Option<&str>,
+    work_areas: &[(i32, i32, i32, i32)],
+) -> bool {
+    // A window closed by its target action doesn't count against being home
+    if !backend.exists(w.id) {
+        return w.target_action == WindowAction::Close;
+    }
+    // Neither does a window the toggle leaves on another virtual desktop
+    if workspace.other_desktop == OtherDesktopAction::Skip
+        && !backend.is_on_current_virtual_desktop(w.id)
+    {
+        return true;
+    }
+    let mut home = w.home_at(profile, work_areas);
+    if w.clamp_to_work_area {
+        home = clamp_to_work_area(home, work_areas);
+    }
+    match w.hide_at {
+        HidePosition::Home => !backend.is_visible(w.id),
+        HidePosition::Target => {
+            backend.is_visible(w.id)
+                && is_window_at_position(backend, w.id, home, &w.position_flags)
+        }
+        HidePosition::Never => is_window_at_position(backend, w.id, home, &w.position_flags),
+    }
+}
+
+/// Toggles workspace windows between their home and target locations.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace to toggle windows for.
+///
+/// - If all windows are at their home positions, they are moved to their target positions.
+/// - If any window is not at its home or target position, it is moved to its home position.
+/// - Workspaces in `BringToFront` mode only restore and raise their windows.
+/// - Workspaces in `Solo` mode turn solo mode on or off; see `toggle_solo`.
+/// - The windows are moved by `move_workspace_windows`.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::toggle_workspace_windows;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// toggle_workspace_windows(&Win32Backend, &mut workspace);
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(workspace = %workspace.name)))]
+pub fn toggle_workspace_windows(backend: &impl WindowBackend, workspace: &mut Workspace) {
+    if workspace.mode == WorkspaceMode::BringToFront {
+        run_pre_command(workspace, ToggleDirection::BringToFront);
+        if workspace.suppressed_windows.is_empty() {
+            suppress_other_windows(backend, workspace);
+        } else {
+            restore_suppressed_windows(backend, workspace);
+        }
+        let entry = bring_workspace_to_front(backend, workspace);
+        workspace.activity.record(entry);
+        run_post_command(workspace, ToggleDirection::BringToFront);
+        return;
+    }
+    if workspace.mode == WorkspaceMode::Solo {
+        run_pre_command(workspace, ToggleDirection::BringToFront);
+        let entry = toggle_solo(backend, workspace);
+        workspace.activity.record(entry);
+        run_post_command(workspace, ToggleDirection::BringToFront);
+        return;
+    }
+
+    let all_at_home = are_all_windows_at_home(backend, workspace);
+    info!("DEBUG all_at_home {}", all_at_home);
+
+    let position = if all_at_home {
+        PositionKind::Target
+    } else {
+        PositionKind::Home
+    };
+    move_workspace_windows(backend, workspace, position);
+}
+
+/// Moves every window of a workspace to its home or target position, wherever the windows
+/// are now.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace whose windows to move.
+/// - `position`: Whether to send the windows home or to their targets.
+///
+/// - Failed moves are retried according to the workspace's retry settings; windows that still
+///   fail have the error recorded in `move_error` so it can be shown in the GUI.
+/// - Each window's `settle_ms` is waited out after it moves, and the workspace's `move_delay_ms`
+///   is inserted between successive windows.
+/// - Focus is applied afterwards according to the workspace's `focus_behavior`. When going
+///   home, the workspace window that has focus is remembered in `last_focused`.
+/// - Windows whose destination is their hidden pseudo-position are hidden instead of moved,
+///   and shown again when toggled back.
+/// - Windows whose target action is `Minimize` or `Close` are minimized or closed instead of
+///   moved to their targets.
+/// - In exclusive mode, other visible windows are minimized or hidden when the workspace moves
+///   to its target positions and restored when it moves back home.
+/// - The direction and each window's outcome are recorded in the workspace's activity log.
+/// - If a monitor profile matches the connected displays, its positions are used instead of
+///   the windows' default home and target.
+/// - Windows with grid cells have their positions computed from them on the current monitors.
+/// - The workspace's pre-toggle command runs first and its post-toggle command after; see
+///   `run_hook`.
+/// - If the workspace sets the taskbar's auto-hide, it does so before the targets are
+///   computed, and the previous setting is restored when it goes home.
+/// - If the workspace has its own virtual desktop, it is switched to and the windows are
+///   brought along; see `switch_workspace_desktop`.
+/// - Windows that clamp to the work area have their positions fitted inside it.
+/// - If the workspace auto-arranges, targets are computed by its layout algorithm over the
+///   windows that exist now; see `auto_arranged_targets`.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::move_workspace_windows;
+/// # use multi_manager::validation::PositionKind;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// move_workspace_windows(&Win32Backend, &mut workspace, PositionKind::Home);
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(workspace = %workspace.name, ?position)))]
+pub fn move_workspace_windows(
+    backend: &impl WindowBackend,
+    workspace: &mut Workspace,
+    position: PositionKind,
+) {
+    let to_target = position == PositionKind::Target;
+    let direction = if to_target {
+        ToggleDirection::ToTarget
+    } else {
+        ToggleDirection::ToHome
+    };
+    run_pre_command(workspace, direction);
+    if to_target {
+        suppress_other_windows(backend, workspace);
+    } else {
+        restore_suppressed_windows(backend, workspace);
+    }
+    let follow_desktop = switch_workspace_desktop(backend, workspace, to_target);
+    if !follow_desktop {
+        switch_to_windows_desktop(backend, workspace);
+    }
+    apply_taskbar_auto_hide(backend, workspace, to_target);
+
+    let retries = workspace.move_retries;
+    let backoff_ms = workspace.retry_backoff_ms;
+    let move_delay_ms = workspace.move_delay_ms;
+    let focus_behavior = workspace.focus_behavior;
+    let match_rule = workspace.match_rule;
+    let other_desktop = workspace.other_desktop;
+    let animation = workspace.animation.resolve();
+    let profile = workspace
+        .active_profile(&backend.monitors())
+        .map(str::to_string);
+    if let Some(name) = &profile {
+        info!("Using monitor profile '{}'.", name);
+    }
+    let work_areas = backend.work_areas();
+    let arranged = if to_target {
+        auto_arranged_targets(backend, workspace)
+    } else {
+        Vec::new()
+    };
+    let previous_foreground = backend.foreground();
+    if !to_target {
+        remember_focused_window(workspace, previous_foreground);
+    }
+    let mut moved_any = false;
+    let mut entry = ActivityEntry::new(direction);
+    let mut claimed = live_window_ids(backend, workspace);
+
+    for (index, window) in workspace.windows.iter_mut().enumerate() {
+        // Check if the window is valid, re-resolving stale handles by exe/class/title
+        if !backend.exists(window.id) {
+            match resolve_window(backend, window, match_rule, &claimed) {
+                WindowResolution::Found(found) => {
+                    info!(
+                        "Re-resolved window '{}' to HWND {:?}.",
+                        window.capture_title, found
+                    );
+                    claimed.insert(found);
+                    window.id = found;
+                    window.valid = true;
+                    window.move_error = None;
+                }
+                WindowResolution::Ambiguous(candidates) => {
+                    warn!(
+                        "{} windows match '{}'; waiting for the user to choose.",
+                        candidates.len(),
+                        window.capture_title
+                    );
+                    entry.push(&window.title, WindowOutcome::Ambiguous(candidates.len()));
+                    window.candidates = candidates;
+                    continue;
+                }
+                WindowResolution::NotFound if window.target_action == WindowAction::Close => {
+                    info!("Window '{}' is closed; skipping it.", window.title);
+                    entry.push(&window.title, WindowOutcome::Closed);
+                    continue;
+                }
+                WindowResolution::NotFound => {
+                    warn!("Skipping invalid window '{}'.", window.title);
+                    window.move_error = Some(MultiManagerError::WindowNotFound {
+                        workspace: workspace.name.clone(),
+                        window: window.title.clone(),
+                    });
+                    entry.push(&window.title, WindowOutcome::NotFound);
+                    continue;
+                }
+            }
+        }
+        let id = window.id;
+
+        // Stagger successive windows
+        if moved_any && move_delay_ms > 0 {
+            thread::sleep(Duration::from_millis(move_delay_ms));
+        }
+        moved_any = true;
+
+        if to_target && window.target_action == WindowAction::Minimize {
+            backend.show(id, ShowCommand::MinimizeNoActivate);
+            info!("Minimized window '{}'.", window.title);
+            entry.push(&window.title, WindowOutcome::Minimized);
+            window.move_error = None;
+            continue;
+        }
+        if to_target && window.target_action == WindowAction::Close {
+            match backend.close(id) {
+                Ok(()) => {
+                    info!("Asked window '{}' to close.", window.title);
+                    entry.push(&window.title, WindowOutcome::Closed);
+                    window.move_error = None;
+                }
+                Err(e) => {
+                    warn!("Failed to close window '{}': {}", window.title, e);
+                    let error =
+                        MultiManagerError::win32("Close", &workspace.name, &window.title, &e);
+                    entry.push(&window.title, WindowOutcome::Failed(error.clone()));
+                    window.move_error = Some(error);
+                }
+            }
+            continue;
+        }
+
+        if follow_desktop {
+            if !backend.move_to_current_virtual_desktop(id) {
+                warn!(
+                    "Could not bring window '{}' to the current virtual desktop.",
+                    window.title
+                );
+            }
+        } else if !bring_to_current_desktop(backend, other_desktop, id, &window.title) {
+            entry.push(&window.title, WindowOutcome::OtherDesktop);
+            continue;
+        }
+
+        let hide_now = match window.hide_at {
+            HidePosition::Home => !to_target,
+            HidePosition::Target => to_target,
+            HidePosition::Never => false,
+        };
+
+        if hide_now {
+            backend.show(id, ShowCommand::Hide);
+            window.hidden = true;
+            info!("Hid window '{}'.", window.title);
+            entry.push(&window.title, WindowOutcome::Hidden);
+            continue;
+        }
+
+        let was_hidden =
+            window.hidden || (window.hide_at != HidePosition::Never && !backend.is_visible(id));
+        if was_hidden {
+            backend.show(id, ShowCommand::ShowNoActivate);
+            window.hidden = false;
+            info!("Showed hidden window '{}'.", window.title);
+        }
+
+        if backend.is_minimized(id) {
+            if !backend.show(id, ShowCommand::Restore) {
+                warn!("Failed to restore minimized window '{}'.", window.title);
+            } else {
+                info!("Restored minimized window '{}'.", window.title);
+            }
+        }
+
+        let target_position = if to_target {
+            arranged
+                .get(index)
+                .copied()
+                .flatten()
+                .unwrap_or_else(|| window.target_at(profile.as_deref(), &work_areas))
+        } else {
+            window.home_at(profile.as_deref(), &work_areas)
+        };
+        let target_position = if window.clamp_to_work_area {
+            clamp_to_work_area(target_position, &work_areas)
+        } else {
+            target_position
+        };
+
+        animate_window(
+            backend,
+            id,
+            target_position,
+            &window.position_flags,
+            animation,
+        );
+
+        // Move the window
+        if let Err(e) = move_window_with_retry(
+            backend,
+            id,
+            target_position,
+            &window.position_flags,
+            retries,
+            backoff_ms,
+        ) {
+            warn!("Failed to move window '{}': {}", window.title, e);
+            let error = MultiManagerError::win32("Move", &workspace.name, &window.title, &e);
+            entry.push(&window.title, WindowOutcome::Failed(error.clone()));
+            window.move_error = Some(error);
+        } else if let Some(actual) =
+            enforce_window_rect(backend, id, target_position, &window.position_flags)
+        {
+            warn!(
+                "Window '{}' could not achieve requested rect {:?}; it is at {:?}.",
+                window.title, target_position, actual
+            );
+            entry.push(
+                &window.title,
+                WindowOutcome::Resisted {
+                    requested: target_position,
+                    actual,
+                },
+            );
+            window.move_error = None;
+        } else {
+            info!(
+                "Moved window '{}' to position: {:?}",
+                window.title, target_position
+            );
+            entry.push(&window.title, WindowOutcome::Moved(target_position));
+            window.move_error = None;
+        }
+
+        if window.settle_ms > 0 {
+            thread::sleep(Duration::from_millis(window.settle_ms));
+        }
+
+        // Activate the window
+        if focus_behavior == FocusBehavior::ActivateAll {
+            activate_window(backend, id, &window.title);
+        }
+    }
+
+    apply_focus_behavior(backend, workspace, previous_foreground, to_target);
+    workspace.activity.record(entry);
+    run_post_command(workspace, direction);
+}
+
+/// Switches to the virtual desktop of a workspace's windows if the workspace is set to, so
+/// they are toggled where they are rather than pulled away. The desktop of the first window
+/// that is on another desktop is used.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace being toggled.
+fn switch_to_windows_desktop(backend: &impl WindowBackend, workspace: &Workspace) {
+    if workspace.other_desktop != OtherDesktopAction::SwitchDesktop {
+        return;
+    }
+    let desktop = workspace
+        .windows
+        .iter()
+        .filter(|window| backend.exists(window.id))
+        .find(|window| !backend.is_on_current_virtual_desktop(window.id))
+        .and_then(|window| backend.window_virtual_desktop(window.id));
+    if let Some(desktop) = desktop {
+        info!(
+            "Switching to virtual desktop {} where the windows of '{}' are.",
+            desktop + 1,
+            workspace.name
+        );
+        if !backend.switch_virtual_desktop(desktop) {
+            warn!("Could not switch to virtual desktop {}.", desktop + 1);
+        }
+    }
+}
+
+/// Deals with a window on another virtual desktop as the workspace is set to, so it is
+/// either brought to the current desktop or deliberately left out, rather than failing to
+/// move or focus silently.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `action`: The workspace's setting for windows on other desktops.
+/// - `id`: The window's handle.
+/// - `title`: The window title, used for logging.
+///
+/// # Returns
+/// - `false` if the window is on another desktop and should be skipped.
+fn bring_to_current_desktop(
+    backend: &impl WindowBackend,
+    action: OtherDesktopAction,
+    id: usize,
+    title: &str,
+) -> bool {
+    if backend.is_on_current_virtual_desktop(id) {
+        return true;
+    }
+    if action == OtherDesktopAction::Skip {
+        info!(
+            "Window '{}' is on another virtual desktop; skipping it.",
+            title
+        );
+        return false;
+    }
+
+    if backend.move_to_current_virtual_desktop(id) {
+        info!("Brought window '{}' to the current virtual desktop.", title);
+    } else {
+        warn!(
+            "Could not bring window '{}' to the current virtual desktop.",
+            title
+        );
+    }
+    true
+}
+
+/// Switches to a workspace's own virtual desktop when it goes to its targets, creating the
+/// desktop if there is none by that name yet, or back to the desktop it came from when it
+/// goes home.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace being moved; its `previous_desktop` is updated.
+/// - `to_target`: Whether the workspace is going to its targets.
+///
+/// # Returns
+/// - `true` if the desktop was switched, so the windows should be brought along.
+fn switch_workspace_desktop(
+    backend: &impl WindowBackend,
+    workspace: &mut Workspace,
+    to_target: bool,
+) -> bool {
+    if !to_target {
+        let Some(previous) = workspace.previous_desktop.take() else {
+            return false;
+        };
+        info!(
+            "Returning from the virtual desktop of '{}' to desktop {}.",
+            workspace.name,
+            previous + 1
+        );
+        return backend.switch_virtual_desktop(previous);
+    }
+
+    let Some(name) = workspace
+        .virtual_desktop
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+    else {
+        return false;
+    };
+    let current = backend.current_virtual_desktop();
+    let existing = backend
+        .virtual_desktops()
+        .iter()
+        .position(|desktop| desktop.eq_ignore_ascii_case(&name));
+    let desktop = match existing {
+        Some(index) if Some(index) == current => return true,
+        Some(index) => backend.switch_virtual_desktop(index).then_some(index),
+        None => backend.create_virtual_desktop(&name),
+    };
+
+    match desktop {
+        Some(index) => {
+            info!(
+                "Switched to virtual desktop '{}' ({}) for '{}'.",
+                name,
+                index + 1,
+                workspace.name
+            );
+            if workspace.previous_desktop.is_none() {
+                workspace.previous_desktop = current;
+            }
+            true
+        }
+        None => {
+            warn!(
+                "Could not switch to virtual desktop '{}' for '{}'.",
+                name, workspace.name
+            );
+            false
+        }
+    }
+}
+
+/// Applies a workspace's taskbar auto-hide setting when it goes to its targets, remembering the
+/// previous setting, and restores that when it goes home.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace being moved; its `previous_taskbar_auto_hide` is updated.
+/// - `to_target`: Whether the workspace is going to its targets.
+fn apply_taskbar_auto_hide(
+    backend: &impl WindowBackend,
+    workspace: &mut Workspace,
+    to_target: bool,
+) {
+    let wanted = match (to_target, workspace.taskbar) {
+        (false, _) => workspace.previous_taskbar_auto_hide.take(),
+        (true, TaskbarAutoHide::Unchanged) => None,
+        (true, TaskbarAutoHide::AutoHide) => Some(true),
+        (true, TaskbarAutoHide::AlwaysShow) => Some(false),
+    };
+    let Some(wanted) = wanted else {
+        return;
+    };
+
+    let current = backend.taskbar_auto_hide();
+    if current == wanted {
+        return;
+    }
+    if to_target && workspace.previous_taskbar_auto_hide.is_none() {
+        workspace.previous_taskbar_auto_hide = Some(current);
+    }
+    backend.set_taskbar_auto_hide(wanted);
+    // The work areas are recomputed asynchronously after the taskbar changes
+    thread::sleep(TASKBAR_SETTLE);
+}
+
+/// Runs a workspace's pre-toggle command and waits for it, logging any failure; the windows
+/// are moved regardless.
+fn run_pre_command(workspace: &Workspace, direction: ToggleDirection) {
+    if let Err(e) = run_hook(
+        &workspace.pre_command,
+        &workspace.name,
+        direction,
+        Some(PRE_COMMAND_TIMEOUT),
+    ) {
+        warn!("{}", e);
+    }
+}
+
+/// Starts a workspace's post-toggle command without waiting for it, logging any failure.
+fn run_post_command(workspace: &Workspace, direction: ToggleDirection) {
+    if let Err(e) = run_hook(&workspace.post_command, &workspace.name, direction, None) {
+        warn!("{}", e);
+    }
+}
+
+/// Stores the current position of every window of a workspace as its home or target, in one
+/// action instead of capturing each window separately.
+///
+/// Windows that are invalid, closed, or minimized are skipped and keep their stored position.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `workspace`: The workspace whose positions to overwrite.
+/// - `position`: Whether to overwrite the homes or the targets.
+///
+/// # Returns
+/// - The number of windows whose position was captured.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::capture_workspace_positions;
+/// # use multi_manager::validation::PositionKind;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// let captured = capture_workspace_positions(&Win32Backend, &mut workspace, PositionKind::Home);
+/// println!("Captured {} of {} windows.", captured, workspace.windows.len());
+/// ```
+pub fn capture_workspace_positions(
+    backend: &impl WindowBackend,
+    workspace: &mut Workspace,
+    position: PositionKind,
+) -> usize {
+    let mut captured = 0;
+    for window in workspace.windows.iter_mut().filter(|w| w.valid) {
+        if !backend.exists(window.id) || backend.is_minimized(window.id) {
+            continue;
+        }
+        let Some(rect) = backend.rect(window.id) else {
+            continue;
+        };
+        match position {
+            PositionKind::Home => window.home = rect,
+            PositionKind::Target => window.target = rect,
+        }
+        captured += 1;
+    }
+    info!(
+        "Captured {:?} position of {} window(s) of '{}'.",
+        position, captured, workspace.name
+    );
+    captured
+}
+
+/// What `reset_workspace` did to a workspace.
+///
+/// # Fields
+/// - `recaptured`: How many stale entries were bound to a live window again.
+/// - `removed`: The titles of the entries no window matched, which were removed.
+/// - `restored`: How many windows had their positions restored from the saved workspace.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResetSummary {
+    pub recaptured: usize,
+    pub removed: Vec<String>,
+    pub restored: usize,
+}
+
+/// Resets a workspace that has drifted: re-captures stale handles by the entries' match
+/// criteria, removes entries no window matches any more, and optionally restores the windows'
+/// positions from a saved copy of the workspace.
+///
+/// Entries that match several windows are kept with their candidates for the user to choose.
+/// Saved positions are taken from the saved entry with the same exe, class, and capture title.
+///
+/// # Arguments
+/// - `backend`: The window system to search.
+/// - `workspace`: The workspace to reset.
+/// - `saved`: The workspace as last saved, to restore positions from, if wanted.
+///
+/// # Returns
+/// - A summary of what was changed.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::reset_workspace;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// let summary = reset_workspace(&Win32Backend, &mut workspace, None);
+/// println!("Removed {} window(s).", summary.removed.len());
+/// ```
+pub fn reset_workspace(
+    backend: &impl WindowBackend,
+    workspace: &mut Workspace,
+    saved: Option<&Workspace>,
+) -> ResetSummary {
+    let mut summary = ResetSummary::default();
+    let mut claimed = live_window_ids(backend, workspace);
+
+    for window in &mut workspace.windows {
+        window.move_error = None;
+        if backend.exists(window.id) {
+            window.valid = true;
+            continue;
+        }
+        match resolve_window(backend, window, workspace.match_rule, &claimed) {
+            WindowResolution::Found(found) => {
+                info!(
+                    "Reset re-captured window '{}' as HWND {:?}.",
+                    window.capture_title, found
+                );
+                claimed.insert(found);
+                window.id = found;
+                window.valid = true;
+                window.hidden = false;
+                window.candidates.clear();
+                summary.recaptured += 1;
+            }
+            WindowResolution::Ambiguous(candidates) => {
+                window.valid = true;
+                window.candidates = candidates;
+            }
+            WindowResolution::NotFound => window.valid = false,
+        }
+    }
+
+    summary.removed = workspace
+        .take_windows_where(|w| !w.valid)
+        .into_iter()
+        .map(|w| w.title)
+        .collect();
+
+    if let Some(saved) = saved {
+        for window in &mut workspace.windows {
+            let Some(stored) = saved.windows.iter().find(|stored| {
+                stored.exe.eq_ignore_ascii_case(&window.exe)
+                    && stored.class_name == window.class_name
+                    && stored.capture_title == window.capture_title
+            }) else {
+                continue;
+            };
+            window.home = stored.home;
+            window.target = stored.target;
+            window.profile_positions = stored.profile_positions.clone();
+            window.home_cell = stored.home_cell;
+            window.target_cell = stored.target_cell;
+            summary.restored += 1;
+        }
+    }
+
+    info!(
+        "Reset workspace '{}': {} re-captured, {} removed, {} restored.",
+        workspace.name,
+        summary.recaptured,
+        summary.removed.len(),
+        summary.restored
+    );
+    summary
+}
+
+/// Computes the target of each window of an auto-arranging workspace.
+///
+/// Only windows that exist, or that a stale entry can be matched to, take part, so the
+/// layout has no holes for closed apps. Windows hidden at their target are left out too.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `workspace`: The workspace whose targets to compute.
+///
+/// # Returns
+/// - One entry per window: its computed target, or `None` to use its stored target. Empty if
+///   the workspace doesn't auto-arrange or its monitor is not connected.
+pub fn auto_arranged_targets(
+    backend: &impl WindowBackend,
+    workspace: &Workspace,
+) -> Vec<Option<(i32, i32, i32, i32)>> {
+    let Some(settings) = workspace.auto_arrange else {
+        return Vec::new();
+    };
+    let Some(&area) = backend.work_areas().get(settings.monitor) else {
+        warn!(
+            "Monitor {} of auto-arranged workspace '{}' is not connected; using stored targets.",
+            settings.monitor + 1,
+            workspace.name
+        );
+        return Vec::new();
+    };
+
+    let present: Vec<bool> = workspace
+        .windows
+        .iter()
+        .map(|window| {
+            window.hide_at != HidePosition::Target
+                && (backend.exists(window.id) || !find_matching_windows(backend, window).is_empty())
+        })
+        .collect();
+    let mut rects = arrange(&settings, area, present.iter().filter(|&&p| p).count()).into_iter();
+
+    info!(
+        "Auto-arranging workspace '{}' with {:?} on monitor {}.",
+        workspace.name,
+        settings.algorithm,
+        settings.monitor + 1
+    );
+    present
+        .into_iter()
+        .map(|present| if present { rects.next() } else { None })
+        .collect()
+}
+
+/// Restores and raises a workspace's windows in order without repositioning them.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace whose windows should be brought to the front.
+///
+/// # Returns
+/// - An `ActivityEntry` with the outcome for each window.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::bring_workspace_to_front;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// let entry = bring_workspace_to_front(&Win32Backend, &workspace);
+/// ```
+pub fn bring_workspace_to_front(
+    backend: &impl WindowBackend,
+    workspace: &Workspace,
+) -> ActivityEntry {
+    let previous_foreground = backend.foreground();
+    let mut entry = ActivityEntry::new(ToggleDirection::BringToFront);
+    switch_to_windows_desktop(backend, workspace);
+
+    for window in &workspace.windows {
+        if !backend.exists(window.id) {
+            warn!("Skipping invalid window '{}'.", window.title);
+            entry.push(&window.title, WindowOutcome::NotFound);
+            continue;
+        }
+        if !bring_to_current_desktop(backend, workspace.other_desktop, window.id, &window.title) {
+            entry.push(&window.title, WindowOutcome::OtherDesktop);
+            continue;
+        }
+
+        if backend.is_minimized(window.id) && !backend.show(window.id, ShowCommand::Restore) {
+            warn!("Failed to restore minimized window '{}'.", window.title);
+        }
+
+        if let Err(e) = backend.raise(window.id) {
+            warn!("Failed to raise window '{}': {}", window.title, e);
+            let error = MultiManagerError::win32("Raise", &workspace.name, &window.title, &e);
+            entry.push(&window.title, WindowOutcome::Failed(error));
+        } else {
+            info!("Raised window '{}'.", window.title);
+            entry.push(&window.title, WindowOutcome::Raised);
+        }
+
+        if workspace.focus_behavior == FocusBehavior::ActivateAll {
+            activate_window(backend, window.id, &window.title);
+        }
+    }
+
+    apply_focus_behavior(backend, workspace, previous_foreground, true);
+    entry
+}
+
+/// Turns solo mode on or off for a workspace.
+///
+/// Turning it on maximizes the workspace's solo window (the one marked `solo`, or the first)
+/// and minimizes its other visible windows. Turning it off restores the minimized windows and
+/// puts the solo window back where it was, so nothing outside the workspace is touched either
+/// way.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace to turn solo mode on or off for.
+///
+/// # Returns
+/// - An `ActivityEntry` with the outcome for each window involved.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::toggle_solo;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// let entry = toggle_solo(&Win32Backend, &mut workspace);
+/// workspace.activity.record(entry);
+/// ```
+pub fn toggle_solo(backend: &impl WindowBackend, workspace: &mut Workspace) -> ActivityEntry {
+    let mut entry = ActivityEntry::new(ToggleDirection::BringToFront);
+    let previous = workspace.solo.take();
+    let title = |id: usize| {
+        workspace
+            .windows
+            .iter()
+            .find(|window| window.id == id)
+            .map_or_else(|| backend.title(id), |window| window.title.clone())
+    };
+
+    if let Some(state) = previous {
+        info!("Leaving solo mode for '{}'.", workspace.name);
+        for &id in &state.parked {
+            if backend.exists(id) && backend.is_minimized(id) {
+                backend.show(id, ShowCommand::RestoreNoActivate);
+                entry.push(&title(id), WindowOutcome::Raised);
+            }
+        }
+        if backend.exists(state.window) {
+            backend.show(state.window, ShowCommand::Restore);
+            let flags = workspace
+                .windows
+                .iter()
+                .find(|window| window.id == state.window)
+                .map(|window| window.position_flags)
+                .unwrap_or_default();
+            let outcome = match backend.set_rect(state.window, state.rect, &flags) {
+                Ok(()) => WindowOutcome::Moved(state.rect),
+                Err(e) => {
+                    warn!("Failed to put window '{}' back: {}", title(state.window), e);
+                    let error =
+                        MultiManagerError::win32("Move", &workspace.name, &title(state.window), &e);
+                    WindowOutcome::Failed(error)
+                }
+            };
+            entry.push(&title(state.window), outcome);
+        }
+        return entry;
+    }
+
+    let solo_index = workspace
+        .windows
+        .iter()
+        .position(|window| window.solo)
+        .unwrap_or(0);
+    let Some(solo) = workspace.windows.get(solo_index) else {
+        warn!("Workspace '{}' has no window to solo.", workspace.name);
+        return entry;
+    };
+    if !backend.exists(solo.id) {
+        warn!("Solo window '{}' does not exist.", solo.title);
+        entry.push(&solo.title, WindowOutcome::NotFound);
+        return entry;
+    }
+
+    info!(
+        "Entering solo mode for '{}' with '{}'.",
+        workspace.name, solo.title
+    );
+    let rect = backend.rect(solo.id).unwrap_or(solo.home);
+    let mut parked = Vec::new();
+    for window in &workspace.windows {
+        if window.id == solo.id
+            || !backend.exists(window.id)
+            || !backend.is_visible(window.id)
+            || backend.is_minimized(window.id)
+        {
+            continue;
+        }
+        backend.show(window.id, ShowCommand::MinimizeNoActivate);
+        entry.push(&window.title, WindowOutcome::Minimized);
+        parked.push(window.id);
+    }
+    if !bring_to_current_desktop(backend, workspace.other_desktop, solo.id, &solo.title) {
+        entry.push(&solo.title, WindowOutcome::OtherDesktop);
+    } else {
+        backend.show(solo.id, ShowCommand::Maximize);
+        activate_window(backend, solo.id, &solo.title);
+        entry.push(&solo.title, WindowOutcome::Raised);
+    }
+
+    workspace.solo = Some(SoloState {
+        window: solo.id,
+        rect,
+        parked,
+    });
+    entry
+}
+
+/// Applies the workspace's final focus behavior after its windows have been arranged.
+///
+/// `ActivateAll` is handled per window while iterating, so here it only focuses the window
+/// remembered in `last_focused` again, on activation.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace that was just toggled.
+/// - `previous_foreground`: The window that had focus before the toggle started.
+/// - `activating`: Whether the workspace was sent to its targets or brought to the front,
+///   rather than sent home.
+fn apply_focus_behavior(
+    backend: &impl WindowBackend,
+    workspace: &Workspace,
+    previous_foreground: Option<usize>,
+    activating: bool,
+) {
+    match workspace.focus_behavior {
+        FocusBehavior::ActivateAll if activating => {
+            let remembered = workspace
+                .last_focused
+                .and_then(|id| workspace.windows.iter().find(|w| w.id == id))
+                .filter(|window| backend.exists(window.id));
+            if let Some(window) = remembered {
+                activate_window(backend, window.id, &window.title);
+            }
+        }
+        FocusBehavior::Window(index) => {
+            if let Some(window) = workspace.windows.get(index) {
+                activate_window(backend, window.id, &window.title);
+            } else {
+                warn!(
+                    "Focus window index {} is out of range for workspace '{}'.",
+                    index, workspace.name
+                );
+            }
+        }
+        FocusBehavior::Preserve => {
+            if let Some(previous) = previous_foreground.filter(|&id| backend.exists(id)) {
+                activate_window(backend, previous, "previously focused window");
+            }
+        }
+        FocusBehavior::ActivateAll | FocusBehavior::None => {}
+    }
+}
+
+/// Remembers which of a workspace's windows has focus, to focus it again when the workspace
+/// is next activated. Nothing is remembered if focus is on another window.
+///
+/// # Arguments
+/// - `workspace`: The workspace about to go home.
+/// - `foreground`: The window that has focus now.
+fn remember_focused_window(workspace: &mut Workspace, foreground: Option<usize>) {
+    if let Some(id) = foreground.filter(|&id| workspace.windows.iter().any(|w| w.id == id)) {
+        workspace.last_focused = Some(id);
+    }
+}
+
+/// Minimizes or hides every other visible top-level window, per the workspace's exclusive setting.
+///
+/// The affected handles are recorded in `suppressed_windows` so they can be restored later.
+/// Windows belonging to the workspace and to this application are left alone, and so are the
+/// desktop, the taskbars, and tool windows (see `is_shell_window`), even with
+/// `show_all_windows` on.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace being activated.
+pub fn suppress_other_windows(backend: &impl WindowBackend, workspace: &mut Workspace) {
+    let command = match workspace.exclusive {
+        ExclusiveAction::Off => return,
+        ExclusiveAction::Minimize => ShowCommand::MinimizeNoActivate,
+        ExclusiveAction::Hide => ShowCommand::Hide,
+    };
+
+    let own_process = std::process::id();
+    for id in backend.top_level_windows() {
+        if workspace.windows.iter().any(|w| w.id == id) {
+            continue;
+        }
+
+        if !is_capturable_window(backend, id)
+            || is_shell_window(backend, id)
+            || backend.is_minimized(id)
+            || backend.process_id(id) == own_process
+        {
+            continue;
+        }
+
+        backend.show(id, command);
+        workspace.suppressed_windows.push(id);
+    }
+
+    info!(
+        "Exclusive mode suppressed {} windows for workspace '{}'.",
+        workspace.suppressed_windows.len(),
+        workspace.name
+    );
+}
+
+/// Shows every window currently hidden by a hidden pseudo-position.
+///
+/// This is called when the application exits so that no window is left invisible
+/// without a way to bring it back.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspaces`: The workspaces whose hidden windows should be shown.
+pub fn show_hidden_windows(backend: &impl WindowBackend, workspaces: &mut [Workspace]) {
+    for workspace in workspaces.iter_mut() {
+        for window in workspace.windows.iter_mut().filter(|w| w.hidden) {
+            if backend.exists(window.id) {
+                backend.show(window.id, ShowCommand::ShowNoActivate);
+                info!("Showed hidden window '{}' on exit.", window.title);
+            }
+            window.hidden = false;
+        }
+        restore_suppressed_windows(backend, workspace);
+    }
+}
+
+/// Restores windows previously minimized or hidden by exclusive mode.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspace`: The workspace whose suppressed windows should be restored.
+pub fn restore_suppressed_windows(backend: &impl WindowBackend, workspace: &mut Workspace) {
+    for id in workspace.suppressed_windows.drain(..) {
+        if backend.exists(id) {
+            let command = if backend.is_minimized(id) {
+                ShowCommand::RestoreNoActivate
+            } else {
+                ShowCommand::ShowNoActivate
+            };
+            backend.show(id, command);
+        }
+    }
+}
+
+/// The outcome of resolving a workspace entry to a live window.
+pub enum WindowResolution {
+    /// Exactly one window was chosen.
+    Found(usize),
+    /// Several windows matched and the user needs to choose.
+    Ambiguous(Vec<usize>),
+    /// No window matched.
+    NotFound,
+}
+
+/// Adds the windows matching a workspace's auto-capture rules that it doesn't hold yet.
+///
+/// A window is already held if an entry refers to it, or if an entry whose handle is stale
+/// would be re-resolved to it, so a restarted app is not added a second time. Adopted
+/// windows keep their current position as home and get the rule's target. Shell and tool
+/// windows are never adopted; see `is_capturable_window`.
+///
+/// # Arguments
+/// - `backend`: The window system to search.
+/// - `workspace`: The workspace whose rules to apply; adopted windows are appended to it.
+///
+/// # Returns
+/// - The entries that were appended, in z-order.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::adopt_rule_windows;
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// let adopted = adopt_rule_windows(&Win32Backend, &mut workspace);
+/// println!("Adopted {} window(s).", adopted.len());
+/// ```
+pub fn adopt_rule_windows(backend: &impl WindowBackend, workspace: &mut Workspace) -> Vec<Window> {
+    if workspace.capture_rules.iter().all(|rule| rule.is_empty()) {
+        return Vec::new();
+    }
+
+    let mut held: HashSet<usize> = HashSet::new();
+    for window in &workspace.windows {
+        if backend.exists(window.id) {
+            held.insert(window.id);
+        } else {
+            held.extend(find_matching_windows(backend, window));
+        }
+    }
+
+    let mut adopted = Vec::new();
+    for id in backend.top_level_windows() {
+        if held.contains(&id) || !is_capturable_window(backend, id) {
+            continue;
+        }
+        let title = backend.title(id);
+        let exe = backend.exe(id);
+        let class_name = backend.class_name(id);
+        let Some(rule) = workspace
+            .capture_rules
+            .iter()
+            .find(|rule| rule.matches(&exe, &class_name, &title))
+        else {
+            continue;
+        };
+
+        let home = backend.rect(id).unwrap_or(rule.target);
+        info!(
+            "Auto-capturing window '{}' ({}) into workspace '{}'.",
+            title, exe, workspace.name
+        );
+        adopted.push(Window {
+            id,
+            title: title.clone(),
+            capture_title: title,
+            exe,
+            class_name,
+            home,
+            target: rule.target,
+            valid: true,
+            ..Default::default()
+        });
+        held.insert(id);
+    }
+
+    workspace.windows.extend(adopted.iter().cloned());
+    adopted
+}
+
+/// Finds the live top-level windows that match a workspace entry.
+///
+/// Windows must match the stored executable and class name when those are known. Entries
+/// captured without them fall back to an exact match on the capture title. When several
+/// windows match, those whose title still equals the capture title are preferred.
+///
+/// # Arguments
+/// - `backend`: The window system to search.
+/// - `window`: The workspace entry to match.
+///
+/// # Returns
+/// - The matching handles in z-order, topmost first.
+pub fn find_matching_windows(backend: &impl WindowBackend, window: &Window) -> Vec<usize> {
+    let has_identity = !window.exe.is_empty() || !window.class_name.is_empty();
+    let matches = identity_matches(backend, window);
+
+    if matches.len() > 1 && has_identity {
+        let exact: Vec<usize> = matches
+            .iter()
+            .copied()
+            .filter(|&id| backend.title(id) == window.capture_title)
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+    }
+    matches
+}
+
+/// Finds the visible windows with an entry's exe and class, or with its capture title if it
+/// has neither, in z-order.
+fn identity_matches(backend: &impl WindowBackend, window: &Window) -> Vec<usize> {
+    let has_identity = !window.exe.is_empty() || !window.class_name.is_empty();
+
+    backend
+        .top_level_windows()
+        .into_iter()
+        .filter(|&id| backend.is_visible(id))
+        .filter(|&id| {
+            if has_identity {
+                (window.exe.is_empty() || backend.exe(id).eq_ignore_ascii_case(&window.exe))
+                    && (window.class_name.is_empty() || backend.class_name(id) == window.class_name)
+            } else {
+                backend.title(id) == window.capture_title
+            }
+        })
+        .collect()
+}
+
+/// Returns the handles of a workspace's windows that still exist, which stale entries must
+/// not be resolved to.
+fn live_window_ids(backend: &impl WindowBackend, workspace: &Workspace) -> HashSet<usize> {
+    workspace
+        .windows
+        .iter()
+        .map(|window| window.id)
+        .filter(|&id| backend.exists(id))
+        .collect()
+}
+
+/// Resolves a workspace entry to a live window, applying the workspace's match rule.
+///
+/// Entries with an instance number take that instance of the windows with their exe and
+/// class, ordered as the entry says, so the result is deterministic without asking.
+/// Windows already claimed by other entries are never chosen, so two entries with the same
+/// exe and class resolve to different windows.
+///
+/// # Arguments
+/// - `backend`: The window system to search.
+/// - `window`: The workspace entry to resolve.
+/// - `rule`: How to choose between several matching windows.
+/// - `claimed`: The handles held by the workspace's other entries.
+///
+/// # Returns
+/// - A `WindowResolution` describing the outcome.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(window = %window.title)))]
+pub fn resolve_window(
+    backend: &impl WindowBackend,
+    window: &Window,
+    rule: MatchRule,
+    claimed: &HashSet<usize>,
+) -> WindowResolution {
+    if let Some(instance) = window.instance {
+        let mut matches = identity_matches(backend, window);
+        if instance.order == InstanceOrder::CreationOrder {
+            matches.sort_by_key(|&id| (backend.process_start_time(id).unwrap_or(u64::MAX), id));
+        }
+        return match matches.get(instance.number.max(1) - 1) {
+            Some(&found) if !claimed.contains(&found) => WindowResolution::Found(found),
+            _ => WindowResolution::NotFound,
+        };
+    }
+
+    let mut matches = find_matching_windows(backend, window);
+    matches.retain(|id| !claimed.contains(id));
+    match (matches.len(), rule) {
+        (0, _) => WindowResolution::NotFound,
+        (1, _) | (_, MatchRule::MostRecentlyActive) => WindowResolution::Found(matches[0]),
+        (_, MatchRule::Ask) => WindowResolution::Ambiguous(matches),
+    }
+}
+
+/// Moves a window, retrying with exponential backoff if the move fails.
+///
+/// Some windows reject `SetWindowPos` while they are busy or animating, so a failed move is
+/// retried up to `retries` times. The delay starts at `backoff_ms` and doubles after each attempt.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `id`: The window's handle.
+/// - `rect`: The new position `(x, y, width, height)` of the window.
+/// - `flags`: The window's position flags.
+/// - `retries`: The number of additional attempts after the first failure.
+/// - `backoff_ms`: The delay in milliseconds before the first retry.
+///
+/// # Returns
+/// - `Ok(())` if any attempt succeeded.
+/// - The error from the last attempt otherwise.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::move_window_with_retry;
+/// # use multi_manager::workspace::PositionFlags;
+/// # let id = 0x10010;
+/// if let Err(e) = move_window_with_retry(&Win32Backend, id, (100, 100, 800, 600), &PositionFlags::default(), 3, 50) {
+///     println!("Failed to move window: {}", e);
+/// }
+/// ```
+pub fn move_window_with_retry(
+    backend: &impl WindowBackend,
+    id: usize,
+    rect: (i32, i32, i32, i32),
+    flags: &PositionFlags,
+    retries: u32,
+    backoff_ms: u64,
+) -> Result<()> {
+    let mut delay = backoff_ms;
+    let mut attempt = 0;
+    loop {
+        match backend.set_rect(id, rect, flags) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < retries => {
+                attempt += 1;
+                warn!(
+                    "Move attempt {} for HWND {:?} failed: {}. Retrying in {} ms.",
+                    attempt, id, e, delay
+                );
+                thread::sleep(Duration::from_millis(delay));
+                delay = delay.saturating_mul(2);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Glides a window from where it is towards a new position, frame by frame. The last frame
+/// is left to the caller's actual move, which retries and checks the result.
+///
+/// Nothing is done for instant animations or windows whose position can't be read.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `id`: The window's handle.
+/// - `rect`: The position `(x, y, width, height)` the window is going to.
+/// - `flags`: The window's position flags.
+/// - `animation`: The duration and easing of the move.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::{animate_window, move_window_with_retry};
+/// # use multi_manager::workspace::{Window, Workspace};
+/// # fn main() -> windows::core::Result<()> {
+/// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+/// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+/// # ).unwrap();
+/// # let window = Window::default();
+/// # let (id, rect) = (window.id, window.target);
+/// animate_window(&Win32Backend, id, rect, &window.position_flags, workspace.animation.resolve());
+/// move_window_with_retry(&Win32Backend, id, rect, &window.position_flags, 3, 50)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn animate_window(
+    backend: &impl WindowBackend,
+    id: usize,
+    rect: (i32, i32, i32, i32),
+    flags: &PositionFlags,
+    animation: Animation,
+) {
+    let Some(from) = backend.rect(id) else {
+        return;
+    };
+    for frame in animation.frames(from, rect) {
+        if backend.set_rect(id, frame, flags).is_err() {
+            return;
+        }
+        thread::sleep(FRAME_INTERVAL);
+    }
+}
+
+/// Checks that a window really is where it was just moved, for windows that accept
+/// `SetWindowPos` but ignore or clamp it, such as fullscreen games, maximized windows, or apps
+/// enforcing a minimum size.
+///
+/// If the window is elsewhere, it is restored and moved once more before giving up.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `id`: The window's handle.
+/// - `rect`: The position `(x, y, width, height)` the window was moved to.
+/// - `flags`: The window's position flags.
+///
+/// # Returns
+/// - `None` if the window is at `rect`.
+/// - The rect the window is actually at otherwise.
+///
+/// # Example
+/// ```no_run
+/// # use multi_manager::backend::Win32Backend;
+/// # use multi_manager::engine::enforce_window_rect;
+/// # use multi_manager::workspace::PositionFlags;
+/// # let (id, rect) = (0x10010, (100, 100, 800, 600));
+/// if let Some(actual) = enforce_window_rect(&Win32Backend, id, rect, &PositionFlags::default()) {
+///     println!("The window stayed at {:?}", actual);
+/// }
+/// ```
+pub fn enforce_window_rect(
+    backend: &impl WindowBackend,
+    id: usize,
+    rect: (i32, i32, i32, i32),
+    flags: &PositionFlags,
+) -> Option<(i32, i32, i32, i32)> {
+    if is_window_at_position(backend, id, rect, flags) {
+        return None;
+    }
+
+    info!(
+        "HWND {:?} is not at {:?} after moving; restoring it and moving it again.",
+        id, rect
+    );
+    backend.show(id, ShowCommand::RestoreNoActivate);
+    if let Err(e) = backend.set_rect(id, rect, flags) {
+        warn!("Second move of HWND {:?} failed: {}", id, e);
+    }
+
+    if is_window_at_position(backend, id, rect, flags) {
+        None
+    } else {
+        backend.rect(id)
+    }
+}
+
+/// Brings a window to the foreground, logging the outcome.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `id`: The window's handle.
+/// - `title`: The window title, used for logging.
+fn activate_window(backend: &impl WindowBackend, id: usize, title: &str) {
+    if backend.activate(id) {
+        info!("Activated window '{}'", title);
+    } else {
+        warn!("Failed to activate window '{}'", title);
+    }
+}
+
+/// Checks if a window is at the specified position.
+///
+/// Components that the window's position flags prevent from changing (position for
+/// `no_move`, size for `no_size`) are ignored in the comparison.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `id`: The window's handle.
+/// - `rect`: The expected position `(x, y, width, height)`.
+/// - `flags`: The window's position flags.
+///
+/// # Returns
+/// - `true` if the window matches the specified position.
+/// - `false` otherwise.
+fn is_window_at_position(
+    backend: &impl WindowBackend,
+    id: usize,
+    rect: (i32, i32, i32, i32),
+    flags: &PositionFlags,
+) -> bool {
+    if let Some((wx, wy, ww, wh)) = backend.rect(id) {
+        let position_matches = flags.no_move || (wx == rect.0 && wy == rect.1);
+        let size_matches = flags.no_size || (ww == rect.2 && wh == rect.3);
+        position_matches && size_matches
+    } else {
+        false
+    }
+}
@@ -0,0 +1,112 @@
+use thiserror::Error;
+
+/// Errors produced by workspace, hotkey and window operations.
+///
+/// Each variant carries enough context (workspace, window, Win32 code) for the GUI to show
+/// the user what failed and what they can do about it, rather than only logging it.
+#[derive(Clone, Debug, Error)]
+pub enum MultiManagerError {
+    /// A Win32 call on one of a workspace's windows failed.
+    #[error("{operation} failed for window '{window}' in workspace '{workspace}': {message} (Win32 error {code:#010X})")]
+    Win32 {
+        operation: &'static str,
+        workspace: String,
+        window: String,
+        code: i32,
+        message: String,
+    },
+    /// A workspace entry no longer refers to a live window and no replacement was found.
+    #[error(
+        "Window '{window}' in workspace '{workspace}' no longer exists; recapture or remove it"
+    )]
+    WindowNotFound { workspace: String, window: String },
+    /// A hotkey string is not a valid key combination.
+    #[error("Hotkey '{hotkey}' is not a valid key combination")]
+    InvalidHotkey { hotkey: String },
+    /// A hotkey is already registered by this application.
+    #[error("Hotkey '{hotkey}' is already registered")]
+    HotkeyAlreadyRegistered { hotkey: String },
+    /// The system refused to register a hotkey.
+    #[error("Hotkey '{hotkey}' could not be registered; another application may be using it (Win32 error {code:#010X})")]
+    HotkeyRegistration { hotkey: String, code: i32 },
+    /// The workspaces file could not be written.
+    #[error("Could not save workspaces to '{path}': {message}")]
+    Save { path: String, message: String },
+    /// The settings file could not be written.
+    #[error("Could not save settings to '{path}': {message}")]
+    SaveSettings { path: String, message: String },
+    /// The logging configuration could not be built or applied.
+    #[error("Could not apply logging settings: {message}")]
+    Logging { message: String },
+    /// Checking GitHub for a newer release failed.
+    #[error("Could not check for updates: {message}")]
+    UpdateCheck { message: String },
+    /// The workspaces file exists but could not be read or parsed.
+    #[error("Could not load workspaces from '{path}': {message}")]
+    Load { path: String, message: String },
+    /// The workspaces file was read but is not valid workspace JSON.
+    #[error("Could not parse workspaces in '{path}' at line {line}, column {column}: {message}")]
+    Parse {
+        path: String,
+        message: String,
+        line: usize,
+        column: usize,
+        snippet: String,
+    },
+    /// The clipboard could not be read or written.
+    #[error("Could not access the clipboard: {message}")]
+    Clipboard { message: String },
+    /// A workspaces file could not be encrypted or decrypted.
+    #[error("Could not encrypt or decrypt the workspaces: {message}")]
+    Encryption { message: String },
+    /// A file exported for the user, such as the hotkey cheat sheet, could not be written.
+    #[error("Could not export to '{path}': {message}")]
+    Export { path: String, message: String },
+    /// A workspace's pre- or post-toggle command could not be run or failed.
+    #[error("Command for workspace '{workspace}' failed: {message} ({command})")]
+    Hook {
+        workspace: String,
+        command: String,
+        message: String,
+    },
+    /// Mouse gestures could not be turned on.
+    #[error("Mouse gestures are not available: {message}")]
+    Gesture { message: String },
+    /// Pasted text is not a workspace or a list of workspaces.
+    #[error("The clipboard does not contain a workspace: {message}")]
+    ImportWorkspace { message: String },
+    /// The application could not be started again as administrator.
+    #[error("Could not relaunch as administrator: {message}")]
+    Relaunch { message: String },
+}
+
+impl MultiManagerError {
+    /// Builds a `Win32` error from a failed call on a workspace window.
+    ///
+    /// # Arguments
+    /// - `operation`: A short description of what was attempted (e.g. "Move").
+    /// - `workspace`: The name of the workspace the window belongs to.
+    /// - `window`: The title of the window.
+    /// - `error`: The error returned by the Win32 call.
+    ///
+    /// # Example
+    /// ```ignore
+    /// if let Err(e) = make_borderless_fullscreen(hwnd) {
+    ///     let error = MultiManagerError::win32("Borderless fullscreen", &workspace.name, &window.title, &e);
+    /// }
+    /// ```
+    pub fn win32(
+        operation: &'static str,
+        workspace: &str,
+        window: &str,
+        error: &windows::core::Error,
+    ) -> Self {
+        MultiManagerError::Win32 {
+            operation,
+            workspace: workspace.to_string(),
+            window: window.to_string(),
+            code: error.code().0,
+            message: error.message(),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Mouse gestures drawn on the desktop with the right button held, such as an L or a zigzag,
+//! to trigger workspaces without the keyboard.
+//!
+//! A low-level mouse hook watches for the right button going down over the desktop. While it
+//! is held, the pointer's path is recorded; when it is released, the path is turned into a
+//! gesture of strokes (`U`, `D`, `L`, and `R`). A right-click that draws no gesture is
+//! replayed, so the desktop's context menu still works.
+
+use crate::window_manager::get_window_class;
+use log::{info, warn};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use windows::Win32::Foundation::{LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEINPUT,
+    MOUSE_EVENT_FLAGS,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetAncestor, GetMessageW, PostThreadMessageW, SetWindowsHookExW,
+    UnhookWindowsHookEx, WindowFromPoint, GA_ROOT, HC_ACTION, LLMHF_INJECTED, MSG, MSLLHOOKSTRUCT,
+    WH_MOUSE_LL, WM_MOUSEMOVE, WM_QUIT, WM_RBUTTONDOWN, WM_RBUTTONUP,
+};
+
+/// How far in pixels the pointer must travel in one direction to count as a stroke.
+pub const GESTURE_MIN_STROKE: i32 = 40;
+
+/// The window classes of the desktop, the only place gestures are drawn.
+const DESKTOP_CLASSES: [&str; 2] = ["Progman", "WorkerW"];
+
+/// What the hook passes on to the dispatcher thread.
+enum GestureEvent {
+    /// A gesture was drawn.
+    Gesture(String),
+    /// The right button was clicked without drawing a gesture, so the click is replayed.
+    Click,
+}
+
+/// The state shared with the hook procedure, which can't capture anything.
+///
+/// # Fields
+/// - `sender`: Where recognized gestures and clicks to replay are sent.
+/// - `points`: The pointer's path while the right button is held on the desktop.
+struct HookState {
+    sender: Sender<GestureEvent>,
+    points: Option<Vec<(i32, i32)>>,
+}
+
+static HOOK_STATE: Mutex<Option<HookState>> = Mutex::new(None);
+
+/// A running mouse gesture hook. Dropping it removes the hook.
+pub struct GestureHook {
+    thread_id: u32,
+}
+
+impl GestureHook {
+    /// Installs the mouse hook on a thread of its own and starts recognizing gestures.
+    ///
+    /// # Arguments
+    /// - `on_gesture`: Called with each gesture drawn, e.g. `"DR"` for an L, on a background
+    ///   thread.
+    ///
+    /// # Returns
+    /// - The running hook, or `None` if the hook could not be installed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let hook = GestureHook::start(|gesture| println!("Drew {}", gesture));
+    /// ```
+    pub fn start(on_gesture: impl Fn(&str) + Send + 'static) -> Option<GestureHook> {
+        let (sender, receiver) = mpsc::channel();
+        *HOOK_STATE.lock().unwrap() = Some(HookState {
+            sender,
+            points: None,
+        });
+
+        thread::Builder::new()
+            .name("Gesture Dispatcher".to_string())
+            .spawn(move || {
+                for event in receiver {
+                    match event {
+                        GestureEvent::Gesture(gesture) => {
+                            info!("Recognized mouse gesture '{}'.", gesture);
+                            on_gesture(&gesture);
+                        }
+                        GestureEvent::Click => replay_right_click(),
+                    }
+                }
+            })
+            .ok()?;
+
+        let (started, started_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("Gesture Hook".to_string())
+            .spawn(move || run_hook(started))
+            .ok()?;
+        match started_receiver.recv() {
+            Ok(Some(thread_id)) => {
+                info!("Mouse gesture hook installed.");
+                Some(GestureHook { thread_id })
+            }
+            _ => {
+                HOOK_STATE.lock().unwrap().take();
+                None
+            }
+        }
+    }
+}
+
+impl Drop for GestureHook {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        // Dropping the sender ends the dispatcher thread
+        HOOK_STATE.lock().unwrap().take();
+        info!("Mouse gesture hook removed.");
+    }
+}
+
+/// Turns a pointer path into strokes: each time the pointer has moved at least
+/// `GESTURE_MIN_STROKE` pixels, the main direction of the move is added, unless it is the same
+/// as the previous stroke.
+///
+/// # Arguments
+/// - `points`: The pointer's positions in order.
+///
+/// # Returns
+/// - The gesture as `U`, `D`, `L`, and `R` strokes, or an empty string if the pointer hardly
+///   moved.
+///
+/// # Example
+/// ```
+/// # use multi_manager::gestures::recognize_gesture;
+/// assert_eq!(recognize_gesture(&[(0, 0), (0, 100), (100, 100)]), "DR");
+/// ```
+pub fn recognize_gesture(points: &[(i32, i32)]) -> String {
+    let mut gesture = String::new();
+    let Some(&(mut anchor_x, mut anchor_y)) = points.first() else {
+        return gesture;
+    };
+
+    for &(x, y) in points {
+        let (dx, dy) = (x - anchor_x, y - anchor_y);
+        if dx.abs().max(dy.abs()) < GESTURE_MIN_STROKE {
+            continue;
+        }
+        let stroke = if dx.abs() > dy.abs() {
+            if dx > 0 {
+                'R'
+            } else {
+                'L'
+            }
+        } else if dy > 0 {
+            'D'
+        } else {
+            'U'
+        };
+        if !gesture.ends_with(stroke) {
+            gesture.push(stroke);
+        }
+        (anchor_x, anchor_y) = (x, y);
+    }
+    gesture
+}
+
+/// Checks whether a gesture can be drawn: one or more of `U`, `D`, `L`, and `R`, with no
+/// stroke repeated straight after itself.
+///
+/// # Arguments
+/// - `gesture`: The gesture to check, e.g. `"DR"`.
+pub fn is_valid_gesture(gesture: &str) -> bool {
+    !gesture.is_empty()
+        && gesture.chars().all(|stroke| "UDLR".contains(stroke))
+        && !gesture.as_bytes().windows(2).any(|pair| pair[0] == pair[1])
+}
+
+/// Installs the hook and runs the message loop the hook needs until `WM_QUIT` is posted.
+fn run_hook(started: Sender<Option<u32>>) {
+    let hook = match unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), None, 0) } {
+        Ok(hook) => hook,
+        Err(e) => {
+            warn!("Failed to install the mouse gesture hook: {}", e);
+            let _ = started.send(None);
+            return;
+        }
+    };
+    let _ = started.send(Some(unsafe { GetCurrentThreadId() }));
+
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {}
+
+    unsafe {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// The low-level mouse hook: records right-button drags that start on the desktop and
+/// swallows their button events, passing everything else on.
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        if event.flags & LLMHF_INJECTED == 0 {
+            if let Some(state) = HOOK_STATE.lock().unwrap().as_mut() {
+                let point = (event.pt.x, event.pt.y);
+                match wparam.0 as u32 {
+                    WM_RBUTTONDOWN if is_desktop_at(event.pt) => {
+                        state.points = Some(vec![point]);
+                        return LRESULT(1);
+                    }
+                    WM_MOUSEMOVE => {
+                        if let Some(points) = &mut state.points {
+                            points.push(point);
+                        }
+                    }
+                    WM_RBUTTONUP => {
+                        if let Some(mut points) = state.points.take() {
+                            points.push(point);
+                            let gesture = recognize_gesture(&points);
+                            let _ = state.sender.send(if gesture.is_empty() {
+                                GestureEvent::Click
+                            } else {
+                                GestureEvent::Gesture(gesture)
+                            });
+                            return LRESULT(1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Checks whether the desktop is under a point, rather than a window.
+fn is_desktop_at(point: POINT) -> bool {
+    let root = unsafe { GetAncestor(WindowFromPoint(point), GA_ROOT) };
+    DESKTOP_CLASSES.contains(&get_window_class(root).as_str())
+}
+
+/// Sends the right-click swallowed by the hook, so the desktop's context menu opens.
+fn replay_right_click() {
+    let input = |flags: MOUSE_EVENT_FLAGS| INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let inputs = [input(MOUSEEVENTF_RIGHTDOWN), input(MOUSEEVENTF_RIGHTUP)];
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strokes_follow_the_main_direction() {
+        let step = GESTURE_MIN_STROKE;
+        for (points, gesture) in [
+            (vec![(0, 0), (0, -step)], "U"),
+            (vec![(0, 0), (0, step)], "D"),
+            (vec![(0, 0), (-step, 0)], "L"),
+            (vec![(0, 0), (step, 0)], "R"),
+            (vec![(0, 0), (step, step / 2)], "R"),
+            (vec![(0, 0), (step / 2, -step)], "U"),
+            (vec![(0, 0), (0, step), (step, step), (step, 0)], "DRU"),
+        ] {
+            assert_eq!(recognize_gesture(&points), gesture, "{:?}", points);
+        }
+    }
+
+    #[test]
+    fn repeated_strokes_are_merged() {
+        let step = GESTURE_MIN_STROKE;
+        let points: Vec<_> = (0..5).map(|k| (k * step, 0)).collect();
+        assert_eq!(recognize_gesture(&points), "R");
+    }
+
+    #[test]
+    fn small_moves_are_ignored() {
+        let jitter = GESTURE_MIN_STROKE - 1;
+        assert_eq!(recognize_gesture(&[]), "");
+        assert_eq!(recognize_gesture(&[(5, 5)]), "");
+        assert_eq!(
+            recognize_gesture(&[(0, 0), (jitter, 0), (0, jitter), (-jitter, 0)]),
+            ""
+        );
+    }
+
+    #[test]
+    fn small_moves_add_up_to_a_stroke() {
+        let part = GESTURE_MIN_STROKE / 2 + 1;
+        assert_eq!(recognize_gesture(&[(0, 0), (0, part), (0, 2 * part)]), "D");
+    }
+
+    #[test]
+    fn recognized_gestures_are_valid() {
+        let step = GESTURE_MIN_STROKE;
+        let gesture = recognize_gesture(&[(0, 0), (step, 0), (step, step), (0, step)]);
+        assert_eq!(gesture, "RDL");
+        assert!(is_valid_gesture(&gesture));
+        assert!(!is_valid_gesture("RR"));
+        assert!(!is_valid_gesture(""));
+        assert!(!is_valid_gesture("RX"));
+    }
+}
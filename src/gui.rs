@@ -1,53 +1,326 @@
-use crate::utils::*;
-use crate::window_manager::*;
-use crate::workspace::*;
 use eframe::egui;
 use eframe::egui::ViewportBuilder;
 use eframe::NativeOptions;
 use eframe::{self, App as EframeApp};
 use log::{info, warn};
+use multi_manager::activity::*;
+use multi_manager::agent::*;
+use multi_manager::animation::*;
+use multi_manager::backend::*;
+use multi_manager::caps_layer::*;
+use multi_manager::cheat_sheet::*;
+use multi_manager::close_watch::*;
+use multi_manager::controller::*;
+use multi_manager::elevation::*;
+use multi_manager::encryption::set_encryption_enabled;
+use multi_manager::engine::*;
+use multi_manager::error::MultiManagerError;
+use multi_manager::gestures::*;
+use multi_manager::history::*;
+use multi_manager::hotkey::{migrate_hotkey, migrate_hotkey_base, Hotkey};
+use multi_manager::idle::*;
+use multi_manager::journal::*;
+use multi_manager::key_hook::*;
+use multi_manager::layout::*;
+use multi_manager::logging::*;
+use multi_manager::monitor_watch::*;
+use multi_manager::paths::*;
+use multi_manager::schedule::*;
+use multi_manager::settings::*;
+use multi_manager::snap::*;
+use multi_manager::thumbnails::Thumbnails;
+use multi_manager::tray::{TrayIcon, TrayMenuItem};
+use multi_manager::update::*;
+use multi_manager::usage::*;
+use multi_manager::utils::*;
+use multi_manager::validation::*;
+use multi_manager::window_browser::*;
+use multi_manager::window_manager::*;
+use multi_manager::window_ops::*;
+use multi_manager::workspace::*;
 use poll_promise::Promise;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use windows::Win32::Foundation::HWND;
-use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_SHOWNA};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+/// The result of offering a fix for a validation issue.
+enum IssueFix {
+    /// Nothing was changed.
+    None,
+    /// The issue was resolved and can be removed from the report.
+    Fixed,
+}
+
+/// The egui memory key holding the latest status message for the status bar.
+const ANNOUNCEMENT_ID: &str = "announcement";
+
+/// How many triggered workspaces are listed in the Recent section.
+const RECENT_TRIGGERS_LIMIT: usize = 5;
+
+/// The egui memory key holding the index of the workspace the keyboard cursor is on.
+const WORKSPACE_CURSOR_ID: &str = "workspace_cursor";
+
+/// A workspace list action requested from the keyboard, applied to the workspace under the
+/// keyboard cursor.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ListKey {
+    None,
+    /// The cursor moved, so its workspace should be scrolled into view.
+    Moved,
+    /// Enter: expand or collapse the workspace.
+    Expand,
+}
+
+/// The egui memory key holding the tab shown in the main window.
+const MAIN_TAB_ID: &str = "main_tab";
+
+/// The egui memory key holding whether the window browser is shown beside the workspace list.
+const WINDOW_BROWSER_OPEN_ID: &str = "window_browser_open";
+
+/// The egui memory key holding logging settings edited but not yet applied.
+const SETTINGS_LOGGING_DRAFT_ID: &str = "settings_logging_draft";
+
+/// The egui memory key holding whether the hotkey cheat sheet is shown.
+const CHEAT_SHEET_OPEN_ID: &str = "cheat_sheet_open";
+
+/// The egui memory key holding the workspace the cheat sheet should scroll to and highlight.
+const CHEAT_SHEET_FOCUS_ID: &str = "cheat_sheet_focus";
+
+/// The egui memory key set while a hotkey field has keyboard focus during the frame.
+const HOTKEY_EDITING_ID: &str = "hotkey_editing";
+
+/// The egui memory key set when **File → Exit** asks to close the window, so it isn't hidden
+/// instead.
+const EXIT_REQUESTED_ID: &str = "exit_requested";
+
+/// The egui memory key holding when the workspace file was last checked for changes made by
+/// other programs.
+const FILE_CHECK_ID: &str = "file_check";
+
+/// How often the workspace file is checked for changes made by other programs.
+const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How many lines of the log file the Logs tab shows.
+const LOG_TAIL_LINES: usize = 500;
+
+/// The height of a window's live preview in the workspace editor, and the widest it gets.
+const THUMBNAIL_HEIGHT: f32 = 72.0;
+const THUMBNAIL_MAX_WIDTH: f32 = 192.0;
+
+/// Title bar icons loaded as textures, by HWND and exe. `None` if the window has no icon.
+pub type WindowIcons = HashMap<(usize, String), Option<egui::TextureHandle>>;
+
+/// A capture started with "Capture in N s" or "Capture Under Cursor", taken when the
+/// countdown ends.
+///
+/// # Fields
+/// * `workspace` - The index of the workspace to add the window to.
+/// * `workspace_name` - Its name, so the capture is dropped if the list changed meanwhile.
+/// * `deadline` - When the window is captured.
+/// * `under_cursor` - Whether to capture the window under the mouse cursor rather than the
+///   active window.
+pub struct CountdownCapture {
+    pub workspace: usize,
+    pub workspace_name: String,
+    pub deadline: Instant,
+    pub under_cursor: bool,
+}
+
+/// A page of the main window, chosen from the tab row under the menu bar.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MainTab {
+    Workspaces,
+    Statistics,
+    History,
+    Logs,
+    Settings,
+}
+
+impl MainTab {
+    const ALL: [MainTab; 5] = [
+        MainTab::Workspaces,
+        MainTab::Statistics,
+        MainTab::History,
+        MainTab::Logs,
+        MainTab::Settings,
+    ];
+
+    /// Returns the title shown on the tab.
+    fn title(self) -> &'static str {
+        match self {
+            MainTab::Workspaces => "Workspaces",
+            MainTab::Statistics => "Usage Statistics",
+            MainTab::History => "Edit History",
+            MainTab::Logs => "Logs",
+            MainTab::Settings => "Settings",
+        }
+    }
+}
+
+/// A column the Usage Statistics table can be sorted by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum UsageColumn {
+    Name,
+    ToTarget,
+    ToHome,
+    BringToFront,
+    Total,
+    LastUsed,
+}
 
 #[derive(Clone)]
 pub struct App {
     pub app_title_name: String,
     pub workspaces: Arc<Mutex<Vec<Workspace>>>,
     pub last_hotkey_info: Arc<Mutex<Option<(String, Instant)>>>,
+    pub recent_triggers: Arc<Mutex<VecDeque<(String, Instant)>>>, // Names of the last workspaces triggered by hotkey or Toggle button, newest first
     pub hotkey_promise: Arc<Mutex<Option<Promise<()>>>>,
     pub initial_validation_done: Arc<Mutex<bool>>, // New flag for initial validation
     pub registered_hotkeys: Arc<Mutex<HashMap<String, usize>>>, // Tracks registered hotkeys
+    pub validation_promise: Arc<Mutex<Option<Promise<ValidationReport>>>>, // Background "Validate All" run
+    pub window_cache: Arc<Mutex<WindowStateCache>>, // Throttles per-frame IsWindow/title queries
+    pub window_ops: WindowOps, // Worker thread performing Win32 window operations
+    pub errors: Arc<Mutex<Vec<MultiManagerError>>>, // Errors awaiting acknowledgement in the GUI
+    pub settings: Arc<Mutex<Settings>>, // Application-wide settings from the Settings panel
+    pub update_check: Arc<Mutex<Option<Promise<UpdateCheckResult>>>>, // Background GitHub release check
+    pub workspaces_path: Arc<Mutex<String>>, // The workspace file opened, saved to by "Save"
+    pub workspaces_modified: Arc<Mutex<Option<SystemTime>>>, // The workspace file's modification time when last loaded or saved
+    pub file_conflict: Arc<Mutex<bool>>, // The workspace file was changed by another program
+    pub history: Arc<Mutex<Option<Vec<HistoryEntry>>>>, // Edit history shown in its tab; None until the tab is first opened
+    pub log_lines: Arc<Mutex<Vec<String>>>, // The end of the log file, read when the Logs tab is opened or refreshed
+    pub window_browser: Arc<Mutex<WindowBrowser>>, // Top-level windows listed in the window browser, refreshed in the background
+    pub window_icons: Arc<Mutex<WindowIcons>>, // Icons shown next to window titles, loaded on first use
+    pub thumbnails: Arc<Mutex<Thumbnails>>, // Live DWM previews of captured windows, placed again every frame
+    pub archive: Arc<Mutex<Vec<Workspace>>>, // Deleted workspaces that can still be restored, most recently deleted first
+    pub countdown_capture: Arc<Mutex<Option<CountdownCapture>>>, // A delayed capture waiting for its countdown to end
+    pub usage: Arc<Mutex<UsageStats>>, // Activation counts per workspace, updated by the window ops worker
+    pub gesture_hook: Arc<Mutex<Option<GestureHook>>>, // The mouse gesture hook, while gestures are enabled
+    pub caps_layer: Arc<Mutex<Option<CapsLayerHook>>>, // The CapsLock layer hook, while the layer is enabled
+    pub keyboard_hook: Arc<Mutex<(bool, Option<KeyboardHook>)>>, // Whether a workspace hotkey is in hook mode, and the keyboard hook for it
+    pub scheduled_moves: Arc<Mutex<Vec<PendingMove>>>, // Scheduled moves waiting out their grace period, skippable from the prompt
+    pub egui_ctx: Arc<Mutex<Option<egui::Context>>>, // Set once the GUI is running, so background threads can request a repaint
+    pub hotkeys_suspended: Arc<Mutex<bool>>, // A hotkey field has focus, so typing a combination doesn't trigger workspaces
+    pub gui_requested: Arc<Mutex<bool>>, // The window should be open; false while started in the background until the show hotkey is pressed
+    pub tray_running: Arc<Mutex<bool>>, // The tray icon was added, so a window hidden on close can be shown from it and exited
+    pub gui_hwnd: Arc<Mutex<Option<usize>>>, // The main window's handle, so the show hotkey can bring it back after it was hidden on close
+    pub agent_attached: bool, // A background agent was running at startup; it handles hotkeys and is told to reload after saves
+    pub journal: Arc<Mutex<Journal>>, // Unsaved edits, journaled so they can be recovered after a crash
+    pub parse_failure: Arc<Mutex<Option<MultiManagerError>>>, // Workspace file parse error awaiting the user's choice
 }
 
 /// Launches the application GUI and manages the lifecycle of the application.
 ///
-/// - Loads workspaces from the saved file (`workspaces.json`).
+/// - Loads workspaces from the current workspace file (`workspaces.json` by default).
 /// - Starts a background thread to monitor hotkey presses.
-/// - Launches the GUI using `eframe`.
+/// - Launches the GUI using `eframe`; in the background, only once the show hotkey asks for it.
 ///
 /// # Arguments
 /// * `app` - The application state encapsulated in an `App` struct.
-pub fn run_gui(app: App) {
-    {
-        let mut workspaces = app.workspaces.lock().unwrap();
-        *workspaces = load_workspaces("workspaces.json", &app);
-    }
+/// * `background` - Whether to run without a window until the show hotkey is pressed.
+/// * `handoff` - The workspaces handed over by the instance that relaunched as administrator,
+///   loaded instead of the workspace file and then deleted.
+pub fn run_gui(app: App, background: bool, handoff: Option<String>) {
+    let workspaces = app.workspaces.clone();
+    let journal = app.journal.clone();
+    load_startup_workspaces(&app, handoff.as_deref());
 
     app.validate_initial_hotkeys(); // Perform initial validation of hotkeys
 
-    // Start hotkey checker in a background thread with PollPromise
-    let app_for_promise = app.clone();
-    let hotkey_promise = Promise::spawn_thread("Hotkey Checker", move || loop {
-        check_hotkeys(&app_for_promise);
-        thread::sleep(Duration::from_millis(100));
-    });
-    *app.hotkey_promise.lock().unwrap() = Some(hotkey_promise);
+    if app.settings.lock().unwrap().check_for_updates {
+        app.start_update_check();
+    }
+
+    // Journal unsaved edits so they survive a crash
+    let app_for_journal = app.clone();
+    thread::Builder::new()
+        .name("Edit Journal".to_string())
+        .spawn(move || journal_edits(&app_for_journal))
+        .expect("Failed to spawn edit journal");
+
+    // While an agent is running, it handles hotkeys and the watchers; this is only a front end
+    let _close_hook = if app.agent_attached {
+        info!("Attached to the background agent; it handles hotkeys while this window is open.");
+        None
+    } else {
+        start_services(&app)
+    };
+
+    // Exit from the tray before the window was ever opened
+    let exit_requested = Arc::new(AtomicBool::new(false));
+    let tray = start_tray(&app, &exit_requested);
+    *app.tray_running.lock().unwrap() = tray.is_some();
+
+    if background {
+        match app.settings.lock().unwrap().show_gui_hotkey.clone() {
+            Some(hotkey) if is_valid_key_combo(&hotkey) => {
+                info!(
+                    "Running in the background; press '{}' or double-click the tray icon to open the window.",
+                    hotkey
+                )
+            }
+            _ if tray.is_some() => {
+                info!("Running in the background; double-click the tray icon to open the window.")
+            }
+            _ => {
+                warn!("Started in the background without a tray icon or a valid show hotkey; opening the window instead.");
+                *app.gui_requested.lock().unwrap() = true;
+            }
+        }
+        // A toggle that leaves a window to choose opens the window to ask
+        let app_for_choices = app.clone();
+        app.window_ops
+            .set_on_change(move || app_for_choices.show_gui_for_window_choices());
+        while !*app.gui_requested.lock().unwrap() && !exit_requested.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    if exit_requested.load(Ordering::SeqCst) {
+        info!("Exiting from the tray icon.");
+    } else {
+        info!("Opening the window.");
+        run_window(app);
+    }
+
+    // Never leave windows hidden once the manager is gone
+    show_hidden_windows(&Win32Backend, &mut workspaces.lock().unwrap());
+
+    // Exiting normally, so the journal is not needed to recover from a crash
+    let mut journal = journal.lock().unwrap();
+    if journal.recovery().is_none() {
+        journal.clear();
+    }
+}
 
+/// Adds the tray icon, which opens the window when double-clicked and offers **Exit**.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+/// * `exit_requested` - Set when **Exit** is chosen before the window was ever opened.
+///
+/// # Returns
+/// * The tray icon, or `None` if it could not be added.
+fn start_tray(app: &App, exit_requested: &Arc<AtomicBool>) -> Option<TrayIcon> {
+    let app_for_open = app.clone();
+    let app_for_menu = app.clone();
+    let exit_requested = exit_requested.clone();
+    TrayIcon::start(
+        &app.app_title_name,
+        move || app_for_open.show_gui("from the tray icon"),
+        move || app_for_menu.tray_menu(&exit_requested),
+    )
+}
+
+/// Runs the window until it is closed for good.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+fn run_window(app: App) {
     // Load embedded icon
     let icon_data = include_bytes!("../resources/app_icon.ico");
 
@@ -68,14 +341,226 @@ pub fn run_gui(app: App) {
         ..Default::default()
     };
 
+    let ui_scale = app.settings.lock().unwrap().clamped_ui_scale();
+
     eframe::run_native(
         &app.app_title_name.clone(),
         options,
-        Box::new(|_cc| Ok(Box::new(app))),
+        Box::new(move |cc| {
+            cc.egui_ctx.set_zoom_factor(ui_scale);
+            *app.egui_ctx.lock().unwrap() = Some(cc.egui_ctx.clone());
+            let ctx = cc.egui_ctx.clone();
+            let app_for_choices = app.clone();
+            app.window_ops.set_on_change(move || {
+                ctx.request_repaint();
+                // The window may be hidden on close, when no frames run to show the chooser
+                app_for_choices.show_gui_for_window_choices();
+            });
+            Ok(Box::new(app))
+        }),
     )
     .expect("Failed to run GUI");
 }
 
+/// Loads the workspaces at startup, registering their hotkeys unless an agent holds them, from the
+/// current workspace file or from the handoff file of the instance that relaunched as
+/// administrator.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+/// * `handoff` - The handoff file to load instead of the workspace file, deleted once read.
+fn load_startup_workspaces(app: &App, handoff: Option<&str>) {
+    let mut workspaces = app.workspaces.lock().unwrap();
+    let path = app.workspaces_path.lock().unwrap().clone();
+    let loaded = match handoff {
+        Some(handoff) => {
+            info!("Taking over the workspaces handed over in '{}'.", handoff);
+            let loaded = app.load_workspace_file(handoff);
+            remove_handoff(handoff);
+            loaded
+        }
+        None => app.load_workspace_file(&path),
+    };
+    match loaded {
+        Ok(loaded) => {
+            // Handed-over workspaces may have unsaved edits, so compare with the file itself
+            let baseline = match handoff {
+                Some(_) => read_workspaces(&path).unwrap_or_default(),
+                None => loaded.clone(),
+            };
+            app.journal.lock().unwrap().set_baseline(&baseline);
+            *workspaces = loaded;
+            *app.workspaces_modified.lock().unwrap() = file_modified(&path);
+        }
+        Err(e) => app.report_load_error(e),
+    }
+}
+
+/// Starts the always-on parts that run in the background: mouse gestures if enabled, the
+/// window-close hook, the hotkey checker, and the monitor, idle, and schedule watchers. They
+/// run in the GUI, or in the agent when there is one.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+///
+/// # Returns
+/// * The window-close hook, which stops reacting to closed windows when dropped.
+fn start_services(app: &App) -> Option<WindowCloseHook> {
+    if app.settings.lock().unwrap().mouse_gestures {
+        app.set_gestures_enabled(true);
+    }
+    if app.settings.lock().unwrap().caps_lock_layer {
+        app.set_caps_layer_enabled(true);
+    }
+
+    // React to workspace windows closing; the hook lives as long as the returned guard
+    let app_for_close = app.clone();
+    let close_hook = WindowCloseHook::start(move |closed| {
+        let reactions: Vec<_> = {
+            let workspaces = app_for_close.workspaces.lock().unwrap();
+            close_reactions(&workspaces, closed)
+                .into_iter()
+                .map(|(index, position)| {
+                    let workspace = &workspaces[index];
+                    (workspace.runtime_id, position, workspace.name.clone())
+                })
+                .collect()
+        };
+        for (workspace, position, name) in reactions {
+            info!(
+                "A window of '{}' closed; moving the rest to {:?}.",
+                name, position
+            );
+            app_for_close.window_ops.send(WindowOp::MoveAll {
+                workspace,
+                position,
+            });
+        }
+    });
+
+    // Start hotkey checker in a background thread with PollPromise
+    let app_for_promise = app.clone();
+    let hotkey_promise = Promise::spawn_thread("Hotkey Checker", move || {
+        let mut controllers = ControllerPoller::new();
+        loop {
+            check_hotkeys(&app_for_promise, &mut controllers);
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+    *app.hotkey_promise.lock().unwrap() = Some(hotkey_promise);
+
+    // Move windows back onto monitors that are connected again, e.g. after docking
+    let app_for_monitors = app.clone();
+    thread::Builder::new()
+        .name("Monitor Watcher".to_string())
+        .spawn(move || watch_monitors(&app_for_monitors))
+        .expect("Failed to spawn monitor watcher");
+
+    // Send every workspace home once nobody has used the machine for a while, if enabled
+    let app_for_idle = app.clone();
+    thread::Builder::new()
+        .name("Idle Watcher".to_string())
+        .spawn(move || watch_idle(&app_for_idle))
+        .expect("Failed to spawn idle watcher");
+
+    // Move workspaces at the times of day in their schedules
+    let app_for_schedule = app.clone();
+    thread::Builder::new()
+        .name("Scheduler".to_string())
+        .spawn(move || run_scheduler(&app_for_schedule))
+        .expect("Failed to spawn scheduler");
+
+    close_hook
+}
+
+/// Runs the background agent: loads the workspaces, starts hotkeys and the watchers, and then
+/// answers requests from GUIs on the agent's pipe until the process is ended. Never opens a
+/// window.
+///
+/// Hotkeys are registered and reloaded on this thread, as Windows ties them to the thread that
+/// registered them.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+pub fn run_agent(app: App) {
+    let Some(server) = AgentServer::bind() else {
+        return;
+    };
+    load_startup_workspaces(&app, None);
+    app.validate_initial_hotkeys();
+    let _close_hook = start_services(&app);
+
+    // There is no window to ask in, so entries that match several windows take the first
+    let app_for_choices = app.clone();
+    app.window_ops.set_on_change(move || {
+        let mut moves = Vec::new();
+        for workspace in app_for_choices.workspaces.lock().unwrap().iter_mut() {
+            let direction = workspace
+                .activity
+                .newest_first()
+                .next()
+                .map(|e| e.direction);
+            let workspace_id = workspace.runtime_id;
+            for window in workspace.windows.iter_mut() {
+                if let Some(&candidate) = window.candidates.first() {
+                    warn!(
+                        "The agent can't ask which window to use for '{}'; taking HWND {:?}.",
+                        window.capture_title, candidate
+                    );
+                    moves.extend(apply_window_choice(
+                        workspace_id,
+                        direction,
+                        window,
+                        candidate,
+                    ));
+                }
+            }
+        }
+        for op in moves {
+            app_for_choices.window_ops.send(op);
+        }
+    });
+
+    server.serve(|command| match command {
+        AgentCommand::Ping => true,
+        AgentCommand::Reload(path) => app.reload_for_agent(&path),
+    })
+}
+
+/// Uses the chosen window for an entry that matched several, and returns the move that sends it
+/// where the rest of its workspace went on the last toggle.
+///
+/// # Arguments
+/// * `workspace` - The runtime ID of the entry's workspace.
+/// * `direction` - Where the workspace was last toggled, if it was.
+/// * `window` - The entry, whose candidates are cleared.
+/// * `candidate` - The handle of the chosen window.
+///
+/// # Returns
+/// * The move to queue, or `None` if the workspace was not toggled or its windows not moved.
+fn apply_window_choice(
+    workspace: RuntimeId,
+    direction: Option<ToggleDirection>,
+    window: &mut Window,
+    candidate: usize,
+) -> Option<WindowOp> {
+    window.id = candidate;
+    window.title = get_window_title(HWND(candidate as *mut std::ffi::c_void));
+    window.valid = true;
+    window.move_error = None;
+    window.candidates.clear();
+    let position = match direction? {
+        ToggleDirection::ToHome => PositionKind::Home,
+        ToggleDirection::ToTarget => PositionKind::Target,
+        ToggleDirection::BringToFront => return None,
+    };
+    Some(WindowOp::MoveWindow {
+        workspace,
+        window: window.runtime_id,
+        position,
+    })
+}
+
 impl EframeApp for App {
     /// Updates the GUI layout and handles user interactions.
     ///
@@ -86,19 +571,99 @@ impl EframeApp for App {
     ///
     /// # Arguments
     /// * `ctx` - The egui context for rendering the GUI.
-    /// * `_frame` - The frame provided by eframe for managing the application window.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    /// * `frame` - The frame provided by eframe for managing the application window.
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let mut workspace_to_delete = None;
         let mut save_workspaces_flag = false;
         let mut new_workspace_to_add: Option<Workspace> = None;
+        let mut paste_requested = false;
+        let mut expand_all: Option<bool> = None;
+        let mut expansion_changes: Vec<(String, bool)> = Vec::new();
+        let mut dropped_window: Option<(usize, Arc<WindowSummary>)> = None;
+        ctx.memory_mut(|mem| mem.data.remove::<bool>(egui::Id::new(HOTKEY_EDITING_ID)));
+        let list_key = if current_tab(ctx) == MainTab::Workspaces {
+            self.handle_list_keys(ctx, &mut workspace_to_delete)
+        } else {
+            ListKey::None
+        };
+        let cursor = ctx
+            .memory(|mem| {
+                mem.data
+                    .get_temp::<usize>(egui::Id::new(WORKSPACE_CURSOR_ID))
+            })
+            .unwrap_or(0);
+
+        // Background threads request a repaint when something changes; otherwise only keep
+        // relative times ("5m ago") current
+        ctx.request_repaint_after(self.idle_repaint_interval());
+
+        // Window thumbnails are drawn by DWM straight into this window
+        if let Ok(handle) = frame.window_handle() {
+            if let RawWindowHandle::Win32(handle) = handle.as_raw() {
+                self.thumbnails
+                    .lock()
+                    .unwrap()
+                    .set_host(handle.hwnd.get() as usize);
+                *self.gui_hwnd.lock().unwrap() = Some(handle.hwnd.get() as usize);
+            }
+        }
+        self.handle_close_request(ctx);
+        self.render_window_choices(ctx);
+        let (show_thumbnails, mut capture_delay_secs) = {
+            let settings = self.settings.lock().unwrap();
+            (settings.show_thumbnails, settings.capture_delay_secs)
+        };
+        let capture_delay_before = capture_delay_secs;
+        self.render_capture_countdown(ctx);
+        self.render_schedule_prompt(ctx);
+
+        // F1 shows or hides the hotkey cheat sheet, unless a text field is being edited
+        if !ctx.wants_keyboard_input()
+            && ctx.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::F1))
+        {
+            toggle_cheat_sheet(ctx);
+        }
+
+        self.persist_ui_scale(ctx);
+        self.render_menu_bar(ctx);
+        self.render_tabs(ctx);
+        self.render_status_bar(ctx);
+        self.render_errors(ctx);
+        self.render_cheat_sheet(ctx);
+        let tab = current_tab(ctx);
+        if tab == MainTab::Workspaces {
+            self.render_window_browser(ctx);
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            match tab {
+                MainTab::Workspaces => {}
+                MainTab::Statistics => return self.render_usage_stats(ui),
+                MainTab::History => return self.render_history(ui),
+                MainTab::Logs => return self.render_logs(ui),
+                MainTab::Settings => return self.render_settings(ui),
+            }
+
             ui.heading("Multi Manager");
 
+            self.render_update_banner(ui);
+
             ui.horizontal(|ui| {
                 if ui.button("Save Workspaces").clicked() {
                     save_workspaces_flag = true;
-                    show_message_box("Save Workspaces Successful","Workspace Result");
+                }
+
+                let validating = self
+                    .validation_promise
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .is_some_and(|promise| promise.ready().is_none());
+                if ui
+                    .add_enabled(!validating, egui::Button::new("Validate All"))
+                    .clicked()
+                {
+                    self.start_validation();
                 }
 
                 if ui.button("Add New Workspace").clicked() {
@@ -106,34 +671,120 @@ impl EframeApp for App {
                     new_workspace_to_add = Some(Workspace {
                         name: format!("Workspace {}", workspaces.len() + 1),
                         hotkey: None,
+                        hotkey_mode: HotkeyMode::default(),
+                        home_hotkey: None,
+                        target_hotkey: None,
+                        save_hotkey: None,
+                        window_hotkey_base: None,
+                        gesture: None,
+                        controller_buttons: None,
                         windows: Vec::new(),
                         disabled: false,
-                        valid: false
+                        valid: false,
+                        move_retries: default_move_retries(),
+                        retry_backoff_ms: default_retry_backoff_ms(),
+                        move_delay_ms: 0,
+                        animation: AnimationOverride::default(),
+                        focus_behavior: FocusBehavior::default(),
+                        match_rule: MatchRule::default(),
+                        mode: WorkspaceMode::default(),
+                        exclusive: ExclusiveAction::default(),
+                        taskbar: TaskbarAutoHide::default(),
+                        other_desktop: OtherDesktopAction::default(),
+                        on_window_close: CloseReaction::default(),
+                        profiles: Vec::new(),
+                        color: None,
+                        icon: String::new(),
+                        notes: String::new(),
+                        capture_rules: Vec::new(),
+                        auto_arrange: None,
+                        pre_command: String::new(),
+                        post_command: String::new(),
+                        virtual_desktop: None,
+                        schedule: None,
+                        suppressed_windows: Vec::new(),
+                        previous_desktop: None,
+                        previous_taskbar_auto_hide: None,
+                        solo: None,
+                        last_focused: None,
+                        activity: ActivityLog::default(),
+                        selected: false,
+                        runtime_id: RuntimeId::default(),
                     });
                 }
+
+                ui.separator();
+                if ui.button("Expand All").clicked() {
+                    expand_all = Some(true);
+                }
+                if ui.button("Collapse All").clicked() {
+                    expand_all = Some(false);
+                }
+                let mut list_view = self.settings.lock().unwrap().list_view;
+                let detailed = ui.selectable_value(&mut list_view, ListView::Detailed, "Detailed");
+                let compact = ui.selectable_value(&mut list_view, ListView::Compact, "Compact");
+                if detailed.changed() || compact.changed() {
+                    let mut settings = self.settings.lock().unwrap();
+                    settings.list_view = list_view;
+                    if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+                        self.report_error(e);
+                    }
+                }
+                ui.separator();
+
+                let browser_id = egui::Id::new(WINDOW_BROWSER_OPEN_ID);
+                let mut browser_open = ui.memory(|mem| mem.data.get_temp::<bool>(browser_id).unwrap_or(false));
+                if ui
+                    .toggle_value(&mut browser_open, "Window Browser")
+                    .on_hover_text("List the open windows; drag one onto a workspace to add it")
+                    .changed()
+                {
+                    ui.memory_mut(|mem| mem.data.insert_temp(browser_id, browser_open));
+                }
+
+                if ui
+                    .button("Paste Workspace")
+                    .on_hover_text("Add workspaces copied as JSON from the clipboard")
+                    .clicked()
+                {
+                    paste_requested = true;
+                }
             });
 
             // Display debug info for the last detected hotkey
-            if let Some((hotkey, timestamp)) = self.last_hotkey_info.lock().unwrap().clone() {
+            if self.agent_attached {
+                ui.label("Hotkeys are handled by the background agent.");
+            } else if let Some((hotkey, timestamp)) = self.last_hotkey_info.lock().unwrap().clone() {
                 ui.label(format!(
-                    "Last Hotkey Detected: {} at {:?}",
+                    "Last Hotkey Detected: {} ({})",
                     hotkey,
-                    timestamp.elapsed()
+                    format_ago(timestamp.elapsed())
                 ));
             } else {
                 ui.label("No hotkey detected yet.");
             }
+            self.render_recent_triggers(ui);
             ui.separator();
 
             egui::ScrollArea::both()
                 .auto_shrink([false;2])
                 .show(ui, |ui| {
                     let mut workspaces = self.workspaces.lock().unwrap();
+                    let mut window_cache = self.window_cache.lock().unwrap();
 
                     let workspaces_len = workspaces.len();
+                    let monitors = Win32Backend.monitors();
+                    let monitor_info = enumerate_monitor_info();
+                    let work_areas = enumerate_work_areas();
+                    let (compact, collapsed_workspaces) = {
+                        let settings = self.settings.lock().unwrap();
+                        (settings.list_view == ListView::Compact, settings.collapsed_workspaces.clone())
+                    };
 
                     let mut move_up_index: Option<usize> = None;
                     let mut move_down_index: Option<usize> = None;
+                    let mut windows_to_transfer: Option<(usize, usize)> = None;
+                    let workspace_names: Vec<String> = workspaces.iter().map(|w| w.name.clone()).collect();
 
                     for (i, workspace) in workspaces.iter_mut().enumerate() {
                         let header_id = egui::Id::new(format!("workspace_{}_header", i));
@@ -147,41 +798,62 @@ impl EframeApp for App {
                         });
         
                         // Check if the workspace is valid
-                        let is_workspace_valid = {
-                            let hotkey_valid = workspace
-                                .hotkey
-                                .as_ref().is_some_and(|hotkey| is_valid_key_combo(hotkey));
-        
-                            let any_valid_window = workspace.windows.iter().any(|window| {
-                                unsafe { IsWindow(HWND(window.id as *mut std::ffi::c_void)).as_bool() }
-                            });
-        
-                            hotkey_valid && any_valid_window
-                        };
+                        let is_workspace_valid = validate_workspace(workspace, &mut window_cache);
         
                         workspace.valid = is_workspace_valid;
-        
-                        // Set header text color based on validity
-                        let header_text = if workspace.disabled{
-                            egui::RichText::new(&workspace.name).color(egui::Color32::ORANGE)
+
+                        if compact {
+                            let row = self.render_compact_row(ui, workspace);
+                            if i == cursor {
+                                highlight_cursor(ui, &row, list_key);
+                            }
+                            if let Some(window) = accept_window_drop(ui, &row) {
+                                dropped_window = Some((i, window));
+                            }
+                            continue;
                         }
-                        else if is_workspace_valid {
-                            egui::RichText::new(&workspace.name).color(egui::Color32::GREEN)
-                        } else {
-                            egui::RichText::new(&workspace.name).color(egui::Color32::RED)
-                        };
         
-                        ui.horizontal(|ui| {
+                        // Show the state with an icon and a text badge as well as color
+                        let (status_icon, status_badge, status_color) = workspace_status(workspace.disabled, is_workspace_valid);
+                        let header_text = egui::RichText::new(format!(
+                            "{} {}{} [{}]",
+                            status_icon,
+                            icon_prefix(workspace),
+                            workspace.get_header_text(),
+                            status_badge
+                        ))
+                        .color(status_color);
         
+                        ui.horizontal(|ui| {
+
+                        accessible(ui.checkbox(&mut workspace.selected, ""), format!("Select workspace '{}'", workspace.name))
+                            .on_hover_text("Select for File → Export Selected");
+                        color_swatch(ui, workspace);
+
+                        // The saved expansion is authoritative, as egui keys the state by index and
+                        // would otherwise carry it over when workspaces are reordered. Then apply
+                        // Expand/Collapse All or Enter on the workspace under the keyboard cursor
+                        let was_open = !collapsed_workspaces.contains(&workspace.name);
+                        let state_id = ui.make_persistent_id(egui::Id::new(i));
+                        let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(ctx, state_id, was_open);
+                        state.set_open(was_open);
+                        if let Some(open) = expand_all {
+                            state.set_open(open);
+                        }
+                        if i == cursor && list_key == ListKey::Expand {
+                            state.toggle(ui);
+                        }
+                        state.store(ctx);
+
                         let header_response = egui::CollapsingHeader::new(header_text)
                             .id_salt(i)
-                            .default_open(true)
+                            .default_open(was_open)
                             .show(ui, |ui| {
                                 use egui::{self, Color32};
                                 use log::{info, warn};
         
                                 ui.horizontal(|ui| {
-                                    ui.label("Hotkey:");
+                                    let hotkey_label = ui.label("Hotkey:");
         
                                     // Retrieve or initialize the temporary hotkey
                                     let workspace_id = i;
@@ -200,7 +872,8 @@ impl EframeApp for App {
                                     });
         
                                     // Editable text field for the hotkey
-                                    let response = ui.text_edit_singleline(&mut temp_hotkey);
+                                    let response = ui.text_edit_singleline(&mut temp_hotkey).labelled_by(hotkey_label.id);
+                                    suspend_hotkeys_while_focused(ui, &response);
         
                                     if response.changed() {
                                         // Save temporary changes back to memory
@@ -237,156 +910,808 @@ impl EframeApp for App {
         
                                     // Display validation result indicator
                                     match validation_result {
-                                        Some(true) => ui.colored_label(Color32::GREEN, "Valid"),
-                                        Some(false) => ui.colored_label(Color32::RED, "Invalid"),
+                                        Some(true) => ui.colored_label(Color32::GREEN, format!("{} Valid", ICON_VALID)),
+                                        Some(false) => ui.colored_label(Color32::RED, format!("{} Invalid", ICON_INVALID)),
                                         None => ui.label("Awaiting validation..."),
+                                    };
+
+                                    ui.separator();
+                                    if accessible(ui.add_enabled(!workspace.disabled, egui::Button::new("Toggle Now")), format!("Toggle '{}' now", workspace.name))
+                                        .on_hover_text("Toggle the windows as the hotkey would, to test the layout")
+                                        .clicked()
+                                    {
+                                        info!("Toggling workspace '{}' from its editor.", workspace.name);
+                                        self.record_trigger(&workspace.name);
+                                        self.window_ops.send(WindowOp::Toggle(workspace.runtime_id));
+                                    }
+                                    for (position, text) in [(PositionKind::Home, "All to Home"), (PositionKind::Target, "All to Target")] {
+                                        if accessible(ui.add_enabled(!workspace.disabled, egui::Button::new(text)), format!("Move every window of '{}' to {:?}", workspace.name, position))
+                                            .on_hover_text("Move every window there, wherever the windows are now")
+                                            .clicked()
+                                        {
+                                            info!("Moving every window of '{}' to {:?} from its editor.", workspace.name, position);
+                                            self.record_trigger(&workspace.name);
+                                            self.window_ops.send(WindowOp::MoveAll { workspace: workspace.runtime_id, position });
+                                        }
                                     }
                                 });
-        
-                                let mut window_to_delete = None;
-                                for (j, window) in workspace.windows.iter_mut().enumerate() {
-                                    let hwnd = HWND(window.id as *mut std::ffi::c_void); 
-                                    let exists = unsafe { IsWindow(hwnd).as_bool() }; 
-                                    window.valid = exists;
-                                    ui.horizontal(|ui| {
-                                        ui.label(&window.title);
-                                
-                                        if ui.button("Delete").clicked() {
-                                            window_to_delete = Some(j);
-                                            info!("Deleting window '{}'", window.title);
+
+                                ui.horizontal(|ui| {
+                                    let before = workspace.hotkey_mode;
+                                    ui.label("Hotkey mode:");
+                                    ui.radio_value(&mut workspace.hotkey_mode, HotkeyMode::Register, "Registered")
+                                        .on_hover_text("Register the hotkey with Windows; fails if another program holds the combination");
+                                    ui.radio_value(&mut workspace.hotkey_mode, HotkeyMode::Hook, "Keyboard hook")
+                                        .on_hover_text("Catch the hotkey with a low-level keyboard hook, taking it from the program that holds it");
+                                    if workspace.hotkey_mode != before {
+                                        info!("Hotkey mode of '{}' set to {:?}.", workspace.name, workspace.hotkey_mode);
+                                        // Unregister in the old mode; the hotkey is registered again in the new one
+                                        unregister_hotkey(&self.registered_hotkeys, i as i32);
+                                        if let Some(hotkey) = workspace.hotkey.as_deref().and_then(Hotkey::parse) {
+                                            set_hotkey_hooked(hotkey, workspace.hotkey_mode == HotkeyMode::Hook);
                                         }
-        
-                                        // Add the colored indicator for HWND validity
-                                        if exists {
-                                                    // Define the label and capture its response
-                                                    let label_response = ui.colored_label(
-                                                        egui::Color32::GREEN,
-                                                        format!("HWND: {:?}", window.id),
-                                                    );
-                                                
-                                                    // Create a unique ID for the popup menu
-                                                    let popup_id = egui::Id::new(format!("hwnd_context_menu_workspace_{}_{}", i, j));
+                                    }
+                                });
+                                if workspace.hotkey_mode == HotkeyMode::Hook {
+                                    ui.colored_label(
+                                        Color32::ORANGE,
+                                        "The keyboard hook sees every key press and swallows this combination from other programs. \
+                                         It misses keys typed into elevated windows unless Multi Manager runs as administrator, \
+                                         can't take Win+L or Ctrl+Alt+Delete, and is removed by Windows if the system stalls.",
+                                    );
+                                }
 
-                                                    // Handle right-click to toggle popup visibility
-                                                    if label_response.hovered() && ui.input(|i| i.pointer.secondary_clicked()) && !ui.memory(|mem| mem.is_popup_open(popup_id)) {
-                                                        ui.memory_mut(|mem| mem.open_popup(popup_id));
-                                                        }
-                                                
-                                                    // Render the popup menu if it's open
-                                                    egui::popup::popup_below_widget(
-                                                        ui,
-                                                        popup_id,
-                                                        &label_response, 
-                                                        egui::PopupCloseBehavior::CloseOnClickOutside,
-                                                        |ui| {
-                                                            ui.label("Options:");
-                                                
-                                                            // Add the "Force Recapture" button
-                                                            if ui.button("Force Recapture").clicked() {
-                                                                info!("Force Recapture triggered for HWND: {:?}", window.id);
-                                                                if let Some("Enter") = listen_for_keys_with_dialog() {
-                                                                    if let Some((new_hwnd, new_title)) = get_active_window() {
+                                ui.horizontal(|ui| {
+                                    optional_hotkey_edit(ui, "All to Home hotkey:", &mut workspace.home_hotkey);
+                                    optional_hotkey_edit(ui, "All to Target hotkey:", &mut workspace.target_hotkey);
+                                    optional_hotkey_edit(ui, "Save Layout hotkey:", &mut workspace.save_hotkey);
+                                });
 
-                                                                        window.id = new_hwnd.0 as usize;
-                                                                        window.title = new_title;
-                                                                        info!(
-                                                                            "Force Recaptured window '{}', new HWND: {:?}",
-                                                                            window.title, new_hwnd
-                                                                        );
-                                                                    } else {
-                                                                        warn!("Force Recapture canceled or no active window detected.");
-                                                                    }
-                                                                }
-                                                                
-                                                                ui.memory_mut(|mem| mem.close_popup());
-                                                            }
-                                                        },
-                                                    );
-                                                    
-                                        } else {
-                                            ui.colored_label(egui::Color32::RED, format!("HWND: {:?}", window.id));
-                                            if ui.button("Recapture").clicked() {
-                                                if let Some("Enter") = listen_for_keys_with_dialog() {
-                                                    if let Some((new_hwnd, new_title)) = get_active_window() {
-                                                        // Update the invalid window with the new HWND but retain home/target
-                                                        window.id = new_hwnd.0 as usize;
-                                                        window.title = new_title;
-                                                        info!(
-                                                            "Recaptured window '{}', new HWND: {:?}",
-                                                            window.title, new_hwnd
-                                                            );
-                                                        } else {
-                                                            warn!("Recapture canceled or no active window detected.");
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                    });
-                                
-                                    ui.horizontal(|ui| {
-                                        ui.label("Home:");
-                                        ui.add(egui::DragValue::new(&mut window.home.0).prefix("x: "));
-                                        ui.add(egui::DragValue::new(&mut window.home.1).prefix("y: "));
-                                        ui.add(egui::DragValue::new(&mut window.home.2).prefix("w: "));
-                                        ui.add(egui::DragValue::new(&mut window.home.3).prefix("h: "));
-                                
-                                        if ui.button("Capture Home").clicked() {
-                                            if let Ok((x, y, w, h)) = get_window_position(hwnd) {
-                                                window.home = (x, y, w, h);
-                                                info!(
-                                                    "Captured window position for Home using window ID {:?}: {:?}",
-                                                    window.id, window.home
-                                                );
-                                            } else {
-                                                warn!(
-                                                    "Failed to capture window position for Home using window ID {:?}",
-                                                    window.id
-                                                );
-                                            }
+                                ui.horizontal(|ui| {
+                                    let label = ui.label("Mouse gesture:");
+                                    let mut text = workspace.gesture.clone().unwrap_or_default();
+                                    if ui
+                                        .add(egui::TextEdit::singleline(&mut text).desired_width(60.0).hint_text("e.g. DR"))
+                                        .labelled_by(label.id)
+                                        .on_hover_text("Strokes drawn with the right button held on the desktop: U, D, L, and R. DR is an L; RDR a Z.")
+                                        .changed()
+                                    {
+                                        let text = text.trim().to_uppercase();
+                                        workspace.gesture = (!text.is_empty()).then_some(text);
+                                    }
+                                    match &workspace.gesture {
+                                        Some(gesture) if is_valid_gesture(gesture) => {
+                                            ui.colored_label(Color32::GREEN, ICON_VALID);
                                         }
-                                
-                                        if ui.button("Move to Home").clicked() {
-                                            if let Err(e) = move_window(hwnd, window.home.0, window.home.1, window.home.2, window.home.3) {
-                                                warn!("Error moving window '{}': {}", window.title, e);
-                                            }
+                                        Some(_) => {
+                                            ui.colored_label(Color32::RED, format!("{} Use U, D, L, and R", ICON_INVALID));
                                         }
-                                    });
-                                
-                                    ui.horizontal(|ui| {
-                                        ui.label("Target:");
-                                        ui.add(egui::DragValue::new(&mut window.target.0).prefix("x: "));
-                                        ui.add(egui::DragValue::new(&mut window.target.1).prefix("y: "));
-                                        ui.add(egui::DragValue::new(&mut window.target.2).prefix("w: "));
-                                        ui.add(egui::DragValue::new(&mut window.target.3).prefix("h: "));
+                                        None => {}
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let label = ui.label("Controller buttons:");
+                                    let mut text = workspace.controller_buttons.clone().unwrap_or_default();
+                                    if ui
+                                        .add(egui::TextEdit::singleline(&mut text).desired_width(100.0).hint_text("e.g. LB+RB+A"))
+                                        .labelled_by(label.id)
+                                        .on_hover_text("Game controller buttons pressed together, joined by +: A, B, X, Y, LB, RB, LT, RT, Back, Start, LS, RS, Up, Down, Left, Right.")
+                                        .changed()
+                                    {
+                                        let text = text.trim().to_string();
+                                        workspace.controller_buttons = (!text.is_empty()).then_some(text);
+                                    }
+                                    match &workspace.controller_buttons {
+                                        Some(combo) if parse_controller_combo(combo).is_some() => {
+                                            ui.colored_label(Color32::GREEN, ICON_VALID);
+                                        }
+                                        Some(_) => {
+                                            ui.colored_label(Color32::RED, format!("{} Unknown button", ICON_INVALID));
+                                        }
+                                        None => {}
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let label = ui.label("Window number hotkeys:");
+                                    let mut text = workspace.window_hotkey_base.clone().unwrap_or_default();
+                                    let response = ui
+                                        .add(egui::TextEdit::singleline(&mut text).desired_width(100.0).hint_text("e.g. Ctrl+Alt"))
+                                        .labelled_by(label.id)
+                                        .on_hover_text("Modifiers that, pressed with 1 to 9, toggle that window of the workspace");
+                                    suspend_hotkeys_while_focused(ui, &response);
+                                    if response.changed() {
+                                        workspace.window_hotkey_base = (!text.trim().is_empty()).then(|| text.trim().to_string());
+                                    }
+                                    if response.lost_focus() {
+                                        migrate_hotkey_base(&mut workspace.window_hotkey_base);
+                                    }
+                                    match &workspace.window_hotkey_base {
+                                        Some(base) if is_valid_hotkey_base(base) => {
+                                            ui.colored_label(Color32::GREEN, ICON_VALID);
+                                            ui.weak(format!("{}+1 … {}+9", base, base));
+                                        }
+                                        Some(_) => {
+                                            ui.colored_label(Color32::RED, format!("{} Only Ctrl, Alt, Shift, Win, and Caps", ICON_INVALID));
+                                        }
+                                        None => {}
+                                    }
+                                });
+        
+                                ui.horizontal(|ui| {
+                                    let label = ui.label("Move retries:");
+                                    ui.add(egui::DragValue::new(&mut workspace.move_retries).range(0..=10)).labelled_by(label.id);
+                                    let label = ui.label("Backoff (ms):");
+                                    ui.add(egui::DragValue::new(&mut workspace.retry_backoff_ms).range(0..=5000)).labelled_by(label.id);
+                                    let label = ui.label("Delay between windows (ms):");
+                                    ui.add(egui::DragValue::new(&mut workspace.move_delay_ms).range(0..=10000)).labelled_by(label.id);
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Animation:").on_hover_text("How the windows move to their positions");
+                                    let custom = match workspace.animation {
+                                        AnimationOverride::Custom(animation) => animation,
+                                        _ => global_animation(),
+                                    };
+                                    ui.radio_value(&mut workspace.animation, AnimationOverride::Global, "As in settings");
+                                    ui.radio_value(&mut workspace.animation, AnimationOverride::Instant, "Instant");
+                                    let is_custom = matches!(workspace.animation, AnimationOverride::Custom(_));
+                                    if ui.radio(is_custom, "Custom").clicked() && !is_custom {
+                                        workspace.animation = AnimationOverride::Custom(custom);
+                                    }
+                                    if let AnimationOverride::Custom(animation) = &mut workspace.animation {
+                                        animation_edit(ui, &format!("animation_{}", i), animation);
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Appearance:");
+                                    let mut has_color = workspace.color.is_some();
+                                    if accessible(ui.checkbox(&mut has_color, "Color"), format!("Give workspace '{}' a color", workspace.name)).changed() {
+                                        workspace.color = has_color.then_some([100, 150, 250]);
+                                    }
+                                    if let Some(color) = &mut workspace.color {
+                                        accessible(ui.color_edit_button_srgb(color), format!("Color of workspace '{}'", workspace.name));
+                                    }
+                                    let label = ui.label("Icon:");
+                                    ui.add(egui::TextEdit::singleline(&mut workspace.icon).char_limit(WORKSPACE_ICON_CHAR_LIMIT).desired_width(40.0).hint_text("🖥"))
+                                        .labelled_by(label.id)
+                                        .on_hover_text("An emoji or symbol shown before the name");
+                                });
+
+                                ui.horizontal_top(|ui| {
+                                    let label = ui.label("Notes:");
+                                    ui.add(egui::TextEdit::multiline(&mut workspace.notes).desired_rows(2).hint_text("What this layout is for, app quirks..."))
+                                        .labelled_by(label.id);
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("When several windows match:");
+                                    ui.radio_value(&mut workspace.match_rule, MatchRule::MostRecentlyActive, "Most recently active");
+                                    ui.radio_value(&mut workspace.match_rule, MatchRule::Ask, "Ask me");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Mode:");
+                                    ui.radio_value(&mut workspace.mode, WorkspaceMode::Toggle, "Toggle positions");
+                                    ui.radio_value(&mut workspace.mode, WorkspaceMode::BringToFront, "Bring to front only");
+                                    ui.radio_value(&mut workspace.mode, WorkspaceMode::Solo, "Solo")
+                                        .on_hover_text("Maximize one window and minimize the workspace's others; activate again to put them back");
+                                    if workspace.mode == WorkspaceMode::Solo && !workspace.windows.is_empty() {
+                                        let solo_index = workspace.windows.iter().position(|window| window.solo).unwrap_or(0);
+                                        let mut selected = solo_index;
+                                        let solo_label = ui.label("Solo window:");
+                                        egui::ComboBox::from_id_salt(format!("solo_window_{}", i))
+                                            .selected_text(workspace.windows[solo_index].title.clone())
+                                            .show_ui(ui, |ui| {
+                                                for (j, window) in workspace.windows.iter().enumerate() {
+                                                    ui.selectable_value(&mut selected, j, &window.title);
+                                                }
+                                            })
+                                            .response
+                                            .labelled_by(solo_label.id);
+                                        if selected != solo_index {
+                                            for (j, window) in workspace.windows.iter_mut().enumerate() {
+                                                window.solo = j == selected;
+                                            }
+                                        }
+                                    }
+                                    if workspace.solo.is_some() {
+                                        ui.colored_label(Color32::YELLOW, "Solo is on");
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Other windows:");
+                                    ui.radio_value(&mut workspace.exclusive, ExclusiveAction::Off, "Leave alone");
+                                    ui.radio_value(&mut workspace.exclusive, ExclusiveAction::Minimize, "Minimize");
+                                    ui.radio_value(&mut workspace.exclusive, ExclusiveAction::Hide, "Hide");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("Taskbar:").on_hover_text("Set when sending the workspace to its targets; the previous setting is restored when it goes home");
+                                    ui.radio_value(&mut workspace.taskbar, TaskbarAutoHide::Unchanged, "Leave alone");
+                                    ui.radio_value(&mut workspace.taskbar, TaskbarAutoHide::AutoHide, "Auto-hide");
+                                    ui.radio_value(&mut workspace.taskbar, TaskbarAutoHide::AlwaysShow, "Always show");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("On another desktop:").on_hover_text("What toggling does with windows that are on another virtual desktop");
+                                    ui.radio_value(&mut workspace.other_desktop, OtherDesktopAction::Pull, "Bring here");
+                                    ui.radio_value(&mut workspace.other_desktop, OtherDesktopAction::Skip, "Skip");
+                                    ui.radio_value(&mut workspace.other_desktop, OtherDesktopAction::SwitchDesktop, "Switch to their desktop");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    ui.label("When a window closes:").on_hover_text("What happens to the other windows when one of them closes, or its app exits, while the workspace is at its targets");
+                                    ui.radio_value(&mut workspace.on_window_close, CloseReaction::Nothing, "Nothing");
+                                    ui.add_enabled_ui(workspace.auto_arrange.is_some() || workspace.on_window_close == CloseReaction::Rearrange, |ui| {
+                                        ui.radio_value(&mut workspace.on_window_close, CloseReaction::Rearrange, "Re-arrange the rest")
+                                            .on_hover_text("Lay the remaining windows out again; needs Auto-arrange targets")
+                                            .on_disabled_hover_text("Needs Auto-arrange targets");
+                                    });
+                                    ui.radio_value(&mut workspace.on_window_close, CloseReaction::AllHome, "Send all home");
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let mut enabled = workspace.auto_arrange.is_some();
+                                    if accessible(ui.checkbox(&mut enabled, "Auto-arrange targets"), format!("Auto-arrange the targets of '{}'", workspace.name))
+                                        .on_hover_text("Compute target positions when toggling, over whichever windows exist, instead of using the stored targets")
+                                        .changed()
+                                    {
+                                        workspace.auto_arrange = enabled.then(AutoArrange::default);
+                                    }
+                                    if let Some(settings) = &mut workspace.auto_arrange {
+                                        let algorithm_label = ui.label("Layout:");
+                                        egui::ComboBox::from_id_salt(format!("auto_arrange_{}", i))
+                                            .selected_text(settings.algorithm.label())
+                                            .show_ui(ui, |ui| {
+                                                for algorithm in LayoutAlgorithm::ALL {
+                                                    ui.selectable_value(&mut settings.algorithm, algorithm, algorithm.label());
+                                                }
+                                            })
+                                            .response
+                                            .labelled_by(algorithm_label.id);
+                                        let mut monitor = settings.monitor + 1;
+                                        let label = ui.label("Monitor:");
+                                        ui.add(egui::DragValue::new(&mut monitor).range(1..=monitor_info.len().max(1))).labelled_by(label.id);
+                                        settings.monitor = monitor - 1;
+                                        let label = ui.label("Gap:");
+                                        ui.add(egui::DragValue::new(&mut settings.gap).range(0..=200).suffix(" px")).labelled_by(label.id);
+                                        if settings.algorithm == LayoutAlgorithm::MainAndSide {
+                                            let label = ui.label("Main:");
+                                            ui.add(egui::DragValue::new(&mut settings.main_percent).range(10..=90).suffix("%")).labelled_by(label.id);
+                                        }
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let mut enabled = workspace.virtual_desktop.is_some();
+                                    if accessible(ui.checkbox(&mut enabled, "Own virtual desktop"), format!("Give '{}' its own virtual desktop", workspace.name))
+                                        .on_hover_text("Switch to this virtual desktop, creating it if needed, when sending the workspace to its targets, and bring its windows along. Going home returns to the previous desktop.")
+                                        .changed()
+                                    {
+                                        workspace.virtual_desktop = enabled.then(|| workspace.name.clone());
+                                    }
+                                    if let Some(name) = &mut workspace.virtual_desktop {
+                                        let label = ui.label("Name:");
+                                        ui.add(egui::TextEdit::singleline(name).desired_width(140.0)).labelled_by(label.id);
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let mut enabled = workspace.schedule.is_some();
+                                    if accessible(ui.checkbox(&mut enabled, "Schedule"), format!("Activate '{}' at set times of day", workspace.name))
+                                        .on_hover_text("Send the workspace to its targets at the start time and home at the end time, every day, after a prompt that can skip it")
+                                        .changed()
+                                    {
+                                        workspace.schedule = enabled.then(|| Schedule {
+                                            start: "09:00".to_string(),
+                                            end: "17:00".to_string(),
+                                        });
+                                    }
+                                    if let Some(schedule) = &mut workspace.schedule {
+                                        let label = ui.label("Targets at:");
+                                        ui.add(egui::TextEdit::singleline(&mut schedule.start).desired_width(50.0).hint_text("09:00")).labelled_by(label.id);
+                                        let label = ui.label("Home at:");
+                                        ui.add(egui::TextEdit::singleline(&mut schedule.end).desired_width(50.0).hint_text("none"))
+                                            .labelled_by(label.id)
+                                            .on_hover_text("Leave empty to keep the windows where they are");
+                                        if schedule.is_valid() {
+                                            ui.colored_label(Color32::GREEN, ICON_VALID);
+                                        } else {
+                                            ui.colored_label(Color32::RED, format!("{} Use 24-hour times such as 17:30", ICON_INVALID));
+                                        }
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let focus_label = ui.label("Focus after toggle:");
+                                    let selected_text = match workspace.focus_behavior {
+                                        FocusBehavior::ActivateAll => "Activate all windows".to_string(),
+                                        FocusBehavior::None => "Focus nothing".to_string(),
+                                        FocusBehavior::Preserve => "Keep previous focus".to_string(),
+                                        FocusBehavior::Window(index) => match workspace.windows.get(index) {
+                                            Some(window) => format!("Focus '{}'", window.title),
+                                            None => format!("Focus window #{}", index + 1),
+                                        },
+                                    };
+                                    let focus_combo = egui::ComboBox::from_id_salt(format!("focus_behavior_{}", i))
+                                        .selected_text(selected_text)
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(&mut workspace.focus_behavior, FocusBehavior::ActivateAll, "Activate all windows");
+                                            ui.selectable_value(&mut workspace.focus_behavior, FocusBehavior::None, "Focus nothing");
+                                            ui.selectable_value(&mut workspace.focus_behavior, FocusBehavior::Preserve, "Keep previous focus");
+                                            for (j, window) in workspace.windows.iter().enumerate() {
+                                                ui.selectable_value(&mut workspace.focus_behavior, FocusBehavior::Window(j), format!("Focus '{}'", window.title));
+                                            }
+                                        });
+                                    focus_combo.response.labelled_by(focus_label.id);
+                                });
+
+                                egui::CollapsingHeader::new("Layout")
+                                    .id_salt(format!("layout_{}", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        let position_id = egui::Id::new(("layout_position", i));
+                                        let mut position = ui.memory(|mem| mem.data.get_temp::<PositionKind>(position_id)).unwrap_or(PositionKind::Target);
+                                        ui.horizontal(|ui| {
+                                            ui.label("Edit:");
+                                            ui.selectable_value(&mut position, PositionKind::Home, "Home");
+                                            ui.selectable_value(&mut position, PositionKind::Target, "Target");
+                                        });
+                                        ui.memory_mut(|mem| mem.data.insert_temp(position_id, position));
+                                        ui.weak("Drag a window to move it, or its corner to resize it.");
+                                        let fill = workspace.color.map_or(egui::Color32::from_rgb(100, 150, 250), |[r, g, b]| egui::Color32::from_rgb(r, g, b));
+                                        layout_canvas(ui, i, &monitor_info, &mut workspace.windows, position, fill);
+                                    });
+
+                                egui::CollapsingHeader::new("Monitor Profiles")
+                                    .id_salt(format!("profiles_{}", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        let active = workspace.active_profile(&monitors).map(str::to_string);
+                                        match &active {
+                                            Some(name) => ui.label(format!("Toggling uses profile '{}' for the connected displays.", name)),
+                                            None => ui.label("No profile matches the connected displays; toggling uses the positions above."),
+                                        };
+
+                                        let mut profile_to_load = None;
+                                        let mut profile_to_remove = None;
+                                        for profile in &workspace.profiles {
+                                            ui.horizontal(|ui| {
+                                                if active.as_deref() == Some(profile.name.as_str()) {
+                                                    ui.colored_label(Color32::GREEN, format!("{} {} (active)", ICON_VALID, profile.name));
+                                                } else {
+                                                    ui.label(&profile.name);
+                                                }
+                                                ui.weak(format!("{} monitor(s)", profile.monitors.len()));
+                                                if accessible(ui.button("Load"), format!("Load profile '{}'", profile.name)).on_hover_text("Copy this profile's positions into Home and Target for editing").clicked() {
+                                                    profile_to_load = Some(profile.name.clone());
+                                                }
+                                                if accessible(ui.button("Delete"), format!("Delete profile '{}'", profile.name)).clicked() {
+                                                    profile_to_remove = Some(profile.name.clone());
+                                                }
+                                            });
+                                        }
+                                        if let Some(name) = profile_to_load {
+                                            workspace.load_profile(&name);
+                                        }
+                                        if let Some(name) = profile_to_remove {
+                                            workspace.remove_profile(&name);
+                                        }
+
+                                        ui.horizontal(|ui| {
+                                            let name_id = egui::Id::new(format!("profile_name_{}", i));
+                                            let mut profile_name = ui.memory_mut(|mem| mem.data.get_temp::<String>(name_id).unwrap_or_default());
+                                            accessible(ui.add(egui::TextEdit::singleline(&mut profile_name).hint_text("Profile name")), "New monitor profile name");
+                                            let can_save = !profile_name.trim().is_empty();
+                                            if ui.add_enabled(can_save, egui::Button::new("Save Positions for Current Displays")).clicked() {
+                                                workspace.save_profile(profile_name.trim(), monitors.clone());
+                                                profile_name.clear();
+                                            }
+                                            ui.memory_mut(|mem| mem.data.insert_temp(name_id, profile_name));
+                                        });
+                                    });
+
+                                egui::CollapsingHeader::new(format!("Auto-Capture Rules ({})", workspace.capture_rules.len()))
+                                    .id_salt(format!("capture_rules_{}", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        if render_capture_rules(ui, i, &mut workspace.capture_rules) {
+                                            let adopted = adopt_rule_windows(&Win32Backend, workspace);
+                                            announce(ctx, format!("Auto-captured {} window(s) into '{}'.", adopted.len(), workspace.name));
+                                        }
+                                    });
+
+                                let commands = !workspace.pre_command.trim().is_empty() as usize + !workspace.post_command.trim().is_empty() as usize;
+                                egui::CollapsingHeader::new(format!("Commands ({})", commands))
+                                    .id_salt(format!("commands_{}", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        ui.weak("Shell commands run on every toggle, e.g. to start a VPN or switch OBS scenes. %WORKSPACE% and %DIRECTION% (Target, Home, or Front) are replaced, and are also in MM_WORKSPACE and MM_DIRECTION.");
+                                        ui.horizontal(|ui| {
+                                            let label = ui.label("Before:");
+                                            ui.add(egui::TextEdit::singleline(&mut workspace.pre_command).hint_text("Waited for before windows move").desired_width(f32::INFINITY)).labelled_by(label.id);
+                                        });
+                                        ui.horizontal(|ui| {
+                                            let label = ui.label("After:");
+                                            ui.add(egui::TextEdit::singleline(&mut workspace.post_command).hint_text("Started after windows move").desired_width(f32::INFINITY)).labelled_by(label.id);
+                                        });
+                                    });
+
+                                egui::CollapsingHeader::new("Activity")
+                                    .id_salt(format!("activity_{}", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        if workspace.activity.is_empty() {
+                                            ui.label("No toggles recorded yet.");
+                                            return;
+                                        }
+                                        if accessible(ui.button("Clear"), format!("Clear activity of '{}'", workspace.name)).clicked() {
+                                            workspace.activity.clear();
+                                            return;
+                                        }
+                                        for entry in workspace.activity.newest_first() {
+                                            ui.label(format!(
+                                                "{} - {:?}",
+                                                entry.triggered_at.format("%H:%M:%S"),
+                                                entry.direction
+                                            ));
+                                            ui.indent(entry.triggered_at.timestamp_nanos_opt(), |ui| {
+                                                for window in &entry.windows {
+                                                    let (icon, color) = if window.outcome.is_success() {
+                                                        (ICON_VALID, Color32::GREEN)
+                                                    } else if matches!(window.outcome, WindowOutcome::Resisted { .. }) {
+                                                        ("⚠", Color32::YELLOW)
+                                                    } else {
+                                                        (ICON_INVALID, Color32::RED)
+                                                    };
+                                                    ui.colored_label(
+                                                        color,
+                                                        format!("{} {}: {}", icon, window.title, window.outcome.describe()),
+                                                    );
+                                                }
+                                            });
+                                        }
+                                    });
+
+                                let mut window_to_delete = None;
+                                let active_profile = workspace.active_profile(&monitors).map(str::to_string);
+                                for (j, window) in workspace.windows.iter_mut().enumerate() {
+                                    let hwnd = HWND(window.id as *mut std::ffi::c_void); 
+                                    let cached = window_cache.get(window.id);
+                                    let exists = cached.exists;
+                                    let current_rect = cached.rect;
+                                    window.valid = exists;
+
+                                    // Keep the displayed title current as documents change
+                                    if exists && !cached.title.is_empty() && cached.title != window.title {
+                                        window.title = cached.title.clone();
+                                    }
+
+                                    ui.horizontal(|ui| {
+                                        accessible(ui.checkbox(&mut window.selected, ""), format!("Select window '{}'", window.title))
+                                            .on_hover_text("Select for bulk editing");
+                                        if let Some(icon) = exists.then(|| self.window_icon(ctx, window)).flatten() {
+                                            ui.image((icon.id(), egui::vec2(16.0, 16.0)));
+                                        }
+                                        let title_response = ui.label(&window.title);
+                                        if window.title != window.capture_title {
+                                            title_response.on_hover_text(format!("Captured as: {}", window.capture_title));
+                                        }
+                                        if !window.exe.is_empty() {
+                                            ui.weak(&window.exe);
+                                        }
+                                        if let Some(rect) = current_rect {
+                                            position_chip(ui, rect, window.home_for(active_profile.as_deref()), window.target_for(active_profile.as_deref()));
+                                        }
+                                
+                                        if accessible(ui.button("Delete"), format!("Delete window '{}'", window.title)).clicked() {
+                                            window_to_delete = Some(j);
+                                            info!("Deleting window '{}'", window.title);
+                                        }
+        
+                                        // Add the colored indicator for HWND validity
+                                        if exists {
+                                                    // Define the label and capture its response; it is clickable so the
+                                                    // options can also be opened from the keyboard
+                                                    let label_response = accessible(
+                                                        ui.add(egui::Label::new(egui::RichText::new(format!("{} HWND: {:?}", ICON_VALID, window.id)).color(egui::Color32::GREEN)).sense(egui::Sense::click())),
+                                                        format!("Options for window '{}'", window.title),
+                                                    );
+                                                
+                                                    // Create a unique ID for the popup menu
+                                                    let popup_id = egui::Id::new(format!("hwnd_context_menu_workspace_{}_{}", i, j));
+
+                                                    // Handle right-click (or click/Enter) to toggle popup visibility
+                                                    if (label_response.clicked() || (label_response.hovered() && ui.input(|i| i.pointer.secondary_clicked()))) && !ui.memory(|mem| mem.is_popup_open(popup_id)) {
+                                                        ui.memory_mut(|mem| mem.open_popup(popup_id));
+                                                        }
+                                                
+                                                    // Render the popup menu if it's open
+                                                    egui::popup::popup_below_widget(
+                                                        ui,
+                                                        popup_id,
+                                                        &label_response, 
+                                                        egui::PopupCloseBehavior::CloseOnClickOutside,
+                                                        |ui| {
+                                                            ui.label("Options:");
+                                                
+                                                            // Add the "Force Recapture" button
+                                                            if ui.button("Force Recapture").clicked() {
+                                                                info!("Force Recapture triggered for HWND: {:?}", window.id);
+                                                                if let Some("Enter") = listen_for_keys_with_dialog() {
+                                                                    if let Some((new_hwnd, new_title)) = get_active_window() {
+
+                                                                        window.id = new_hwnd.0 as usize;
+                                                                        window.capture_title = new_title.clone();
+                                                                        window.exe = get_window_exe(new_hwnd);
+                                                                        window.class_name = get_window_class(new_hwnd);
+                                                                        window.title = new_title;
+                                                                        announce(ctx, format!(
+                                                                            "Force Recaptured window '{}', new HWND: {:?}",
+                                                                            window.title, new_hwnd
+                                                                        ));
+                                                                    } else {
+                                                                        warn!("Force Recapture canceled or no active window detected.");
+                                                                        announce(ctx, "Recapture canceled or no active window detected.");
+                                                                    }
+                                                                }
+                                                                
+                                                                ui.memory_mut(|mem| mem.close_popup());
+                                                            }
+
+                                                            ui.separator();
+                                                            let mut copied = None;
+                                                            if ui.button("Copy HWND").clicked() {
+                                                                copied = Some(("HWND", format!("{:#x}", window.id)));
+                                                            }
+                                                            if ui.button("Copy Current Rect").clicked() {
+                                                                match get_window_position(hwnd) {
+                                                                    Ok((x, y, w, h)) => copied = Some(("current rect", format!("{}, {}, {}, {}", x, y, w, h))),
+                                                                    Err(e) => announce(ctx, format!("Could not read the position of '{}': {}", window.title, e)),
+                                                                }
+                                                            }
+                                                            if ui.button("Copy Process Path").clicked() {
+                                                                match get_process_path(hwnd) {
+                                                                    Some(path) => copied = Some(("process path", path)),
+                                                                    None => announce(ctx, format!("Could not read the process path of '{}'.", window.title)),
+                                                                }
+                                                            }
+                                                            if let Some((what, text)) = copied {
+                                                                match set_clipboard_text(&text) {
+                                                                    Ok(()) => announce(ctx, format!("Copied the {} of '{}': {}", what, window.title, text)),
+                                                                    Err(e) => self.report_error(e),
+                                                                }
+                                                                ui.memory_mut(|mem| mem.close_popup());
+                                                            }
+                                                        },
+                                                    );
+                                                    
+                                        } else {
+                                            ui.colored_label(egui::Color32::RED, format!("{} HWND: {:?} (not found)", ICON_INVALID, window.id));
+                                            if accessible(ui.button("Recapture"), format!("Recapture window '{}'", window.title)).clicked() {
+                                                if let Some("Enter") = listen_for_keys_with_dialog() {
+                                                    if let Some((new_hwnd, new_title)) = get_active_window() {
+                                                        // Update the invalid window with the new HWND but retain home/target
+                                                        window.id = new_hwnd.0 as usize;
+                                                        window.capture_title = new_title.clone();
+                                                        window.exe = get_window_exe(new_hwnd);
+                                                        window.class_name = get_window_class(new_hwnd);
+                                                        window.title = new_title;
+                                                        announce(ctx, format!(
+                                                            "Recaptured window '{}', new HWND: {:?}",
+                                                            window.title, new_hwnd
+                                                            ));
+                                                        } else {
+                                                            warn!("Recapture canceled or no active window detected.");
+                                                            announce(ctx, "Recapture canceled or no active window detected.");
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                    });
+
+                                    if exists && show_thumbnails {
+                                        self.window_thumbnail(ui, window);
+                                    }
+
+                                    ui.horizontal(|ui| {
+                                        let label = ui.label("Settle (ms):");
+                                        accessible(ui.add(egui::DragValue::new(&mut window.settle_ms).range(0..=10000)), format!("Settle time in milliseconds for '{}'", window.title)).labelled_by(label.id);
+                                        ui.checkbox(&mut window.position_flags.no_activate, "Don't activate");
+                                        ui.checkbox(&mut window.position_flags.no_size, "Move only");
+                                        ui.checkbox(&mut window.position_flags.no_move, "Resize only");
+                                        ui.checkbox(&mut window.position_flags.keep_z_order, "Keep z-order");
+                                        ui.checkbox(&mut window.clamp_to_work_area, "Keep in work area")
+                                            .on_hover_text("Fit positions inside the monitor's work area so they never cover the taskbar or docked toolbars");
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        let mut by_instance = window.instance.is_some();
+                                        if accessible(ui.checkbox(&mut by_instance, "Match instance #"), format!("Match '{}' by instance number", window.title))
+                                            .on_hover_text("When several windows match (e.g. three Chrome windows), always re-capture this one of them instead of asking or taking the most recent")
+                                            .changed()
+                                        {
+                                            window.instance = by_instance.then_some(InstanceMatch { number: 1, order: InstanceOrder::default() });
+                                        }
+                                        if let Some(instance) = &mut window.instance {
+                                            accessible(ui.add(egui::DragValue::new(&mut instance.number).range(1..=99)), format!("Instance number of '{}'", window.title));
+                                            ui.radio_value(&mut instance.order, InstanceOrder::ZOrder, "by Z-order");
+                                            ui.radio_value(&mut instance.order, InstanceOrder::CreationOrder, "by creation order");
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label("At target:");
+                                        ui.radio_value(&mut window.target_action, WindowAction::Move, "Move");
+                                        ui.radio_value(&mut window.target_action, WindowAction::Minimize, "Minimize");
+                                        ui.radio_value(&mut window.target_action, WindowAction::Close, "Close")
+                                            .on_hover_text("Closes the window as its close button would; the app may ask to save");
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.label("Hidden position:");
+                                        ui.radio_value(&mut window.hide_at, HidePosition::Never, "None");
+                                        ui.radio_value(&mut window.hide_at, HidePosition::Home, "Home");
+                                        ui.radio_value(&mut window.hide_at, HidePosition::Target, "Target");
+
+                                        if window.hidden {
+                                            ui.colored_label(Color32::YELLOW, format!("{} Hidden", ICON_DISABLED));
+                                            if accessible(ui.button("Show"), format!("Show hidden window '{}'", window.title)).clicked() {
+                                                unsafe {
+                                                    let _ = ShowWindow(hwnd, SW_SHOWNA);
+                                                }
+                                                window.hidden = false;
+                                            }
+                                        }
+                                    });
+
+                                    if let Some(err) = &window.move_error {
+                                        ui.colored_label(Color32::RED, format!("{} {}", ICON_INVALID, err));
+                                    }
+
+                                    ui.horizontal(|ui| {
+                                        if accessible(ui.button("Toggle Window"), format!("Toggle only '{}' between home and target", window.title))
+                                            .on_hover_text("Move just this window to its target if it is home, otherwise home")
+                                            .clicked()
+                                        {
+                                            self.window_ops.send(WindowOp::ToggleWindow { workspace: workspace.runtime_id, window: window.runtime_id });
+                                        }
+                                        optional_hotkey_edit(ui, "Window hotkey:", &mut window.toggle_hotkey);
+                                    });
+                                
+                                    ui.horizontal(|ui| {
+                                        ui.label("Home:");
+                                        accessible(ui.add(egui::DragValue::new(&mut window.home.0).prefix("x: ")), format!("Home x of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.home.1).prefix("y: ")), format!("Home y of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.home.2).prefix("w: ")), format!("Home width of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.home.3).prefix("h: ")), format!("Home height of '{}'", window.title));
+                                
+                                        if accessible(ui.button("Capture Home"), format!("Capture home position of '{}'", window.title)).clicked() {
+                                            if let Ok((x, y, w, h)) = get_window_position(hwnd) {
+                                                window.home = (x, y, w, h);
+                                                info!(
+                                                    "Captured window position for Home using window ID {:?}: {:?}",
+                                                    window.id, window.home
+                                                );
+                                                announce(ctx, format!("Home of '{}' set to {:?}", window.title, window.home));
+                                            } else {
+                                                warn!(
+                                                    "Failed to capture window position for Home using window ID {:?}",
+                                                    window.id
+                                                );
+                                                announce(ctx, format!("Could not read the position of '{}'", window.title));
+                                            }
+                                        }
+                                
+                                        if accessible(ui.button("Move to Home"), format!("Move '{}' to home", window.title)).clicked() {
+                                            self.window_ops.send(WindowOp::MoveWindow {
+                                                workspace: workspace.runtime_id,
+                                                window: window.runtime_id,
+                                                position: PositionKind::Home,
+                                            });
+                                        }
+                                        monitor_label(ui, &monitor_info, window.home);
+                                    });
+                                
+                                    ui.horizontal(|ui| {
+                                        ui.label("Target:");
+                                        accessible(ui.add(egui::DragValue::new(&mut window.target.0).prefix("x: ")), format!("Target x of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.target.1).prefix("y: ")), format!("Target y of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.target.2).prefix("w: ")), format!("Target width of '{}'", window.title));
+                                        accessible(ui.add(egui::DragValue::new(&mut window.target.3).prefix("h: ")), format!("Target height of '{}'", window.title));
                                 
-                                        if ui.button("Capture Target").clicked() {
+                                        if accessible(ui.button("Capture Target"), format!("Capture target position of '{}'", window.title)).clicked() {
                                             if let Ok((x, y, w, h)) = get_window_position(hwnd) {
                                                 window.target = (x, y, w, h);
                                                 info!(
                                                     "Captured window position for Target using window ID {:?}: {:?}",
                                                     window.id, window.target
                                                 );
+                                                announce(ctx, format!("Target of '{}' set to {:?}", window.title, window.target));
                                             } else {
                                                 warn!(
                                                     "Failed to capture window position for Target using window ID {:?}",
                                                     window.id
                                                 );
+                                                announce(ctx, format!("Could not read the position of '{}'", window.title));
                                             }
                                         }
+
+                                        if let Some(preset) = snap_preset_menu(ui, &window.title, &work_areas) {
+                                            window.target = preset.rect;
+                                            info!("Set target of '{}' to snap preset '{}': {:?}", window.title, preset.label, preset.rect);
+                                            announce(ctx, format!("Target of '{}' set to {}", window.title, preset.label));
+                                        }
                                 
-                                        if ui.button("Move to Target").clicked() {
-                                            if let Err(e) = move_window(hwnd, window.target.0, window.target.1, window.target.2, window.target.3) {
-                                                warn!("Error moving window '{}': {}", window.title, e);
-                                            }
+                                        if accessible(ui.button("Move to Target"), format!("Move '{}' to target", window.title)).clicked() {
+                                            self.window_ops.send(WindowOp::MoveWindow {
+                                                workspace: workspace.runtime_id,
+                                                window: window.runtime_id,
+                                                position: PositionKind::Target,
+                                            });
                                         }
-                                    });
-                                }
+                                        monitor_label(ui, &monitor_info, window.target);
+
+                                        if let Some(saved) = window.saved_style {
+                                            if accessible(ui.button("Restore Borders"), format!("Restore borders of '{}'", window.title)).clicked() {
+                                                match restore_window_style(hwnd, &saved) {
+                                                    Ok(()) => window.saved_style = None,
+                                                    Err(e) => self.report_error(MultiManagerError::win32("Restore borders", &workspace.name, &window.title, &e)),
+                                                }
+                                            }
+                                        } else if accessible(ui.button("Borderless Fullscreen"), format!("Make '{}' borderless fullscreen", window.title)).clicked() {
+                                            match make_borderless_fullscreen(hwnd) {
+                                                Ok(saved) => window.saved_style = Some(saved),
+                                                Err(e) => self.report_error(MultiManagerError::win32("Borderless fullscreen", &workspace.name, &window.title, &e)),
+                                            }
+                                        }
+                                    });
+
+                                    let cells = window.home_cell.is_some() as usize + window.target_cell.is_some() as usize;
+                                    egui::CollapsingHeader::new(format!("Grid Positions ({})", cells))
+                                        .id_salt(("grid_positions", i, j))
+                                        .show(ui, |ui| {
+                                            ui.weak("Positions given as grid cells are computed on the monitor when toggling, so they fit any resolution.");
+                                            grid_cell_edit(ui, &format!("Home of '{}'", window.title), &mut window.home_cell, &work_areas);
+                                            grid_cell_edit(ui, &format!("Target of '{}'", window.title), &mut window.target_cell, &work_areas);
+                                        });
+                                }
         
                                 if let Some(index) = window_to_delete {
                                     workspace.windows.remove(index);
+
+                                    // Keep the designated focus window pointing at the same entry
+                                    if let FocusBehavior::Window(focus_index) = workspace.focus_behavior {
+                                        if focus_index == index {
+                                            workspace.focus_behavior = FocusBehavior::ActivateAll;
+                                        } else if focus_index > index {
+                                            workspace.focus_behavior = FocusBehavior::Window(focus_index - 1);
+                                        }
+                                    }
+                                }
+
+                                if let Some(destination) = render_bulk_window_edit(ui, i, workspace, &workspace_names) {
+                                    windows_to_transfer = Some((i, destination));
                                 }
         
-                                if ui.button("Capture Active Window").clicked() {
+                                ui.horizontal(|ui| {
+                                    for (text, position) in [("Set All Home to Current", PositionKind::Home), ("Set All Target to Current", PositionKind::Target)] {
+                                        let kind = format!("{:?}", position).to_lowercase();
+                                        if accessible(ui.button(text), format!("Store the current position of every window of '{}' as its {}", workspace.name, kind))
+                                            .on_hover_text(format!("Captures the live position of every open window as its {} in one go; closed and minimized windows keep theirs", kind))
+                                            .clicked()
+                                        {
+                                            let captured = capture_workspace_positions(&Win32Backend, workspace, position);
+                                            announce(ctx, format!("Captured the {} of {} of {} window(s) of '{}'.", kind, captured, workspace.windows.len(), workspace.name));
+                                        }
+                                    }
+                                });
+
+                                if accessible(ui.button("Capture Active Window"), format!("Capture active window into '{}'", workspace.name)).clicked() {
                                     
                                     if let Some("Enter") = listen_for_keys_with_dialog() {
                                         if let Some((hwnd, title)) = get_active_window() {
@@ -394,35 +1719,130 @@ impl EframeApp for App {
                                                 workspace.windows.push(Window {
                                                     id: hwnd.0 as usize,
                                                     title: title.clone(),
+                                                    capture_title: title.clone(),
+                                                    exe: get_window_exe(hwnd),
+                                                    class_name: get_window_class(hwnd),
                                                     home: (0, 0, 800, 600),
                                                     target: (0, 0, 800, 600),
-                                                    valid: false
+                                                    ..Default::default()
                                                 });
                                             }
                                             else{
                                                 show_message_box("Can not capture window that contains app name for safety", "Capture Info");
                                                 info!("Capture was potentially the app name");
                                             }
-                                            info!("Captured active window: '{}'.", title);
+                                            announce(ctx, format!("Captured active window '{}' into '{}'.", title, workspace.name));
                                         }
                                     } else {
                                         warn!("Capture canceled.");
+                                        announce(ctx, "Capture canceled.");
                                     }
                                 }
+
+                                ui.horizontal(|ui| {
+                                    let mut countdown = self.countdown_capture.lock().unwrap();
+                                    if countdown.as_ref().is_some_and(|capture| capture.workspace == i) {
+                                        if accessible(ui.button("Cancel Capture"), format!("Cancel the delayed capture into '{}'", workspace.name)).clicked() {
+                                            *countdown = None;
+                                            info!("Delayed capture into '{}' canceled.", workspace.name);
+                                            announce(ctx, "Capture canceled.");
+                                        }
+                                    } else {
+                                        let mut under_cursor = None;
+                                        if accessible(ui.add_enabled(countdown.is_none(), egui::Button::new(format!("Capture in {} s", capture_delay_secs))), format!("Capture the active window into '{}' after a countdown", workspace.name))
+                                            .on_hover_text("Gives you time to focus and arrange the window, e.g. open a menu or go fullscreen, before it is captured")
+                                            .clicked()
+                                        {
+                                            under_cursor = Some(false);
+                                        }
+                                        if accessible(ui.add_enabled(countdown.is_none(), egui::Button::new("Capture Under Cursor")), format!("Capture the window under the cursor into '{}' after a countdown", workspace.name))
+                                            .on_hover_text("Captures the window the mouse points at when the countdown ends, for windows that can't easily be brought to the front")
+                                            .clicked()
+                                        {
+                                            under_cursor = Some(true);
+                                        }
+                                        if let Some(under_cursor) = under_cursor {
+                                            *countdown = Some(CountdownCapture {
+                                                workspace: i,
+                                                workspace_name: workspace.name.clone(),
+                                                deadline: Instant::now() + Duration::from_secs(capture_delay_secs as u64),
+                                                under_cursor,
+                                            });
+                                            let action = if under_cursor { "Point at" } else { "Focus" };
+                                            info!("Capturing into '{}' in {} s.", workspace.name, capture_delay_secs);
+                                            announce(ctx, format!("{} the window to capture into '{}'; capturing in {} seconds.", action, workspace.name, capture_delay_secs));
+                                        }
+                                    }
+                                    let label = ui.label("Delay (s):");
+                                    ui.add(egui::DragValue::new(&mut capture_delay_secs).range(1..=MAX_CAPTURE_DELAY_SECS)).labelled_by(label.id);
+                                });
         
                                 ui.horizontal(|ui| {
                                     // Checkbox for "Disable"
-                                    ui.checkbox(&mut workspace.disabled, "Disable Workspace");
+                                    accessible(ui.checkbox(&mut workspace.disabled, "Disable Workspace"), format!("Disable workspace '{}'", workspace.name));
                             
                                     if workspace.disabled{
-                                        unregister_hotkey(self,i as i32); 
+                                        unregister_hotkey(&self.registered_hotkeys,i as i32); 
                                     } else if let Some(hotkey) = &workspace.hotkey {
-                                        register_hotkey(self,i as i32, hotkey); 
+                                        let _ = self.register_workspace_hotkey(i as i32, hotkey);
                                     }
         
-                                    if ui.button("Delete Workspace").clicked() {
+                                    if accessible(ui.button("Copy as JSON"), format!("Copy workspace '{}' as JSON", workspace.name)).clicked() {
+                                        match set_clipboard_text(&workspace_to_json(workspace)) {
+                                            Ok(()) => announce(ctx, format!("Copied workspace '{}' to the clipboard.", workspace.name)),
+                                            Err(e) => self.report_error(e),
+                                        }
+                                    }
+
+                                    let reset = ui.menu_button("Reset ⏷", |ui| {
+                                        let mut restore = None;
+                                        if ui.button("Re-capture and Remove Missing").on_hover_text("Re-captures stale windows by exe, class, and title and removes the ones that can't be found").clicked() {
+                                            restore = Some(false);
+                                        }
+                                        if ui.button("...and Restore Saved Positions").on_hover_text("Also restores every window's positions from the last saved workspace file").clicked() {
+                                            restore = Some(true);
+                                        }
+                                        let Some(restore) = restore else {
+                                            return;
+                                        };
+                                        ui.close_menu();
+
+                                        let message = format!(
+                                            "Reset the workspace '{}'?\n\nStale windows are re-captured and windows that can't be found are removed{}.",
+                                            workspace.name,
+                                            if restore { ", and positions are restored from the last saved file" } else { "" }
+                                        );
+                                        if !show_confirmation_box(&message, "Confirm Reset") {
+                                            return;
+                                        }
+
+                                        let saved = if restore {
+                                            let path = self.workspaces_path.lock().unwrap().clone();
+                                            match read_workspaces(&path) {
+                                                Ok(saved) => saved.into_iter().find(|saved| saved.name == workspace.name),
+                                                Err(e) => {
+                                                    self.report_error(e);
+                                                    return;
+                                                }
+                                            }
+                                        } else {
+                                            None
+                                        };
+                                        if restore && saved.is_none() {
+                                            warn!("'{}' is not in the saved file; positions are not restored.", workspace.name);
+                                        }
+
+                                        let summary = reset_workspace(&Win32Backend, workspace, saved.as_ref());
+                                        announce(ctx, format!(
+                                            "Reset '{}': {} window(s) re-captured, {} removed, {} restored.",
+                                            workspace.name, summary.recaptured, summary.removed.len(), summary.restored
+                                        ));
+                                    });
+                                    accessible(reset.response, format!("Reset workspace '{}'", workspace.name));
+
+                                    if accessible(ui.button("Delete Workspace"), format!("Delete workspace '{}'", workspace.name)).clicked() {
                                         let confirmation_message = format!(
-                                            "Are you sure you want to delete the workspace \n'{}'?\n\nThis action cannot be undone.",
+                                            "Are you sure you want to delete the workspace \n'{}'?\n\nIt is moved to the archive at the bottom of the list, where it can be restored.",
                                             workspace.name
                                         );
                                         if show_confirmation_box(&confirmation_message, "Confirm Deletion") {
@@ -432,87 +1852,3928 @@ impl EframeApp for App {
                                     }
                                 });
 
-                                ui.horizontal(|ui| {
-                                    if i > 0 && ui.button("Move ⏶").clicked() {
-                                            move_up_index = Some(i);
-                                        }
-                                    if i < workspaces_len - 1 && ui.button("Move ⏷").clicked() {
-                                            move_down_index = Some(i);
-                                        }
-                                });
-                                ui.separator();
-                
+                                ui.horizontal(|ui| {
+                                    if i > 0 && accessible(ui.button("Move ⏶"), format!("Move workspace '{}' up", workspace.name)).clicked() {
+                                            move_up_index = Some(i);
+                                        }
+                                    if i < workspaces_len - 1 && accessible(ui.button("Move ⏷"), format!("Move workspace '{}' down", workspace.name)).clicked() {
+                                            move_down_index = Some(i);
+                                        }
+                                });
+                                ui.separator();
+                
+                            });
+
+                            if i == cursor {
+                                highlight_cursor(ui, &header_response.header_response, list_key);
+                            }
+                            if let Some(window) = accept_window_drop(ui, &header_response.header_response) {
+                                dropped_window = Some((i, window));
+                            }
+
+                            let is_open = egui::collapsing_header::CollapsingState::load(ctx, state_id)
+                                .is_none_or(|state| state.is_open());
+                            if is_open != was_open {
+                                expansion_changes.push((workspace.name.clone(), !is_open));
+                            }
+
+                            header_response.header_response.context_menu(|ui| {
+                                if ui.button("Rename...").clicked() {
+                                    is_renaming = true;
+                                    ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
+                                    ui.close_menu();
+                                }
+                                if ui.add_enabled(!workspace.disabled, egui::Button::new("Toggle Now")).clicked() {
+                                    info!("Toggling workspace '{}' from its context menu.", workspace.name);
+                                    self.record_trigger(&workspace.name);
+                                    self.window_ops.send(WindowOp::Toggle(workspace.runtime_id));
+                                    ui.close_menu();
+                                }
+                                ui.separator();
+                                let hotkey = workspace.hotkey.clone();
+                                if ui.add_enabled(hotkey.is_some(), egui::Button::new("Copy Hotkey")).clicked() {
+                                    if let Some(hotkey) = &hotkey {
+                                        match set_clipboard_text(hotkey) {
+                                            Ok(()) => announce(ctx, format!("Copied hotkey '{}' of '{}'.", hotkey, workspace.name)),
+                                            Err(e) => self.report_error(e),
+                                        }
+                                    }
+                                    ui.close_menu();
+                                }
+                                if ui.add_enabled(hotkey.is_some(), egui::Button::new("Clear Hotkey")).clicked() {
+                                    unregister_hotkey(&self.registered_hotkeys, i as i32);
+                                    workspace.hotkey = None;
+                                    ui.memory_mut(|mem| mem.data.insert_temp::<String>(egui::Id::new(i), "None".to_string()));
+                                    info!("Cleared the hotkey of workspace '{}'.", workspace.name);
+                                    announce(ctx, format!("Cleared the hotkey of '{}'.", workspace.name));
+                                    ui.close_menu();
+                                }
+                                if ui.button("Show in Cheat Sheet").clicked() {
+                                    show_in_cheat_sheet(ctx, &workspace.name);
+                                    ui.close_menu();
+                                }
+                            });
+            
+                            // Show a popup window for renaming the workspace
+                            if is_renaming {
+                                egui::Window::new("Rename Workspace")
+                                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0]) // Center the popup
+                                    .collapsible(false)
+                                    .resizable(false)
+                                    .show(ctx, |ui| {
+                                        let label = ui.label("Enter a new name for the workspace:");
+                                        let response = ui.text_edit_singleline(&mut new_name).labelled_by(label.id);
+            
+                                        if response.changed() {
+                                            ui.memory_mut(|mem| {
+                                                mem.data.insert_temp(header_id.with("wrkspce_name"), new_name.clone());
+                                            });
+                                        }
+            
+                                        ui.horizontal(|ui| {
+                                            if ui.button("Ok").clicked() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
+                                                // Save the new name and close the popup
+                                                self.rename_workspace_state(&workspace.name, &new_name);
+                                                workspace.name = new_name.clone();
+                                                is_renaming = false;
+                                                ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
+                                            }
+            
+                                            if ui.button("Cancel").clicked() {
+                                                // Cancel renaming and close the popup
+                                                is_renaming = false;
+                                                ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
+                                            }
+                                        });
+                                    });
+                            }
+                        });
+                    }//Per Workspace
+
+                    if let Some((from, to)) = windows_to_transfer {
+                        let moved = workspaces[from].take_selected_windows();
+                        info!("Moved {} window(s) from '{}' to '{}'.", moved.len(), workspaces[from].name, workspaces[to].name);
+                        workspaces[to].windows.extend(moved);
+                    }
+                    if let Some(i) = move_up_index {
+                        if i > 0 {
+                            workspaces.swap(i, i - 1);
+                        }
+                    }
+                    if let Some(i) = move_down_index {
+                        if i < workspaces.len() - 1 {
+                            workspaces.swap(i, i + 1);
+                        }
+                    }
+                if let Some((index, window)) = &dropped_window {
+                    self.add_browsed_window(ctx, &mut workspaces[*index], window);
+                }
+                if let Some(new_workspace) = new_workspace_to_add {
+                    workspaces.push(new_workspace);
+                }
+    
+                if let Some(index) = workspace_to_delete {
+                    unregister_hotkey(&self.registered_hotkeys, index as i32);
+                    let workspace = workspaces.remove(index);
+                    self.archive_workspace(ctx, workspace);
+                }
+
+                self.render_archive(ui, &mut workspaces);
+            }); //ScrollArea
+        });
+
+        if paste_requested {
+            self.paste_workspaces();
+        }
+        if capture_delay_secs != capture_delay_before {
+            let mut settings = self.settings.lock().unwrap();
+            settings.capture_delay_secs = capture_delay_secs;
+            if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+                self.report_error(e);
+            }
+        }
+
+        self.save_expansion(&expansion_changes);
+        self.render_validation_report(ctx);
+        self.render_file_conflict(ctx);
+        self.render_recovery_prompt(ctx);
+        self.render_parse_failure(ctx);
+
+        if save_workspaces_flag {
+            let path = self.workspaces_path.lock().unwrap().clone();
+            self.save_workspaces_file(path);
+        }
+
+        self.thumbnails.lock().unwrap().end_frame();
+
+        let editing = ctx.memory(|mem| mem.data.get_temp::<bool>(egui::Id::new(HOTKEY_EDITING_ID)));
+        let mut suspended = self.hotkeys_suspended.lock().unwrap();
+        if *suspended != editing.is_some() {
+            *suspended = editing.is_some();
+            info!(
+                "Hotkeys {} while a hotkey field has focus.",
+                if *suspended { "suspended" } else { "resumed" }
+            );
+        }
+    }
+}
+
+/// Shows the hotkey cheat sheet if it is hidden, or hides it if it is shown.
+///
+/// # Arguments
+/// * `ctx` - The egui context holding whether the cheat sheet is open.
+fn toggle_cheat_sheet(ctx: &egui::Context) {
+    let open_id = egui::Id::new(CHEAT_SHEET_OPEN_ID);
+    ctx.memory_mut(|mem| {
+        let open = mem.data.get_temp_mut_or_default::<bool>(open_id);
+        *open = !*open;
+    });
+}
+
+/// Opens the hotkey cheat sheet scrolled to a workspace, with its hotkeys highlighted.
+///
+/// # Arguments
+/// * `ctx` - The egui context holding the cheat sheet's state.
+/// * `workspace` - The name of the workspace to show.
+fn show_in_cheat_sheet(ctx: &egui::Context, workspace: &str) {
+    let focus_id = egui::Id::new(CHEAT_SHEET_FOCUS_ID);
+    ctx.memory_mut(|mem| {
+        mem.data
+            .insert_temp(egui::Id::new(CHEAT_SHEET_OPEN_ID), true);
+        mem.data.insert_temp(focus_id, workspace.to_string());
+        mem.data.insert_temp(focus_id.with("scroll"), true);
+    });
+}
+
+/// Renders the "Snap" menu offering Windows Snap positions as presets: the zones of the
+/// Windows 11 snap layouts on each monitor, and the positions of the windows currently
+/// snapped, so a layout arranged with native snapping can be copied.
+///
+/// # Arguments
+/// * `ui` - The UI to render into.
+/// * `title` - The title of the window being edited, for the screen reader label.
+/// * `work_areas` - The work area of every connected monitor.
+///
+/// # Returns
+/// * The preset picked this frame, if any.
+fn snap_preset_menu(
+    ui: &mut egui::Ui,
+    title: &str,
+    work_areas: &[(i32, i32, i32, i32)],
+) -> Option<SnapPreset> {
+    let mut picked = None;
+    let response = ui.menu_button("Snap ⏷", |ui| {
+        let zones = snap_zone_presets(work_areas);
+        for monitor in 0..work_areas.len() {
+            ui.menu_button(format!("Monitor {}", monitor + 1), |ui| {
+                for preset in zones
+                    .iter()
+                    .filter(|preset| preset.monitor == Some(monitor))
+                {
+                    if ui.button(&preset.label).clicked() {
+                        picked = Some(SnapPreset {
+                            label: format!(
+                                "{} of monitor {}",
+                                preset.label.to_lowercase(),
+                                monitor + 1
+                            ),
+                            ..preset.clone()
+                        });
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+
+        ui.separator();
+        ui.label("Snapped windows");
+        let snapped = snapped_window_presets();
+        if snapped.is_empty() {
+            ui.weak("No window is snapped right now.");
+        }
+        for preset in snapped {
+            let (x, y, width, height) = preset.rect;
+            if ui
+                .button(&preset.label)
+                .on_hover_text(format!("{}x{} at ({}, {})", width, height, x, y))
+                .clicked()
+            {
+                picked = Some(SnapPreset {
+                    label: format!("the position of snapped window '{}'", preset.label),
+                    ..preset
+                });
+                ui.close_menu();
+            }
+        }
+    });
+    accessible(
+        response.response,
+        format!("Set the target of '{}' from Windows Snap", title),
+    );
+    picked
+}
+
+/// Renders the editor for one grid-cell position: a checkbox to use it and, if used, the
+/// monitor, the grid size, and the columns and rows covered, numbered from 1.
+///
+/// # Arguments
+/// * `ui` - The UI to render into.
+/// * `label` - What the position is, e.g. "Home of 'Notepad'"; its first word labels the row.
+/// * `cell` - The position, edited in place.
+/// * `work_areas` - The work area of every connected monitor, to show the resulting rect.
+fn grid_cell_edit(
+    ui: &mut egui::Ui,
+    label: &str,
+    cell: &mut Option<GridCell>,
+    work_areas: &[(i32, i32, i32, i32)],
+) {
+    ui.horizontal(|ui| {
+        let mut enabled = cell.is_some();
+        let name = label.split_whitespace().next().unwrap_or(label);
+        if accessible(
+            ui.checkbox(&mut enabled, name),
+            format!("Use a grid cell for the {}", label.to_lowercase()),
+        )
+        .changed()
+        {
+            *cell = enabled.then(GridCell::default);
+        }
+        let Some(cell) = cell else {
+            return;
+        };
+
+        let mut monitor = cell.monitor + 1;
+        accessible(
+            ui.add(
+                egui::DragValue::new(&mut monitor)
+                    .range(1..=work_areas.len().max(1))
+                    .prefix("Monitor "),
+            ),
+            format!("Monitor of the {}", label.to_lowercase()),
+        );
+        cell.monitor = monitor - 1;
+
+        accessible(
+            ui.add(
+                egui::DragValue::new(&mut cell.columns)
+                    .range(1..=48)
+                    .suffix(" cols"),
+            ),
+            format!("Grid columns of the {}", label.to_lowercase()),
+        );
+        accessible(
+            ui.add(
+                egui::DragValue::new(&mut cell.rows)
+                    .range(1..=48)
+                    .suffix(" rows"),
+            ),
+            format!("Grid rows of the {}", label.to_lowercase()),
+        );
+
+        for (axis, start, span, count) in [
+            ("Col", &mut cell.column, &mut cell.column_span, cell.columns),
+            ("Row", &mut cell.row, &mut cell.row_span, cell.rows),
+        ] {
+            let mut first = (*start + 1).min(count);
+            let mut last = (*start + *span).clamp(first, count);
+            ui.label(format!("{}:", axis));
+            accessible(
+                ui.add(egui::DragValue::new(&mut first).range(1..=count)),
+                format!(
+                    "First {} of the {}",
+                    axis.to_lowercase(),
+                    label.to_lowercase()
+                ),
+            );
+            ui.label("–");
+            accessible(
+                ui.add(egui::DragValue::new(&mut last).range(first..=count)),
+                format!(
+                    "Last {} of the {}",
+                    axis.to_lowercase(),
+                    label.to_lowercase()
+                ),
+            );
+            *start = first - 1;
+            *span = last.max(first) - first + 1;
+        }
+
+        if let Some((x, y, width, height)) = cell.rect(work_areas) {
+            ui.weak(format!("= {}x{} at ({}, {})", width, height, x, y));
+        } else {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ No monitor");
+        }
+    });
+}
+
+/// Renders the editor for a workspace's auto-capture rules: the exe, class, and title each
+/// rule matches and the target given to windows it adopts.
+///
+/// # Arguments
+/// * `ui` - The UI to render into.
+/// * `index` - The index of the workspace, to keep widget IDs unique.
+/// * `rules` - The workspace's rules, edited in place.
+///
+/// # Returns
+/// * `true` if "Apply Now" was clicked.
+fn render_capture_rules(ui: &mut egui::Ui, index: usize, rules: &mut Vec<CaptureRule>) -> bool {
+    ui.weak(
+        "Matching windows are added when the workspace is toggled. Empty fields match anything.",
+    );
+
+    let mut rule_to_remove = None;
+    for (k, rule) in rules.iter_mut().enumerate() {
+        ui.push_id(("capture_rule", index, k), |ui| {
+            ui.horizontal(|ui| {
+                accessible(
+                    ui.add(
+                        egui::TextEdit::singleline(&mut rule.exe)
+                            .hint_text("Exe")
+                            .desired_width(110.0),
+                    ),
+                    format!("Executable of rule {}", k + 1),
+                );
+                accessible(
+                    ui.add(
+                        egui::TextEdit::singleline(&mut rule.class_name)
+                            .hint_text("Class")
+                            .desired_width(110.0),
+                    ),
+                    format!("Window class of rule {}", k + 1),
+                );
+                accessible(
+                    ui.add(
+                        egui::TextEdit::singleline(&mut rule.title)
+                            .hint_text("Title contains")
+                            .desired_width(140.0),
+                    ),
+                    format!("Title text of rule {}", k + 1),
+                );
+                if accessible(ui.button("Delete"), format!("Delete rule {}", k + 1)).clicked() {
+                    rule_to_remove = Some(k);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Target:");
+                accessible(
+                    ui.add(egui::DragValue::new(&mut rule.target.0).prefix("x: ")),
+                    format!("Target x of rule {}", k + 1),
+                );
+                accessible(
+                    ui.add(egui::DragValue::new(&mut rule.target.1).prefix("y: ")),
+                    format!("Target y of rule {}", k + 1),
+                );
+                accessible(
+                    ui.add(egui::DragValue::new(&mut rule.target.2).prefix("w: ")),
+                    format!("Target width of rule {}", k + 1),
+                );
+                accessible(
+                    ui.add(egui::DragValue::new(&mut rule.target.3).prefix("h: ")),
+                    format!("Target height of rule {}", k + 1),
+                );
+                if rule.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "⚠ Matches nothing until a field is filled in",
+                    );
+                }
+            });
+        });
+    }
+    if let Some(k) = rule_to_remove {
+        rules.remove(k);
+    }
+
+    let mut apply = false;
+    ui.horizontal(|ui| {
+        if ui.button("Add Rule").clicked() {
+            rules.push(CaptureRule {
+                target: (0, 0, 800, 600),
+                ..Default::default()
+            });
+        }
+        if ui
+            .add_enabled(
+                rules.iter().any(|rule| !rule.is_empty()),
+                egui::Button::new("Apply Now"),
+            )
+            .on_hover_text("Add the matching windows open right now")
+            .clicked()
+        {
+            apply = true;
+        }
+    });
+    apply
+}
+
+/// Highlights a workspace while a window from the window browser is dragged over it.
+///
+/// # Arguments
+/// * `ui` - The egui UI the workspace was rendered into.
+/// * `response` - The workspace's header or compact row.
+///
+/// # Returns
+/// * The window dropped onto the workspace this frame, if any.
+fn accept_window_drop(ui: &egui::Ui, response: &egui::Response) -> Option<Arc<WindowSummary>> {
+    if response.dnd_hover_payload::<WindowSummary>().is_some() {
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            2.0,
+            ui.visuals().selection.stroke,
+        );
+    }
+    response.dnd_release_payload::<WindowSummary>()
+}
+
+/// The largest size of the layout canvas, in points.
+const LAYOUT_CANVAS_MAX: egui::Vec2 = egui::vec2(600.0, 300.0);
+
+/// The size of the resize handle in a window's corner on the layout canvas, in points.
+const LAYOUT_HANDLE_SIZE: f32 = 8.0;
+
+/// Draws the monitors to scale with one rectangle per window at its home or target position.
+/// Rectangles can be dragged to move the window and resized from their bottom-right corner,
+/// writing the result back to the position.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `index` - The workspace's index in the list, to keep widget IDs unique.
+/// * `monitors` - The connected monitors.
+/// * `windows` - The workspace's windows.
+/// * `position` - Which of each window's positions to show and edit.
+/// * `fill` - The color of the window rectangles.
+fn layout_canvas(
+    ui: &mut egui::Ui,
+    index: usize,
+    monitors: &[MonitorInfo],
+    windows: &mut [Window],
+    position: PositionKind,
+    fill: egui::Color32,
+) {
+    let to_rect = |(x, y, w, h): (i32, i32, i32, i32)| {
+        egui::Rect::from_min_size(
+            egui::pos2(x as f32, y as f32),
+            egui::vec2(w as f32, h as f32),
+        )
+    };
+
+    // Fit every monitor and window on the canvas, so off-screen windows can be dragged back
+    let stored = |window: &Window| match position {
+        PositionKind::Home => window.home,
+        PositionKind::Target => window.target,
+    };
+    let bounds = monitors
+        .iter()
+        .map(|monitor| to_rect(monitor.rect))
+        .chain(windows.iter().map(|window| to_rect(stored(window))))
+        .reduce(|a, b| a.union(b));
+    let Some(bounds) = bounds.filter(|bounds| bounds.width() > 0.0 && bounds.height() > 0.0) else {
+        ui.label("No monitors found.");
+        return;
+    };
+
+    let max = egui::vec2(
+        ui.available_width().min(LAYOUT_CANVAS_MAX.x),
+        LAYOUT_CANVAS_MAX.y,
+    );
+    let scale = (max.x / bounds.width()).min(max.y / bounds.height());
+    let (canvas, painter) = ui.allocate_painter(bounds.size() * scale, egui::Sense::hover());
+    let to_screen = |rect: egui::Rect| {
+        egui::Rect::from_min_size(
+            canvas.rect.min + (rect.min - bounds.min) * scale,
+            rect.size() * scale,
+        )
+    };
+
+    painter.rect_filled(canvas.rect, 0.0, ui.visuals().extreme_bg_color);
+    for (number, monitor) in monitors.iter().enumerate() {
+        let rect = to_screen(to_rect(monitor.rect));
+        painter.rect(
+            rect,
+            2.0,
+            ui.visuals().faint_bg_color,
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+        painter.text(
+            rect.left_top() + egui::vec2(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{}", number + 1),
+            egui::FontId::proportional(14.0),
+            ui.visuals().weak_text_color(),
+        );
+    }
+
+    for (j, window) in windows.iter_mut().enumerate() {
+        let stored_rect = match position {
+            PositionKind::Home => &mut window.home,
+            PositionKind::Target => &mut window.target,
+        };
+        let rect = to_screen(to_rect(*stored_rect));
+        let id = egui::Id::new(("layout_window", index, j));
+        let body = ui.interact(rect, id, egui::Sense::drag());
+        let handle_rect = egui::Rect::from_min_size(
+            rect.right_bottom() - egui::vec2(LAYOUT_HANDLE_SIZE, LAYOUT_HANDLE_SIZE),
+            egui::vec2(LAYOUT_HANDLE_SIZE, LAYOUT_HANDLE_SIZE),
+        );
+        let handle = ui.interact(handle_rect, id.with("resize"), egui::Sense::drag());
+
+        if handle.dragged() {
+            let delta = handle.drag_delta() / scale;
+            stored_rect.2 = (stored_rect.2 + delta.x.round() as i32).max(1);
+            stored_rect.3 = (stored_rect.3 + delta.y.round() as i32).max(1);
+        } else if body.dragged() {
+            let delta = body.drag_delta() / scale;
+            stored_rect.0 += delta.x.round() as i32;
+            stored_rect.1 += delta.y.round() as i32;
+        }
+        if handle.hovered() || handle.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeNwSe);
+        } else if body.hovered() || body.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+        }
+
+        let rect = to_screen(to_rect(*stored_rect));
+        let stroke = if body.hovered() || body.dragged() || handle.dragged() {
+            ui.visuals().selection.stroke
+        } else {
+            egui::Stroke::new(1.0, fill)
+        };
+        painter.rect(rect, 2.0, fill.gamma_multiply(0.35), stroke);
+        painter.rect_filled(handle_rect, 0.0, fill);
+        painter.with_clip_rect(rect).text(
+            rect.left_top() + egui::vec2(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            &window.title,
+            egui::FontId::proportional(12.0),
+            ui.visuals().strong_text_color(),
+        );
+        body.on_hover_text(format!("{} {:?}", window.title, *stored_rect));
+    }
+}
+
+/// Renders the bulk actions for the windows selected in a workspace: delete them, give them
+/// the same target, shift their positions by an offset, or move them to another workspace.
+///
+/// Nothing is shown while no window is selected.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `index` - The workspace's index in the list.
+/// * `workspace` - The workspace whose selected windows are edited.
+/// * `workspace_names` - The names of all workspaces, to offer as destinations.
+///
+/// # Returns
+/// * The index of the workspace the selected windows should be moved to, if one was chosen.
+fn render_bulk_window_edit(
+    ui: &mut egui::Ui,
+    index: usize,
+    workspace: &mut Workspace,
+    workspace_names: &[String],
+) -> Option<usize> {
+    let selected_count = workspace.windows.iter().filter(|w| w.selected).count();
+    if selected_count == 0 {
+        return None;
+    }
+
+    let target_id = egui::Id::new(("bulk_target", index));
+    let offset_id = egui::Id::new(("bulk_offset", index));
+    let mut target = ui
+        .memory(|mem| mem.data.get_temp::<(i32, i32, i32, i32)>(target_id))
+        .unwrap_or((0, 0, 800, 600));
+    let mut offset = ui
+        .memory(|mem| mem.data.get_temp::<(i32, i32)>(offset_id))
+        .unwrap_or((0, 0));
+    let mut destination = None;
+
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.strong(format!("{} window(s) selected", selected_count));
+            if ui.button("Select None").clicked() {
+                for window in &mut workspace.windows {
+                    window.selected = false;
+                }
+            }
+            if accessible(
+                ui.button("Delete Selected"),
+                format!("Delete the selected windows of '{}'", workspace.name),
+            )
+            .clicked()
+                && show_confirmation_box(
+                    &format!(
+                        "Delete {} window(s) from '{}'?",
+                        selected_count, workspace.name
+                    ),
+                    "Confirm Deletion",
+                )
+            {
+                let removed = workspace.take_selected_windows();
+                info!(
+                    "Deleted {} window(s) from '{}'.",
+                    removed.len(),
+                    workspace.name
+                );
+            }
+            egui::ComboBox::from_id_salt(("bulk_move", index))
+                .selected_text("Move to...")
+                .show_ui(ui, |ui| {
+                    for (other, name) in workspace_names.iter().enumerate() {
+                        if other != index && ui.selectable_label(false, name).clicked() {
+                            destination = Some(other);
+                        }
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Target:");
+            ui.add(egui::DragValue::new(&mut target.0).prefix("x: "))
+                .labelled_by(label.id);
+            ui.add(egui::DragValue::new(&mut target.1).prefix("y: "))
+                .labelled_by(label.id);
+            ui.add(egui::DragValue::new(&mut target.2).prefix("w: "))
+                .labelled_by(label.id);
+            ui.add(egui::DragValue::new(&mut target.3).prefix("h: "))
+                .labelled_by(label.id);
+            if ui.button("Set Target").clicked() {
+                for window in workspace.windows.iter_mut().filter(|w| w.selected) {
+                    window.target = target;
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Shift by:");
+            ui.add(egui::DragValue::new(&mut offset.0).prefix("x: "))
+                .labelled_by(label.id);
+            ui.add(egui::DragValue::new(&mut offset.1).prefix("y: "))
+                .labelled_by(label.id);
+            for (text, home, to_target) in [
+                ("Shift Home", true, false),
+                ("Shift Target", false, true),
+                ("Shift Both", true, true),
+            ] {
+                if ui.button(text).clicked() {
+                    for window in workspace.windows.iter_mut().filter(|w| w.selected) {
+                        if home {
+                            window.home.0 += offset.0;
+                            window.home.1 += offset.1;
+                        }
+                        if to_target {
+                            window.target.0 += offset.0;
+                            window.target.1 += offset.1;
+                        }
+                    }
+                }
+            }
+        });
+    });
+
+    ui.memory_mut(|mem| {
+        mem.data.insert_temp(target_id, target);
+        mem.data.insert_temp(offset_id, offset);
+    });
+    destination
+}
+
+/// Renders an editor for an optional hotkey with a validity indicator. Clearing the text
+/// removes the hotkey.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `label` - The label shown before the field.
+/// * `hotkey` - The hotkey being edited.
+fn optional_hotkey_edit(ui: &mut egui::Ui, label: &str, hotkey: &mut Option<String>) {
+    let label = ui.label(label);
+    let mut text = hotkey.clone().unwrap_or_default();
+    let response = ui
+        .add(
+            egui::TextEdit::singleline(&mut text)
+                .desired_width(100.0)
+                .hint_text("None"),
+        )
+        .labelled_by(label.id);
+    suspend_hotkeys_while_focused(ui, &response);
+    if response.changed() {
+        *hotkey = (!text.trim().is_empty()).then(|| text.trim().to_string());
+    }
+    if response.lost_focus() {
+        migrate_hotkey(hotkey);
+    }
+    match hotkey {
+        Some(hotkey) if is_valid_key_combo(hotkey) => {
+            ui.colored_label(egui::Color32::GREEN, ICON_VALID);
+        }
+        Some(_) => {
+            ui.colored_label(egui::Color32::RED, format!("{} Invalid", ICON_INVALID));
+        }
+        None => {}
+    }
+}
+
+/// Marks the frame as editing a hotkey if a hotkey field has keyboard focus, so the hotkey
+/// checker ignores combinations typed into it instead of triggering the workspaces they belong to.
+///
+/// # Arguments
+/// * `ui` - The egui UI the field is in.
+/// * `response` - The response of the hotkey text field.
+fn suspend_hotkeys_while_focused(ui: &egui::Ui, response: &egui::Response) {
+    if response.has_focus() {
+        ui.ctx()
+            .memory_mut(|mem| mem.data.insert_temp(egui::Id::new(HOTKEY_EDITING_ID), true));
+    }
+}
+
+/// Renders the duration and easing of an animation.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `id_salt` - Makes the easing combo box's ID unique.
+/// * `animation` - The animation being edited.
+///
+/// # Returns
+/// * `true` if the animation was changed.
+fn animation_edit(ui: &mut egui::Ui, id_salt: &str, animation: &mut Animation) -> bool {
+    let before = *animation;
+    let label = ui.label("Duration:");
+    ui.add(
+        egui::DragValue::new(&mut animation.duration_ms)
+            .range(0..=MAX_ANIMATION_MS)
+            .suffix(" ms"),
+    )
+    .labelled_by(label.id)
+    .on_hover_text("0 moves windows instantly");
+    let label = ui.label("Easing:");
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(animation.easing.label())
+        .show_ui(ui, |ui| {
+            for easing in Easing::ALL {
+                ui.selectable_value(&mut animation.easing, easing, easing.label());
+            }
+        })
+        .response
+        .labelled_by(label.id);
+    *animation != before
+}
+
+/// Shows whether a window is currently at its home or target position, or elsewhere, as a
+/// colored chip with text, so the workspace's state is visible without toggling it.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `rect` - The window's current position, from the window state cache.
+/// * `home` - The stored home position.
+/// * `target` - The stored target position.
+fn position_chip(
+    ui: &mut egui::Ui,
+    rect: (i32, i32, i32, i32),
+    home: (i32, i32, i32, i32),
+    target: (i32, i32, i32, i32),
+) {
+    let (text, color) = match matching_position(rect, home, target) {
+        Some(PositionKind::Home) => ("At Home", egui::Color32::from_rgb(70, 130, 180)),
+        Some(PositionKind::Target) => ("At Target", egui::Color32::from_rgb(46, 139, 87)),
+        None => ("Elsewhere", egui::Color32::GRAY),
+    };
+    ui.label(
+        egui::RichText::new(format!(" {} ", text))
+            .color(egui::Color32::WHITE)
+            .background_color(color),
+    )
+    .on_hover_text(format!("Currently at {:?}", rect));
+}
+
+/// Shows which monitor a stored position falls on and that monitor's scaling, or a warning
+/// if it is on no monitor, so multi-monitor layouts can be checked without reading pixels.
+///
+/// # Arguments
+/// * `ui` - The egui UI to render into.
+/// * `monitors` - The connected monitors.
+/// * `rect` - The stored position `(x, y, width, height)`.
+fn monitor_label(ui: &mut egui::Ui, monitors: &[MonitorInfo], rect: (i32, i32, i32, i32)) {
+    match monitor_for_rect(monitors, rect) {
+        Some(index) => {
+            let monitor = &monitors[index];
+            let (x, y, width, height) = monitor.rect;
+            ui.weak(format!(
+                "Monitor {} · {:.0}%",
+                index + 1,
+                monitor.scale * 100.0
+            ))
+            .on_hover_text(format!("{}x{} at ({}, {})", width, height, x, y));
+        }
+        None => {
+            ui.colored_label(egui::Color32::YELLOW, "⚠ No monitor")
+                .on_hover_text("This position is not on any connected monitor");
+        }
+    }
+}
+
+/// Returns the tab the main window is showing.
+///
+/// # Arguments
+/// * `ctx` - The egui context whose memory holds the current tab.
+fn current_tab(ctx: &egui::Context) -> MainTab {
+    ctx.memory(|mem| mem.data.get_temp::<MainTab>(egui::Id::new(MAIN_TAB_ID)))
+        .unwrap_or(MainTab::Workspaces)
+}
+
+/// Icons shown next to states that are also conveyed by color, so they remain readable for
+/// color-blind users. The bundled fonts have no ✓ or ✗, so the heavy variants are used.
+const ICON_VALID: &str = "✔";
+const ICON_INVALID: &str = "✖";
+const ICON_DISABLED: &str = "⏸";
+
+/// The most characters a workspace icon may have; enough for emoji made of several code points.
+const WORKSPACE_ICON_CHAR_LIMIT: usize = 4;
+
+/// Returns a workspace's icon followed by a space, or nothing if it has none.
+fn icon_prefix(workspace: &Workspace) -> String {
+    let icon = workspace.icon.trim();
+    if icon.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", icon)
+    }
+}
+
+/// Draws a small square in the workspace's color, if it has one.
+///
+/// # Arguments
+/// * `ui` - The egui UI to draw into.
+/// * `workspace` - The workspace whose color is shown.
+fn color_swatch(ui: &mut egui::Ui, workspace: &Workspace) {
+    if let Some([r, g, b]) = workspace.color {
+        let size = egui::vec2(12.0, 12.0);
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+    }
+}
+
+/// Returns how a workspace's state is shown in its header.
+///
+/// # Arguments
+/// * `disabled` - Whether the workspace is disabled.
+/// * `valid` - Whether all of its windows exist.
+///
+/// # Returns
+/// * The icon, a text badge, and the color, e.g. `("✔", "valid", GREEN)`.
+fn workspace_status(disabled: bool, valid: bool) -> (&'static str, &'static str, egui::Color32) {
+    if disabled {
+        (ICON_DISABLED, "disabled", egui::Color32::ORANGE)
+    } else if valid {
+        (ICON_VALID, "valid", egui::Color32::GREEN)
+    } else {
+        (ICON_INVALID, "invalid", egui::Color32::RED)
+    }
+}
+
+/// Sets the name a screen reader reads for a widget whose visible text is ambiguous out of
+/// context, such as the "Delete" button repeated for every window.
+///
+/// # Arguments
+/// * `response` - The widget's response.
+/// * `label` - The accessible name, e.g. "Delete window 'Notepad'".
+///
+/// # Returns
+/// * The same response, for chaining.
+fn accessible(response: egui::Response, label: impl Into<String>) -> egui::Response {
+    let label = label.into();
+    response
+        .ctx
+        .accesskit_node_builder(response.id, |node| node.set_label(label));
+    response
+}
+
+/// Outlines the workspace under the keyboard cursor, scrolling it into view when the cursor
+/// has just moved.
+///
+/// # Arguments
+/// * `ui` - The egui UI the workspace was rendered into.
+/// * `response` - The workspace's header or row.
+/// * `list_key` - This frame's keyboard action.
+fn highlight_cursor(ui: &egui::Ui, response: &egui::Response, list_key: ListKey) {
+    ui.painter().rect_stroke(
+        response.rect.expand(2.0),
+        2.0,
+        ui.visuals().selection.stroke,
+    );
+    if list_key == ListKey::Moved {
+        response.scroll_to_me(None);
+    }
+}
+
+/// Shows a message in the status bar, which screen readers announce as a live region.
+///
+/// # Arguments
+/// * `ctx` - The egui context.
+/// * `message` - What changed, e.g. "Captured 'Notepad' into 'Coding'".
+fn announce(ctx: &egui::Context, message: impl Into<String>) {
+    let message = message.into();
+    info!("{}", message);
+    ctx.memory_mut(|mem| {
+        mem.data
+            .insert_temp(egui::Id::new(ANNOUNCEMENT_ID), message)
+    });
+    ctx.request_repaint();
+}
+
+impl App {
+    /// Renders a workspace as a single line: its state, name, hotkey, and a toggle button.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    /// * `workspace` - The workspace, whose `valid` flag is already up to date.
+    ///
+    /// # Returns
+    /// * The response covering the whole row.
+    fn render_compact_row(&self, ui: &mut egui::Ui, workspace: &mut Workspace) -> egui::Response {
+        let (status_icon, status_badge, status_color) =
+            workspace_status(workspace.disabled, workspace.valid);
+
+        ui.horizontal(|ui| {
+            accessible(
+                ui.checkbox(&mut workspace.selected, ""),
+                format!("Select workspace '{}'", workspace.name),
+            )
+            .on_hover_text("Select for File → Export Selected");
+            color_swatch(ui, workspace);
+            ui.colored_label(
+                status_color,
+                format!(
+                    "{} {}{}",
+                    status_icon,
+                    icon_prefix(workspace),
+                    workspace.get_header_text()
+                ),
+            );
+            ui.weak(workspace.hotkey.as_deref().unwrap_or("No hotkey"));
+            ui.label(format!("[{}]", status_badge));
+            if accessible(
+                ui.add_enabled(!workspace.disabled, egui::Button::new("Toggle")),
+                format!("Toggle '{}'", workspace.name),
+            )
+            .clicked()
+            {
+                info!("Toggling workspace '{}' from the list.", workspace.name);
+                self.record_trigger(&workspace.name);
+                self.window_ops.send(WindowOp::Toggle(workspace.runtime_id));
+            }
+        })
+        .response
+    }
+
+    /// Handles the workspace list's keyboard shortcuts when no widget has keyboard focus:
+    /// Up/Down move the cursor, Enter expands or collapses, Delete deletes after
+    /// confirmation, and F2 renames.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for reading input.
+    /// * `workspace_to_delete` - Set to the cursor's workspace if it should be deleted.
+    ///
+    /// # Returns
+    /// * The action to apply while rendering the workspace under the cursor.
+    fn handle_list_keys(
+        &self,
+        ctx: &egui::Context,
+        workspace_to_delete: &mut Option<usize>,
+    ) -> ListKey {
+        // Leave keys to text fields and to egui's own focus navigation
+        if ctx.wants_keyboard_input() || ctx.memory(|mem| mem.focused().is_some()) {
+            return ListKey::None;
+        }
+
+        let cursor_id = egui::Id::new(WORKSPACE_CURSOR_ID);
+        let names: Vec<String> = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|workspace| workspace.name.clone())
+            .collect();
+        if names.is_empty() {
+            return ListKey::None;
+        }
+        let cursor = ctx
+            .memory(|mem| mem.data.get_temp::<usize>(cursor_id))
+            .unwrap_or(0)
+            .min(names.len() - 1);
+
+        let (up, down, enter, delete, rename) = ctx.input_mut(|input| {
+            (
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Delete),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::F2),
+            )
+        });
+
+        let mut action = ListKey::None;
+        let mut new_cursor = cursor;
+        if up {
+            new_cursor = cursor.saturating_sub(1);
+            action = ListKey::Moved;
+        }
+        if down {
+            new_cursor = (cursor + 1).min(names.len() - 1);
+            action = ListKey::Moved;
+        }
+        if enter {
+            action = ListKey::Expand;
+        }
+        if delete {
+            let confirmation_message = format!(
+                "Are you sure you want to delete the workspace \n'{}'?\n\nIt is moved to the archive at the bottom of the list, where it can be restored.",
+                names[new_cursor]
+            );
+            if show_confirmation_box(&confirmation_message, "Confirm Deletion") {
+                *workspace_to_delete = Some(new_cursor);
+                info!("Deleting workspace '{}'.", names[new_cursor]);
+            }
+        }
+        if rename {
+            let header_id = egui::Id::new(format!("workspace_{}_header", new_cursor));
+            ctx.memory_mut(|mem| mem.data.insert_temp(header_id, true));
+        }
+
+        ctx.memory_mut(|mem| mem.data.insert_temp(cursor_id, new_cursor));
+        if action != ListKey::None {
+            announce(ctx, format!("Workspace '{}'", names[new_cursor]));
+        }
+        action
+    }
+
+    /// Saves the UI scale when it was changed outside the Settings panel, e.g. with Ctrl+Plus.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context whose zoom factor is the current UI scale.
+    fn persist_ui_scale(&self, ctx: &egui::Context) {
+        let zoom = ctx.zoom_factor();
+        let mut settings = self.settings.lock().unwrap();
+        if (settings.ui_scale - zoom).abs() < f32::EPSILON {
+            return;
+        }
+        settings.ui_scale = zoom;
+        if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+            self.report_error(e);
+        }
+    }
+
+    /// Renders the status bar with the latest announcement.
+    ///
+    /// The message is exposed to screen readers as a polite live region, so capture results
+    /// and other state changes are read out without moving focus.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_status_bar(&self, ctx: &egui::Context) {
+        let message = ctx
+            .memory(|mem| mem.data.get_temp::<String>(egui::Id::new(ANNOUNCEMENT_ID)))
+            .unwrap_or_default();
+
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            let response = ui.label(&message);
+            ctx.accesskit_node_builder(response.id, |node| {
+                node.set_live(egui::accesskit::Live::Polite);
+            });
+        });
+    }
+
+    /// Renders the menu bar with the File menu and the path of the current workspace file.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_menu_bar(&self, ctx: &egui::Context) {
+        let current_path = self.workspaces_path.lock().unwrap().clone();
+        let recent_files = self.settings.lock().unwrap().recent_files.clone();
+        let selected_count = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|workspace| workspace.selected)
+            .count();
+        let mut export_requested = false;
+        let mut recent_to_open = None;
+        let mut clear_recent = false;
+        let mut open_requested = false;
+        let mut save_requested = false;
+        let mut save_as_requested = false;
+        let mut tab_requested = None;
+        let animations_before = self.settings.lock().unwrap().animations_enabled;
+        let mut animations_enabled = animations_before;
+
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Open...").clicked() {
+                        open_requested = true;
+                        ui.close_menu();
+                    }
+                    ui.add_enabled_ui(!recent_files.is_empty(), |ui| {
+                        ui.menu_button("Open Recent", |ui| {
+                            for path in &recent_files {
+                                // A missing file would load as an empty workspace list
+                                let exists = std::path::Path::new(path).is_file();
+                                if ui.add_enabled(exists, egui::Button::new(path)).clicked() {
+                                    recent_to_open = Some(path.clone());
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Clear Recent").clicked() {
+                                clear_recent = true;
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                    if ui.button("Save").clicked() {
+                        save_requested = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Save As...").clicked() {
+                        save_as_requested = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let export_label = format!("Export Selected ({})...", selected_count);
+                    if ui
+                        .add_enabled(selected_count > 0, egui::Button::new(export_label))
+                        .clicked()
+                    {
+                        export_requested = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let elevated = is_elevated();
+                    if ui
+                        .add_enabled(!elevated, egui::Button::new("Relaunch as Administrator"))
+                        .on_hover_text("Start again with administrator rights, keeping unsaved edits, so windows of programs running as administrator can be moved")
+                        .on_disabled_hover_text("Already running as administrator")
+                        .clicked()
+                    {
+                        self.relaunch_as_admin(ctx);
+                        ui.close_menu();
+                    }
+                    if ui.button("Exit").clicked() {
+                        ctx.memory_mut(|mem| mem.data.insert_temp(egui::Id::new(EXIT_REQUESTED_ID), true));
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("View", |ui| {
+                    for tab in MainTab::ALL {
+                        if ui.button(tab.title()).clicked() {
+                            tab_requested = Some(tab);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    if ui.add(egui::Button::new("Hotkey Cheat Sheet").shortcut_text("F1")).clicked() {
+                        toggle_cheat_sheet(ctx);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.checkbox(&mut animations_enabled, "Animate Window Moves").clicked() {
+                        ui.close_menu();
+                    }
+                });
+                ui.weak(&current_path);
+            });
+        });
+
+        if open_requested {
+            if let Some(path) = show_open_file_dialog("Open Workspaces", &current_path) {
+                self.open_workspaces_file(Some(ctx), path);
+            }
+        }
+        if let Some(path) = recent_to_open {
+            self.open_workspaces_file(Some(ctx), path);
+        }
+        if animations_enabled != animations_before {
+            self.set_animations(animations_enabled);
+        }
+        if clear_recent {
+            let mut settings = self.settings.lock().unwrap();
+            settings.recent_files.clear();
+            if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+                self.report_error(e);
+            }
+        }
+        if save_requested {
+            self.save_workspaces_file(current_path.clone());
+        }
+        if save_as_requested {
+            if let Some(path) = show_save_file_dialog("Save Workspaces As", &current_path) {
+                self.save_workspaces_file(path);
+            }
+        }
+        if export_requested {
+            if let Some(path) = show_save_file_dialog("Export Selected Workspaces", "") {
+                self.export_selected_workspaces(&path);
+            }
+        }
+        if let Some(tab) = tab_requested {
+            self.select_tab(ctx, tab);
+        }
+    }
+
+    /// Renders the hotkey cheat sheet when it is open: every active hotkey grouped by
+    /// workspace, built from the registered hotkeys, with buttons to copy or save it as text
+    /// for printing.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_cheat_sheet(&self, ctx: &egui::Context) {
+        let open_id = egui::Id::new(CHEAT_SHEET_OPEN_ID);
+        let mut open = ctx.memory(|mem| mem.data.get_temp::<bool>(open_id).unwrap_or(false));
+        if !open {
+            return;
+        }
+
+        let groups = {
+            let workspaces = self.workspaces.lock().unwrap();
+            let registered = self.registered_hotkeys.lock().unwrap();
+            cheat_sheet(&workspaces, &registered)
+        };
+        let focus_id = egui::Id::new(CHEAT_SHEET_FOCUS_ID);
+        let focus = ctx.memory(|mem| mem.data.get_temp::<String>(focus_id));
+        // Scroll to the workspace once, when it was just picked, then leave scrolling to the user
+        let scroll_to_focus = ctx.memory_mut(|mem| {
+            mem.data
+                .remove_temp::<bool>(focus_id.with("scroll"))
+                .unwrap_or(false)
+        });
+        let mut copy_requested = false;
+        let mut save_requested = false;
+
+        egui::Window::new("Hotkey Cheat Sheet")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Copy as Text").clicked() {
+                        copy_requested = true;
+                    }
+                    if ui.button("Save as Text...").clicked() {
+                        save_requested = true;
+                    }
+                });
+                ui.separator();
+
+                if groups.is_empty() {
+                    ui.label("No hotkeys are assigned.");
+                    return;
+                }
+                if let Some(name) = focus
+                    .as_ref()
+                    .filter(|name| !groups.iter().any(|group| &group.workspace == *name))
+                {
+                    ui.weak(format!("'{}' has no active hotkeys.", name));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(480.0)
+                    .show(ui, |ui| {
+                        for group in &groups {
+                            let heading = egui::RichText::new(&group.workspace).strong();
+                            if focus.as_deref() == Some(group.workspace.as_str()) {
+                                let response = ui.label(
+                                    heading.background_color(ui.visuals().selection.bg_fill),
+                                );
+                                if scroll_to_focus {
+                                    response.scroll_to_me(Some(egui::Align::TOP));
+                                }
+                            } else {
+                                ui.label(heading);
+                            }
+                            egui::Grid::new(("cheat_sheet", &group.workspace))
+                                .num_columns(2)
+                                .spacing([24.0, 4.0])
+                                .show(ui, |ui| {
+                                    for (hotkey, action) in &group.bindings {
+                                        ui.monospace(hotkey);
+                                        ui.label(action);
+                                        ui.end_row();
+                                    }
+                                });
+                            ui.add_space(6.0);
+                        }
+                    });
+            });
+        ctx.memory_mut(|mem| {
+            mem.data.insert_temp(open_id, open);
+            if !open {
+                mem.data.remove::<String>(focus_id);
+            }
+        });
+
+        if copy_requested {
+            match set_clipboard_text(&cheat_sheet_text(&groups)) {
+                Ok(()) => announce(ctx, "Copied the hotkey cheat sheet."),
+                Err(e) => self.report_error(e),
+            }
+        }
+        if save_requested {
+            if let Some(path) = show_save_file_dialog("Save Hotkey Cheat Sheet", "hotkeys.txt") {
+                match std::fs::write(&path, cheat_sheet_text(&groups)) {
+                    Ok(()) => {
+                        info!("Saved the hotkey cheat sheet to '{}'.", path);
+                        announce(ctx, format!("Saved the hotkey cheat sheet to {}", path));
+                    }
+                    Err(e) => self.report_error(MultiManagerError::Export {
+                        path,
+                        message: e.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Renders the window browser beside the workspace list when it is open: every visible
+    /// top-level window with its exe, class, and monitor, refreshed in the background.
+    ///
+    /// Windows can be dragged onto a workspace or added with "Add to". Seeing the exe and
+    /// class of each window also helps explain why a stored window is not matched.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_window_browser(&self, ctx: &egui::Context) {
+        let open = ctx.memory(|mem| {
+            mem.data
+                .get_temp::<bool>(egui::Id::new(WINDOW_BROWSER_OPEN_ID))
+                .unwrap_or(false)
+        });
+        if !open {
+            return;
+        }
+
+        let filter_id = egui::Id::new("window_browser_filter");
+        let mut filter = ctx
+            .memory(|mem| mem.data.get_temp::<String>(filter_id))
+            .unwrap_or_default();
+        let workspace_names: Vec<String> = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|workspace| workspace.name.clone())
+            .collect();
+        let mut browser = self.window_browser.lock().unwrap();
+        let mut refresh_requested = false;
+        // Show each refresh of the list, which happens in the background
+        ctx.request_repaint_after(WINDOW_BROWSER_REFRESH);
+        let mut to_add = None;
+
+        egui::SidePanel::right("window_browser")
+            .resizable(true)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Windows");
+                    if ui.button("Refresh").clicked() {
+                        refresh_requested = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let label = ui.label("Filter:");
+                    ui.text_edit_singleline(&mut filter).labelled_by(label.id);
+                });
+                ui.weak("Drag a window onto a workspace to add it.");
+                ui.separator();
+
+                let needle = filter.to_lowercase();
+                egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .show(ui, |ui| {
+                        for window in browser.poll().iter().filter(|window| {
+                            needle.is_empty()
+                                || window.title.to_lowercase().contains(&needle)
+                                || window.exe.to_lowercase().contains(&needle)
+                                || window.class_name.to_lowercase().contains(&needle)
+                        }) {
+                            let drag_id = egui::Id::new(("browsed_window", window.id));
+                            ui.dnd_drag_source(drag_id, window.clone(), |ui| {
+                                ui.strong(&window.title);
+                            });
+                            ui.horizontal(|ui| {
+                                let monitor = match window.monitor {
+                                    Some(index) => format!("Monitor {}", index + 1),
+                                    None => "No monitor".to_string(),
+                                };
+                                ui.weak(format!(
+                                    "{} · {} · {} · HWND {:#x}",
+                                    window.exe, window.class_name, monitor, window.id
+                                ));
+                                accessible(
+                                    ui.add_enabled_ui(!workspace_names.is_empty(), |ui| {
+                                        ui.menu_button("Add to", |ui| {
+                                            for (index, name) in workspace_names.iter().enumerate()
+                                            {
+                                                if ui.button(name).clicked() {
+                                                    to_add = Some((index, window.clone()));
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        })
+                                    })
+                                    .inner
+                                    .response,
+                                    format!("Add '{}' to a workspace", window.title),
+                                );
+                            });
+                            ui.separator();
+                        }
+                    });
+            });
+
+        if refresh_requested {
+            browser.refresh();
+        }
+        drop(browser);
+        ctx.memory_mut(|mem| mem.data.insert_temp(filter_id, filter));
+
+        if let Some((index, window)) = to_add {
+            if let Some(workspace) = self.workspaces.lock().unwrap().get_mut(index) {
+                self.add_browsed_window(ctx, workspace, &window);
+            }
+        }
+    }
+
+    /// Adds a window picked in the window browser to a workspace, with home and target at
+    /// its current position. The manager's own window is refused, as when capturing.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for announcing the result.
+    /// * `workspace` - The workspace to add the window to.
+    /// * `window` - The window picked in the browser.
+    fn add_browsed_window(
+        &self,
+        ctx: &egui::Context,
+        workspace: &mut Workspace,
+        window: &WindowSummary,
+    ) {
+        if window.title.contains(&self.app_title_name) {
+            show_message_box(
+                "Can not capture window that contains app name for safety",
+                "Capture Info",
+            );
+            return;
+        }
+        workspace.windows.push(window.to_window());
+        info!(
+            "Added window '{}' to workspace '{}'.",
+            window.title, workspace.name
+        );
+        announce(
+            ctx,
+            format!("Added window '{}' to '{}'.", window.title, workspace.name),
+        );
+    }
+
+    /// Returns the title bar icon of a window as a texture, loading it the first time.
+    ///
+    /// Icons are kept by HWND and exe, so a handle reused by another program gets its own.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context that owns the textures.
+    /// * `window` - The window whose icon to show.
+    ///
+    /// # Returns
+    /// * The icon texture, or `None` if the window has no icon.
+    fn window_icon(&self, ctx: &egui::Context, window: &Window) -> Option<egui::TextureHandle> {
+        let mut icons = self.window_icons.lock().unwrap();
+        icons
+            .entry((window.id, window.exe.clone()))
+            .or_insert_with(|| {
+                let hwnd = HWND(window.id as *mut std::ffi::c_void);
+                get_window_icon(hwnd).map(|(width, height, pixels)| {
+                    ctx.load_texture(
+                        format!("window_icon_{}", window.id),
+                        egui::ColorImage::from_rgba_unmultiplied([width, height], &pixels),
+                        egui::TextureOptions::LINEAR,
+                    )
+                })
+            })
+            .clone()
+    }
+
+    /// Shows a live preview of a window, sized to the window's aspect ratio.
+    ///
+    /// DWM draws the preview over everything in the GUI window, so it is only placed while
+    /// its whole area is visible and not covered by a popup or another egui window;
+    /// otherwise the space is left empty.
+    ///
+    /// # Arguments
+    /// * `ui` - The UI the preview is laid out in.
+    /// * `window` - The window to preview.
+    fn window_thumbnail(&self, ui: &mut egui::Ui, window: &Window) {
+        let mut thumbnails = self.thumbnails.lock().unwrap();
+        let Some((width, height)) = thumbnails.source_size(window.id) else {
+            return;
+        };
+        let size = egui::vec2(
+            THUMBNAIL_HEIGHT * width as f32 / height as f32,
+            THUMBNAIL_HEIGHT,
+        )
+        .min(egui::vec2(THUMBNAIL_MAX_WIDTH, THUMBNAIL_HEIGHT));
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        accessible(response, format!("Preview of '{}'", window.title));
+
+        let layer = ui.layer_id();
+        let uncovered = ui.clip_rect().contains_rect(rect)
+            && [
+                rect.left_top(),
+                rect.right_top(),
+                rect.left_bottom(),
+                rect.right_bottom(),
+                rect.center(),
+            ]
+            .into_iter()
+            .all(|point| ui.ctx().layer_id_at(point).is_none_or(|id| id == layer));
+        if !uncovered {
+            return;
+        }
+
+        let pixels = ui.ctx().pixels_per_point();
+        let shown = thumbnails.show(
+            window.id,
+            (
+                (rect.left() * pixels).round() as i32,
+                (rect.top() * pixels).round() as i32,
+                (rect.right() * pixels).round() as i32,
+                (rect.bottom() * pixels).round() as i32,
+            ),
+        );
+        if !shown {
+            ui.painter()
+                .rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+        }
+    }
+
+    /// Renders the row of tabs under the menu bar that switches the main window between the
+    /// workspace list, statistics, history, logs, and settings.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_tabs(&self, ctx: &egui::Context) {
+        let current = current_tab(ctx);
+        let mut selected = None;
+
+        egui::TopBottomPanel::top("main_tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for tab in MainTab::ALL {
+                    if ui.selectable_label(tab == current, tab.title()).clicked() {
+                        selected = Some(tab);
+                    }
+                }
+            });
+        });
+
+        if let Some(tab) = selected {
+            self.select_tab(ctx, tab);
+        }
+    }
+
+    /// Switches the main window to a tab, reading the files it shows so they are current.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context whose memory holds the current tab.
+    /// * `tab` - The tab to show.
+    fn select_tab(&self, ctx: &egui::Context, tab: MainTab) {
+        let previous = current_tab(ctx);
+        match tab {
+            MainTab::History => self.load_history(),
+            MainTab::Logs => self.load_logs(),
+            _ => {}
+        }
+        ctx.memory_mut(|mem| {
+            mem.data.insert_temp(egui::Id::new(MAIN_TAB_ID), tab);
+            // Unapplied logging edits are dropped when leaving the Settings tab, as they were
+            // when the Settings window was closed
+            if previous == MainTab::Settings && tab != MainTab::Settings {
+                mem.data
+                    .remove::<LoggingSettings>(egui::Id::new(SETTINGS_LOGGING_DRAFT_ID));
+            }
+        });
+        announce(ctx, format!("{} tab", tab.title()));
+    }
+
+    /// Appends the workspaces copied as JSON on the clipboard.
+    fn paste_workspaces(&self) {
+        let pasted = match get_clipboard_text().and_then(|text| workspaces_from_json(&text)) {
+            Ok(pasted) => pasted,
+            Err(e) => {
+                self.report_error(e);
+                return;
+            }
+        };
+
+        let mut workspaces = self.workspaces.lock().unwrap();
+        self.append_workspaces(&mut workspaces, pasted);
+    }
+
+    /// Moves a deleted workspace to the top of the archive and saves the archive.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context, for announcing the deletion.
+    /// * `workspace` - The workspace removed from the list.
+    fn archive_workspace(&self, ctx: &egui::Context, mut workspace: Workspace) {
+        workspace.selected = false;
+        info!("Archived workspace '{}'.", workspace.name);
+        announce(
+            ctx,
+            format!("Moved workspace '{}' to the archive.", workspace.name),
+        );
+
+        let mut archive = self.archive.lock().unwrap();
+        archive.insert(0, workspace);
+        self.save_archive(&archive);
+    }
+
+    /// Saves the archived workspaces, encrypted like the workspace files if that is enabled.
+    fn save_archive(&self, archive: &[Workspace]) {
+        if let Err(e) = save_workspaces(archive, &config_path(ARCHIVE_FILE)) {
+            self.report_error(e);
+        }
+    }
+
+    /// Renders the archived workspaces below the workspace list, with actions to restore
+    /// one to the end of the list or purge it for good. Archived workspaces have no hotkeys
+    /// registered until they are restored.
+    ///
+    /// # Arguments
+    /// * `ui` - The UI of the workspace list.
+    /// * `workspaces` - The current workspace list, which restored workspaces are appended to.
+    fn render_archive(&self, ui: &mut egui::Ui, workspaces: &mut Vec<Workspace>) {
+        let mut archive = self.archive.lock().unwrap();
+        if archive.is_empty() {
+            return;
+        }
+
+        let mut to_restore = None;
+        let mut to_purge = None;
+        let mut purge_all = false;
+
+        ui.separator();
+        egui::CollapsingHeader::new(format!("Archived ({})", archive.len()))
+            .id_salt("archived_workspaces")
+            .show(ui, |ui| {
+                for (index, workspace) in archive.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(&workspace.name);
+                        ui.weak(format!("{} window(s)", workspace.windows.len()));
+                        if accessible(
+                            ui.button("Restore"),
+                            format!("Restore archived workspace '{}'", workspace.name),
+                        )
+                        .clicked()
+                        {
+                            to_restore = Some(index);
+                        }
+                        if accessible(
+                            ui.button("Purge"),
+                            format!("Purge archived workspace '{}'", workspace.name),
+                        )
+                        .clicked()
+                        {
+                            to_purge = Some(index);
+                        }
+                    });
+                }
+                if ui.button("Purge All").clicked() {
+                    purge_all = true;
+                }
+            });
+
+        if let Some(index) = to_restore {
+            let workspace = archive.remove(index);
+            info!("Restored archived workspace '{}'.", workspace.name);
+            announce(
+                ui.ctx(),
+                format!("Restored workspace '{}'.", workspace.name),
+            );
+            self.append_workspaces(workspaces, vec![workspace]);
+            self.save_archive(&archive);
+        }
+        if let Some(index) = to_purge {
+            let message = format!(
+                "Permanently delete the archived workspace \n'{}'?\n\nThis action cannot be undone.",
+                archive[index].name
+            );
+            if show_confirmation_box(&message, "Confirm Purge") {
+                let workspace = archive.remove(index);
+                info!("Purged archived workspace '{}'.", workspace.name);
+                self.save_archive(&archive);
+            }
+        }
+        if purge_all {
+            let message = format!(
+                "Permanently delete all {} archived workspaces?\n\nThis action cannot be undone.",
+                archive.len()
+            );
+            if show_confirmation_box(&message, "Confirm Purge") {
+                info!("Purged {} archived workspaces.", archive.len());
+                archive.clear();
+                self.save_archive(&archive);
+            }
+        }
+    }
+
+    /// Appends workspaces from outside the current file, registering their hotkeys.
+    ///
+    /// A hotkey already used by another workspace is dropped rather than shared.
+    ///
+    /// # Arguments
+    /// * `workspaces` - The current workspace list.
+    /// * `added` - The workspaces to append.
+    fn append_workspaces(&self, workspaces: &mut Vec<Workspace>, added: Vec<Workspace>) {
+        for mut workspace in added {
+            if let Some(hotkey) = workspace.hotkey.clone() {
+                let in_use = workspaces.iter().any(|existing| {
+                    existing
+                        .hotkey
+                        .as_ref()
+                        .is_some_and(|h| h.eq_ignore_ascii_case(&hotkey))
+                });
+                if in_use {
+                    warn!(
+                        "Hotkey '{}' of added workspace '{}' is already in use; clearing it.",
+                        hotkey, workspace.name
+                    );
+                    workspace.hotkey = None;
+                } else if !workspace.disabled {
+                    if let Err(e) = self.register_workspace_hotkey(workspaces.len() as i32, &hotkey)
+                    {
+                        self.report_error(e);
+                    }
+                }
+            }
+
+            info!("Added workspace '{}'.", workspace.name);
+            workspaces.push(workspace);
+        }
+    }
+
+    /// Saves only the selected workspaces to a new file, leaving the current file unchanged.
+    ///
+    /// # Arguments
+    /// * `path` - The file to export to.
+    fn export_selected_workspaces(&self, path: &str) {
+        let selected: Vec<Workspace> = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|workspace| workspace.selected)
+            .cloned()
+            .collect();
+
+        match save_workspaces(&selected, path) {
+            Ok(()) => {
+                info!("Exported {} workspaces to '{}'.", selected.len(), path);
+                show_message_box(
+                    &format!("Exported {} workspace(s) to\n{}", selected.len(), path),
+                    "Export Workspaces",
+                );
+            }
+            Err(e) => self.report_error(e),
+        }
+    }
+
+    /// Replaces the workspaces with those in another file, which becomes the current file.
+    ///
+    /// Hotkeys of the old workspaces are unregistered first and hidden windows are shown
+    /// again. If the file cannot be loaded the current workspaces are kept.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context, whose per-workspace editing state is reset; `None` before the
+    ///   window was ever opened, e.g. when opened from the tray icon in the background.
+    /// * `path` - The workspace file to open.
+    fn open_workspaces_file(&self, ctx: Option<&egui::Context>, path: String) {
+        let mut workspaces = self.workspaces.lock().unwrap();
+
+        // Hotkey IDs are workspace indices, which the new file reuses
+        for (i, workspace) in workspaces.iter().enumerate() {
+            if workspace.hotkey.is_some() {
+                unregister_hotkey(&self.registered_hotkeys, i as i32);
+            }
+        }
+
+        match self.load_workspace_file(&path) {
+            Ok(loaded) => {
+                show_hidden_windows(&Win32Backend, &mut workspaces);
+                self.journal.lock().unwrap().set_baseline(&loaded);
+                *workspaces = loaded;
+                self.add_recent_file(&path);
+                *self.workspaces_modified.lock().unwrap() = file_modified(&path);
+                *self.file_conflict.lock().unwrap() = false;
+                *self.workspaces_path.lock().unwrap() = path;
+                *self.validation_promise.lock().unwrap() = None;
+
+                // Temporary hotkey and name edits are keyed by index and belong to the old file
+                if let Some(ctx) = ctx {
+                    ctx.memory_mut(|mem| mem.data.clear());
+                    ctx.request_repaint();
+                }
+            }
+            Err(e) => {
+                for (i, workspace) in workspaces.iter().enumerate() {
+                    if let (Some(hotkey), false) = (&workspace.hotkey, workspace.disabled) {
+                        let _ = register_hotkey(&self.registered_hotkeys, i as i32, hotkey);
+                    }
+                }
+                self.report_load_error(e);
+            }
+        }
+    }
+
+    /// Saves the workspaces to a file, which becomes the current file on success.
+    ///
+    /// If the current file was changed by another program since it was loaded or saved, it is
+    /// not overwritten; the conflict dialog is shown instead.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file to write.
+    fn save_workspaces_file(&self, path: String) {
+        let unparsed = matches!(
+            &*self.parse_failure.lock().unwrap(),
+            Some(MultiManagerError::Parse { path: broken, .. }) if *broken == path
+        );
+        if unparsed {
+            warn!(
+                "'{}' could not be parsed; not overwriting it until it is fixed or backed up.",
+                path
+            );
+            return;
+        }
+        if self.file_changed_on_disk(&path) {
+            warn!(
+                "'{}' was changed by another program; not overwriting it.",
+                path
+            );
+            *self.file_conflict.lock().unwrap() = true;
+            return;
+        }
+        self.write_workspaces_file(path);
+    }
+
+    /// Stores the current position of every window of a workspace as its target and saves the
+    /// workspace file without a confirmation message, for the Save Layout hotkey.
+    ///
+    /// If the file was changed by another program, the targets are still updated but the file
+    /// is not overwritten; the conflict dialog is shown instead.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the workspace to update.
+    fn save_current_layout(&self, index: usize) {
+        {
+            let mut workspaces = self.workspaces.lock().unwrap();
+            let Some(workspace) = workspaces.get_mut(index) else {
+                return;
+            };
+            capture_workspace_positions(&Win32Backend, workspace, PositionKind::Target);
+        }
+
+        let path = self.workspaces_path.lock().unwrap().clone();
+        if self.file_changed_on_disk(&path) {
+            warn!(
+                "'{}' was changed by another program; not overwriting it.",
+                path
+            );
+            *self.file_conflict.lock().unwrap() = true;
+            return;
+        }
+        self.store_workspaces_file(path);
+    }
+
+    /// Saves the workspaces to a file without checking for external changes.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file to write.
+    fn write_workspaces_file(&self, path: String) {
+        if self.store_workspaces_file(path) {
+            show_message_box("Save Workspaces Successful", "Workspace Result");
+        }
+    }
+
+    /// Saves the workspaces to a file and records the change in the edit history, reporting
+    /// any error.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file to write.
+    ///
+    /// # Returns
+    /// * `true` if the file was written.
+    fn store_workspaces_file(&self, path: String) -> bool {
+        // What is being replaced, so the history can say what changed
+        let before = read_workspaces(&path).unwrap_or_default();
+        let workspaces = self.workspaces.lock().unwrap();
+        let result = save_workspaces(&workspaces, &path);
+        let changes = describe_changes(&before, &workspaces);
+        let saved = workspaces.clone();
+        drop(workspaces);
+
+        match result {
+            Ok(()) => {
+                info!("Workspaces saved to '{}'.", path);
+                if let Err(e) = append_history(&config_path(HISTORY_FILE), &path, &changes) {
+                    self.report_error(e);
+                }
+                if self.history.lock().unwrap().is_some() {
+                    self.load_history();
+                }
+                self.add_recent_file(&path);
+                *self.workspaces_modified.lock().unwrap() = file_modified(&path);
+                *self.file_conflict.lock().unwrap() = false;
+                *self.workspaces_path.lock().unwrap() = path;
+                self.journal.lock().unwrap().set_baseline(&saved);
+                self.notify_agent();
+                true
+            }
+            Err(e) => {
+                self.report_error(e);
+                false
+            }
+        }
+    }
+
+    /// Reads the edit history from disk for the Edit History tab.
+    fn load_history(&self) {
+        match read_history(&config_path(HISTORY_FILE)) {
+            Ok(entries) => *self.history.lock().unwrap() = Some(entries),
+            Err(e) => self.report_error(e),
+        }
+    }
+
+    /// Renders the Edit History tab listing saved configuration changes, newest first,
+    /// with a filter to narrow them down (e.g. to one workspace name).
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_history(&self, ui: &mut egui::Ui) {
+        let history = self.history.lock().unwrap();
+        let Some(entries) = history.as_ref() else {
+            ui.label("The edit history could not be read.");
+            return;
+        };
+
+        let filter_id = egui::Id::new("history_filter");
+        let mut filter = ui
+            .memory(|mem| mem.data.get_temp::<String>(filter_id))
+            .unwrap_or_default();
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Filter:");
+            ui.text_edit_singleline(&mut filter).labelled_by(label.id);
+        });
+        ui.separator();
+
+        if entries.is_empty() {
+            ui.label("No changes have been saved yet.");
+        } else {
+            let needle = filter.to_lowercase();
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .show(ui, |ui| {
+                    egui::Grid::new("history_grid")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for entry in entries.iter().rev().filter(|entry| {
+                                needle.is_empty() || entry.change.to_lowercase().contains(&needle)
+                            }) {
+                                ui.label(&entry.timestamp);
+                                ui.label(&entry.user);
+                                ui.label(&entry.change).on_hover_text(&entry.file);
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+
+        ui.memory_mut(|mem| mem.data.insert_temp(filter_id, filter));
+    }
+
+    /// Reads the end of the log file for the Logs tab.
+    fn load_logs(&self) {
+        let path = log_file_path(self.settings.lock().unwrap().logging.as_ref());
+        match read_log_tail(&path, LOG_TAIL_LINES) {
+            Ok(lines) => *self.log_lines.lock().unwrap() = lines,
+            Err(e) => self.report_error(e),
+        }
+    }
+
+    /// Renders the Logs tab with the last lines of the log file and a filter, so problems can
+    /// be looked into without finding the file in the configuration folder.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_logs(&self, ui: &mut egui::Ui) {
+        let filter_id = egui::Id::new("logs_filter");
+        let mut filter = ui
+            .memory(|mem| mem.data.get_temp::<String>(filter_id))
+            .unwrap_or_default();
+        let path = log_file_path(self.settings.lock().unwrap().logging.as_ref());
+        let mut refresh_requested = false;
+
+        ui.horizontal(|ui| {
+            if ui.button("Refresh").clicked() {
+                refresh_requested = true;
+            }
+            let label = ui.label("Filter:");
+            ui.text_edit_singleline(&mut filter).labelled_by(label.id);
+            ui.weak(&path);
+        });
+        ui.separator();
+
+        let needle = filter.to_lowercase();
+        let lines = self.log_lines.lock().unwrap();
+        if lines.is_empty() {
+            ui.label("Nothing has been logged yet.");
+        } else {
+            egui::ScrollArea::both()
+                .auto_shrink(false)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in lines
+                        .iter()
+                        .filter(|line| needle.is_empty() || line.to_lowercase().contains(&needle))
+                    {
+                        ui.monospace(line);
+                    }
+                });
+        }
+        drop(lines);
+
+        ui.memory_mut(|mem| mem.data.insert_temp(filter_id, filter));
+        if refresh_requested {
+            self.load_logs();
+        }
+    }
+
+    /// Remembers a workspace as the most recently triggered one.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the triggered workspace.
+    fn record_trigger(&self, name: &str) {
+        let mut recent = self.recent_triggers.lock().unwrap();
+        recent.retain(|(recent_name, _)| recent_name != name);
+        recent.push_front((name.to_string(), Instant::now()));
+        recent.truncate(RECENT_TRIGGERS_LIMIT);
+        drop(recent);
+        self.request_repaint();
+    }
+
+    /// Renders the Recent section listing the last workspaces triggered, each with
+    /// a button to toggle it again.
+    ///
+    /// Workspaces are looked up by name, so entries for deleted or renamed workspaces are
+    /// shown disabled.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_recent_triggers(&self, ui: &mut egui::Ui) {
+        let recent = self.recent_triggers.lock().unwrap().clone();
+        if recent.is_empty() {
+            return;
+        }
+
+        let mut to_toggle = None;
+        egui::CollapsingHeader::new("Recent")
+            .default_open(true)
+            .show(ui, |ui| {
+                let workspaces = self.workspaces.lock().unwrap();
+                for (name, triggered_at) in &recent {
+                    let index = workspaces.iter().position(|w| &w.name == name);
+                    ui.horizontal(|ui| {
+                        if accessible(
+                            ui.add_enabled(index.is_some(), egui::Button::new("Toggle")),
+                            format!("Toggle '{}'", name),
+                        )
+                        .clicked()
+                        {
+                            to_toggle = index;
+                        }
+                        ui.label(name);
+                        ui.weak(format_ago(triggered_at.elapsed()));
+                    });
+                }
+            });
+
+        if let Some(index) = to_toggle {
+            let (name, id) = {
+                let workspaces = self.workspaces.lock().unwrap();
+                (workspaces[index].name.clone(), workspaces[index].runtime_id)
+            };
+            info!("Toggling recent workspace '{}'.", name);
+            self.record_trigger(&name);
+            self.window_ops.send(WindowOp::Toggle(id));
+        }
+    }
+
+    /// Moves state kept by workspace name, its usage statistics and whether it is collapsed,
+    /// to its new name and saves it.
+    ///
+    /// # Arguments
+    /// * `old_name` - The workspace's current name.
+    /// * `new_name` - The name it is being renamed to.
+    fn rename_workspace_state(&self, old_name: &str, new_name: &str) {
+        let mut usage = self.usage.lock().unwrap();
+        usage.rename(old_name, new_name);
+        if let Err(e) = save_usage_stats(&usage, &config_path(USAGE_FILE)) {
+            self.report_error(e);
+        }
+        drop(usage);
+
+        let mut settings = self.settings.lock().unwrap();
+        if settings.is_collapsed(old_name) {
+            settings.set_collapsed(old_name, false);
+            settings.set_collapsed(new_name, true);
+            if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+                self.report_error(e);
+            }
+        }
+    }
+
+    /// Saves which workspaces were expanded or collapsed this frame.
+    ///
+    /// # Arguments
+    /// * `changes` - The names of the workspaces whose state changed, and whether each is now
+    ///   collapsed.
+    fn save_expansion(&self, changes: &[(String, bool)]) {
+        if changes.is_empty() {
+            return;
+        }
+        let mut settings = self.settings.lock().unwrap();
+        for (name, collapsed) in changes {
+            settings.set_collapsed(name, *collapsed);
+        }
+        if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+            self.report_error(e);
+        }
+    }
+
+    /// Renders the Usage Statistics tab: how often each workspace was activated in each
+    /// direction and when it was last used, sortable by any column so layouts that are never
+    /// triggered stand out.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_usage_stats(&self, ui: &mut egui::Ui) {
+        let sort_id = egui::Id::new("usage_sort");
+        // Least used first by default, as the view is mostly for finding layouts to prune
+        let (mut sort_column, mut ascending) = ui.memory(|mem| {
+            mem.data
+                .get_temp::<(UsageColumn, bool)>(sort_id)
+                .unwrap_or((UsageColumn::Total, true))
+        });
+        let mut reset_requested = false;
+
+        let mut rows: Vec<(String, WorkspaceUsage)> = {
+            let usage = self.usage.lock().unwrap();
+            self.workspaces
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|workspace| (workspace.name.clone(), usage.get(&workspace.name)))
+                .collect()
+        };
+        rows.sort_by(|(a_name, a), (b_name, b)| {
+            let ordering = match sort_column {
+                UsageColumn::Name => a_name.to_lowercase().cmp(&b_name.to_lowercase()),
+                UsageColumn::ToTarget => a.to_target.cmp(&b.to_target),
+                UsageColumn::ToHome => a.to_home.cmp(&b.to_home),
+                UsageColumn::BringToFront => a.bring_to_front.cmp(&b.bring_to_front),
+                UsageColumn::Total => a.total().cmp(&b.total()),
+                UsageColumn::LastUsed => a.last_used.cmp(&b.last_used),
+            };
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+
+        if ui.button("Reset Statistics").clicked()
+            && show_confirmation_box(
+                "Forget how often every workspace has been used?",
+                "Reset Statistics",
+            )
+        {
+            reset_requested = true;
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                egui::Grid::new("usage_grid")
+                    .striped(true)
+                    .num_columns(6)
+                    .show(ui, |ui| {
+                        for (column, label) in [
+                            (UsageColumn::Name, "Workspace"),
+                            (UsageColumn::ToTarget, "To Target"),
+                            (UsageColumn::ToHome, "To Home"),
+                            (UsageColumn::BringToFront, "To Front"),
+                            (UsageColumn::Total, "Total"),
+                            (UsageColumn::LastUsed, "Last Used"),
+                        ] {
+                            let arrow = match (column == sort_column, ascending) {
+                                (true, true) => " ⬆",
+                                (true, false) => " ⬇",
+                                (false, _) => "",
+                            };
+                            if ui.button(format!("{}{}", label, arrow)).clicked() {
+                                if column == sort_column {
+                                    ascending = !ascending;
+                                } else {
+                                    sort_column = column;
+                                    ascending = true;
+                                }
+                            }
+                        }
+                        ui.end_row();
+
+                        for (name, usage) in &rows {
+                            ui.label(name);
+                            ui.label(usage.to_target.to_string());
+                            ui.label(usage.to_home.to_string());
+                            ui.label(usage.bring_to_front.to_string());
+                            ui.label(usage.total().to_string());
+                            ui.label(usage.last_used.as_deref().unwrap_or("Never"));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if reset_requested {
+            let mut usage = self.usage.lock().unwrap();
+            usage.clear();
+            if let Err(e) = save_usage_stats(&usage, &config_path(USAGE_FILE)) {
+                self.report_error(e);
+            }
+        }
+        ui.memory_mut(|mem| mem.data.insert_temp(sort_id, (sort_column, ascending)));
+    }
+
+    /// Checks whether the current workspace file was changed by another program.
+    ///
+    /// # Arguments
+    /// * `path` - The file about to be written. Only the current file is checked; other
+    ///   paths come from Save As, which asks before overwriting.
+    ///
+    /// # Returns
+    /// * `true` if `path` is the current file and its modification time differs from when it
+    ///   was last loaded or saved.
+    fn file_changed_on_disk(&self, path: &str) -> bool {
+        *self.workspaces_path.lock().unwrap() == path
+            && file_modified(path) != *self.workspaces_modified.lock().unwrap()
+    }
+
+    /// Renders the dialog shown when a workspace file could not be parsed: the error, where it
+    /// is, and the lines around it, with options to open the file in Notepad, load it again
+    /// once fixed, or back it up and start with no workspaces.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_parse_failure(&self, ctx: &egui::Context) {
+        let failure = self.parse_failure.lock().unwrap().clone();
+        let Some(MultiManagerError::Parse {
+            path,
+            message,
+            line,
+            column,
+            snippet,
+        }) = failure
+        else {
+            return;
+        };
+
+        let mut open = false;
+        let mut retry = false;
+        let mut start_fresh = false;
+        egui::Window::new("Workspace File Unreadable")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("'{}' could not be parsed:", path));
+                ui.colored_label(egui::Color32::RED, &message);
+                ui.label(format!("Line {}, column {}", line, column));
+                ui.add(egui::Label::new(egui::RichText::new(&snippet).monospace()).extend());
+                ui.horizontal(|ui| {
+                    open = ui
+                        .button("Open File")
+                        .on_hover_text("Open the file in Notepad to fix it")
+                        .clicked();
+                    retry = ui
+                        .button("Retry")
+                        .on_hover_text("Load the file again")
+                        .clicked();
+                    start_fresh = ui
+                        .button("Start Fresh")
+                        .on_hover_text(
+                            "Rename the broken file as a backup and start with no workspaces",
+                        )
+                        .clicked();
+                });
+            });
+
+        if open {
+            info!("Opening '{}' in Notepad.", path);
+            if let Err(e) = std::process::Command::new("notepad.exe").arg(&path).spawn() {
+                warn!("Failed to open '{}' in Notepad: {}", path, e);
+            }
+        } else if retry {
+            *self.parse_failure.lock().unwrap() = None;
+            self.open_workspaces_file(Some(ctx), path);
+        } else if start_fresh {
+            match back_up_broken_file(&path) {
+                Ok(_) => {
+                    *self.parse_failure.lock().unwrap() = None;
+                    // The file is gone now, so it opens as an empty workspace list
+                    self.open_workspaces_file(Some(ctx), path);
+                }
+                Err(e) => self.report_error(e),
+            }
+        }
+    }
+
+    /// Renders the dialog offering to recover the unsaved edits journaled before the previous
+    /// run ended unexpectedly.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_recovery_prompt(&self, ctx: &egui::Context) {
+        let mut recover = false;
+        let mut discard = false;
+        {
+            let journal = self.journal.lock().unwrap();
+            let Some(recovery) = journal.recovery() else {
+                return;
+            };
+            egui::Window::new("Recover Unsaved Edits")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(
+                        "Multi Manager did not exit normally last time. These edits were not saved:",
+                    );
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for entry in &recovery.entries {
+                            ui.label(format!("{}  {}", entry.timestamp, entry.change));
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        recover = ui
+                            .button("Recover")
+                            .on_hover_text("Replace the workspaces with the unsaved ones; save to keep them")
+                            .clicked();
+                        discard = ui
+                            .button("Discard")
+                            .on_hover_text("Keep the workspaces from the file and forget the unsaved edits")
+                            .clicked();
+                    });
+                });
+        }
+
+        if discard {
+            info!("Discarding the unsaved edits from the previous run.");
+            self.journal.lock().unwrap().take_recovery();
+        } else if recover {
+            let Some(recovery) = self.journal.lock().unwrap().take_recovery() else {
+                return;
+            };
+            info!(
+                "Recovering {} unsaved edit(s) from the previous run.",
+                recovery.entries.len()
+            );
+            let mut workspaces = self.workspaces.lock().unwrap();
+            for (i, workspace) in workspaces.iter().enumerate() {
+                if workspace.hotkey.is_some() {
+                    unregister_hotkey(&self.registered_hotkeys, i as i32);
+                }
+            }
+            *workspaces = recovery.workspaces;
+            for (i, workspace) in workspaces.iter().enumerate() {
+                if let (Some(hotkey), false) = (&workspace.hotkey, workspace.disabled) {
+                    if let Err(e) = self.register_workspace_hotkey(i as i32, hotkey) {
+                        warn!(
+                            "Failed to register hotkey for workspace '{}': {}",
+                            workspace.name, e
+                        );
+                    }
+                }
+            }
+            // Temporary hotkey and name edits are keyed by index and belong to the old workspaces
+            ctx.memory_mut(|mem| mem.data.clear());
+        }
+    }
+
+    /// Renders the dialog shown when the current workspace file was changed by another
+    /// program, offering to keep the in-memory workspaces, take the file's, or merge them.
+    ///
+    /// The file is checked every `FILE_CHECK_INTERVAL`. The dialog is only shown when there
+    /// are unsaved edits and the file's workspaces differ from the ones last loaded or saved;
+    /// otherwise the change is taken without asking.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_file_conflict(&self, ctx: &egui::Context) {
+        let path = self.workspaces_path.lock().unwrap().clone();
+        if !*self.file_conflict.lock().unwrap() {
+            let check_id = egui::Id::new(FILE_CHECK_ID);
+            let last_check = ctx.memory(|mem| mem.data.get_temp::<Instant>(check_id));
+            if last_check.is_some_and(|checked| checked.elapsed() < FILE_CHECK_INTERVAL) {
+                return;
+            }
+            ctx.memory_mut(|mem| mem.data.insert_temp(check_id, Instant::now()));
+            if !self.file_changed_on_disk(&path) || !self.file_change_needs_choice(&path) {
+                return;
+            }
+            info!("'{}' was changed by another program.", path);
+            *self.file_conflict.lock().unwrap() = true;
+        }
+
+        let mut keep_mine = false;
+        let mut take_theirs = false;
+        let mut merge = false;
+        egui::Window::new("Workspace File Changed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("'{}' was changed by another program.", path));
+                ui.label("Choose which workspaces to keep:");
+                ui.horizontal(|ui| {
+                    keep_mine = ui
+                        .button("Keep Mine")
+                        .on_hover_text("Overwrite the file with the workspaces shown here")
+                        .clicked();
+                    take_theirs = ui
+                        .button("Take Theirs")
+                        .on_hover_text("Discard changes made here and reload the file")
+                        .clicked();
+                    merge = ui
+                        .button("Merge")
+                        .on_hover_text("Keep the workspaces shown here and add those only in the file, then save")
+                        .clicked();
+                });
+            });
+
+        if keep_mine {
+            self.write_workspaces_file(path);
+        } else if take_theirs {
+            self.open_workspaces_file(Some(ctx), path);
+        } else if merge {
+            self.merge_workspaces_file(path);
+        }
+    }
+
+    /// Takes a change another program made to the current workspace file without asking, if
+    /// that loses nothing: when the file's workspaces are the ones last loaded or saved, or
+    /// when there are no unsaved edits, in which case the file is reloaded.
+    ///
+    /// # Arguments
+    /// * `path` - The current workspace file, changed on disk.
+    ///
+    /// # Returns
+    /// * `true` if the user has to choose which workspaces to keep.
+    fn file_change_needs_choice(&self, path: &str) -> bool {
+        let Ok(theirs) = read_workspaces(path) else {
+            return true;
+        };
+        let mine = self.workspaces.lock().unwrap().clone();
+        let (unchanged, unsaved) = {
+            let journal = self.journal.lock().unwrap();
+            (
+                journal.matches_baseline(&theirs),
+                !journal.matches_baseline(&mine),
+            )
+        };
+        if unchanged {
+            info!(
+                "'{}' was rewritten by another program with the same workspaces.",
+                path
+            );
+            *self.workspaces_modified.lock().unwrap() = file_modified(path);
+            return false;
+        }
+        if unsaved {
+            return true;
+        }
+        info!(
+            "'{}' was changed by another program; reloading it, as nothing here is unsaved.",
+            path
+        );
+        if let Some(loaded) = self.reload_workspace_file(path) {
+            self.journal.lock().unwrap().set_baseline(&loaded);
+        }
+        false
+    }
+
+    /// Adds the workspaces that exist only in the file on disk, matched by name, then saves.
+    ///
+    /// Workspaces present in both keep their in-memory version.
+    ///
+    /// # Arguments
+    /// * `path` - The current workspace file.
+    fn merge_workspaces_file(&self, path: String) {
+        let theirs = match read_workspaces(&path) {
+            Ok(theirs) => theirs,
+            Err(e) => {
+                self.report_error(e);
+                return;
+            }
+        };
+
+        {
+            let mut workspaces = self.workspaces.lock().unwrap();
+            let added: Vec<Workspace> = theirs
+                .into_iter()
+                .filter(|theirs| !workspaces.iter().any(|mine| mine.name == theirs.name))
+                .collect();
+            info!("Merging {} workspace(s) from '{}'.", added.len(), path);
+            self.append_workspaces(&mut workspaces, added);
+        }
+        self.write_workspaces_file(path);
+    }
+
+    /// Turns every window move animation on or off and saves the setting, from **View → Animate
+    /// Window Moves** or the tray menu.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether windows may glide to their positions.
+    fn set_animations(&self, enabled: bool) {
+        let mut settings = self.settings.lock().unwrap();
+        settings.animations_enabled = enabled;
+        set_animations_enabled(enabled);
+        if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+            self.report_error(e);
+        }
+        drop(settings);
+        self.notify_agent();
+        self.request_repaint();
+    }
+
+    /// Adds a workspace file to the recent files and saves the settings.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file that was opened or saved.
+    fn add_recent_file(&self, path: &str) {
+        let mut settings = self.settings.lock().unwrap();
+        settings.add_recent_file(path);
+        if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+            self.report_error(e);
+        }
+    }
+
+    /// Loads a workspace file, registering the hotkeys of its workspaces unless a background
+    /// agent is attached, which holds the hotkeys itself.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file to load.
+    ///
+    /// # Returns
+    /// * The loaded workspaces, or the error from reading the file.
+    fn load_workspace_file(&self, path: &str) -> Result<Vec<Workspace>, MultiManagerError> {
+        if self.agent_attached {
+            read_workspaces(path)
+        } else {
+            load_workspaces(path, &self.registered_hotkeys)
+        }
+    }
+
+    /// Registers the hotkey of a workspace, unless a background agent is attached and holds the
+    /// workspace hotkeys itself.
+    ///
+    /// # Arguments
+    /// * `id` - The hotkey ID, the index of the workspace.
+    /// * `hotkey` - The hotkey to register.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the hotkey was registered or the agent handles it, or the registration error.
+    fn register_workspace_hotkey(&self, id: i32, hotkey: &str) -> Result<(), MultiManagerError> {
+        if self.agent_attached {
+            return Ok(());
+        }
+        register_hotkey(&self.registered_hotkeys, id, hotkey)
+    }
+
+    /// Records an error from loading a workspace file. Parse errors open the dialog that shows
+    /// where the file is broken; other errors go to the error panel.
+    ///
+    /// # Arguments
+    /// * `error` - The error to show.
+    fn report_load_error(&self, error: MultiManagerError) {
+        match error {
+            MultiManagerError::Parse { .. } => {
+                *self.parse_failure.lock().unwrap() = Some(error);
+                self.request_repaint();
+            }
+            error => self.report_error(error),
+        }
+    }
+
+    /// Records an error so it is shown in the error panel until the user dismisses it.
+    ///
+    /// # Arguments
+    /// * `error` - The error to show.
+    fn report_error(&self, error: MultiManagerError) {
+        warn!("{}", error);
+        self.errors.lock().unwrap().push(error);
+        self.request_repaint();
+    }
+
+    /// Asks the GUI to repaint soon, for changes made by background threads such as hotkey
+    /// triggers. Does nothing before the GUI is running.
+    fn request_repaint(&self) {
+        if let Some(ctx) = self.egui_ctx.lock().unwrap().as_ref() {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Shows a chooser for every entry that matched several windows when its workspace was
+    /// toggled, whether or not the workspace is expanded or shown in the list, and moves the
+    /// chosen window to where the rest of its workspace went.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context to show the choosers in.
+    fn render_window_choices(&self, ctx: &egui::Context) {
+        let mut moves = Vec::new();
+        let mut workspaces = self.workspaces.lock().unwrap();
+        for workspace in workspaces.iter_mut() {
+            let direction = workspace
+                .activity
+                .newest_first()
+                .next()
+                .map(|e| e.direction);
+            let (workspace_id, workspace_name) = (workspace.runtime_id, workspace.name.clone());
+            for window in workspace
+                .windows
+                .iter_mut()
+                .filter(|window| !window.candidates.is_empty())
+            {
+                let mut chosen = None;
+                let mut dismissed = false;
+                egui::Window::new(format!("Choose window for '{}'", window.capture_title))
+                    .id(egui::Id::new(("disambiguate", window.runtime_id)))
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.label(format!(
+                            "Several windows match this entry of '{}'. Pick the one to use:",
+                            workspace_name
+                        ));
+                        for &candidate in &window.candidates {
+                            let candidate_hwnd = HWND(candidate as *mut std::ffi::c_void);
+                            if ui
+                                .button(format!(
+                                    "{} (HWND: {:?})",
+                                    get_window_title(candidate_hwnd),
+                                    candidate
+                                ))
+                                .clicked()
+                            {
+                                chosen = Some(candidate);
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            dismissed = true;
+                        }
+                    });
+                if let Some(candidate) = chosen {
+                    info!(
+                        "User chose HWND {:?} for '{}'.",
+                        candidate, window.capture_title
+                    );
+                    moves.extend(apply_window_choice(
+                        workspace_id,
+                        direction,
+                        window,
+                        candidate,
+                    ));
+                } else if dismissed {
+                    window.candidates.clear();
+                }
+            }
+        }
+        drop(workspaces);
+        for op in moves {
+            self.window_ops.send(op);
+        }
+    }
+
+    /// Opens the window if an entry is waiting for the user to choose between several matching
+    /// windows, so the choice can be made while running in the background or hidden on close.
+    fn show_gui_for_window_choices(&self) {
+        let waiting = self.workspaces.lock().unwrap().iter().any(|workspace| {
+            workspace
+                .windows
+                .iter()
+                .any(|window| !window.candidates.is_empty())
+        });
+        if waiting {
+            self.show_gui("to choose between matching windows");
+        }
+    }
+
+    /// Opens the window for the show hotkey: asks `run_gui` to create it if the application is
+    /// running in the background, or shows it if it was hidden on close or minimized, and
+    /// brings it to the front.
+    ///
+    /// # Arguments
+    /// * `reason` - Why the window is opened, for the log, e.g. "via hotkey 'Ctrl+Alt+M'".
+    fn show_gui(&self, reason: &str) {
+        let mut requested = self.gui_requested.lock().unwrap();
+        if !*requested {
+            info!("Opening the window {}.", reason);
+            *requested = true;
+        }
+        // The window may be hidden, when egui no longer runs its frames, so it is shown directly
+        if let Some(hwnd) = *self.gui_hwnd.lock().unwrap() {
+            if !Win32Backend.is_visible(hwnd) || Win32Backend.is_minimized(hwnd) {
+                Win32Backend.show(hwnd, ShowCommand::Restore);
+            }
+            Win32Backend.activate(hwnd);
+        }
+    }
+
+    /// Builds the tray icon's menu: the enabled workspaces with their icons and colors and the
+    /// recently triggered ones to toggle, the recent workspace files to open, and the animation
+    /// switch.
+    ///
+    /// # Arguments
+    /// * `exit_requested` - Set by **Exit** when there is no window to close yet.
+    ///
+    /// # Returns
+    /// * The menu items.
+    fn tray_menu(&self, exit_requested: &Arc<AtomicBool>) -> Vec<TrayMenuItem> {
+        let workspace_items = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|workspace| !workspace.disabled)
+            .map(|workspace| {
+                let label = format!("{} {}", workspace.icon, workspace.name);
+                self.tray_toggle(label.trim(), workspace.runtime_id)
+                    .color(workspace.color)
+            })
+            .collect();
+        let recent_items = {
+            let workspaces = self.workspaces.lock().unwrap();
+            self.recent_triggers
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, _)| {
+                    // Deleted or renamed workspaces are greyed out, as in the Recent section
+                    match workspaces.iter().find(|workspace| &workspace.name == name) {
+                        Some(workspace) => self.tray_toggle(name, workspace.runtime_id),
+                        None => TrayMenuItem::command(name.clone(), || {}).enabled(false),
+                    }
+                })
+                .collect()
+        };
+        let recent_files = self.settings.lock().unwrap().recent_files.clone();
+        let file_items = recent_files
+            .into_iter()
+            .map(|path| {
+                // A missing file would load as an empty workspace list
+                let exists = std::path::Path::new(&path).is_file();
+                let app = self.clone();
+                TrayMenuItem::command(path.clone(), move || {
+                    info!("Opening '{}' from the tray icon.", path);
+                    let ctx = app.egui_ctx.lock().unwrap().clone();
+                    app.open_workspaces_file(ctx.as_ref(), path);
+                })
+                .enabled(exists)
+            })
+            .collect();
+        let animations_enabled = self.settings.lock().unwrap().animations_enabled;
+
+        let app_for_show = self.clone();
+        let app_for_animations = self.clone();
+        let app_for_exit = self.clone();
+        let exit_requested = exit_requested.clone();
+        vec![
+            TrayMenuItem::command(format!("Show {}", self.app_title_name), move || {
+                app_for_show.show_gui("from the tray icon")
+            }),
+            TrayMenuItem::Separator,
+            TrayMenuItem::submenu("Workspaces", workspace_items),
+            TrayMenuItem::submenu("Recent Workspaces", recent_items),
+            TrayMenuItem::submenu("Open Recent", file_items),
+            TrayMenuItem::Separator,
+            TrayMenuItem::command("Animate Window Moves", move || {
+                app_for_animations.set_animations(!animations_enabled)
+            })
+            .checked(animations_enabled),
+            TrayMenuItem::Separator,
+            TrayMenuItem::command("Exit", move || app_for_exit.exit_from_tray(&exit_requested)),
+        ]
+    }
+
+    /// Creates a tray menu item that toggles a workspace, as its hotkey would.
+    ///
+    /// # Arguments
+    /// * `label` - The text of the item.
+    /// * `id` - The workspace to toggle.
+    ///
+    /// # Returns
+    /// * The menu item.
+    fn tray_toggle(&self, label: &str, id: RuntimeId) -> TrayMenuItem {
+        let app = self.clone();
+        TrayMenuItem::command(label, move || {
+            let name = app
+                .workspaces
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|workspace| workspace.runtime_id == id)
+                .map(|workspace| workspace.name.clone());
+            if let Some(name) = name {
+                info!("Toggling workspace '{}' from the tray icon.", name);
+                app.record_trigger(&name);
+                app.window_ops.send(WindowOp::Toggle(id));
+            }
+        })
+    }
+
+    /// Exits the application from the tray icon: closes the window for good if it is open, even
+    /// when hidden on close, or stops `run_gui` waiting in the background for it.
+    ///
+    /// # Arguments
+    /// * `exit_requested` - Set when there is no window to close yet.
+    fn exit_from_tray(&self, exit_requested: &AtomicBool) {
+        let ctx = self.egui_ctx.lock().unwrap().clone();
+        match ctx {
+            Some(ctx) => {
+                // A hidden window runs no frames, so it is shown to handle the close
+                self.show_gui("to exit");
+                ctx.memory_mut(|mem| mem.data.insert_temp(egui::Id::new(EXIT_REQUESTED_ID), true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                ctx.request_repaint();
+            }
+            None => exit_requested.store(true, Ordering::SeqCst),
+        }
+    }
+
+    /// Asks the background agent, if this GUI is attached to one, to load the saved settings and
+    /// the current workspace file, so hotkeys follow the changes.
+    fn notify_agent(&self) {
+        if !self.agent_attached {
+            return;
+        }
+        let path = self.workspaces_path.lock().unwrap().clone();
+        if !send_to_agent(&AgentCommand::Reload(path)) {
+            warn!("The background agent did not reload; restart it to apply the changes.");
+        }
+    }
+
+    /// Reloads the settings and a workspace file in the agent, when a GUI saved them.
+    ///
+    /// Hotkeys are registered again from the reloaded workspaces. Workspaces whose windows are
+    /// unchanged keep their runtime state, such as which windows are hidden.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file the GUI saved, which becomes the agent's current file.
+    ///
+    /// # Returns
+    /// * `true` if the workspace file was loaded.
+    fn reload_for_agent(&self, path: &str) -> bool {
+        let settings = load_settings(&config_path(SETTINGS_FILE));
+        set_encryption_enabled(settings.encrypt_workspaces);
+        set_show_all_windows(settings.show_all_windows);
+        set_global_animation(settings.animation);
+        set_animations_enabled(settings.animations_enabled);
+        set_respect_reduced_motion(settings.respect_reduced_motion);
+        self.set_gestures_enabled(settings.mouse_gestures);
+        self.set_caps_layer_enabled(settings.caps_lock_layer);
+        *self.settings.lock().unwrap() = settings;
+
+        let reloaded = self.reload_workspace_file(path).is_some();
+        if reloaded {
+            info!("Agent reloaded '{}'.", path);
+        }
+        reloaded
+    }
+
+    /// Loads a workspace file in place of the current workspaces, registering its hotkeys
+    /// again. Workspaces whose windows are unchanged keep their runtime state, such as which
+    /// windows are hidden.
+    ///
+    /// # Arguments
+    /// * `path` - The workspace file to load, which becomes the current file.
+    ///
+    /// # Returns
+    /// * The loaded workspaces, or `None` if the file could not be loaded.
+    fn reload_workspace_file(&self, path: &str) -> Option<Vec<Workspace>> {
+        let ids: Vec<usize> = self
+            .registered_hotkeys
+            .lock()
+            .unwrap()
+            .values()
+            .copied()
+            .collect();
+        for id in ids {
+            unregister_hotkey(&self.registered_hotkeys, id as i32);
+        }
+        match self.load_workspace_file(path) {
+            Ok(mut loaded) => {
+                let mut workspaces = self.workspaces.lock().unwrap();
+                for workspace in &mut loaded {
+                    if let Some(previous) = workspaces.iter().find(|previous| {
+                        previous.name == workspace.name
+                            && previous.windows.len() == workspace.windows.len()
+                    }) {
+                        // The reloaded entries are new, so they take over the identities of the ones they replace
+                        workspace.runtime_id = previous.runtime_id;
+                        for (window, previous) in
+                            workspace.windows.iter_mut().zip(&previous.windows)
+                        {
+                            window.runtime_id = previous.runtime_id;
+                        }
+                        workspace.merge_runtime_state(previous);
+                    }
+                }
+                *workspaces = loaded.clone();
+                *self.workspaces_path.lock().unwrap() = path.to_string();
+                *self.workspaces_modified.lock().unwrap() = file_modified(path);
+                Some(loaded)
+            }
+            Err(e) => {
+                self.report_error(e);
+                None
+            }
+        }
+    }
+
+    /// Relaunches the application as administrator with the workspaces in memory and exits.
+    ///
+    /// Hotkeys are released first so the elevated instance can register them, and registered
+    /// again if the relaunch fails or consent is not given.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context of the main window, closed once the new instance started.
+    fn relaunch_as_admin(&self, ctx: &egui::Context) {
+        let snapshot = self.workspaces.lock().unwrap().clone();
+        let path = self.workspaces_path.lock().unwrap().clone();
+        let registered: Vec<(String, usize)> = self
+            .registered_hotkeys
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(hotkey, &id)| (hotkey.clone(), id))
+            .collect();
+        for (_, id) in &registered {
+            unregister_hotkey(&self.registered_hotkeys, *id as i32);
+        }
+
+        match relaunch_elevated(&snapshot, &path) {
+            Ok(()) => {
+                ctx.memory_mut(|mem| mem.data.insert_temp(egui::Id::new(EXIT_REQUESTED_ID), true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            Err(e) => {
+                for (hotkey, id) in registered {
+                    if let Err(e) = register_hotkey(&self.registered_hotkeys, id as i32, &hotkey) {
+                        warn!("Failed to register hotkey '{}' again: {}", hotkey, e);
+                    }
+                }
+                self.report_error(e);
+            }
+        }
+    }
+
+    /// Hides the window to the tray instead of exiting when it is closed with "Hide on close"
+    /// on, so hotkeys keep working. Without the tray icon or a show hotkey to bring it back, it
+    /// is minimized instead. **File → Exit** and the tray icon's **Exit** always exit.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context of the main window.
+    fn handle_close_request(&self, ctx: &egui::Context) {
+        if !ctx.input(|input| input.viewport().close_requested()) {
+            return;
+        }
+        let exiting = ctx.memory(|mem| {
+            mem.data
+                .get_temp::<bool>(egui::Id::new(EXIT_REQUESTED_ID))
+                .unwrap_or(false)
+        });
+        let (hide_on_close, can_show) = {
+            let settings = self.settings.lock().unwrap();
+            (
+                settings.hide_on_close,
+                *self.tray_running.lock().unwrap()
+                    || settings
+                        .show_gui_hotkey
+                        .as_deref()
+                        .is_some_and(is_valid_key_combo),
+            )
+        };
+        if exiting || !hide_on_close {
+            return;
+        }
+
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        match *self.gui_hwnd.lock().unwrap() {
+            Some(hwnd) if can_show => {
+                info!("Hiding the window to the tray on close; hotkeys keep running.");
+                Win32Backend.show(hwnd, ShowCommand::Hide);
+            }
+            _ => {
+                info!(
+                    "Minimizing the window on close, as there is no tray icon or show hotkey to bring it back."
+                );
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            }
+        }
+    }
+
+    /// Returns how long the GUI may idle before repainting when nothing changes.
+    ///
+    /// Relative times show seconds for the first minute, so the GUI repaints every second
+    /// while the most recent trigger or toggle is that new, and every 30 seconds otherwise.
+    fn idle_repaint_interval(&self) -> Duration {
+        let last_hotkey = self
+            .last_hotkey_info
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(_, at)| at.elapsed());
+        let last_trigger = self
+            .recent_triggers
+            .lock()
+            .unwrap()
+            .front()
+            .map(|(_, at)| at.elapsed());
+        let last_toggle = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|workspace| workspace.activity.newest_first().next())
+            .map(|entry| {
+                (chrono::Local::now() - entry.triggered_at)
+                    .to_std()
+                    .unwrap_or_default()
+            })
+            .min();
+        let newest = [last_hotkey, last_trigger, last_toggle]
+            .into_iter()
+            .flatten()
+            .min();
+        if newest.is_some_and(|elapsed| elapsed < Duration::from_secs(60)) {
+            Duration::from_secs(1)
+        } else {
+            Duration::from_secs(30)
+        }
+    }
+
+    /// Shows the countdown of a delayed capture in a small always-on-top window that never
+    /// takes focus, so menus and fullscreen windows stay open, and captures the active window
+    /// or the window under the cursor when it ends.
+    ///
+    /// The window's position and size are captured as both its home and target, as the user
+    /// has just arranged it.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_capture_countdown(&self, ctx: &egui::Context) {
+        let mut countdown = self.countdown_capture.lock().unwrap();
+        let Some(capture) = countdown.as_ref() else {
+            return;
+        };
+
+        let remaining = capture.deadline.saturating_duration_since(Instant::now());
+        if !remaining.is_zero() {
+            // Keep counting while another window has focus
+            ctx.request_repaint_after(Duration::from_millis(100));
+            let seconds = remaining.as_secs() + 1;
+            let instruction = if capture.under_cursor {
+                format!("Point at the window to add to '{}'", capture.workspace_name)
+            } else {
+                format!("Focus the window to add to '{}'", capture.workspace_name)
+            };
+            ctx.show_viewport_immediate(
+                egui::ViewportId::from_hash_of("capture_countdown"),
+                ViewportBuilder::default()
+                    .with_title(format!("{} - Capture", self.app_title_name))
+                    .with_inner_size([260.0, 70.0])
+                    .with_decorations(false)
+                    .with_always_on_top()
+                    .with_active(false)
+                    .with_mouse_passthrough(true)
+                    .with_taskbar(false)
+                    .with_resizable(false),
+                |ctx, _| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.heading(format!("Capturing in {}...", seconds));
+                            ui.label(&instruction);
+                        });
+                    });
+                },
+            );
+            return;
+        }
+
+        let Some(capture) = countdown.take() else {
+            return;
+        };
+        drop(countdown);
+
+        let mut workspaces = self.workspaces.lock().unwrap();
+        let Some(workspace) = workspaces
+            .get_mut(capture.workspace)
+            .filter(|workspace| workspace.name == capture.workspace_name)
+        else {
+            warn!(
+                "Workspace '{}' changed before the delayed capture; capture dropped.",
+                capture.workspace_name
+            );
+            announce(ctx, "Capture canceled: the workspace list changed.");
+            return;
+        };
+
+        let captured = if capture.under_cursor {
+            get_window_under_cursor()
+        } else {
+            get_active_window()
+        };
+        let Some((hwnd, title)) = captured else {
+            warn!("Delayed capture found no window to capture.");
+            announce(ctx, "Capture canceled: no window found.");
+            return;
+        };
+        if title.contains(&self.app_title_name) {
+            info!("Delayed capture was potentially the app name");
+            announce(
+                ctx,
+                "Can not capture window that contains app name for safety.",
+            );
+            return;
+        }
+
+        let rect = get_window_position(hwnd).unwrap_or((0, 0, 800, 600));
+        workspace.windows.push(Window {
+            id: hwnd.0 as usize,
+            title: title.clone(),
+            capture_title: title.clone(),
+            exe: get_window_exe(hwnd),
+            class_name: get_window_class(hwnd),
+            home: rect,
+            target: rect,
+            ..Default::default()
+        });
+        info!(
+            "Captured '{}' at {:?} into '{}' after a countdown.",
+            title, rect, workspace.name
+        );
+        announce(
+            ctx,
+            format!(
+                "Captured active window '{}' into '{}'.",
+                title, workspace.name
+            ),
+        );
+    }
+
+    /// Shows the grace prompt for scheduled moves in a small always-on-top window, with a
+    /// countdown for each and buttons to move now or skip the move.
+    ///
+    /// The moves themselves are made by the scheduler thread when their deadline passes, so
+    /// they happen even if this window is minimized.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_schedule_prompt(&self, ctx: &egui::Context) {
+        let pending = self.scheduled_moves.lock().unwrap().clone();
+        if pending.is_empty() {
+            return;
+        }
+
+        // Keep counting while another window has focus
+        ctx.request_repaint_after(Duration::from_millis(250));
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("schedule_prompt"),
+            ViewportBuilder::default()
+                .with_title(format!("{} - Scheduled Layout", self.app_title_name))
+                .with_inner_size([380.0, 50.0 + 30.0 * pending.len() as f32])
+                .with_always_on_top()
+                .with_active(false)
+                .with_resizable(false),
+            |ctx, _| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.heading("Scheduled layout change");
+                    for entry in &pending {
+                        let seconds = entry
+                            .deadline
+                            .saturating_duration_since(Instant::now())
+                            .as_secs()
+                            + 1;
+                        let destination = match entry.position {
+                            PositionKind::Target => "its targets",
+                            PositionKind::Home => "home",
+                        };
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "'{}' moves to {} in {} s",
+                                entry.workspace_name, destination, seconds
+                            ));
+                            let mut moves = self.scheduled_moves.lock().unwrap();
+                            let same = |other: &PendingMove| {
+                                other.workspace == entry.workspace
+                                    && other.deadline == entry.deadline
+                            };
+                            if ui.button("Move Now").clicked() {
+                                if let Some(other) = moves.iter_mut().find(|other| same(other)) {
+                                    other.deadline = Instant::now();
+                                }
+                            }
+                            if ui.button("Skip").clicked() {
+                                info!(
+                                    "Skipped the scheduled move of '{}' to {:?}.",
+                                    entry.workspace_name, entry.position
+                                );
+                                moves.retain(|other| !same(other));
+                            }
+                        });
+                    }
+                });
+            },
+        );
+    }
+
+    /// Renders the panel listing errors that have not yet been dismissed.
+    ///
+    /// The panel is hidden when there are no errors.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_errors(&self, ctx: &egui::Context) {
+        let mut errors = self.errors.lock().unwrap();
+        if errors.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        let mut dismiss_all = false;
+        egui::TopBottomPanel::bottom("errors").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::RED, format!("{} error(s)", errors.len()));
+                if ui.button("Dismiss All").clicked() {
+                    dismiss_all = true;
+                }
+            });
+            egui::ScrollArea::vertical()
+                .max_height(120.0)
+                .show(ui, |ui| {
+                    for (k, error) in errors.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Dismiss").clicked() {
+                                dismissed = Some(k);
+                            }
+                            ui.colored_label(egui::Color32::RED, error.to_string());
+                        });
+                    }
+                });
+        });
+
+        if dismiss_all {
+            errors.clear();
+        } else if let Some(k) = dismissed {
+            errors.remove(k);
+        }
+    }
+
+    /// Renders the Settings tab.
+    ///
+    /// Logging changes are edited as a draft and only take effect, and are saved to
+    /// `settings.json`, when "Apply" is clicked. The logger is reconfigured in place.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_settings(&self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let draft_id = egui::Id::new(SETTINGS_LOGGING_DRAFT_ID);
+
+        let mut settings = self.settings.lock().unwrap();
+        let mut draft = ctx
+            .memory(|mem| mem.data.get_temp::<LoggingSettings>(draft_id))
+            .unwrap_or_else(|| settings.logging.clone().unwrap_or_default());
+        let mut apply = false;
+        let mut save_requested = false;
+        let mut resave_workspaces = false;
+
+        egui::ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+            ui.heading("Logging");
+            if settings.logging.is_none() {
+                ui.label("Currently configured by log4rs.yaml; applying these settings overrides it.");
+            }
+
+            egui::Grid::new("logging_settings").num_columns(2).show(ui, |ui| {
+                let label = ui.label("Level:");
+                egui::ComboBox::from_id_salt("log_level")
+                    .selected_text(format!("{:?}", draft.level))
+                    .show_ui(ui, |ui| {
+                        for level in LogLevel::ALL {
+                            ui.selectable_value(&mut draft.level, level, format!("{:?}", level));
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+                ui.end_row();
+
+                let label = ui.label("Log file:");
+                ui.text_edit_singleline(&mut draft.file_path).labelled_by(label.id);
+                ui.end_row();
+
+                ui.label("Rotate:");
+                ui.checkbox(&mut draft.rotate, "Roll over when the file gets large");
+                ui.end_row();
+
+                let label = ui.label("Max size:");
+                ui.add_enabled(draft.rotate, egui::DragValue::new(&mut draft.max_size_mb).range(1..=1024).suffix(" MB")).labelled_by(label.id);
+                ui.end_row();
+
+                let label = ui.label("Files kept:");
+                ui.add_enabled(draft.rotate, egui::DragValue::new(&mut draft.max_files).range(1..=100)).labelled_by(label.id);
+                ui.end_row();
+            });
+
+            if ui.button("Apply").clicked() {
+                apply = true;
+            }
+
+            ui.separator();
+            ui.heading("Updates");
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut settings.check_for_updates, "Check for updates at startup").changed() {
+                    save_requested = true;
+                }
+                if ui.button("Check Now").clicked() {
+                    self.start_update_check();
+                }
+            });
+            let status = match self.update_check.lock().unwrap().as_ref().map(|p| p.ready()) {
+                None => format!("Running version {}.", CURRENT_VERSION),
+                Some(None) => "Checking for updates...".to_string(),
+                Some(Some(Ok(None))) => format!("Version {} is up to date.", CURRENT_VERSION),
+                Some(Some(Ok(Some(release)))) => format!("Version {} is available.", release.version),
+                Some(Some(Err(_))) => "The last update check failed.".to_string(),
+            };
+            ui.label(status);
+
+            ui.separator();
+            ui.heading("Appearance");
+            ui.horizontal(|ui| {
+                let label = ui.label("UI scale:");
+                let response = ui
+                    .add(egui::Slider::new(&mut settings.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE).step_by(0.05))
+                    .labelled_by(label.id);
+                if response.changed() {
+                    ctx.set_zoom_factor(settings.ui_scale);
+                }
+                // Save once the slider is released rather than on every step of a drag
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    save_requested = true;
+                }
+                if ui.button("Reset").clicked() {
+                    settings.ui_scale = 1.0;
+                    ctx.set_zoom_factor(1.0);
+                    save_requested = true;
+                }
+            });
+            ui.weak("Ctrl + Plus / Minus / 0 also zoom the window.");
+            if ui.checkbox(&mut settings.show_thumbnails, "Show window previews")
+                .on_hover_text("Show a live preview of each captured window in the workspace editor.")
+                .changed()
+            {
+                save_requested = true;
+            }
+
+            ui.separator();
+            ui.heading("Animation");
+            if ui.checkbox(&mut settings.animations_enabled, "Animate window moves")
+                .on_hover_text("Turn off to move every window instantly, whatever its workspace's animation. Also under View → Animate Window Moves.")
+                .changed()
+            {
+                set_animations_enabled(settings.animations_enabled);
+                save_requested = true;
+            }
+            if ui.checkbox(&mut settings.respect_reduced_motion, "Follow the Windows reduce motion setting")
+                .on_hover_text("Move windows instantly while Windows' Animation effects are off, under Settings → Accessibility → Visual effects.")
+                .changed()
+            {
+                set_respect_reduced_motion(settings.respect_reduced_motion);
+                save_requested = true;
+            }
+            if settings.animations_enabled && settings.respect_reduced_motion && !system_animations_enabled() {
+                ui.colored_label(egui::Color32::YELLOW, "Windows is set to reduce motion, so windows move instantly.");
+            }
+            ui.horizontal(|ui| {
+                if animation_edit(ui, "settings_animation", &mut settings.animation) {
+                    set_global_animation(settings.animation);
+                    save_requested = true;
+                }
+            });
+            ui.weak("Windows glide to their positions over this duration, unless their workspace sets its own animation.");
+
+            ui.separator();
+            ui.heading("Hotkeys");
+            ui.horizontal(|ui| {
+                let before = settings.cycle_hotkey.clone();
+                optional_hotkey_edit(ui, "Cycle focused window:", &mut settings.cycle_hotkey);
+                if settings.cycle_hotkey != before {
+                    save_requested = true;
+                }
+            });
+            ui.weak("Moves the focused window to its target if it is home, otherwise home, whichever workspace it is in.");
+            if ui.checkbox(&mut settings.mouse_gestures, "Mouse gestures")
+                .on_hover_text("Hold the right button on the desktop and draw a workspace's gesture, e.g. down then right for an L, to toggle it.")
+                .changed()
+            {
+                // The agent turns gestures on or off when it reloads the settings
+                if !self.agent_attached {
+                    self.set_gestures_enabled(settings.mouse_gestures);
+                }
+                save_requested = true;
+            }
+            if ui.checkbox(&mut settings.caps_lock_layer, "CapsLock as a modifier")
+                .on_hover_text("Hold CapsLock with a key for hotkeys such as Caps+H, which no other program uses. Tapping CapsLock alone still toggles it.")
+                .changed()
+            {
+                // The agent turns the layer on or off when it reloads the settings
+                if !self.agent_attached {
+                    self.set_caps_layer_enabled(settings.caps_lock_layer);
+                }
+                save_requested = true;
+            }
+            if !settings.caps_lock_layer {
+                ui.weak("Hotkeys with Caps, e.g. Caps+H, only work while CapsLock is a modifier.");
+            }
+
+            ui.separator();
+            ui.heading("Displays");
+            if ui.checkbox(&mut settings.restore_on_monitor_reconnect, "Restore windows when a monitor reconnects")
+                .on_hover_text("When a monitor is connected again, e.g. after docking, move the windows of the workspaces that belong on it back to their positions.")
+                .changed()
+            {
+                save_requested = true;
+            }
+
+            ui.separator();
+            ui.heading("Schedules");
+            ui.horizontal(|ui| {
+                let label = ui.label("Prompt before scheduled moves for:");
+                let response = ui
+                    .add(egui::DragValue::new(&mut settings.schedule_grace_secs).range(0..=MAX_SCHEDULE_GRACE_SECS).suffix(" s"))
+                    .labelled_by(label.id)
+                    .on_hover_text("How long the prompt offers to skip a workspace's scheduled move. With 0, windows move without a prompt.");
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    save_requested = true;
+                }
+            });
+
+            ui.separator();
+            ui.heading("Idle");
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut settings.idle_restore, "Send all workspaces home after")
+                    .on_hover_text("When nobody has used the keyboard or mouse for this long, move the windows of every enabled workspace home, so a shared machine returns to its baseline layout.")
+                    .changed()
+                {
+                    save_requested = true;
+                }
+                let response = ui.add_enabled(
+                    settings.idle_restore,
+                    egui::DragValue::new(&mut settings.idle_restore_minutes).range(1..=MAX_IDLE_RESTORE_MINUTES).suffix(" min"),
+                );
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    save_requested = true;
+                }
+                ui.label("without input");
+            });
+
+            ui.separator();
+            ui.heading("Security");
+            if ui.checkbox(&mut settings.encrypt_workspaces, "Encrypt workspace files")
+                .on_hover_text("Window titles and exe paths are encrypted with a key kept in Windows Credential Manager. Encrypted files can only be opened by this Windows account on this machine.")
+                .changed()
+            {
+                set_encryption_enabled(settings.encrypt_workspaces);
+                save_requested = true;
+                resave_workspaces = true;
+            }
+
+            ui.separator();
+            ui.heading("Startup");
+            if ui.checkbox(&mut settings.start_in_background, "Start in the background")
+                .on_hover_text("Start with only hotkeys and the background features running, without this window, as --background does. Press the show hotkey to open it.")
+                .changed()
+            {
+                save_requested = true;
+            }
+            ui.horizontal(|ui| {
+                let before = settings.show_gui_hotkey.clone();
+                optional_hotkey_edit(ui, "Show Multi Manager:", &mut settings.show_gui_hotkey);
+                if settings.show_gui_hotkey != before {
+                    save_requested = true;
+                }
+            });
+            if ui.checkbox(&mut settings.hide_on_close, "Hide the window when it is closed")
+                .on_hover_text("Closing the window hides it to the tray and keeps hotkeys running; double-click the tray icon or press the show hotkey to bring it back. Use Exit in the tray menu or File → Exit to quit.")
+                .changed()
+            {
+                save_requested = true;
+            }
+            if (settings.start_in_background || settings.hide_on_close) && settings.show_gui_hotkey.is_none() && !*self.tray_running.lock().unwrap() {
+                ui.colored_label(egui::Color32::YELLOW, "Without the tray icon or a show hotkey, the window opens at startup anyway and is minimized instead of hidden.");
+            }
+
+            ui.separator();
+            ui.heading("Advanced");
+            if ui.checkbox(&mut settings.show_all_windows, "Show all windows")
+                .on_hover_text("List tool windows, zero-size windows, and the desktop and taskbar in the window browser and snap presets, and let capture rules match them.")
+                .changed()
+            {
+                set_show_all_windows(settings.show_all_windows);
+                self.window_browser.lock().unwrap().refresh();
+                save_requested = true;
+            }
+        });
+
+        if apply {
+            match apply_logging_settings(&draft) {
+                Ok(()) => {
+                    settings.logging = Some(draft.clone());
+                    save_requested = true;
+                }
+                Err(e) => self.report_error(e),
+            }
+        }
+
+        if save_requested {
+            if let Err(e) = save_settings(&settings, &config_path(SETTINGS_FILE)) {
+                self.report_error(e);
+            }
+        }
+        drop(settings);
+        if save_requested {
+            self.notify_agent();
+        }
+
+        // Rewrite the current file so the change takes effect now rather than on the next save
+        if resave_workspaces {
+            let path = self.workspaces_path.lock().unwrap().clone();
+            self.save_workspaces_file(path);
+        }
+
+        ctx.memory_mut(|mem| mem.data.insert_temp(draft_id, draft));
+    }
+
+    /// Installs or removes the keyboard hook for workspace hotkeys in hook mode.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether any hotkey is in hook mode.
+    fn set_keyboard_hook_enabled(&self, enabled: bool) {
+        let mut state = self.keyboard_hook.lock().unwrap();
+        // Installing is tried once each time it becomes needed, not on every poll if it fails
+        if state.0 == enabled {
+            return;
+        }
+        state.0 = enabled;
+        state.1 = if enabled { KeyboardHook::start() } else { None };
+    }
+
+    /// Installs or removes the CapsLock layer hook, which hotkeys with the Caps modifier need.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether CapsLock works as a modifier.
+    fn set_caps_layer_enabled(&self, enabled: bool) {
+        let mut layer = self.caps_layer.lock().unwrap();
+        if !enabled {
+            layer.take();
+        } else if layer.is_none() {
+            *layer = CapsLayerHook::start();
+        }
+    }
+
+    /// Installs or removes the mouse gesture hook. Each gesture drawn toggles the enabled
+    /// workspaces it is assigned to.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether mouse gestures are recognized.
+    fn set_gestures_enabled(&self, enabled: bool) {
+        let mut hook = self.gesture_hook.lock().unwrap();
+        if !enabled {
+            hook.take();
+            return;
+        }
+        if hook.is_some() {
+            return;
+        }
+
+        let app = self.clone();
+        *hook = GestureHook::start(move |gesture| {
+            let matching: Vec<(RuntimeId, String)> = app
+                .workspaces
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|workspace| !workspace.disabled)
+                .filter(|workspace| {
+                    workspace
+                        .gesture
+                        .as_deref()
+                        .is_some_and(|g| g.eq_ignore_ascii_case(gesture))
+                })
+                .map(|workspace| (workspace.runtime_id, workspace.name.clone()))
+                .collect();
+            if matching.is_empty() {
+                info!("No workspace is assigned mouse gesture '{}'.", gesture);
+            }
+            for (id, name) in matching {
+                info!(
+                    "Activating workspace '{}' via mouse gesture '{}'.",
+                    name, gesture
+                );
+                app.record_trigger(&name);
+                app.window_ops.send(WindowOp::Toggle(id));
+            }
+        });
+        if hook.is_none() {
+            self.report_error(MultiManagerError::Gesture {
+                message: "The mouse hook could not be installed; see the log for details."
+                    .to_string(),
+            });
+        }
+    }
+
+    /// Starts checking GitHub for a newer release on a background thread.
+    ///
+    /// The result is shown by `render_update_banner` and in the Settings tab.
+    fn start_update_check(&self) {
+        let app = self.clone();
+        *self.update_check.lock().unwrap() =
+            Some(Promise::spawn_thread("Update Check", move || {
+                let result = check_for_update();
+                app.request_repaint();
+                result
+            }));
+    }
+
+    /// Renders a banner with a download link when a newer release is available.
+    ///
+    /// A failed check is moved to the error panel instead.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    fn render_update_banner(&self, ui: &mut egui::Ui) {
+        let mut update_check = self.update_check.lock().unwrap();
+        let Some(result) = update_check.as_ref().and_then(|p| p.ready()).cloned() else {
+            return;
+        };
+
+        match result {
+            Ok(Some(release)) => {
+                let mut dismissed = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::LIGHT_BLUE,
+                        format!(
+                            "Multi Manager {} is available (you have {}).",
+                            release.version, CURRENT_VERSION
+                        ),
+                    );
+                    ui.hyperlink_to("Download", &release.url);
+                    if ui.small_button("Dismiss").clicked() {
+                        dismissed = true;
+                    }
+                });
+                if dismissed {
+                    *update_check = None;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                *update_check = None;
+                self.report_error(e);
+            }
+        }
+    }
+
+    /// Starts a "Validate All" pass on the window operations worker.
+    ///
+    /// The worker validates a snapshot of the workspaces so the GUI stays responsive
+    /// while validation runs. The resulting report is shown by `render_validation_report`.
+    fn start_validation(&self) {
+        let (sender, promise) = Promise::new();
+        // The agent holds the workspace hotkeys, so this process has none registered
+        self.window_ops.send(WindowOp::Validate {
+            check_hotkeys: !self.agent_attached,
+            report: sender,
+        });
+        *self.validation_promise.lock().unwrap() = Some(promise);
+    }
+
+    /// Renders the results of the last "Validate All" pass with per-issue fix buttons.
+    ///
+    /// Fixed issues are removed from the report; closing the dialog discards it.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context for rendering the GUI.
+    fn render_validation_report(&self, ctx: &egui::Context) {
+        let mut promise_guard = self.validation_promise.lock().unwrap();
+        let Some(promise) = promise_guard.as_mut() else {
+            return;
+        };
+
+        let Some(report) = promise.ready_mut() else {
+            egui::Window::new("Validation Report")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Validating workspaces...");
+                    });
+                });
+            return;
+        };
+
+        report.forget_stale(&self.workspaces.lock().unwrap());
+
+        let mut open = true;
+        let mut fixed = None;
+        egui::Window::new("Validation Report")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Checked {} workspaces and {} windows.",
+                    report.workspaces_checked, report.windows_checked
+                ));
+                if report.issues.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::GREEN,
+                        format!("{} No issues found.", ICON_VALID),
+                    );
+                    return;
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (k, issue) in report.issues.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let mut workspaces = self.workspaces.lock().unwrap();
+                                ui.label(issue.describe());
+                                if let IssueFix::Fixed =
+                                    self.render_issue_fix(ui, issue, &mut workspaces)
+                                {
+                                    fixed = Some(k);
+                                }
                             });
+                        }
+                    });
+            });
 
-                            if header_response.header_response.hovered() && ui.input(|i| i.pointer.secondary_clicked()) {
-                                // Right-click detected on header
-                                is_renaming = true;
-                                ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
-                            }
-            
-                            // Show a popup window for renaming the workspace
-                            if is_renaming {
-                                egui::Window::new("Rename Workspace")
-                                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0]) // Center the popup
-                                    .collapsible(false)
-                                    .resizable(false)
-                                    .show(ctx, |ui| {
-                                        ui.label("Enter a new name for the workspace:");
-                                        let response = ui.text_edit_singleline(&mut new_name);
-            
-                                        if response.changed() {
-                                            ui.memory_mut(|mem| {
-                                                mem.data.insert_temp(header_id.with("wrkspce_name"), new_name.clone());
-                                            });
-                                        }
-            
-                                        ui.horizontal(|ui| {
-                                            if ui.button("Ok").clicked() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
-                                                // Save the new name and close the popup
-                                                workspace.name = new_name.clone();
-                                                is_renaming = false;
-                                                ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
-                                            }
-            
-                                            if ui.button("Cancel").clicked() {
-                                                // Cancel renaming and close the popup
-                                                is_renaming = false;
-                                                ui.memory_mut(|mem| mem.data.insert_temp(header_id, is_renaming));
-                                            }
-                                        });
-                                    });
-                            }
-                        });
-                    }//Per Workspace
+        if let Some(k) = fixed {
+            report.issues.remove(k);
+        }
+        if !open {
+            *promise_guard = None;
+        }
+    }
 
-                    if let Some(i) = move_up_index {
-                        if i > 0 {
-                            workspaces.swap(i, i - 1);
+    /// Renders the fix button(s) for a single validation issue and applies the chosen fix.
+    ///
+    /// The issue is located in the current workspace list first, so a fix is only ever
+    /// applied to the workspace and window it was raised for, and not at all if they have
+    /// changed since validation.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render into.
+    /// * `issue` - The issue to offer a fix for.
+    /// * `workspaces` - The current workspace list, modified in place by the fix.
+    ///
+    /// # Returns
+    /// * The `IssueFix` describing what, if anything, was changed.
+    fn render_issue_fix(
+        &self,
+        ui: &mut egui::Ui,
+        issue: &ValidationIssue,
+        workspaces: &mut [Workspace],
+    ) -> IssueFix {
+        let Some(IssueLocation { workspace, window }) = issue.locate(workspaces) else {
+            return IssueFix::None;
+        };
+        match issue {
+            ValidationIssue::InvalidWindow { .. } => {
+                let Some(window) = window else {
+                    return IssueFix::None;
+                };
+                let entry = &mut workspaces[workspace].windows[window];
+                if ui.button("Recapture").clicked() {
+                    if let Some("Enter") = listen_for_keys_with_dialog() {
+                        if let Some((hwnd, title)) = get_active_window() {
+                            entry.id = hwnd.0 as usize;
+                            entry.capture_title = title.clone();
+                            entry.exe = get_window_exe(hwnd);
+                            entry.class_name = get_window_class(hwnd);
+                            entry.title = title;
+                            return IssueFix::Fixed;
                         }
                     }
-                    if let Some(i) = move_down_index {
-                        if i < workspaces.len() - 1 {
-                            workspaces.swap(i, i + 1);
-                        }
+                }
+                if ui.button("Remove").clicked() {
+                    workspaces[workspace].windows.remove(window);
+                    return IssueFix::Fixed;
+                }
+                IssueFix::None
+            }
+            ValidationIssue::InvalidHotkey { .. } => {
+                if ui.button("Clear Hotkey").clicked() {
+                    workspaces[workspace].hotkey = None;
+                    return IssueFix::Fixed;
+                }
+                IssueFix::None
+            }
+            ValidationIssue::UnregisteredHotkey { hotkey, .. } => {
+                if ui.button("Register").clicked() {
+                    match register_hotkey(&self.registered_hotkeys, workspace as i32, hotkey) {
+                        Ok(()) => return IssueFix::Fixed,
+                        Err(e) => self.report_error(e),
                     }
-                if let Some(new_workspace) = new_workspace_to_add {
-                    workspaces.push(new_workspace);
                 }
-    
-                if let Some(index) = workspace_to_delete {
-                    workspaces.remove(index);
+                IssueFix::None
+            }
+            ValidationIssue::OffScreenRect { kind, .. } => {
+                if ui.button("Move to Primary Monitor").clicked() {
+                    if let Some(entry) =
+                        window.and_then(|window| workspaces[workspace].windows.get_mut(window))
+                    {
+                        let rect = match kind {
+                            PositionKind::Home => &mut entry.home,
+                            PositionKind::Target => &mut entry.target,
+                        };
+                        rect.0 = 0;
+                        rect.1 = 0;
+                        return IssueFix::Fixed;
+                    }
                 }
-            }); //ScrollArea
-        });
-
-        if save_workspaces_flag {
-            save_workspaces(&self.workspaces.lock().unwrap(), "workspaces.json");
-            info!("Workspaces saved to file.");
+                IssueFix::None
+            }
         }
     }
-}
 
-impl App {
     /// Validates all hotkeys associated with the workspaces during application startup.
     ///
     /// - Ensures that hotkeys are valid and registerable.
@@ -542,25 +5803,172 @@ impl App {
 /// - Iterates through all registered workspaces.
 /// - Skips disabled workspaces.
 /// - Toggles windows for any workspace whose hotkey is pressed.
+/// - Does nothing while a hotkey field in the GUI has focus.
 ///
 /// # Arguments
 /// * `app` - The application state encapsulated in an `App` struct.
-fn check_hotkeys(app: &App) {
+/// * `controllers` - The game controller state, polled only while a workspace has controller
+///   buttons assigned.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn check_hotkeys(app: &App, controllers: &mut ControllerPoller) {
+    if *app.hotkeys_suspended.lock().unwrap() {
+        // Presses swallowed while a hotkey is being typed must not fire once the field loses focus
+        take_hooked_presses();
+        return;
+    }
     let mut workspaces_to_toggle = Vec::new();
+    let mut workspaces_to_move = Vec::new();
+    let mut workspaces_to_save = Vec::new();
+    let mut windows_to_toggle = Vec::new();
+    let (cycle_hotkey, show_gui_hotkey) = {
+        let settings = app.settings.lock().unwrap();
+        (
+            settings.cycle_hotkey.clone(),
+            settings.show_gui_hotkey.clone(),
+        )
+    };
+    if let Some(hotkey) = show_gui_hotkey.filter(|hotkey| is_hotkey_pressed(hotkey)) {
+        app.show_gui(&format!("via hotkey '{}'", hotkey));
+    }
+    let cycle_pressed = cycle_hotkey
+        .as_ref()
+        .is_some_and(|hotkey| is_hotkey_pressed(hotkey));
+    // Hotkeys in hook mode are swallowed by the hook, which queues each press for this poll
+    let hooked_presses = take_hooked_presses();
     let workspaces = app.workspaces.lock().unwrap();
 
+    // The keyboard hook runs while any workspace hotkey is in hook mode
+    let hooked = hooked_hotkeys(&workspaces);
+    app.set_keyboard_hook_enabled(!hooked.is_empty());
+    set_hooked_hotkeys(hooked);
+
+    let controller_combos: Vec<(usize, u32)> = workspaces
+        .iter()
+        .enumerate()
+        .filter(|(_, workspace)| !workspace.disabled)
+        .filter_map(|(i, workspace)| {
+            workspace
+                .controller_buttons
+                .as_deref()
+                .and_then(parse_controller_combo)
+                .map(|combo| (i, combo))
+        })
+        .collect();
+    if !controller_combos.is_empty() {
+        controllers.poll();
+    }
+    for (i, combo) in controller_combos {
+        if controllers.just_pressed(combo) {
+            let workspace = &workspaces[i];
+            info!(
+                "Activating workspace '{}' via controller buttons '{}'.",
+                workspace.name,
+                workspace.controller_buttons.as_deref().unwrap_or_default()
+            );
+            workspaces_to_toggle.push(workspace.runtime_id);
+            app.record_trigger(&workspace.name);
+        }
+    }
+
+    if cycle_pressed {
+        let focused = Win32Backend.foreground();
+        let held = workspaces
+            .iter()
+            .enumerate()
+            .filter(|(_, workspace)| !workspace.disabled)
+            .find_map(|(i, workspace)| {
+                workspace
+                    .windows
+                    .iter()
+                    .position(|window| Some(window.id) == focused)
+                    .map(|j| (i, j))
+            });
+        match held {
+            Some((i, j)) => {
+                info!(
+                    "Cycling focused window '{}' of '{}' via the cycle hotkey.",
+                    workspaces[i].windows[j].title, workspaces[i].name
+                );
+                windows_to_toggle.push((
+                    workspaces[i].runtime_id,
+                    workspaces[i].windows[j].runtime_id,
+                ));
+            }
+            None => info!("The focused window is not in any enabled workspace; nothing to cycle."),
+        }
+        if let Some(hotkey) = cycle_hotkey {
+            *app.last_hotkey_info.lock().unwrap() = Some((hotkey, Instant::now()));
+        }
+    }
+
     for (i, workspace) in workspaces.iter().enumerate() {
         if workspace.disabled {
             continue;
         }
 
         if let Some(ref hotkey) = workspace.hotkey {
-            if is_hotkey_pressed(hotkey) {
+            let hooked_press =
+                Hotkey::parse(hotkey).is_some_and(|parsed| hooked_presses.contains(&parsed));
+            if hooked_press || is_hotkey_pressed(hotkey) {
                 info!(
                     "Activating workspace '{}' via hotkey '{}'.",
                     workspace.name, hotkey
                 );
-                workspaces_to_toggle.push(i);
+                workspaces_to_toggle.push(workspace.runtime_id);
+
+                let mut last_hotkey_info = app.last_hotkey_info.lock().unwrap();
+                *last_hotkey_info = Some((hotkey.clone(), Instant::now()));
+                app.record_trigger(&workspace.name);
+            }
+        }
+
+        for (position, hotkey) in [
+            (PositionKind::Home, &workspace.home_hotkey),
+            (PositionKind::Target, &workspace.target_hotkey),
+        ] {
+            if let Some(hotkey) = hotkey.as_ref().filter(|hotkey| is_hotkey_pressed(hotkey)) {
+                info!(
+                    "Moving every window of '{}' to {:?} via hotkey '{}'.",
+                    workspace.name, position, hotkey
+                );
+                workspaces_to_move.push((workspace.runtime_id, position));
+
+                let mut last_hotkey_info = app.last_hotkey_info.lock().unwrap();
+                *last_hotkey_info = Some((hotkey.clone(), Instant::now()));
+                app.record_trigger(&workspace.name);
+            }
+        }
+
+        if let Some(hotkey) = workspace
+            .save_hotkey
+            .as_ref()
+            .filter(|hotkey| is_hotkey_pressed(hotkey))
+        {
+            info!(
+                "Saving the current layout of '{}' as its targets via hotkey '{}'.",
+                workspace.name, hotkey
+            );
+            workspaces_to_save.push(i);
+
+            let mut last_hotkey_info = app.last_hotkey_info.lock().unwrap();
+            *last_hotkey_info = Some((hotkey.clone(), Instant::now()));
+        }
+
+        for (j, window) in workspace.windows.iter().enumerate() {
+            let hotkeys = [
+                window.toggle_hotkey.clone(),
+                workspace.window_digit_hotkey(j),
+            ];
+            if let Some(hotkey) = hotkeys
+                .iter()
+                .flatten()
+                .find(|hotkey| is_hotkey_pressed(hotkey))
+            {
+                info!(
+                    "Toggling window '{}' of '{}' via hotkey '{}'.",
+                    window.title, workspace.name, hotkey
+                );
+                windows_to_toggle.push((workspace.runtime_id, window.runtime_id));
 
                 let mut last_hotkey_info = app.last_hotkey_info.lock().unwrap();
                 *last_hotkey_info = Some((hotkey.clone(), Instant::now()));
@@ -570,10 +5978,200 @@ fn check_hotkeys(app: &App) {
 
     drop(workspaces); // Release lock before toggling
 
-    let mut workspaces = app.workspaces.lock().unwrap();
-    for index in workspaces_to_toggle {
-        if let Some(workspace) = workspaces.get_mut(index) {
-            toggle_workspace_windows(workspace);
+    // Show the detected hotkey right away
+    if cycle_pressed
+        || !workspaces_to_toggle.is_empty()
+        || !workspaces_to_move.is_empty()
+        || !workspaces_to_save.is_empty()
+        || !windows_to_toggle.is_empty()
+    {
+        app.request_repaint();
+    }
+    for id in workspaces_to_toggle {
+        app.window_ops.send(WindowOp::Toggle(id));
+    }
+    for (workspace, position) in workspaces_to_move {
+        app.window_ops.send(WindowOp::MoveAll {
+            workspace,
+            position,
+        });
+    }
+    for index in workspaces_to_save {
+        app.save_current_layout(index);
+    }
+    for (workspace, window) in windows_to_toggle {
+        app.window_ops
+            .send(WindowOp::ToggleWindow { workspace, window });
+    }
+}
+
+/// Journals edits to the workspaces every `JOURNAL_INTERVAL` while they differ from the saved
+/// file. Never returns.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+fn journal_edits(app: &App) {
+    loop {
+        thread::sleep(JOURNAL_INTERVAL);
+        let workspaces = app.workspaces.lock().unwrap().clone();
+        let path = app.workspaces_path.lock().unwrap().clone();
+        app.journal.lock().unwrap().record(&workspaces, &path);
+    }
+}
+
+/// Moves workspaces at the times of day in their schedules. A move due is first offered in
+/// the grace prompt for `schedule_grace_secs`, and made when that ends unless it was
+/// skipped. Never returns.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+fn run_scheduler(app: &App) {
+    let mut scheduler = Scheduler::new();
+    loop {
+        let due = scheduler.poll(&app.workspaces.lock().unwrap(), chrono::Local::now().time());
+        let grace = Duration::from_secs(app.settings.lock().unwrap().schedule_grace_secs as u64);
+        let prompt_changed = !due.is_empty();
+
+        let ready: Vec<PendingMove> = {
+            let workspaces = app.workspaces.lock().unwrap();
+            let mut pending = app.scheduled_moves.lock().unwrap();
+            for (workspace, position) in due {
+                // A newer move replaces one still waiting for the same workspace
+                pending.retain(|entry| entry.workspace != workspace);
+                info!(
+                    "Scheduled move of '{}' to {:?} is due.",
+                    workspaces[workspace].name, position
+                );
+                pending.push(PendingMove {
+                    workspace,
+                    workspace_name: workspaces[workspace].name.clone(),
+                    position,
+                    deadline: Instant::now() + grace,
+                });
+            }
+            let now = Instant::now();
+            let (ready, waiting) = pending.drain(..).partition(|entry| entry.deadline <= now);
+            *pending = waiting;
+            ready
+        };
+        if prompt_changed || !ready.is_empty() {
+            // Show or close the grace prompt without waiting for the next repaint
+            app.request_repaint();
+        }
+
+        for entry in ready {
+            let current = app
+                .workspaces
+                .lock()
+                .unwrap()
+                .get(entry.workspace)
+                .filter(|workspace| workspace.name == entry.workspace_name)
+                .map(|workspace| workspace.runtime_id);
+            let Some(id) = current else {
+                warn!(
+                    "Workspace '{}' changed before its scheduled move; move dropped.",
+                    entry.workspace_name
+                );
+                continue;
+            };
+            info!(
+                "Moving '{}' to {:?} as scheduled.",
+                entry.workspace_name, entry.position
+            );
+            app.window_ops.send(WindowOp::MoveAll {
+                workspace: id,
+                position: entry.position,
+            });
+            app.record_trigger(&entry.workspace_name);
+        }
+        thread::sleep(SCHEDULE_POLL_INTERVAL);
+    }
+}
+
+/// Watches for the user going idle and sends the windows of every enabled workspace that is
+/// not already home back home, if enabled in the settings. Never returns.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+fn watch_idle(app: &App) {
+    let mut watcher = IdleWatcher::new();
+    loop {
+        let (enabled, minutes) = {
+            let settings = app.settings.lock().unwrap();
+            (settings.idle_restore, settings.idle_restore_minutes.max(1))
+        };
+        let idle = idle_time();
+        if enabled && watcher.poll(idle, Duration::from_secs(minutes as u64 * 60)) {
+            info!(
+                "No input for {} minute(s); sending every workspace home.",
+                idle.as_secs() / 60
+            );
+            let snapshot = app.workspaces.lock().unwrap().clone();
+            for entry in &snapshot {
+                if entry.disabled
+                    || entry.windows.is_empty()
+                    || are_all_windows_at_home(&Win32Backend, entry)
+                {
+                    continue;
+                }
+                info!(
+                    "Sending the windows of '{}' home after inactivity.",
+                    entry.name
+                );
+                app.window_ops.send(WindowOp::MoveAll {
+                    workspace: entry.runtime_id,
+                    position: PositionKind::Home,
+                });
+            }
+        }
+        thread::sleep(IDLE_POLL_INTERVAL);
+    }
+}
+
+/// Watches for monitors being connected and moves the windows of the affected workspaces
+/// back onto them, if enabled in the settings. Never returns.
+///
+/// # Arguments
+/// * `app` - The application state encapsulated in an `App` struct.
+fn watch_monitors(app: &App) {
+    let mut watcher = MonitorWatcher::new();
+    loop {
+        let added = watcher.poll(enumerate_monitors());
+        if !added.is_empty() && app.settings.lock().unwrap().restore_on_monitor_reconnect {
+            info!(
+                "{} monitor(s) connected; waiting for the displays to settle.",
+                added.len()
+            );
+            thread::sleep(MONITOR_SETTLE);
+
+            let monitors = enumerate_monitors();
+            let work_areas = enumerate_work_areas();
+            let added: Vec<_> = added
+                .into_iter()
+                .filter(|monitor| monitors.contains(monitor))
+                .collect();
+            let restore: Vec<_> = {
+                let workspaces = app.workspaces.lock().unwrap();
+                workspaces_to_restore(&workspaces, &added, &monitors, &work_areas)
+                    .into_iter()
+                    .map(|(index, position)| {
+                        let workspace = &workspaces[index];
+                        (workspace.runtime_id, position, workspace.name.clone())
+                    })
+                    .collect()
+            };
+            for (workspace, position, name) in restore {
+                info!(
+                    "Moving the windows of '{}' back to {:?} on the reconnected monitor.",
+                    name, position
+                );
+                app.window_ops.send(WindowOp::MoveAll {
+                    workspace,
+                    position,
+                });
+            }
+            watcher.poll(monitors);
         }
+        thread::sleep(MONITOR_POLL_INTERVAL);
     }
 }
@@ -0,0 +1,241 @@
+use crate::encryption::{decrypt, encrypt, encryption_enabled};
+use crate::error::MultiManagerError;
+use crate::workspace::{Window, Workspace};
+use chrono::Local;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+
+/// The format of the timestamp recorded with each change.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Starts a line holding an entry encrypted while workspace encryption is on, hex-encoded
+/// after the prefix, so workspace names and window titles are not readable on disk either.
+const ENCRYPTED_LINE_PREFIX: &str = "enc:";
+
+/// A single configuration change, one line of the history file.
+///
+/// # Fields
+/// - `timestamp`: When the change was saved, in local time.
+/// - `user`: The Windows account that saved it.
+/// - `file`: The workspace file the change was saved to.
+/// - `change`: A human-readable description, e.g. "Hotkey of 'Coding' changed from Ctrl+1 to Ctrl+2".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub user: String,
+    pub file: String,
+    pub change: String,
+}
+
+/// Describes what changed between two versions of a workspace list.
+///
+/// Workspaces are matched by name and windows by title, so a rename is reported as a removal
+/// and an addition.
+///
+/// # Arguments
+/// - `before`: The workspaces as last saved.
+/// - `after`: The workspaces about to be saved.
+///
+/// # Returns
+/// - One description per change, in workspace order. Empty if nothing that is saved changed.
+///
+/// # Example
+/// ```ignore
+/// let before = read_workspaces("workspaces.json")?;
+/// for change in describe_changes(&before, &workspaces) {
+///     println!("{}", change);
+/// }
+/// ```
+pub fn describe_changes(before: &[Workspace], after: &[Workspace]) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    for old in before {
+        if !after.iter().any(|w| w.name == old.name) {
+            changes.push(format!("Workspace '{}' deleted", old.name));
+        }
+    }
+
+    for new in after {
+        let Some(old) = before.iter().find(|w| w.name == new.name) else {
+            changes.push(format!(
+                "Workspace '{}' added with {} window(s)",
+                new.name,
+                new.windows.len()
+            ));
+            continue;
+        };
+
+        if old.hotkey != new.hotkey {
+            changes.push(format!(
+                "Hotkey of '{}' changed from {} to {}",
+                new.name,
+                describe_hotkey(&old.hotkey),
+                describe_hotkey(&new.hotkey)
+            ));
+        }
+        if old.disabled != new.disabled {
+            let state = if new.disabled { "disabled" } else { "enabled" };
+            changes.push(format!("Workspace '{}' {}", new.name, state));
+        }
+        describe_window_changes(&new.name, &old.windows, &new.windows, &mut changes);
+    }
+
+    changes
+}
+
+/// Adds the window-level differences of one workspace to `changes`.
+fn describe_window_changes(
+    workspace: &str,
+    before: &[Window],
+    after: &[Window],
+    changes: &mut Vec<String>,
+) {
+    for old in before {
+        if !after.iter().any(|w| w.title == old.title) {
+            changes.push(format!(
+                "Window '{}' deleted from '{}'",
+                old.title, workspace
+            ));
+        }
+    }
+
+    for new in after {
+        let Some(old) = before.iter().find(|w| w.title == new.title) else {
+            changes.push(format!("Window '{}' added to '{}'", new.title, workspace));
+            continue;
+        };
+
+        if old.home != new.home {
+            changes.push(format!(
+                "Home of '{}' in '{}' changed from {:?} to {:?}",
+                new.title, workspace, old.home, new.home
+            ));
+        }
+        if old.target != new.target {
+            changes.push(format!(
+                "Target of '{}' in '{}' changed from {:?} to {:?}",
+                new.title, workspace, old.target, new.target
+            ));
+        }
+    }
+}
+
+/// Formats an optional hotkey for a change description.
+fn describe_hotkey(hotkey: &Option<String>) -> &str {
+    hotkey.as_deref().unwrap_or("none")
+}
+
+/// Appends changes to the history file, one JSON object per line.
+///
+/// The file is only ever appended to, so earlier entries are never rewritten. While workspace
+/// encryption is on, each entry is encrypted, as the descriptions name workspaces and windows.
+///
+/// # Arguments
+/// - `history_path`: The history file.
+/// - `file`: The workspace file the changes were saved to.
+/// - `changes`: The descriptions from `describe_changes`.
+///
+/// # Returns
+/// - `Ok(())` if every change was written.
+/// - `Err(Save)` if the history file could not be opened or written, or an entry could not be
+///   encrypted.
+pub fn append_history(
+    history_path: &str,
+    file: &str,
+    changes: &[String],
+) -> Result<(), MultiManagerError> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let save_error = |message: String| MultiManagerError::Save {
+        path: history_path.to_string(),
+        message,
+    };
+
+    let timestamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let user = env::var("USERNAME").unwrap_or_else(|_| "unknown".to_string());
+
+    let mut lines = String::new();
+    for change in changes {
+        let entry = HistoryEntry {
+            timestamp: timestamp.clone(),
+            user: user.clone(),
+            file: file.to_string(),
+            change: change.clone(),
+        };
+        let line = serde_json::to_string(&entry).map_err(|e| save_error(e.to_string()))?;
+        if encryption_enabled() {
+            let encrypted = encrypt(line.as_bytes()).map_err(|e| save_error(e.to_string()))?;
+            lines.push_str(ENCRYPTED_LINE_PREFIX);
+            lines.extend(encrypted.iter().map(|byte| format!("{:02x}", byte)));
+        } else {
+            lines.push_str(&line);
+        }
+        lines.push('\n');
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path)
+        .and_then(|mut history| history.write_all(lines.as_bytes()))
+        .map_err(|e| save_error(e.to_string()))
+}
+
+/// Reads every entry from the history file.
+///
+/// Encrypted entries are decrypted. Lines that cannot be parsed or decrypted are skipped with
+/// a warning rather than hiding the rest.
+///
+/// # Arguments
+/// - `history_path`: The history file.
+///
+/// # Returns
+/// - The entries, oldest first. Empty if no history has been recorded yet.
+/// - `Err(Load)` if the file exists but could not be read.
+pub fn read_history(history_path: &str) -> Result<Vec<HistoryEntry>, MultiManagerError> {
+    let content = match fs::read_to_string(history_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(MultiManagerError::Load {
+                path: history_path.to_string(),
+                message: e.to_string(),
+            })
+        }
+    };
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(number, line)| match parse_line(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping line {} of '{}': {}", number + 1, history_path, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Parses one line of a history file, decrypting it first if it was encrypted.
+fn parse_line(line: &str) -> Result<HistoryEntry, String> {
+    let Some(hex) = line.trim().strip_prefix(ENCRYPTED_LINE_PREFIX) else {
+        return serde_json::from_str(line).map_err(|e| e.to_string());
+    };
+    let data = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| "the encrypted entry is not valid hex".to_string())?;
+    let json = decrypt(&data).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&json).map_err(|e| e.to_string())
+}
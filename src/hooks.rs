@@ -0,0 +1,117 @@
+use crate::activity::ToggleDirection;
+use crate::error::MultiManagerError;
+use log::{info, warn};
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a workspace waits for its pre-toggle command before moving windows anyway.
+pub const PRE_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Starts processes without opening a console window.
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// The environment variable a hook command receives the workspace's name in.
+pub const WORKSPACE_VARIABLE: &str = "MM_WORKSPACE";
+
+/// The environment variable a hook command receives the direction in.
+pub const DIRECTION_VARIABLE: &str = "MM_DIRECTION";
+
+/// Replaces the placeholders of a hook command with references to the environment variables
+/// holding their values.
+///
+/// The references use `cmd`'s delayed expansion, which happens after the command line is
+/// split into commands, so a workspace name such as `a & calc` stays a single argument
+/// instead of starting a second command.
+///
+/// # Arguments
+/// - `command`: The command as entered, e.g. `obs-cli scene switch "%WORKSPACE%"`.
+///
+/// # Returns
+/// - The command with `%WORKSPACE%` and `%DIRECTION%` replaced by `!MM_WORKSPACE!` and
+///   `!MM_DIRECTION!`.
+///
+/// # Example
+/// ```
+/// # use multi_manager::hooks::expand_hook_command;
+/// let command = expand_hook_command("echo %WORKSPACE% %DIRECTION%");
+/// assert_eq!(command, "echo !MM_WORKSPACE! !MM_DIRECTION!");
+/// ```
+pub fn expand_hook_command(command: &str) -> String {
+    command
+        .replace("%WORKSPACE%", &format!("!{}!", WORKSPACE_VARIABLE))
+        .replace("%DIRECTION%", &format!("!{}!", DIRECTION_VARIABLE))
+}
+
+/// Runs a workspace's pre- or post-toggle command through `cmd /V:ON /C`, without a console
+/// window. The workspace's name and the direction are passed in `MM_WORKSPACE` and
+/// `MM_DIRECTION`; see `expand_hook_command`.
+///
+/// # Arguments
+/// - `command`: The command as entered; nothing is run if it is blank.
+/// - `workspace`: The name of the workspace, for `%WORKSPACE%` and errors.
+/// - `direction`: Where the windows go, for `%DIRECTION%`.
+/// - `timeout`: How long to wait for the command to exit, or `None` to not wait at all.
+///
+/// # Returns
+/// - `Ok(())` if the command started and, if waited for, exited successfully or is still
+///   running after the timeout.
+/// - `Err(Hook)` if it could not be started or exited with an error.
+///
+/// # Example
+/// ```ignore
+/// if let Err(e) = run_hook(&workspace.pre_command, &workspace.name, direction, Some(PRE_COMMAND_TIMEOUT)) {
+///     warn!("{}", e);
+/// }
+/// ```
+pub fn run_hook(
+    command: &str,
+    workspace: &str,
+    direction: ToggleDirection,
+    timeout: Option<Duration>,
+) -> Result<(), MultiManagerError> {
+    if command.trim().is_empty() {
+        return Ok(());
+    }
+
+    let hook_error = |message: String| MultiManagerError::Hook {
+        workspace: workspace.to_string(),
+        command: command.to_string(),
+        message,
+    };
+
+    info!("Running command for '{}': {}", workspace, command);
+    let mut child = Command::new("cmd")
+        .args(["/V:ON", "/C"])
+        .raw_arg(expand_hook_command(command))
+        .env(WORKSPACE_VARIABLE, workspace)
+        .env(DIRECTION_VARIABLE, direction.name())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| hook_error(e.to_string()))?;
+
+    let Some(timeout) = timeout else {
+        return Ok(());
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(hook_error(format!("exited with {}", status))),
+            Ok(None) if Instant::now() >= deadline => {
+                warn!(
+                    "Command for '{}' still running after {} s; continuing.",
+                    workspace,
+                    timeout.as_secs()
+                );
+                return Ok(());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(hook_error(e.to_string())),
+        }
+    }
+}
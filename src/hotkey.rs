@@ -0,0 +1,447 @@
+//! Hotkey strings such as "Ctrl+Alt+H": parsing them, with common aliases for modifiers and
+//! keys, and the canonical form they are stored and registered in.
+//!
+//! The canonical form lists the modifiers in the order Ctrl, Alt, Shift, Win, Caps, followed by the
+//! key's name in capitals, e.g. "Ctrl+Shift+ENTER" for "shift + control + Return". Older files
+//! may hold other spellings; they are migrated to the canonical form when loaded.
+
+use log::info;
+use std::fmt;
+
+/// Each modifier's canonical name, in canonical order, with the names accepted for it.
+///
+/// "CapsLock" itself stays a key, as it was before CapsLock could be a modifier.
+const MODIFIERS: [(&str, &[&str]); 5] = [
+    ("Ctrl", &["ctrl", "control", "ctl"]),
+    ("Alt", &["alt"]),
+    ("Shift", &["shift"]),
+    ("Win", &["win", "windows", "super", "meta"]),
+    ("Caps", &["caps", "hyper"]),
+];
+
+/// Other names accepted for keys, with the canonical name each stands for.
+const KEY_ALIASES: [(&str, &str); 15] = [
+    ("RETURN", "ENTER"),
+    ("ESC", "ESCAPE"),
+    ("DEL", "DELETE"),
+    ("INS", "INSERT"),
+    ("PGUP", "PAGEUP"),
+    ("PGDN", "PAGEDOWN"),
+    ("BACK", "BACKSPACE"),
+    ("SPACEBAR", "SPACE"),
+    ("PRTSC", "PRINTSCREEN"),
+    ("ARROWUP", "UP"),
+    ("ARROWDOWN", "DOWN"),
+    ("ARROWLEFT", "LEFT"),
+    ("ARROWRIGHT", "RIGHT"),
+    ("PLUS", "OEM_PLUS"),
+    ("MINUS", "OEM_MINUS"),
+];
+
+/// The keys a hotkey can end with, by canonical name, with their virtual key codes.
+const KEYS: [(&str, u32); 113] = [
+    // Function keys
+    ("F1", 0x70),
+    ("F2", 0x71),
+    ("F3", 0x72),
+    ("F4", 0x73),
+    ("F5", 0x74),
+    ("F6", 0x75),
+    ("F7", 0x76),
+    ("F8", 0x77),
+    ("F9", 0x78),
+    ("F10", 0x79),
+    ("F11", 0x7A),
+    ("F12", 0x7B),
+    ("F13", 0x7C),
+    ("F14", 0x7D),
+    ("F15", 0x7E),
+    ("F16", 0x7F),
+    ("F17", 0x80),
+    ("F18", 0x81),
+    ("F19", 0x82),
+    ("F20", 0x83),
+    ("F21", 0x84),
+    ("F22", 0x85),
+    ("F23", 0x86),
+    ("F24", 0x87),
+    // Alphabet keys
+    ("A", 0x41),
+    ("B", 0x42),
+    ("C", 0x43),
+    ("D", 0x44),
+    ("E", 0x45),
+    ("F", 0x46),
+    ("G", 0x47),
+    ("H", 0x48),
+    ("I", 0x49),
+    ("J", 0x4A),
+    ("K", 0x4B),
+    ("L", 0x4C),
+    ("M", 0x4D),
+    ("N", 0x4E),
+    ("O", 0x4F),
+    ("P", 0x50),
+    ("Q", 0x51),
+    ("R", 0x52),
+    ("S", 0x53),
+    ("T", 0x54),
+    ("U", 0x55),
+    ("V", 0x56),
+    ("W", 0x57),
+    ("X", 0x58),
+    ("Y", 0x59),
+    ("Z", 0x5A),
+    // Number keys
+    ("0", 0x30),
+    ("1", 0x31),
+    ("2", 0x32),
+    ("3", 0x33),
+    ("4", 0x34),
+    ("5", 0x35),
+    ("6", 0x36),
+    ("7", 0x37),
+    ("8", 0x38),
+    ("9", 0x39),
+    // Numpad keys
+    ("NUMPAD0", 0x60),
+    ("NUMPAD1", 0x61),
+    ("NUMPAD2", 0x62),
+    ("NUMPAD3", 0x63),
+    ("NUMPAD4", 0x64),
+    ("NUMPAD5", 0x65),
+    ("NUMPAD6", 0x66),
+    ("NUMPAD7", 0x67),
+    ("NUMPAD8", 0x68),
+    ("NUMPAD9", 0x69),
+    ("NUMPADMULTIPLY", 0x6A),
+    ("NUMPADADD", 0x6B),
+    ("NUMPADSEPARATOR", 0x6C),
+    ("NUMPADSUBTRACT", 0x6D),
+    ("NUMPADDOT", 0x6E),
+    ("NUMPADDIVIDE", 0x6F),
+    // Arrow keys
+    ("UP", 0x26),
+    ("DOWN", 0x28),
+    ("LEFT", 0x25),
+    ("RIGHT", 0x27),
+    // Special keys
+    ("BACKSPACE", 0x08),
+    ("TAB", 0x09),
+    ("ENTER", 0x0D),
+    ("PAUSE", 0x13),
+    ("CAPSLOCK", 0x14),
+    ("ESCAPE", 0x1B),
+    ("SPACE", 0x20),
+    ("PAGEUP", 0x21),
+    ("PAGEDOWN", 0x22),
+    ("END", 0x23),
+    ("HOME", 0x24),
+    ("INSERT", 0x2D),
+    ("DELETE", 0x2E),
+    // Symbols
+    ("OEM_PLUS", 0xBB),   // '+' key
+    ("OEM_COMMA", 0xBC),  // ',' key
+    ("OEM_MINUS", 0xBD),  // '-' key
+    ("OEM_PERIOD", 0xBE), // '.' key
+    ("OEM_1", 0xBA),      // ';:' key
+    ("OEM_2", 0xBF),      // '/?' key
+    ("OEM_3", 0xC0),      // '`~' key
+    ("OEM_4", 0xDB),      // '[{' key
+    ("OEM_5", 0xDC),      // '\|' key
+    ("OEM_6", 0xDD),      // ']}' key
+    ("OEM_7", 0xDE),      // ''"' key
+    // Additional keys
+    ("PRINTSCREEN", 0x2C),
+    ("SCROLLLOCK", 0x91),
+    ("NUMLOCK", 0x90),
+    ("LEFTSHIFT", 0xA0),
+    ("RIGHTSHIFT", 0xA1),
+    ("LEFTCTRL", 0xA2),
+    ("RIGHTCTRL", 0xA3),
+    ("LEFTALT", 0xA4),
+    ("RIGHTALT", 0xA5),
+];
+
+/// A parsed hotkey: the modifiers held and the key pressed with them.
+///
+/// Formatting a hotkey gives its canonical form.
+///
+/// # Fields
+/// - `ctrl`: Whether Ctrl is part of the hotkey.
+/// - `alt`: Whether Alt is part of the hotkey.
+/// - `shift`: Whether Shift is part of the hotkey.
+/// - `win`: Whether the Windows key is part of the hotkey.
+/// - `caps`: Whether CapsLock is held as a modifier, handled by the CapsLock layer rather
+///   than registered with the system.
+/// - `key`: The canonical name of the key, e.g. "H", "F1", or "ENTER".
+/// - `vk`: The key's virtual key code.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub caps: bool,
+    pub key: &'static str,
+    pub vk: u32,
+}
+
+impl Hotkey {
+    /// Parses a hotkey string.
+    ///
+    /// Parts are separated by `+` and matched case-insensitively, ignoring spaces around
+    /// them. Modifiers may come in any order, each at most once; exactly one key is required.
+    ///
+    /// # Arguments
+    /// - `input`: The hotkey string, e.g. "Ctrl+Alt+H" or "control + super + return".
+    ///
+    /// # Returns
+    /// - The hotkey, or `None` if a part is not a modifier or key, a modifier is repeated,
+    ///   or there is not exactly one key.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::hotkey::Hotkey;
+    /// let hotkey = Hotkey::parse("shift+control+return").unwrap();
+    /// assert_eq!(hotkey.to_string(), "Ctrl+Shift+ENTER");
+    /// assert!(Hotkey::parse("Ctrl+Alt").is_none());
+    /// ```
+    pub fn parse(input: &str) -> Option<Hotkey> {
+        let mut modifiers = [false; 5];
+        let mut key = None;
+        for part in input.split('+') {
+            let part = part.trim().to_ascii_lowercase();
+            match MODIFIERS
+                .iter()
+                .position(|(_, names)| names.contains(&part.as_str()))
+            {
+                Some(modifier) if modifiers[modifier] => return None,
+                Some(modifier) => modifiers[modifier] = true,
+                None if key.is_none() => key = Some(find_key(&part)?),
+                None => return None,
+            }
+        }
+
+        let (key, vk) = key?;
+        let [ctrl, alt, shift, win, caps] = modifiers;
+        Some(Hotkey {
+            ctrl,
+            alt,
+            shift,
+            win,
+            caps,
+            key,
+            vk,
+        })
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let held = [self.ctrl, self.alt, self.shift, self.win, self.caps];
+        for ((name, _), _) in MODIFIERS.iter().zip(held).filter(|(_, held)| *held) {
+            write!(f, "{}+", name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// Looks up a key by its canonical name or an alias, ignoring case.
+///
+/// # Returns
+/// - The key's canonical name and virtual key code.
+fn find_key(name: &str) -> Option<(&'static str, u32)> {
+    let name = name.to_ascii_uppercase();
+    let name = KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name.as_str(), |(_, canonical)| canonical);
+    KEYS.iter().find(|(key, _)| *key == name).copied()
+}
+
+/// Converts a hotkey string to its canonical form.
+///
+/// # Arguments
+/// - `input`: The hotkey string.
+///
+/// # Returns
+/// - The canonical form, or `None` if the hotkey is not valid.
+///
+/// # Example
+/// ```
+/// # use multi_manager::hotkey::canonical_hotkey;
+/// assert_eq!(canonical_hotkey("Alt + ctrl + h").as_deref(), Some("Ctrl+Alt+H"));
+/// ```
+pub fn canonical_hotkey(input: &str) -> Option<String> {
+    Hotkey::parse(input).map(|hotkey| hotkey.to_string())
+}
+
+/// Converts the modifiers of a hotkey base, such as a workspace's window hotkey base, to their
+/// canonical form.
+///
+/// # Arguments
+/// - `base`: The modifiers, e.g. "Alt+Control".
+///
+/// # Returns
+/// - The canonical form, e.g. "Ctrl+Alt", or `None` if `base` is not one or more modifiers.
+pub fn canonical_hotkey_base(base: &str) -> Option<String> {
+    let canonical = canonical_hotkey(&format!("{}+1", base))?;
+    canonical
+        .strip_suffix("+1")
+        .filter(|modifiers| !modifiers.is_empty())
+        .map(str::to_string)
+}
+
+/// Rewrites a stored hotkey in its canonical form, if it is valid and not already canonical.
+/// Invalid hotkeys are left as they are, so validation can report them.
+///
+/// # Arguments
+/// - `hotkey`: The stored hotkey, if any.
+///
+/// # Returns
+/// - `true` if the hotkey was rewritten.
+///
+/// # Example
+/// ```
+/// # use multi_manager::hotkey::migrate_hotkey;
+/// let mut hotkey = Some("control+super+Return".to_string());
+/// assert!(migrate_hotkey(&mut hotkey));
+/// assert_eq!(hotkey.as_deref(), Some("Ctrl+Win+ENTER"));
+/// ```
+pub fn migrate_hotkey(hotkey: &mut Option<String>) -> bool {
+    migrate(hotkey, canonical_hotkey)
+}
+
+/// Rewrites stored hotkey base modifiers in their canonical form, like `migrate_hotkey`.
+///
+/// # Arguments
+/// - `base`: The stored modifiers, if any.
+///
+/// # Returns
+/// - `true` if the modifiers were rewritten.
+pub fn migrate_hotkey_base(base: &mut Option<String>) -> bool {
+    migrate(base, canonical_hotkey_base)
+}
+
+/// Replaces a stored string with its canonical form, logging the change.
+fn migrate(stored: &mut Option<String>, canonical: fn(&str) -> Option<String>) -> bool {
+    let Some(old) = stored.as_ref() else {
+        return false;
+    };
+    match canonical(old) {
+        Some(new) if new != *old => {
+            info!("Migrated hotkey '{}' to '{}'.", old, new);
+            *stored = Some(new);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_parse_to_canonical_form() {
+        for (input, canonical) in [
+            ("Ctrl+Alt+H", "Ctrl+Alt+H"),
+            ("control+alt+h", "Ctrl+Alt+H"),
+            ("ctl+Shift+Return", "Ctrl+Shift+ENTER"),
+            ("super+esc", "Win+ESCAPE"),
+            ("windows+del", "Win+DELETE"),
+            ("meta+pgup", "Win+PAGEUP"),
+            ("hyper+arrowleft", "Caps+LEFT"),
+            ("caps+spacebar", "Caps+SPACE"),
+            ("Win+Shift+Alt+Ctrl+plus", "Ctrl+Alt+Shift+Win+OEM_PLUS"),
+            ("CapsLock", "CAPSLOCK"),
+            ("F13", "F13"),
+        ] {
+            assert_eq!(
+                canonical_hotkey(input).as_deref(),
+                Some(canonical),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn case_and_spaces_are_ignored() {
+        for input in [
+            "ctrl+alt+h",
+            "CTRL+ALT+H",
+            " Ctrl + Alt + h ",
+            "alt+ CONTROL +H",
+        ] {
+            assert_eq!(
+                canonical_hotkey(input).as_deref(),
+                Some("Ctrl+Alt+H"),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_hotkeys_are_rejected() {
+        for input in [
+            "",
+            "Ctrl",
+            "Ctrl+Alt",
+            "Ctrl+Alt+",
+            "Ctrl+A+B",
+            "A+A",
+            "Ctrl+Ctrl+H",
+            "Ctrl+control+H",
+            "win+super+H",
+            "Ctrl+Foo",
+            "Ctrl++H",
+        ] {
+            assert_eq!(Hotkey::parse(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn canonical_form_is_stable() {
+        for input in [
+            "shift+control+return",
+            "hyper + win + f1",
+            "Alt+pgdn",
+            "meta+9",
+        ] {
+            let canonical = canonical_hotkey(input).unwrap();
+            assert_eq!(canonical_hotkey(&canonical), Some(canonical.clone()));
+        }
+    }
+
+    #[test]
+    fn hotkey_bases_are_modifiers_only() {
+        assert_eq!(
+            canonical_hotkey_base("Alt+Control").as_deref(),
+            Some("Ctrl+Alt")
+        );
+        assert_eq!(canonical_hotkey_base("super").as_deref(), Some("Win"));
+        assert_eq!(canonical_hotkey_base(""), None);
+        assert_eq!(canonical_hotkey_base("Ctrl+H"), None);
+    }
+
+    #[test]
+    fn legacy_hotkeys_are_migrated_once() {
+        let mut hotkey = Some("control + super + Return".to_string());
+        assert!(migrate_hotkey(&mut hotkey));
+        assert_eq!(hotkey.as_deref(), Some("Ctrl+Win+ENTER"));
+        assert!(!migrate_hotkey(&mut hotkey));
+
+        let mut invalid = Some("Ctrl+Nope".to_string());
+        assert!(!migrate_hotkey(&mut invalid));
+        assert_eq!(invalid.as_deref(), Some("Ctrl+Nope"));
+
+        let mut base = Some("alt+ctl".to_string());
+        assert!(migrate_hotkey_base(&mut base));
+        assert_eq!(base.as_deref(), Some("Ctrl+Alt"));
+
+        assert!(!migrate_hotkey(&mut None));
+    }
+}
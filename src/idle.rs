@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+/// How often the time since the last input is checked.
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Notices the user going idle for long enough to restore the workspaces, once per idle
+/// period: after a restore, nothing is reported again until there has been input.
+#[derive(Debug, Default)]
+pub struct IdleWatcher {
+    restored: bool,
+}
+
+impl IdleWatcher {
+    /// Creates a watcher for a user who is not idle yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records how long the user has been idle and reports when the threshold is crossed.
+    ///
+    /// # Arguments
+    /// - `idle`: The time since the last keyboard or mouse input.
+    /// - `threshold`: How long the user must be idle before the workspaces are restored.
+    ///
+    /// # Returns
+    /// - `true` the first time `idle` reaches `threshold` in an idle period.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::idle::IdleWatcher;
+    /// # use std::time::Duration;
+    /// let mut watcher = IdleWatcher::new();
+    /// let threshold = Duration::from_secs(30 * 60);
+    /// assert!(watcher.poll(Duration::from_secs(31 * 60), threshold));
+    /// assert!(!watcher.poll(Duration::from_secs(32 * 60), threshold));
+    /// assert!(!watcher.poll(Duration::from_secs(1), threshold));
+    /// assert!(watcher.poll(Duration::from_secs(30 * 60), threshold));
+    /// ```
+    pub fn poll(&mut self, idle: Duration, threshold: Duration) -> bool {
+        if idle < threshold {
+            self.restored = false;
+            return false;
+        }
+        !std::mem::replace(&mut self.restored, true)
+    }
+}
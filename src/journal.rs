@@ -0,0 +1,187 @@
+//! A journal of unsaved workspace edits, so they can be recovered after a crash or power loss.
+//!
+//! While the workspaces in memory differ from the file they were loaded from, each edit is
+//! described in the journal, in the same format as the edit history and encrypted like it while
+//! workspace encryption is on, and the latest state is written next to it. Both are deleted when the workspaces are saved or reloaded and when the
+//! application exits normally, so finding them at startup means edits were lost.
+
+use crate::encryption::encryption_enabled;
+use crate::history::{append_history, describe_changes, read_history, HistoryEntry};
+use crate::workspace::{read_workspaces, save_workspaces, Workspace};
+use log::{info, warn};
+use std::fs;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// How often the GUI compares the workspaces with the last journaled state.
+pub const JOURNAL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The description used when only settings `describe_changes` doesn't report were edited.
+const OTHER_CHANGES: &str = "Workspace settings edited";
+
+/// Unsaved edits found at startup.
+///
+/// # Fields
+/// - `entries`: What was edited, oldest first.
+/// - `workspaces`: The workspaces as they were after the last edit.
+pub struct Recovery {
+    pub entries: Vec<HistoryEntry>,
+    pub workspaces: Vec<Workspace>,
+}
+
+/// Records unsaved edits to the journal and the latest state to the snapshot file.
+pub struct Journal {
+    journal_path: String,
+    snapshot_path: String,
+    last: Vec<Workspace>,
+    last_json: String,
+    baseline: Vec<Workspace>,
+    baseline_json: String,
+    plain_entries: bool,
+    recovery: Option<Recovery>,
+}
+
+impl Journal {
+    /// Opens the journal, picking up unsaved edits left by a previous run.
+    ///
+    /// # Arguments
+    /// - `journal_path`: The file edits are described in.
+    /// - `snapshot_path`: The file the latest state is written to.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let journal = Journal::open(&config_path(JOURNAL_FILE), &config_path(UNSAVED_FILE));
+    /// ```
+    pub fn open(journal_path: &str, snapshot_path: &str) -> Self {
+        let recovery = match (read_history(journal_path), read_workspaces(snapshot_path)) {
+            (Ok(entries), Ok(workspaces)) if !entries.is_empty() => {
+                info!(
+                    "Found {} unsaved edit(s) from a previous run.",
+                    entries.len()
+                );
+                Some(Recovery {
+                    entries,
+                    workspaces,
+                })
+            }
+            _ => None,
+        };
+        Journal {
+            journal_path: journal_path.to_string(),
+            snapshot_path: snapshot_path.to_string(),
+            last: Vec::new(),
+            last_json: String::new(),
+            baseline: Vec::new(),
+            baseline_json: String::new(),
+            plain_entries: false,
+            recovery,
+        }
+    }
+
+    /// Returns the unsaved edits found at startup, until they are recovered or discarded.
+    pub fn recovery(&self) -> Option<&Recovery> {
+        self.recovery.as_ref()
+    }
+
+    /// Takes the edits found at startup and deletes the journal. Nothing is journaled while
+    /// they wait for the user's decision.
+    ///
+    /// # Returns
+    /// - The edits, or `None` if there were none.
+    pub fn take_recovery(&mut self) -> Option<Recovery> {
+        let recovery = self.recovery.take();
+        self.clear();
+        recovery
+    }
+
+    /// Marks the workspaces as saved, e.g. after they were loaded or written to their file,
+    /// and deletes the journal.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The workspaces as they are in the file.
+    pub fn set_baseline(&mut self, workspaces: &[Workspace]) {
+        self.last = workspaces.to_vec();
+        self.last_json = comparable_json(workspaces);
+        self.baseline = self.last.clone();
+        self.baseline_json = self.last_json.clone();
+        if self.recovery.is_none() {
+            self.clear();
+        }
+    }
+
+    /// Checks whether workspaces are the same as the ones last marked as saved, ignoring window
+    /// handles.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The workspaces to compare, e.g. those in memory or in the file on disk.
+    ///
+    /// # Returns
+    /// - `true` if they match the baseline.
+    pub fn matches_baseline(&self, workspaces: &[Workspace]) -> bool {
+        comparable_json(workspaces) == self.baseline_json
+    }
+
+    /// Journals the edits made since the last call, if any.
+    ///
+    /// Entries are encrypted while workspace encryption is on. If it was turned on after
+    /// entries were written in plain text, the journal is started over with every edit since
+    /// the last save, so none stay readable.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The workspaces in memory.
+    /// - `file`: The workspace file they will be saved to.
+    ///
+    /// # Example
+    /// ```ignore
+    /// journal.record(&workspaces, &workspaces_path);
+    /// ```
+    pub fn record(&mut self, workspaces: &[Workspace], file: &str) {
+        if self.recovery.is_some() {
+            return;
+        }
+        if self.plain_entries && encryption_enabled() {
+            self.clear();
+            self.last = self.baseline.clone();
+            self.last_json = self.baseline_json.clone();
+        }
+        let json = comparable_json(workspaces);
+        if json == self.last_json {
+            return;
+        }
+        let mut changes = describe_changes(&self.last, workspaces);
+        if changes.is_empty() {
+            changes.push(OTHER_CHANGES.to_string());
+        }
+        if let Err(e) = save_workspaces(workspaces, &self.snapshot_path)
+            .and_then(|()| append_history(&self.journal_path, file, &changes))
+        {
+            warn!("Failed to journal unsaved edits: {}", e);
+            return;
+        }
+        self.plain_entries |= !encryption_enabled();
+        self.last = workspaces.to_vec();
+        self.last_json = json;
+    }
+
+    /// Deletes the journal and the snapshot, e.g. when the application exits normally.
+    pub fn clear(&mut self) {
+        self.plain_entries = false;
+        for path in [&self.journal_path, &self.snapshot_path] {
+            if let Err(e) = fs::remove_file(path) {
+                if e.kind() != ErrorKind::NotFound {
+                    warn!("Failed to delete '{}': {}", path, e);
+                }
+            }
+        }
+    }
+}
+
+/// Serializes the workspaces without the window handles, which change as windows are found
+/// again and are not edits.
+fn comparable_json(workspaces: &[Workspace]) -> String {
+    let mut workspaces = workspaces.to_vec();
+    for window in workspaces.iter_mut().flat_map(|w| w.windows.iter_mut()) {
+        window.id = 0;
+    }
+    serde_json::to_string(&workspaces).unwrap_or_default()
+}
@@ -0,0 +1,235 @@
+//! A low-level keyboard hook that catches workspace hotkeys `RegisterHotKey` can't take, such
+//! as combinations another program has registered or Windows reserves for the shell.
+//!
+//! Hotkeys in hook mode are not registered with the system. While the hook is installed, each
+//! key press is compared with them, using the modifiers held at the time; a match is swallowed,
+//! so the program that owns the combination doesn't see it, and queued for
+//! `take_hooked_presses`, so even a tap shorter than the hotkey poll is acted on.
+//!
+//! The trade-offs, shown in the GUI: the hook sees every key press; Windows doesn't pass it
+//! input for elevated windows unless Multi Manager runs as administrator; combinations such as
+//! Win+L and Ctrl+Alt+Delete are handled before any hook; and Windows silently removes hooks
+//! that respond too slowly.
+
+use crate::hotkey::Hotkey;
+use log::{info, warn};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_KEYUP, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
+    HC_ACTION, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_QUIT,
+    WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+/// An unassigned virtual key, tapped after a swallowed Win combination so releasing Win
+/// doesn't open the Start menu.
+const VK_UNASSIGNED: VIRTUAL_KEY = VIRTUAL_KEY(0xE8);
+
+/// The hotkeys in hook mode, which `register_hotkey` only records.
+static HOOKED_HOTKEYS: Mutex<Vec<Hotkey>> = Mutex::new(Vec::new());
+
+/// The hooked hotkeys whose key is held, swallowed by the hook.
+static PRESSED: Mutex<Vec<Hotkey>> = Mutex::new(Vec::new());
+
+/// The hooked hotkeys pressed since `take_hooked_presses` last ran, in order.
+static PENDING: Mutex<Vec<Hotkey>> = Mutex::new(Vec::new());
+
+/// A running keyboard hook. Dropping it removes the hook.
+pub struct KeyboardHook {
+    thread_id: u32,
+}
+
+impl KeyboardHook {
+    /// Installs the keyboard hook on a thread of its own.
+    ///
+    /// # Returns
+    /// - The running hook, or `None` if the hook could not be installed.
+    pub fn start() -> Option<KeyboardHook> {
+        let (started, started_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("Keyboard Hook".to_string())
+            .spawn(move || run_hook(started))
+            .ok()?;
+        match started_receiver.recv() {
+            Ok(Some(thread_id)) => {
+                info!("Keyboard hook installed for hotkeys in hook mode.");
+                Some(KeyboardHook { thread_id })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Drop for KeyboardHook {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        PRESSED.lock().unwrap().clear();
+        PENDING.lock().unwrap().clear();
+        info!("Keyboard hook removed.");
+    }
+}
+
+/// Replaces the hotkeys handled by the keyboard hook.
+///
+/// # Arguments
+/// - `hotkeys`: Every hotkey in hook mode.
+///
+/// # Returns
+/// - `true` if the hotkeys changed.
+///
+/// # Example
+/// ```ignore
+/// if set_hooked_hotkeys(hooked_hotkeys(&workspaces)) {
+///     info!("Hook mode hotkeys changed.");
+/// }
+/// ```
+pub fn set_hooked_hotkeys(hotkeys: Vec<Hotkey>) -> bool {
+    let mut hooked = HOOKED_HOTKEYS.lock().unwrap();
+    if *hooked == hotkeys {
+        return false;
+    }
+    *hooked = hotkeys;
+    true
+}
+
+/// Adds a hotkey to those handled by the keyboard hook, or removes it, e.g. when its binding is
+/// switched between modes in the GUI.
+///
+/// # Arguments
+/// - `hotkey`: The hotkey.
+/// - `hooked`: Whether the hook handles it.
+pub fn set_hotkey_hooked(hotkey: Hotkey, hooked: bool) {
+    let mut hotkeys = HOOKED_HOTKEYS.lock().unwrap();
+    hotkeys.retain(|h| *h != hotkey);
+    if hooked {
+        hotkeys.push(hotkey);
+    }
+}
+
+/// Checks whether a hotkey is handled by the keyboard hook rather than registered with the
+/// system.
+///
+/// # Arguments
+/// - `hotkey`: The hotkey.
+pub fn is_hooked(hotkey: &Hotkey) -> bool {
+    HOOKED_HOTKEYS.lock().unwrap().contains(hotkey)
+}
+
+/// Takes the presses of hotkeys in hook mode caught since the last call. The hook swallows
+/// their keys, so they are not seen by `GetAsyncKeyState`, and a press is kept until taken
+/// however soon the key is released.
+///
+/// # Returns
+/// - The hotkeys pressed, in order, once per press.
+///
+/// # Example
+/// ```ignore
+/// let presses = take_hooked_presses();
+/// if presses.iter().any(|pressed| Some(*pressed) == Hotkey::parse(hotkey)) {
+///     toggle(workspace);
+/// }
+/// ```
+pub fn take_hooked_presses() -> Vec<Hotkey> {
+    std::mem::take(&mut *PENDING.lock().unwrap())
+}
+
+/// Installs the hook and runs the message loop the hook needs until `WM_QUIT` is posted.
+fn run_hook(started: Sender<Option<u32>>) {
+    let hook = match unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), None, 0) } {
+        Ok(hook) => hook,
+        Err(e) => {
+            warn!("Failed to install the keyboard hook: {}", e);
+            let _ = started.send(None);
+            return;
+        }
+    };
+    let _ = started.send(Some(unsafe { GetCurrentThreadId() }));
+
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {}
+
+    unsafe {
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// The low-level keyboard hook: swallows the presses and releases of hooked hotkeys, passing
+/// everything else on.
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if (event.flags & LLKHF_INJECTED).0 == 0 {
+            match wparam.0 as u32 {
+                WM_KEYDOWN | WM_SYSKEYDOWN => {
+                    if let Some(hotkey) = matching_hotkey(event.vkCode) {
+                        let mut pressed = PRESSED.lock().unwrap();
+                        // Held keys repeat; only the first press needs handling
+                        if !pressed.contains(&hotkey) {
+                            pressed.push(hotkey);
+                            PENDING.lock().unwrap().push(hotkey);
+                            if hotkey.win {
+                                thread::spawn(mask_start_menu);
+                            }
+                        }
+                        return LRESULT(1);
+                    }
+                }
+                WM_KEYUP | WM_SYSKEYUP => {
+                    let mut pressed = PRESSED.lock().unwrap();
+                    let before = pressed.len();
+                    pressed.retain(|hotkey| hotkey.vk != event.vkCode);
+                    if pressed.len() != before {
+                        return LRESULT(1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Finds the hooked hotkey for a key pressed with the modifiers held now.
+fn matching_hotkey(vk: u32) -> Option<Hotkey> {
+    let down = |vk: VIRTUAL_KEY| unsafe { GetAsyncKeyState(vk.0 as i32) < 0 };
+    let (ctrl, alt, shift) = (down(VK_CONTROL), down(VK_MENU), down(VK_SHIFT));
+    let win = down(VK_LWIN) || down(VK_RWIN);
+    HOOKED_HOTKEYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|hotkey| {
+            hotkey.vk == vk
+                && !hotkey.caps
+                && (hotkey.ctrl, hotkey.alt, hotkey.shift, hotkey.win) == (ctrl, alt, shift, win)
+        })
+        .copied()
+}
+
+/// Taps an unassigned key, so Windows doesn't treat the Win key's release as a press of its
+/// own and open the Start menu.
+fn mask_start_menu() {
+    let input = |flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VK_UNASSIGNED,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let inputs = [input(KEYBD_EVENT_FLAGS(0)), input(KEYEVENTF_KEYUP)];
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
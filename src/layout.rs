@@ -0,0 +1,489 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How auto-arrange divides a monitor between a workspace's windows.
+///
+/// # Variants
+/// - `Tile`: A grid as close to square as possible; the last row's windows share its width.
+/// - `Columns`: Side-by-side columns of equal width.
+/// - `MainAndSide`: The first window takes the left part of the screen and the others are
+///   stacked on the right.
+/// - `Spiral`: Each window takes the golden-ratio share of the space left by the ones before
+///   it, turning clockwise (left, top, right, bottom) so the windows spiral inwards.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum LayoutAlgorithm {
+    #[default]
+    Tile,
+    Columns,
+    MainAndSide,
+    Spiral,
+}
+
+impl LayoutAlgorithm {
+    /// Every algorithm, in the order offered in the GUI.
+    pub const ALL: [LayoutAlgorithm; 4] = [
+        LayoutAlgorithm::Tile,
+        LayoutAlgorithm::Columns,
+        LayoutAlgorithm::MainAndSide,
+        LayoutAlgorithm::Spiral,
+    ];
+
+    /// Returns the name shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            LayoutAlgorithm::Tile => "Tile",
+            LayoutAlgorithm::Columns => "Columns",
+            LayoutAlgorithm::MainAndSide => "Main + Side",
+            LayoutAlgorithm::Spiral => "Spiral",
+        }
+    }
+}
+
+/// A workspace's auto-arrange settings, used instead of the stored targets when toggling.
+///
+/// # Fields
+/// - `algorithm`: How the monitor is divided.
+/// - `monitor`: The index of the monitor to arrange on, in `enumerate_monitors` order.
+/// - `gap`: The space in pixels between windows and around the edges of the monitor.
+/// - `main_percent`: How much of the width the main window takes with `MainAndSide`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AutoArrange {
+    pub algorithm: LayoutAlgorithm,
+    pub monitor: usize,
+    pub gap: i32,
+    pub main_percent: u8,
+}
+
+impl Default for AutoArrange {
+    fn default() -> Self {
+        AutoArrange {
+            algorithm: LayoutAlgorithm::default(),
+            monitor: 0,
+            gap: 0,
+            main_percent: 60,
+        }
+    }
+}
+
+/// A position given as cells of a grid laid over a monitor's work area, e.g. columns 1–8 of a
+/// 12-column grid, so it adapts to the monitor's resolution.
+///
+/// Rows and columns are counted from 0; the GUI shows them from 1.
+///
+/// # Fields
+/// - `monitor`: The index of the monitor, in `enumerate_monitors` order.
+/// - `columns`: How many columns the work area is divided into.
+/// - `rows`: How many rows the work area is divided into.
+/// - `column`: The first column the window covers.
+/// - `row`: The first row the window covers.
+/// - `column_span`: How many columns the window covers.
+/// - `row_span`: How many rows the window covers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GridCell {
+    pub monitor: usize,
+    pub columns: u32,
+    pub rows: u32,
+    pub column: u32,
+    pub row: u32,
+    pub column_span: u32,
+    pub row_span: u32,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        GridCell {
+            monitor: 0,
+            columns: 12,
+            rows: 1,
+            column: 0,
+            row: 0,
+            column_span: 6,
+            row_span: 1,
+        }
+    }
+}
+
+impl GridCell {
+    /// Computes the rect of the cells on the connected monitors.
+    ///
+    /// Cells past the edge of the grid are clamped to it, so a hand-edited cell can't place a
+    /// window off-screen.
+    ///
+    /// # Arguments
+    /// - `work_areas`: The work area of every connected monitor.
+    ///
+    /// # Returns
+    /// - The rect `(x, y, width, height)`, or `None` if the monitor is not connected.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::layout::GridCell;
+    /// let cell = GridCell { columns: 12, column: 0, column_span: 8, ..Default::default() };
+    /// assert_eq!(cell.rect(&[(0, 0, 1200, 800)]), Some((0, 0, 800, 800)));
+    /// ```
+    pub fn rect(&self, work_areas: &[(i32, i32, i32, i32)]) -> Option<(i32, i32, i32, i32)> {
+        let &(x, y, width, height) = work_areas.get(self.monitor)?;
+        let columns = self.columns.max(1);
+        let rows = self.rows.max(1);
+        let column = self.column.min(columns - 1);
+        let row = self.row.min(rows - 1);
+        let column_end = (column + self.column_span.max(1)).min(columns);
+        let row_end = (row + self.row_span.max(1)).min(rows);
+
+        let edge = |start: i32, length: i32, index: u32, count: u32| {
+            start + (length as i64 * index as i64 / count as i64) as i32
+        };
+        let left = edge(x, width, column, columns);
+        let right = edge(x, width, column_end, columns);
+        let top = edge(y, height, row, rows);
+        let bottom = edge(y, height, row_end, rows);
+        Some((left, top, right - left, bottom - top))
+    }
+}
+
+/// Fits a position inside a monitor's work area, so it doesn't overlap the taskbar or docked
+/// toolbars. The work area the position overlaps most is used, or the nearest one if it
+/// overlaps none; the position is shrunk if it is larger than the work area, then moved in.
+///
+/// # Arguments
+/// - `rect`: The position `(x, y, width, height)` to fit.
+/// - `work_areas`: The work area of every connected monitor.
+///
+/// # Returns
+/// - The fitted position, or `rect` unchanged if there are no work areas.
+///
+/// # Example
+/// ```
+/// # use multi_manager::layout::clamp_to_work_area;
+/// let fitted = clamp_to_work_area((0, 900, 800, 300), &[(0, 0, 1920, 1040)]);
+/// assert_eq!(fitted, (0, 740, 800, 300));
+/// ```
+pub fn clamp_to_work_area(
+    rect: (i32, i32, i32, i32),
+    work_areas: &[(i32, i32, i32, i32)],
+) -> (i32, i32, i32, i32) {
+    let (x, y, width, height) = rect;
+    let overlap = |&(ax, ay, aw, ah): &(i32, i32, i32, i32)| {
+        let w = ((x + width).min(ax + aw) - x.max(ax)).max(0) as i64;
+        let h = ((y + height).min(ay + ah) - y.max(ay)).max(0) as i64;
+        w * h
+    };
+    let distance = |&(ax, ay, aw, ah): &(i32, i32, i32, i32)| {
+        let (cx, cy) = (x as i64 + width as i64 / 2, y as i64 + height as i64 / 2);
+        let dx = (ax as i64 - cx).max(cx - (ax + aw) as i64).max(0);
+        let dy = (ay as i64 - cy).max(cy - (ay + ah) as i64).max(0);
+        dx * dx + dy * dy
+    };
+    let area = work_areas
+        .iter()
+        .filter(|area| overlap(area) > 0)
+        .max_by_key(|area| overlap(area))
+        .or_else(|| work_areas.iter().min_by_key(|area| distance(area)));
+    let Some(&(ax, ay, aw, ah)) = area else {
+        return rect;
+    };
+
+    let width = width.min(aw);
+    let height = height.min(ah);
+    (
+        x.clamp(ax, ax + aw - width),
+        y.clamp(ay, ay + ah - height),
+        width,
+        height,
+    )
+}
+
+/// Computes the rects for a number of windows laid out on an area.
+///
+/// # Arguments
+/// - `settings`: The algorithm, gap, and main window share to use.
+/// - `area`: The area to fill `(x, y, width, height)`, usually a monitor's work area.
+/// - `count`: How many windows to place.
+///
+/// # Returns
+/// - One rect `(x, y, width, height)` per window, in the order of the windows.
+///
+/// # Example
+/// ```
+/// # use multi_manager::layout::{arrange, AutoArrange, LayoutAlgorithm};
+/// let settings = AutoArrange { algorithm: LayoutAlgorithm::Columns, ..Default::default() };
+/// let rects = arrange(&settings, (0, 0, 1920, 1080), 2);
+/// assert_eq!(rects, vec![(0, 0, 960, 1080), (960, 0, 960, 1080)]);
+/// ```
+pub fn arrange(
+    settings: &AutoArrange,
+    area: (i32, i32, i32, i32),
+    count: usize,
+) -> Vec<(i32, i32, i32, i32)> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    // A gap too large for the area is shrunk so that every window keeps at least half its share
+    let gap = settings
+        .gap
+        .clamp(0, area.2.min(area.3).max(0) / (2 * count as i32 + 2));
+    let (x, y, width, height) = (
+        area.0 + gap,
+        area.1 + gap,
+        area.2 - 2 * gap,
+        area.3 - 2 * gap,
+    );
+
+    match settings.algorithm {
+        LayoutAlgorithm::Columns => split(x, width, count, gap)
+            .into_iter()
+            .map(|(left, w)| (left, y, w, height))
+            .collect(),
+        LayoutAlgorithm::Tile => {
+            let columns = (count as f64).sqrt().ceil() as usize;
+            let rows = count.div_ceil(columns);
+            let mut rects = Vec::with_capacity(count);
+            for (row, (top, h)) in split(y, height, rows, gap).into_iter().enumerate() {
+                let in_row = columns.min(count - row * columns);
+                for (left, w) in split(x, width, in_row, gap) {
+                    rects.push((left, top, w, h));
+                }
+            }
+            rects
+        }
+        LayoutAlgorithm::MainAndSide => {
+            if count == 1 {
+                return vec![(x, y, width, height)];
+            }
+            let percent = settings.main_percent.clamp(10, 90) as i32;
+            let main_width = (width - gap) * percent / 100;
+            let side_x = x + main_width + gap;
+            let side_width = width - main_width - gap;
+
+            let mut rects = vec![(x, y, main_width, height)];
+            rects.extend(
+                split(y, height, count - 1, gap)
+                    .into_iter()
+                    .map(|(top, h)| (side_x, top, side_width, h)),
+            );
+            rects
+        }
+        LayoutAlgorithm::Spiral => spiral((x, y, width, height), count, gap),
+    }
+}
+
+/// The share of the remaining space each window takes in the spiral layout, 1 / φ.
+const GOLDEN_SHARE: f64 = 0.618_033_988_75;
+
+/// Lays windows out in a Fibonacci spiral: each takes the golden-ratio share of the space
+/// left, on the left, top, right, and bottom in turn, and the last one fills what remains.
+///
+/// # Returns
+/// - One rect `(x, y, width, height)` per window, largest first.
+fn spiral(area: (i32, i32, i32, i32), count: usize, gap: i32) -> Vec<(i32, i32, i32, i32)> {
+    let (mut x, mut y, mut width, mut height) = area;
+    let mut rects = Vec::with_capacity(count);
+
+    for k in 0..count {
+        if k == count - 1 {
+            rects.push((x, y, width, height));
+            break;
+        }
+
+        let horizontal = k % 2 == 0;
+        let length = if horizontal { width } else { height };
+        // Deep in the spiral the space left can be narrower than the gap
+        let gap = gap.min(length);
+        let share = ((length - gap) as f64 * GOLDEN_SHARE).round() as i32;
+        let rest = length - gap - share;
+        match k % 4 {
+            // Left, leaving the right
+            0 => {
+                rects.push((x, y, share, height));
+                x += share + gap;
+                width = rest;
+            }
+            // Top, leaving the bottom
+            1 => {
+                rects.push((x, y, width, share));
+                y += share + gap;
+                height = rest;
+            }
+            // Right, leaving the left
+            2 => {
+                rects.push((x + rest + gap, y, share, height));
+                width = rest;
+            }
+            // Bottom, leaving the top
+            _ => {
+                rects.push((x, y + rest + gap, width, share));
+                height = rest;
+            }
+        }
+    }
+    rects
+}
+
+/// Splits a length into equal parts separated by a gap, giving any remainder to the last part
+/// so the parts exactly fill it.
+///
+/// # Returns
+/// - `(start, length)` of each part.
+fn split(start: i32, length: i32, parts: usize, gap: i32) -> Vec<(i32, i32)> {
+    let parts_i32 = parts as i32;
+    let available = length - gap * (parts_i32 - 1);
+    let size = available / parts_i32;
+    (0..parts_i32)
+        .map(|k| {
+            let offset = start + k * (size + gap);
+            let part = if k == parts_i32 - 1 {
+                start + length - offset
+            } else {
+                size
+            };
+            (offset, part)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(algorithm: LayoutAlgorithm, gap: i32) -> AutoArrange {
+        AutoArrange {
+            algorithm,
+            gap,
+            ..Default::default()
+        }
+    }
+
+    /// Asserts that no two rects overlap and that they all lie inside the area.
+    fn assert_disjoint_inside(rects: &[(i32, i32, i32, i32)], area: (i32, i32, i32, i32)) {
+        let (ax, ay, aw, ah) = area;
+        for (i, &(x, y, w, h)) in rects.iter().enumerate() {
+            assert!(w >= 0 && h >= 0, "{:?} has a negative size", rects[i]);
+            assert!(
+                x >= ax && y >= ay && x + w <= ax + aw && y + h <= ay + ah,
+                "{:?} is outside {:?}",
+                rects[i],
+                area
+            );
+            for &(ox, oy, ow, oh) in &rects[i + 1..] {
+                let apart = x + w <= ox || ox + ow <= x || y + h <= oy || oy + oh <= y;
+                assert!(apart, "{:?} overlaps {:?}", rects[i], (ox, oy, ow, oh));
+            }
+        }
+    }
+
+    #[test]
+    fn split_gives_the_remainder_to_the_last_part() {
+        assert_eq!(split(0, 100, 3, 0), vec![(0, 33), (33, 33), (66, 34)]);
+        assert_eq!(split(10, 100, 2, 10), vec![(10, 45), (65, 45)]);
+        assert_eq!(split(0, 100, 1, 10), vec![(0, 100)]);
+    }
+
+    #[test]
+    fn every_algorithm_fills_the_area_without_overlaps() {
+        let area = (-1920, 0, 1920, 1040);
+        for algorithm in LayoutAlgorithm::ALL {
+            for gap in [0, 8] {
+                for count in 1..=7 {
+                    let rects = arrange(&settings(algorithm, gap), area, count);
+                    assert_eq!(rects.len(), count, "{:?}", algorithm);
+                    assert_disjoint_inside(&rects, area);
+                }
+            }
+        }
+        assert!(arrange(&settings(LayoutAlgorithm::Tile, 0), area, 0).is_empty());
+    }
+
+    #[test]
+    fn tile_shares_the_last_row() {
+        let rects = arrange(&settings(LayoutAlgorithm::Tile, 0), (0, 0, 1200, 800), 3);
+        assert_eq!(
+            rects,
+            vec![(0, 0, 600, 400), (600, 0, 600, 400), (0, 400, 1200, 400)]
+        );
+    }
+
+    #[test]
+    fn main_and_side_uses_the_main_percent() {
+        let layout = AutoArrange {
+            algorithm: LayoutAlgorithm::MainAndSide,
+            main_percent: 75,
+            ..Default::default()
+        };
+        let rects = arrange(&layout, (0, 0, 1000, 600), 3);
+        assert_eq!(
+            rects,
+            vec![(0, 0, 750, 600), (750, 0, 250, 300), (750, 300, 250, 300)]
+        );
+    }
+
+    #[test]
+    fn spiral_turns_clockwise() {
+        let rects = spiral((0, 0, 1000, 1000), 4, 0);
+        assert_eq!(
+            rects,
+            vec![
+                (0, 0, 618, 1000),
+                (618, 0, 382, 618),
+                (764, 618, 236, 382),
+                (618, 618, 146, 382),
+            ]
+        );
+    }
+
+    #[test]
+    fn gaps_larger_than_the_area_are_shrunk() {
+        let area = (0, 0, 300, 200);
+        for algorithm in LayoutAlgorithm::ALL {
+            for count in 1..=12 {
+                let rects = arrange(&settings(algorithm, 5000), area, count);
+                assert_eq!(rects.len(), count, "{:?}", algorithm);
+                assert_disjoint_inside(&rects, area);
+            }
+        }
+        let rects = arrange(&settings(LayoutAlgorithm::Columns, 5000), area, 2);
+        assert!(rects.iter().all(|&(_, _, w, h)| w > 0 && h > 0));
+    }
+
+    #[test]
+    fn grid_cell_divides_the_work_area() {
+        let cell = GridCell {
+            monitor: 1,
+            columns: 3,
+            rows: 2,
+            column: 1,
+            row: 1,
+            column_span: 2,
+            row_span: 1,
+        };
+        let work_areas = [(0, 0, 1920, 1040), (1920, 0, 1200, 800)];
+        assert_eq!(cell.rect(&work_areas), Some((2320, 400, 800, 400)));
+        assert_eq!(cell.rect(&work_areas[..1]), None);
+    }
+
+    #[test]
+    fn grid_cell_spans_past_the_edge_are_clamped() {
+        let work_areas = [(0, 0, 1200, 800)];
+        let past_edge = GridCell {
+            columns: 12,
+            column: 10,
+            column_span: 6,
+            rows: 2,
+            row: 5,
+            row_span: 3,
+            ..Default::default()
+        };
+        assert_eq!(past_edge.rect(&work_areas), Some((1000, 400, 200, 400)));
+
+        let degenerate = GridCell {
+            columns: 0,
+            rows: 0,
+            column_span: 0,
+            row_span: 0,
+            ..Default::default()
+        };
+        assert_eq!(degenerate.rect(&work_areas), Some((0, 0, 1200, 800)));
+    }
+}
@@ -0,0 +1,47 @@
+//! The Multi Manager engine: the workspace model and its persistence, hotkey parsing and
+//! registration, the window toggling logic, and the errors they report.
+//!
+//! The toggling engine in `engine` talks to the window system only through the
+//! `backend::WindowBackend` trait, so it can run against `backend::Win32Backend` in the
+//! application or against a simulated backend in tests and other tools. The GUI lives in
+//! the binary crate.
+
+pub mod activity;
+pub mod agent;
+pub mod animation;
+pub mod backend;
+pub mod caps_layer;
+pub mod cheat_sheet;
+pub mod close_watch;
+pub mod controller;
+pub mod elevation;
+pub mod encryption;
+pub mod engine;
+pub mod error;
+pub mod gestures;
+pub mod history;
+pub mod hooks;
+pub mod hotkey;
+pub mod idle;
+pub mod journal;
+pub mod key_hook;
+pub mod layout;
+pub mod logging;
+pub mod monitor_watch;
+pub mod paths;
+pub mod recovery;
+pub mod schedule;
+pub mod session_watch;
+pub mod settings;
+pub mod snap;
+pub mod thumbnails;
+pub mod tray;
+pub mod update;
+pub mod usage;
+pub mod utils;
+pub mod validation;
+pub mod virtual_desktop;
+pub mod window_browser;
+pub mod window_manager;
+pub mod window_ops;
+pub mod workspace;
@@ -0,0 +1,233 @@
+use crate::error::MultiManagerError;
+use crate::paths::config_path;
+use log::LevelFilter;
+use log4rs::append::file::FileAppender;
+use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
+use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
+use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
+use log4rs::append::rolling_file::RollingFileAppender;
+use log4rs::append::Append;
+use log4rs::config::{Appender, Config, Deserializers, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::Handle;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+
+/// The pattern used for every log line, matching the default `log4rs.yaml`.
+const LOG_PATTERN: &str = "{d} - {l} - {m}{n}";
+
+/// The handle of the active logger, kept so its configuration can be replaced at runtime.
+static LOGGER: OnceCell<Handle> = OnceCell::new();
+
+/// The minimum level of messages written to the log file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Every level, in order of increasing verbosity, for the Settings panel.
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::Off,
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    /// Converts the level into the `log` crate's filter.
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Logging options editable from the Settings panel.
+///
+/// # Fields
+/// - `level`: The minimum level of messages written.
+/// - `file_path`: The log file to write to. Relative paths are resolved against the
+///   configuration directory.
+/// - `rotate`: Whether to roll the log over once it reaches `max_size_mb`.
+/// - `max_size_mb`: The size in megabytes at which the log is rolled over.
+/// - `max_files`: How many rolled-over logs (`<file_path>.0`, `.1`, ...) are kept.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingSettings {
+    pub level: LogLevel,
+    pub file_path: String,
+    pub rotate: bool,
+    pub max_size_mb: u64,
+    pub max_files: u32,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        LoggingSettings {
+            level: LogLevel::Info,
+            file_path: "multi_manager.log".to_string(),
+            rotate: false,
+            max_size_mb: 10,
+            max_files: 5,
+        }
+    }
+}
+
+/// Builds a log4rs configuration from the logging settings.
+///
+/// # Arguments
+/// - `settings`: The logging settings to apply.
+///
+/// # Returns
+/// - The log4rs `Config` writing to the configured file.
+/// - `Err(Logging)` if the file cannot be opened or the configuration is invalid.
+fn build_config(settings: &LoggingSettings) -> Result<Config, MultiManagerError> {
+    let logging_error = |message: String| MultiManagerError::Logging { message };
+    let encoder = Box::new(PatternEncoder::new(LOG_PATTERN));
+    let file_path = config_path(&settings.file_path);
+
+    let appender: Box<dyn Append> = if settings.rotate {
+        let roller = FixedWindowRoller::builder()
+            .build(&format!("{}.{{}}", file_path), settings.max_files)
+            .map_err(|e| logging_error(e.to_string()))?;
+        let trigger = SizeTrigger::new(settings.max_size_mb.saturating_mul(1024 * 1024));
+        let policy = CompoundPolicy::new(Box::new(trigger), Box::new(roller));
+        Box::new(
+            RollingFileAppender::builder()
+                .encoder(encoder)
+                .build(&file_path, Box::new(policy))
+                .map_err(|e| logging_error(e.to_string()))?,
+        )
+    } else {
+        Box::new(
+            FileAppender::builder()
+                .append(false)
+                .encoder(encoder)
+                .build(&file_path)
+                .map_err(|e| logging_error(e.to_string()))?,
+        )
+    };
+
+    Config::builder()
+        .appender(Appender::builder().build("file", appender))
+        .build(
+            Root::builder()
+                .appender("file")
+                .build(settings.level.filter()),
+        )
+        .map_err(|e| logging_error(e.to_string()))
+}
+
+/// Installs the logger, either from saved settings or from a log4rs YAML file.
+///
+/// # Arguments
+/// - `settings`: Logging settings saved from the Settings panel, which take precedence.
+/// - `yaml_path`: The log4rs YAML file used when no settings have been saved.
+///
+/// # Returns
+/// - `Ok(())` if the logger was installed.
+/// - `Err(Logging)` if the configuration could not be built or a logger is already installed.
+pub fn init_logging(
+    settings: Option<&LoggingSettings>,
+    yaml_path: &str,
+) -> Result<(), MultiManagerError> {
+    let logging_error = |message: String| MultiManagerError::Logging { message };
+    let config = match settings {
+        Some(settings) => build_config(settings)?,
+        None => log4rs::config::load_config_file(yaml_path, Deserializers::default())
+            .map_err(|e| logging_error(e.to_string()))?,
+    };
+
+    let handle = log4rs::init_config(config).map_err(|e| logging_error(e.to_string()))?;
+    let _ = LOGGER.set(handle);
+    Ok(())
+}
+
+/// Replaces the running logger's configuration without restarting.
+///
+/// # Arguments
+/// - `settings`: The logging settings to apply.
+///
+/// # Returns
+/// - `Ok(())` if the new configuration is in effect.
+/// - `Err(Logging)` if the configuration is invalid or logging was never initialized.
+///
+/// # Example
+/// ```ignore
+/// if let Err(e) = apply_logging_settings(&settings.logging) {
+///     println!("{}", e);
+/// }
+/// ```
+pub fn apply_logging_settings(settings: &LoggingSettings) -> Result<(), MultiManagerError> {
+    let config = build_config(settings)?;
+    let handle = LOGGER.get().ok_or_else(|| MultiManagerError::Logging {
+        message: "logging has not been initialized".to_string(),
+    })?;
+    handle.set_config(config);
+    log::info!("Applied logging settings: {:?}", settings);
+    Ok(())
+}
+
+/// Returns the full path of the log file being written.
+///
+/// Without saved settings this is the default file, which is also where the generated
+/// `log4rs.yaml` writes; a hand-edited `log4rs.yaml` may point elsewhere.
+///
+/// # Arguments
+/// - `settings`: Logging settings saved from the Settings panel, if any.
+pub fn log_file_path(settings: Option<&LoggingSettings>) -> String {
+    match settings {
+        Some(settings) => config_path(&settings.file_path),
+        None => config_path(&LoggingSettings::default().file_path),
+    }
+}
+
+/// Reads the last lines of a log file.
+///
+/// # Arguments
+/// - `file_path`: The log file.
+/// - `max_lines`: How many lines to return at most.
+///
+/// # Returns
+/// - The last `max_lines` lines, oldest first. Empty if nothing has been logged yet.
+/// - `Err(Load)` if the file exists but could not be read.
+///
+/// # Example
+/// ```ignore
+/// for line in read_log_tail(&log_file_path(None), 100)? {
+///     println!("{}", line);
+/// }
+/// ```
+pub fn read_log_tail(file_path: &str, max_lines: usize) -> Result<Vec<String>, MultiManagerError> {
+    let content = match fs::read(file_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(MultiManagerError::Load {
+                path: file_path.to_string(),
+                message: e.to_string(),
+            })
+        }
+    };
+
+    // Window titles are logged verbatim, so don't let one bad byte hide the whole log
+    let content = String::from_utf8_lossy(&content);
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
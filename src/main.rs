@@ -1,71 +1,259 @@
 #![windows_subsystem = "windows"]
 
 mod gui;
-mod utils;
-mod window_manager;
-mod workspace;
 
 use log::info;
-use std::collections::HashMap;
+use multi_manager::agent::{send_to_agent, AgentCommand, AGENT_FLAG};
+use multi_manager::animation::{
+    set_animations_enabled, set_global_animation, set_respect_reduced_motion,
+};
+use multi_manager::backend::Win32Backend;
+use multi_manager::elevation::HANDOFF_FLAG;
+use multi_manager::encryption::set_encryption_enabled;
+use multi_manager::journal::Journal;
+use multi_manager::logging::{init_logging, LoggingSettings};
+use multi_manager::paths::{
+    config_dir, config_path, init_config_dir, ARCHIVE_FILE, EMERGENCY_FILE, JOURNAL_FILE,
+    LOG_CONFIG_FILE, PORTABLE_FLAG, SETTINGS_FILE, UNSAVED_FILE, USAGE_FILE, WORKSPACES_FILE,
+};
+use multi_manager::recovery::install_panic_hook;
+use multi_manager::settings::load_settings;
+use multi_manager::usage::load_usage_stats;
+use multi_manager::window_browser::set_show_all_windows;
+use multi_manager::workspace::{read_workspaces, validate_workspaces_file};
+use multi_manager::{thumbnails, validation, window_browser, window_ops};
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::fs::File;
 use std::io::Write; // Fix for write_all error
 use std::sync::{Arc, Mutex};
+use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+/// The command-line flag that checks a workspaces file and exits instead of starting the GUI.
+const VALIDATE_CONFIG_FLAG: &str = "--validate-config";
+
+/// The command-line flag that starts hotkey processing without opening the window.
+const BACKGROUND_FLAG: &str = "--background";
 
 fn main() {
+    // Keep files in %APPDATA%, or next to the exe in portable mode, not the working directory
+    init_config_dir(env::args().any(|arg| arg == PORTABLE_FLAG));
+
+    // `--validate-config [path]` checks a workspaces file without starting the GUI
+    let args: Vec<String> = env::args().collect();
+    if let Some(position) = args.iter().position(|arg| arg == VALIDATE_CONFIG_FLAG) {
+        let path = args
+            .get(position + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .cloned()
+            .unwrap_or_else(|| config_path(WORKSPACES_FILE));
+        std::process::exit(validate_config(&path));
+    }
+
+    let settings = load_settings(&config_path(SETTINGS_FILE));
+    set_encryption_enabled(settings.encrypt_workspaces);
+    set_show_all_windows(settings.show_all_windows);
+    set_global_animation(settings.animation);
+    set_animations_enabled(settings.animations_enabled);
+    set_respect_reduced_motion(settings.respect_reduced_motion);
+
+    // `--handoff <file> <workspaces file>` takes over from the instance that relaunched as
+    // administrator, with the window open as it was
+    let handoff = args
+        .iter()
+        .position(|arg| arg == HANDOFF_FLAG)
+        .and_then(|position| {
+            Some((
+                args.get(position + 1)?.clone(),
+                args.get(position + 2)?.clone(),
+            ))
+        });
+    // `--agent` runs hotkeys and the watchers without a GUI; a GUI started while an agent runs
+    // attaches to it instead of handling hotkeys itself
+    let agent = args.iter().any(|arg| arg == AGENT_FLAG);
+    let agent_attached = !agent && send_to_agent(&AgentCommand::Ping);
+    let background = handoff.is_none()
+        && !agent_attached
+        && (args.iter().any(|arg| arg == BACKGROUND_FLAG) || settings.start_in_background);
+
     // Ensure logging is initialized
-    ensure_logging_initialized();
+    ensure_logging_initialized(settings.logging.as_ref());
+    #[cfg(feature = "tracing")]
+    init_tracing();
 
     // Backtrace for Debug
     env::set_var("RUST_BACKTRACE", "1");
 
     info!("Starting Multi Manager application...");
+    info!(
+        "Using configuration directory '{}'.",
+        config_dir().display()
+    );
 
     // Initialize the application states
+    let workspaces = Arc::new(Mutex::new(Vec::new()));
+    let registered_hotkeys = Arc::new(Mutex::new(HashMap::new()));
+    let usage = Arc::new(Mutex::new(load_usage_stats(&config_path(USAGE_FILE))));
+    let archive = read_workspaces(&config_path(ARCHIVE_FILE)).unwrap_or_default();
+
+    // Put managed windows back and save unsaved edits if anything panics
+    install_panic_hook(workspaces.clone(), &config_path(EMERGENCY_FILE));
+
     let app = gui::App {
         app_title_name: "Multi Manager".to_string(),
-        workspaces: Arc::new(Mutex::new(Vec::new())),
+        workspaces: workspaces.clone(),
         last_hotkey_info: Arc::new(Mutex::new(None)), // Initialize to None
-        hotkey_promise: Arc::new(Mutex::new(None)),   // Initialize the promise
+        recent_triggers: Arc::new(Mutex::new(VecDeque::new())),
+        hotkey_promise: Arc::new(Mutex::new(None)), // Initialize the promise
         initial_validation_done: Arc::new(Mutex::new(false)), // Initialize flag to false
-        registered_hotkeys: Arc::new(Mutex::new(HashMap::new())), // Initialize the map
+        registered_hotkeys: registered_hotkeys.clone(), // Initialize the map
+        validation_promise: Arc::new(Mutex::new(None)),
+        window_cache: Arc::new(Mutex::new(validation::WindowStateCache::new(
+            validation::WINDOW_CACHE_TTL,
+        ))),
+        window_ops: window_ops::WindowOps::spawn(
+            Win32Backend,
+            workspaces,
+            registered_hotkeys,
+            usage.clone(),
+        ),
+        errors: Arc::new(Mutex::new(Vec::new())),
+        settings: Arc::new(Mutex::new(settings)),
+        update_check: Arc::new(Mutex::new(None)),
+        workspaces_path: Arc::new(Mutex::new(
+            handoff
+                .as_ref()
+                .map(|(_, path)| path.clone())
+                .unwrap_or_else(|| config_path(WORKSPACES_FILE)),
+        )),
+        workspaces_modified: Arc::new(Mutex::new(None)),
+        file_conflict: Arc::new(Mutex::new(false)),
+        history: Arc::new(Mutex::new(None)),
+        log_lines: Arc::new(Mutex::new(Vec::new())),
+        window_browser: Arc::new(Mutex::new(window_browser::WindowBrowser::new())),
+        window_icons: Arc::new(Mutex::new(HashMap::new())),
+        thumbnails: Arc::new(Mutex::new(thumbnails::Thumbnails::new())),
+        archive: Arc::new(Mutex::new(archive)),
+        countdown_capture: Arc::new(Mutex::new(None)),
+        usage,
+        gesture_hook: Arc::new(Mutex::new(None)),
+        caps_layer: Arc::new(Mutex::new(None)),
+        keyboard_hook: Arc::new(Mutex::new((false, None))),
+        scheduled_moves: Arc::new(Mutex::new(Vec::new())),
+        egui_ctx: Arc::new(Mutex::new(None)),
+        hotkeys_suspended: Arc::new(Mutex::new(false)),
+        gui_requested: Arc::new(Mutex::new(!background)),
+        tray_running: Arc::new(Mutex::new(false)),
+        gui_hwnd: Arc::new(Mutex::new(None)),
+        agent_attached,
+        journal: Arc::new(Mutex::new(Journal::open(
+            &config_path(JOURNAL_FILE),
+            &config_path(UNSAVED_FILE),
+        ))),
+        parse_failure: Arc::new(Mutex::new(None)),
     };
 
-    // Launch GUI and set the taskbar icon after creating the window
-    gui::run_gui(app);
+    // Launch GUI and set the taskbar icon after creating the window; in the background, only
+    // once the show hotkey is pressed
+    if agent {
+        gui::run_agent(app);
+    } else {
+        gui::run_gui(app, background, handoff.map(|(file, _)| file));
+    }
 }
 
-/// Ensures a valid log4rs.yaml file exists and initializes logging.
-fn ensure_logging_initialized() {
+/// Checks a workspaces file and prints the result to the console it was started from.
+///
+/// # Returns
+/// - The process exit code: 0 if the file is valid, 1 if problems were found, 2 if the file
+///   could not be read or parsed.
+fn validate_config(path: &str) -> i32 {
+    // The GUI subsystem has no console of its own; borrow the one of the calling shell
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+
+    match validate_workspaces_file(path) {
+        Ok(problems) if problems.is_empty() => {
+            println!("{}: OK", path);
+            0
+        }
+        Ok(problems) => {
+            println!("{}: {} problem(s)", path, problems.len());
+            for problem in problems {
+                println!("  - {}", problem);
+            }
+            1
+        }
+        Err(e) => {
+            println!("{}", e);
+            2
+        }
+    }
+}
+
+/// Writes the timing of every span closed, such as each toggle and validation run, to
+/// trace.log, so performance with large configurations can be measured.
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    let path = config_path(multi_manager::paths::TRACE_FILE);
+    match File::create(&path) {
+        Ok(file) => tracing_subscriber::fmt()
+            .with_writer(Mutex::new(file))
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_span_events(FmtSpan::CLOSE)
+            .init(),
+        Err(e) => eprintln!("Failed to create {}: {}", path, e),
+    }
+}
+
+/// Initializes logging from the saved logging settings, or from log4rs.yaml if there are none,
+/// creating a default log4rs.yaml if needed.
+fn ensure_logging_initialized(settings: Option<&LoggingSettings>) {
+    let yaml_path = config_path(LOG_CONFIG_FILE);
+
     // Attempt to initialize logging configuration
-    if let Err(err) = log4rs::init_file("log4rs.yaml", Default::default()) {
+    if let Err(err) = init_logging(settings, &yaml_path) {
         eprintln!("Failed to initialize log4rs: {}", err);
 
-        // Create a default log4rs.yaml file
-        let default_config = r#"
+        // Fall back to log4rs.yaml if the saved settings could not be applied
+        if settings.is_some() && init_logging(None, &yaml_path).is_ok() {
+            return;
+        }
+
+        // Create a default log4rs.yaml file. log4rs resolves relative paths against the
+        // working directory, so the log file path is written out in full.
+        let log_path = config_path(&LoggingSettings::default().file_path).replace('\'', "''");
+        let default_config = format!(
+            r#"
 appenders:
   file:
     kind: file
-    path: "multi_manager.log"
+    path: '{}'
     append: false
     encoder:
-      pattern: "{d} - {l} - {m}{n}"
+      pattern: "{{d}} - {{l}} - {{m}}{{n}}"
 
 root:
   level: info
   appenders:
     - file
-"#;
+"#,
+            log_path
+        );
 
-        if let Err(e) = File::create("log4rs.yaml")
-            .and_then(|mut file| file.write_all(default_config.as_bytes()))
+        if let Err(e) =
+            File::create(&yaml_path).and_then(|mut file| file.write_all(default_config.as_bytes()))
         {
             eprintln!("Failed to create default log4rs.yaml: {}", e);
             std::process::exit(1); // Exit if we cannot create the default configuration
         }
 
         // Retry initializing log4rs with the newly created configuration file
-        if let Err(e) = log4rs::init_file("log4rs.yaml", Default::default()) {
+        if let Err(e) = init_logging(None, &yaml_path) {
             eprintln!(
                 "Failed to reinitialize log4rs with default configuration: {}",
                 e
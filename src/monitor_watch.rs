@@ -0,0 +1,121 @@
+use crate::activity::ToggleDirection;
+use crate::validation::PositionKind;
+use crate::workspace::Workspace;
+use std::time::Duration;
+
+/// How often the connected monitors are checked for changes.
+pub const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait after a monitor connects before moving windows back onto it, so Windows
+/// has finished moving them off the monitor list it had before.
+pub const MONITOR_SETTLE: Duration = Duration::from_secs(3);
+
+/// Notices monitors being connected, e.g. when a laptop is docked again.
+///
+/// The first poll only records the monitors connected at startup, so nothing is reported
+/// as newly connected when the application starts.
+#[derive(Debug, Default)]
+pub struct MonitorWatcher {
+    connected: Option<Vec<(i32, i32, i32, i32)>>,
+}
+
+impl MonitorWatcher {
+    /// Creates a watcher that has not seen any monitors yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the connected monitors and returns the ones that were not connected at the
+    /// previous poll.
+    ///
+    /// # Arguments
+    /// - `monitors`: The bounds of the monitors connected now.
+    ///
+    /// # Returns
+    /// - The bounds of the newly connected monitors, or nothing on the first poll.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::monitor_watch::MonitorWatcher;
+    /// let mut watcher = MonitorWatcher::new();
+    /// assert!(watcher.poll(vec![(0, 0, 1920, 1080)]).is_empty());
+    /// let added = watcher.poll(vec![(0, 0, 1920, 1080), (1920, 0, 2560, 1440)]);
+    /// assert_eq!(added, vec![(1920, 0, 2560, 1440)]);
+    /// ```
+    pub fn poll(&mut self, monitors: Vec<(i32, i32, i32, i32)>) -> Vec<(i32, i32, i32, i32)> {
+        let added = match &self.connected {
+            Some(previous) => monitors
+                .iter()
+                .filter(|monitor| !previous.contains(monitor))
+                .copied()
+                .collect(),
+            None => Vec::new(),
+        };
+        self.connected = Some(monitors);
+        added
+    }
+}
+
+/// Finds the workspaces with windows that belong on newly connected monitors, and where
+/// to move them back to.
+///
+/// A workspace last sent to its targets is moved to its targets again; any other workspace
+/// is moved home. It is affected if the centre of any of its windows' positions for that
+/// state, with the monitor profile now active, lies on one of the new monitors. Disabled
+/// workspaces are skipped.
+///
+/// # Arguments
+/// - `workspaces`: The workspace list.
+/// - `added`: The bounds of the newly connected monitors.
+/// - `monitors`: The bounds of every monitor connected now.
+/// - `work_areas`: The work area of every monitor connected now, for grid cell positions.
+///
+/// # Returns
+/// - The index of each affected workspace and the position to move its windows to.
+///
+/// # Example
+/// ```ignore
+/// for (index, position) in workspaces_to_restore(&workspaces, &added, &monitors, &work_areas) {
+///     let workspace = workspaces[index].runtime_id;
+///     window_ops.send(WindowOp::MoveAll { workspace, position });
+/// }
+/// ```
+pub fn workspaces_to_restore(
+    workspaces: &[Workspace],
+    added: &[(i32, i32, i32, i32)],
+    monitors: &[(i32, i32, i32, i32)],
+    work_areas: &[(i32, i32, i32, i32)],
+) -> Vec<(usize, PositionKind)> {
+    let on_added = |(x, y, width, height): (i32, i32, i32, i32)| {
+        let (cx, cy) = (x + width / 2, y + height / 2);
+        added
+            .iter()
+            .any(|&(mx, my, mw, mh)| cx >= mx && cx < mx + mw && cy >= my && cy < my + mh)
+    };
+
+    workspaces
+        .iter()
+        .enumerate()
+        .filter(|(_, workspace)| !workspace.disabled)
+        .filter_map(|(index, workspace)| {
+            let last_move = workspace
+                .activity
+                .newest_first()
+                .map(|entry| entry.direction)
+                .find(|direction| *direction != ToggleDirection::BringToFront);
+            let position = match last_move {
+                Some(ToggleDirection::ToTarget) => PositionKind::Target,
+                _ => PositionKind::Home,
+            };
+
+            let profile = workspace.active_profile(monitors);
+            let affected = workspace.windows.iter().any(|window| {
+                on_added(match position {
+                    PositionKind::Home => window.home_at(profile, work_areas),
+                    PositionKind::Target => window.target_at(profile, work_areas),
+                })
+            });
+            affected.then_some((index, position))
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use log::{info, warn};
+use once_cell::sync::OnceCell;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The command-line flag that keeps all files next to the executable.
+pub const PORTABLE_FLAG: &str = "--portable";
+
+/// A file next to the executable whose presence has the same effect as `--portable`.
+pub const PORTABLE_MARKER: &str = "portable.txt";
+
+/// The folder created under `%APPDATA%` for installed (non-portable) use.
+const APP_DIR_NAME: &str = "multi-manager";
+
+/// The saved workspaces.
+pub const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// The application settings.
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// The log4rs configuration used when no logging settings have been saved.
+pub const LOG_CONFIG_FILE: &str = "log4rs.yaml";
+
+/// The append-only history of configuration changes.
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// How often each workspace has been activated.
+pub const USAGE_FILE: &str = "usage.json";
+
+/// Deleted workspaces, kept until restored or purged.
+pub const ARCHIVE_FILE: &str = "archive.json";
+
+/// Where span timings are written when built with the `tracing` feature.
+pub const TRACE_FILE: &str = "trace.log";
+
+/// Descriptions of the workspace edits not saved yet, deleted when they are.
+pub const JOURNAL_FILE: &str = "workspaces.journal.jsonl";
+
+/// The workspaces after the last unsaved edit, kept with the journal.
+pub const UNSAVED_FILE: &str = "workspaces.unsaved.json";
+
+/// Where the panic hook writes unsaved workspaces.
+pub const EMERGENCY_FILE: &str = "workspaces.emergency.json";
+
+/// Files copied from the working directory the first time the `%APPDATA%` folder is used,
+/// so workspaces saved by earlier versions are not lost.
+const MIGRATED_FILES: [&str; 2] = [WORKSPACES_FILE, SETTINGS_FILE];
+
+/// The directory every configuration, workspace, and log file lives in, once resolved.
+static CONFIG_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Resolves and creates the configuration directory. Call once at startup, before any file
+/// is read.
+///
+/// In portable mode (the `--portable` flag, or a `portable.txt` marker next to the
+/// executable) files are kept next to the executable. Otherwise they are kept in
+/// `%APPDATA%\multi-manager`, which is created if needed; on first use, files left in the
+/// working directory by earlier versions are copied there.
+///
+/// # Arguments
+/// - `portable_flag`: Whether `--portable` was passed on the command line.
+///
+/// # Returns
+/// - The resolved configuration directory.
+///
+/// # Example
+/// ```ignore
+/// let portable = std::env::args().any(|arg| arg == PORTABLE_FLAG);
+/// init_config_dir(portable);
+/// ```
+pub fn init_config_dir(portable_flag: bool) -> &'static Path {
+    CONFIG_DIR.get_or_init(|| {
+        let exe_dir = env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+
+        let portable_dir = exe_dir
+            .as_ref()
+            .filter(|dir| portable_flag || dir.join(PORTABLE_MARKER).exists());
+        if let Some(dir) = portable_dir {
+            info!("Portable mode: using '{}'.", dir.display());
+            return dir.clone();
+        }
+
+        match env::var_os("APPDATA") {
+            Some(appdata) => {
+                let dir = PathBuf::from(appdata).join(APP_DIR_NAME);
+                if let Err(e) = fs::create_dir_all(&dir) {
+                    warn!("Failed to create '{}': {}", dir.display(), e);
+                }
+                migrate_legacy_files(&dir);
+                dir
+            }
+            None => {
+                warn!("%APPDATA% is not set; keeping files next to the executable.");
+                exe_dir.unwrap_or_else(|| PathBuf::from("."))
+            }
+        }
+    })
+}
+
+/// Copies files written to the working directory by earlier versions into `dir`, unless
+/// `dir` already has its own copy.
+fn migrate_legacy_files(dir: &Path) {
+    let Ok(cwd) = env::current_dir() else {
+        return;
+    };
+    if cwd == dir {
+        return;
+    }
+
+    for name in MIGRATED_FILES {
+        let (from, to) = (cwd.join(name), dir.join(name));
+        if from.is_file() && !to.exists() {
+            match fs::copy(&from, &to) {
+                Ok(_) => info!("Migrated '{}' to '{}'.", from.display(), to.display()),
+                Err(e) => warn!("Failed to migrate '{}': {}", from.display(), e),
+            }
+        }
+    }
+}
+
+/// The configuration directory, or the working directory if `init_config_dir` was never
+/// called (as in tools embedding the library).
+pub fn config_dir() -> &'static Path {
+    CONFIG_DIR.get().map_or(Path::new("."), PathBuf::as_path)
+}
+
+/// Resolves a file name against the configuration directory.
+///
+/// # Arguments
+/// - `name`: A file name, or a path. Absolute paths are returned unchanged.
+///
+/// # Returns
+/// - The full path of the file.
+///
+/// # Example
+/// ```ignore
+/// let workspaces = load_workspaces(&config_path(WORKSPACES_FILE), &registered_hotkeys);
+/// ```
+pub fn config_path(name: &str) -> String {
+    config_dir().join(name).to_string_lossy().into_owned()
+}
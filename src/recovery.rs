@@ -0,0 +1,95 @@
+use crate::backend::{ShowCommand, Win32Backend, WindowBackend};
+use crate::engine::show_hidden_windows;
+use crate::workspace::{save_workspaces, Workspace};
+use log::{error, info, warn};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+
+/// Set once recovery has started, so a panic during recovery doesn't recurse.
+static RECOVERING: AtomicBool = AtomicBool::new(false);
+
+/// Installs a panic hook that puts managed windows back before the process dies.
+///
+/// When any thread panics, the hook shows hidden windows, restores windows suppressed by
+/// exclusive mode, moves every managed window back to its home position, and writes the
+/// in-memory workspaces to `emergency_path` so unsaved edits are not lost. The real
+/// workspaces file is left untouched. The previously installed hook runs afterwards.
+///
+/// # Arguments
+/// - `workspaces`: The shared workspace list to recover.
+/// - `emergency_path`: Where to write the emergency save.
+///
+/// # Example
+/// ```ignore
+/// install_panic_hook(workspaces.clone(), "workspaces.emergency.json");
+/// ```
+pub fn install_panic_hook(workspaces: Arc<Mutex<Vec<Workspace>>>, emergency_path: &str) {
+    let emergency_path = emergency_path.to_string();
+    let previous_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(move |panic_info| {
+        error!("Panic: {}", panic_info);
+
+        if !RECOVERING.swap(true, Ordering::SeqCst) {
+            recover(&workspaces, &emergency_path);
+        }
+
+        previous_hook(panic_info);
+    }));
+}
+
+/// Restores windows and writes the emergency save.
+///
+/// The workspace lock is only tried, never waited on: the panicking thread may be the one
+/// holding it, in which case recovery is skipped rather than deadlocking.
+fn recover(workspaces: &Mutex<Vec<Workspace>>, emergency_path: &str) {
+    let mut workspaces = match workspaces.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            error!("Workspaces are locked by the panicking thread; skipping window recovery.");
+            return;
+        }
+    };
+
+    show_hidden_windows(&Win32Backend, &mut workspaces);
+    restore_windows_home(&Win32Backend, &workspaces);
+
+    match save_workspaces(&workspaces, emergency_path) {
+        Ok(()) => info!("Emergency save written to '{}'.", emergency_path),
+        Err(e) => error!("Emergency save failed: {}", e),
+    }
+}
+
+/// Moves every live window of every workspace back to its home position.
+///
+/// Minimized windows are restored first, and the home position of the monitor profile
+/// matching the connected displays is used if there is one. Failures are logged and the
+/// remaining windows are still attempted.
+///
+/// # Arguments
+/// - `backend`: The window system to operate on.
+/// - `workspaces`: The workspaces whose windows should be sent home.
+pub fn restore_windows_home(backend: &impl WindowBackend, workspaces: &[Workspace]) {
+    let monitors = backend.monitors();
+    let work_areas = backend.work_areas();
+    for workspace in workspaces {
+        let profile = workspace.active_profile(&monitors);
+        for window in &workspace.windows {
+            if !backend.exists(window.id) {
+                continue;
+            }
+
+            if backend.is_minimized(window.id) {
+                backend.show(window.id, ShowCommand::RestoreNoActivate);
+            }
+
+            let home = window.home_at(profile, &work_areas);
+            match backend.set_rect(window.id, home, &window.position_flags) {
+                Ok(()) => info!("Recovered window '{}' to its home position.", window.title),
+                Err(e) => warn!("Failed to recover window '{}': {}", window.title, e),
+            }
+        }
+    }
+}
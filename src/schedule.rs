@@ -0,0 +1,208 @@
+//! Time-of-day layout switching: a workspace can be sent to its targets at one time of day and
+//! home at another, e.g. "Work" from 9:00 to 17:00, with a grace prompt before windows move.
+
+use crate::validation::PositionKind;
+use crate::workspace::Workspace;
+use chrono::NaiveTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// How often the scheduler compares the time of day with the workspaces' schedules.
+pub const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The longest grace period offered before a scheduled move, in seconds.
+pub const MAX_SCHEDULE_GRACE_SECS: u32 = 300;
+
+/// When a workspace is activated automatically, as local times of day such as "09:00".
+///
+/// # Fields
+/// - `start`: When the workspace is sent to its targets.
+/// - `end`: When the workspace is sent home, or empty to leave it where it is.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Schedule {
+    pub start: String,
+    pub end: String,
+}
+
+impl Schedule {
+    /// Checks whether the start time, and the end time if there is one, can be read.
+    pub fn is_valid(&self) -> bool {
+        parse_time_of_day(&self.start).is_some()
+            && (self.end.trim().is_empty() || parse_time_of_day(&self.end).is_some())
+    }
+}
+
+/// Reads a time of day written as hours and minutes on a 24-hour clock.
+///
+/// # Arguments
+/// - `text`: The time, e.g. "9:00" or "17:30".
+///
+/// # Returns
+/// - The time, or `None` if it is not a valid time of day.
+///
+/// # Example
+/// ```
+/// # use chrono::NaiveTime;
+/// # use multi_manager::schedule::parse_time_of_day;
+/// assert_eq!(parse_time_of_day("9:00"), NaiveTime::from_hms_opt(9, 0, 0));
+/// assert!(parse_time_of_day("25:00").is_none());
+/// ```
+pub fn parse_time_of_day(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+/// A scheduled move waiting for its grace period to end.
+///
+/// # Fields
+/// - `workspace`: The index of the workspace to move.
+/// - `workspace_name`: The workspace's name, to notice the list changing in the meantime.
+/// - `position`: Where the windows are moved.
+/// - `deadline`: When the windows are moved unless the move is skipped.
+#[derive(Clone, Debug)]
+pub struct PendingMove {
+    pub workspace: usize,
+    pub workspace_name: String,
+    pub position: PositionKind,
+    pub deadline: Instant,
+}
+
+/// Notices the clock passing the start and end times of the workspaces' schedules.
+///
+/// The first poll only records the time, so schedules whose times passed before the
+/// application started are not acted on.
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    last_checked: Option<NaiveTime>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler that has not checked the time yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Finds the schedule times passed since the previous poll. Disabled workspaces and
+    /// schedules that can't be read are skipped.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The workspace list.
+    /// - `now`: The local time of day.
+    ///
+    /// # Returns
+    /// - The index of each workspace due to move and where to, in list order.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.poll(&workspaces, NaiveTime::from_hms_opt(8, 59, 59).unwrap());
+    /// let due = scheduler.poll(&workspaces, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+    /// assert_eq!(due, vec![(0, PositionKind::Target)]);
+    /// ```
+    pub fn poll(&mut self, workspaces: &[Workspace], now: NaiveTime) -> Vec<(usize, PositionKind)> {
+        let Some(previous) = self.last_checked.replace(now) else {
+            return Vec::new();
+        };
+        // A time is passed if it lies in (previous, now], wrapping around midnight
+        let passed = |time: NaiveTime| {
+            if previous <= now {
+                time > previous && time <= now
+            } else {
+                time > previous || time <= now
+            }
+        };
+
+        let mut due = Vec::new();
+        for (index, workspace) in workspaces.iter().enumerate() {
+            let Some(schedule) = workspace.schedule.as_ref().filter(|_| !workspace.disabled) else {
+                continue;
+            };
+            if parse_time_of_day(&schedule.start).is_some_and(passed) {
+                due.push((index, PositionKind::Target));
+            }
+            if parse_time_of_day(&schedule.end).is_some_and(passed) {
+                due.push((index, PositionKind::Home));
+            }
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(name: &str, start: &str, end: &str) -> Workspace {
+        let mut workspace: Workspace = serde_json::from_str(&format!(
+            r#"{{"name":"{}","hotkey":null,"windows":[],"disabled":false,"valid":true}}"#,
+            name
+        ))
+        .expect("Failed to build test workspace");
+        workspace.schedule = Some(Schedule {
+            start: start.to_string(),
+            end: end.to_string(),
+        });
+        workspace
+    }
+
+    fn at(hour: u32, minute: u32, second: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn first_poll_only_records_the_time() {
+        let workspaces = [workspace("Work", "9:00", "17:00")];
+        let mut scheduler = Scheduler::new();
+        assert!(scheduler.poll(&workspaces, at(9, 0, 0)).is_empty());
+        assert!(scheduler.poll(&workspaces, at(9, 0, 1)).is_empty());
+    }
+
+    #[test]
+    fn start_and_end_are_reported_once_when_passed() {
+        let workspaces = [workspace("Work", "9:00", "17:00")];
+        let mut scheduler = Scheduler::new();
+        scheduler.poll(&workspaces, at(8, 59, 59));
+        assert_eq!(
+            scheduler.poll(&workspaces, at(9, 0, 0)),
+            vec![(0, PositionKind::Target)]
+        );
+        assert!(scheduler.poll(&workspaces, at(9, 0, 1)).is_empty());
+        scheduler.poll(&workspaces, at(16, 59, 59));
+        assert_eq!(
+            scheduler.poll(&workspaces, at(17, 0, 1)),
+            vec![(0, PositionKind::Home)]
+        );
+    }
+
+    #[test]
+    fn times_passed_across_midnight_are_reported() {
+        let workspaces = [
+            workspace("Night", "23:59", "0:00"),
+            workspace("Early", "0:01", ""),
+            workspace("Day", "12:00", ""),
+        ];
+        let mut scheduler = Scheduler::new();
+        scheduler.poll(&workspaces, at(23, 58, 30));
+        assert_eq!(
+            scheduler.poll(&workspaces, at(0, 1, 0)),
+            vec![
+                (0, PositionKind::Target),
+                (0, PositionKind::Home),
+                (1, PositionKind::Target),
+            ]
+        );
+        assert!(scheduler.poll(&workspaces, at(0, 2, 0)).is_empty());
+    }
+
+    #[test]
+    fn disabled_and_unreadable_schedules_are_skipped() {
+        let mut disabled = workspace("Disabled", "9:00", "");
+        disabled.disabled = true;
+        let workspaces = [disabled, workspace("Broken", "nine", "25:00")];
+        let mut scheduler = Scheduler::new();
+        scheduler.poll(&workspaces, at(8, 0, 0));
+        assert!(scheduler.poll(&workspaces, at(10, 0, 0)).is_empty());
+        assert!(!workspaces[1].schedule.as_ref().unwrap().is_valid());
+    }
+}
@@ -0,0 +1,94 @@
+//! Holds window positioning back while the display geometry is changing, e.g. when a Remote
+//! Desktop session connects at a smaller resolution or a console session takes over again.
+//!
+//! Windows reports the new monitors in steps while a session switches, so positions computed
+//! midway land on monitors that are about to disappear or be resized. Before each window
+//! operation the monitors are compared with the ones seen last; if they differ, the operation
+//! waits until they have stayed the same for `GEOMETRY_SETTLE`.
+
+use crate::backend::WindowBackend;
+use log::{info, warn};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_REMOTESESSION};
+
+/// How long the monitors must stay the same before windows are positioned on them.
+pub const GEOMETRY_SETTLE: Duration = Duration::from_secs(2);
+
+/// The longest an operation waits for the monitors to settle, after which it goes ahead with
+/// the monitors connected at that point.
+pub const GEOMETRY_MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// How often the monitors are checked while waiting for them to settle.
+const GEOMETRY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The bounds of each connected monitor.
+type Geometry = Vec<(i32, i32, i32, i32)>;
+
+/// The monitors seen by the last window operation.
+static LAST_GEOMETRY: Mutex<Option<Geometry>> = Mutex::new(None);
+
+/// Checks whether Multi Manager runs in a Remote Desktop session.
+pub fn is_remote_session() -> bool {
+    unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+}
+
+/// Waits until the monitors have stopped changing, if they changed since the previous call.
+///
+/// The first call only records the monitors, so nothing is held back at startup.
+///
+/// # Arguments
+/// - `backend`: The window system whose monitors are checked.
+///
+/// # Returns
+/// - `true` if the monitors had changed and the call waited for them.
+///
+/// # Example
+/// ```ignore
+/// wait_for_stable_geometry(&backend);
+/// let work_areas = backend.work_areas();
+/// ```
+pub fn wait_for_stable_geometry(backend: &impl WindowBackend) -> bool {
+    let mut monitors = backend.monitors();
+    {
+        let mut last = LAST_GEOMETRY.lock().unwrap();
+        match last.as_ref() {
+            Some(previous) if *previous != monitors => {}
+            _ => {
+                *last = Some(monitors);
+                return false;
+            }
+        }
+    }
+
+    info!(
+        "Display geometry changed{}; waiting for it to settle before positioning windows.",
+        if is_remote_session() {
+            " in a Remote Desktop session"
+        } else {
+            ""
+        }
+    );
+    let started = Instant::now();
+    let mut stable_since = Instant::now();
+    while stable_since.elapsed() < GEOMETRY_SETTLE {
+        if started.elapsed() >= GEOMETRY_MAX_WAIT {
+            warn!(
+                "Display geometry did not settle within {:?}; positioning windows anyway.",
+                GEOMETRY_MAX_WAIT
+            );
+            break;
+        }
+        thread::sleep(GEOMETRY_POLL_INTERVAL);
+        let current = backend.monitors();
+        if current != monitors {
+            monitors = current;
+            stable_since = Instant::now();
+        }
+    }
+
+    info!("Display geometry settled on {} monitor(s).", monitors.len());
+    *LAST_GEOMETRY.lock().unwrap() = Some(monitors);
+    true
+}
@@ -0,0 +1,235 @@
+use crate::animation::Animation;
+use crate::error::MultiManagerError;
+use crate::hotkey::migrate_hotkey;
+use crate::logging::LoggingSettings;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// How many workspace files are kept in the File → Open Recent menu.
+pub const RECENT_FILES_LIMIT: usize = 8;
+
+/// The smallest and largest UI scale offered in the Settings panel.
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+/// The longest countdown offered by "Capture in N s", in seconds.
+pub const MAX_CAPTURE_DELAY_SECS: u32 = 30;
+
+/// The longest idle time offered before workspaces are restored, in minutes.
+pub const MAX_IDLE_RESTORE_MINUTES: u32 = 24 * 60;
+
+/// How many seconds the grace prompt waits before a scheduled move, by default.
+pub const DEFAULT_SCHEDULE_GRACE_SECS: u32 = 30;
+
+/// How the workspace list is shown in the main window.
+///
+/// # Variants
+/// - `Compact`: One line per workspace with its name, hotkey, state, and a toggle button.
+/// - `Detailed`: Each workspace expands into the full editor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListView {
+    Compact,
+    #[default]
+    Detailed,
+}
+
+/// Application-wide settings edited from the Settings panel.
+///
+/// # Fields
+/// - `logging`: Logging options. `None` until first applied from the Settings panel, in which
+///   case `log4rs.yaml` is used instead.
+/// - `check_for_updates`: Whether to ask GitHub for a newer release at startup. Off by default.
+/// - `recent_files`: Workspace files recently opened or saved, most recent first.
+/// - `encrypt_workspaces`: Whether workspace files are encrypted when saved, with the key held
+///   in Windows Credential Manager. Off by default.
+/// - `ui_scale`: The zoom factor applied to the whole GUI, where 1.0 is the default size.
+/// - `list_view`: Whether the workspace list is compact or detailed.
+/// - `collapsed_workspaces`: Names of the workspaces collapsed in the detailed list, so they
+///   stay collapsed across sessions.
+/// - `show_thumbnails`: Whether the workspace editor shows a live preview of each window.
+/// - `capture_delay_secs`: How many seconds "Capture in N s" counts down before capturing.
+/// - `restore_on_monitor_reconnect`: Whether workspaces with windows on a monitor are moved
+///   back onto it when it is connected again, e.g. after docking. On by default.
+/// - `cycle_hotkey`: An optional hotkey that toggles the focused window between its home and
+///   target, in whichever workspace holds it.
+/// - `mouse_gestures`: Whether gestures drawn on the desktop with the right button held
+///   toggle workspaces. Off by default, as it installs a low-level mouse hook.
+/// - `caps_lock_layer`: Whether CapsLock works as a modifier for hotkeys such as "Caps+H".
+///   Off by default, as it installs a low-level keyboard hook.
+/// - `show_all_windows`: Whether shell, tool, and zero-size windows are listed in the window
+///   browser and snap presets and matched by capture rules. Off by default.
+/// - `idle_restore`: Whether every workspace is sent home after `idle_restore_minutes` without
+///   keyboard or mouse input, e.g. on shared machines. Off by default.
+/// - `idle_restore_minutes`: How many minutes without input trigger the idle restore.
+/// - `animation`: How window moves are animated in workspaces that don't override it.
+///   Instant by default.
+/// - `animations_enabled`: Whether window moves are animated at all. Off makes every move
+///   instant, whatever the workspaces' overrides.
+/// - `respect_reduced_motion`: Whether animations are off while Windows is set to reduce
+///   motion. On by default.
+/// - `schedule_grace_secs`: How many seconds a prompt offers to skip a scheduled move before
+///   the windows are moved. With 0, scheduled moves happen without a prompt.
+/// - `start_in_background`: Whether the application starts with only its hotkeys and
+///   background features running, without a window, as `--background` does. Off by default.
+/// - `show_gui_hotkey`: An optional hotkey that opens the window, creating it if the
+///   application started in the background, or brings it to the front.
+/// - `hide_on_close`: Whether closing the window hides it to the tray, keeping hotkeys running,
+///   instead of exiting. **File → Exit** and the tray icon's **Exit** still exit. Off by default.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub logging: Option<LoggingSettings>,
+    pub check_for_updates: bool,
+    pub recent_files: Vec<String>,
+    pub encrypt_workspaces: bool,
+    pub ui_scale: f32,
+    pub list_view: ListView,
+    pub collapsed_workspaces: Vec<String>,
+    pub show_thumbnails: bool,
+    pub capture_delay_secs: u32,
+    pub restore_on_monitor_reconnect: bool,
+    pub show_all_windows: bool,
+    pub cycle_hotkey: Option<String>,
+    pub mouse_gestures: bool,
+    pub caps_lock_layer: bool,
+    pub idle_restore: bool,
+    pub idle_restore_minutes: u32,
+    pub schedule_grace_secs: u32,
+    pub animation: Animation,
+    pub animations_enabled: bool,
+    pub respect_reduced_motion: bool,
+    pub start_in_background: bool,
+    pub show_gui_hotkey: Option<String>,
+    pub hide_on_close: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            logging: None,
+            check_for_updates: false,
+            recent_files: Vec::new(),
+            encrypt_workspaces: false,
+            ui_scale: 1.0,
+            list_view: ListView::default(),
+            collapsed_workspaces: Vec::new(),
+            show_thumbnails: true,
+            capture_delay_secs: 3,
+            restore_on_monitor_reconnect: true,
+            show_all_windows: false,
+            cycle_hotkey: None,
+            mouse_gestures: false,
+            caps_lock_layer: false,
+            idle_restore: false,
+            idle_restore_minutes: 30,
+            schedule_grace_secs: DEFAULT_SCHEDULE_GRACE_SECS,
+            animation: Animation::INSTANT,
+            animations_enabled: true,
+            respect_reduced_motion: true,
+            start_in_background: false,
+            show_gui_hotkey: None,
+            hide_on_close: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Moves a workspace file to the top of the recent files, keeping at most
+    /// `RECENT_FILES_LIMIT` entries.
+    ///
+    /// Paths are compared case-insensitively, as Windows file names are.
+    ///
+    /// # Arguments
+    /// - `path`: The workspace file that was opened or saved.
+    ///
+    /// # Example
+    /// ```ignore
+    /// settings.add_recent_file("C:\\layouts\\docked.json");
+    /// assert_eq!(settings.recent_files[0], "C:\\layouts\\docked.json");
+    /// ```
+    pub fn add_recent_file(&mut self, path: &str) {
+        self.recent_files
+            .retain(|recent| !recent.eq_ignore_ascii_case(path));
+        self.recent_files.insert(0, path.to_string());
+        self.recent_files.truncate(RECENT_FILES_LIMIT);
+    }
+
+    /// Records whether a workspace is collapsed in the detailed list.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the workspace.
+    /// - `collapsed`: Whether it is now collapsed.
+    pub fn set_collapsed(&mut self, name: &str, collapsed: bool) {
+        self.collapsed_workspaces
+            .retain(|collapsed| collapsed != name);
+        if collapsed {
+            self.collapsed_workspaces.push(name.to_string());
+        }
+    }
+
+    /// Returns whether a workspace was last left collapsed.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the workspace.
+    pub fn is_collapsed(&self, name: &str) -> bool {
+        self.collapsed_workspaces
+            .iter()
+            .any(|collapsed| collapsed == name)
+    }
+
+    /// Returns the UI scale limited to the range offered in the Settings panel, so a
+    /// hand-edited value can't make the window unusable.
+    pub fn clamped_ui_scale(&self) -> f32 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Loads the application settings from a JSON file.
+///
+/// # Arguments
+/// - `file_path`: The path to the settings file.
+///
+/// # Returns
+/// - The saved settings, or the defaults if the file is missing or invalid. Hotkeys are
+///   rewritten in their canonical form.
+pub fn load_settings(file_path: &str) -> Settings {
+    let mut settings = match fs::read_to_string(file_path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(
+                "Invalid settings in '{}': {}. Using defaults.",
+                file_path, e
+            );
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    };
+    migrate_hotkey(&mut settings.cycle_hotkey);
+    migrate_hotkey(&mut settings.show_gui_hotkey);
+    settings
+}
+
+/// Saves the application settings to a JSON file.
+///
+/// # Arguments
+/// - `settings`: The settings to save.
+/// - `file_path`: The path to the settings file.
+///
+/// # Returns
+/// - `Ok(())` if the file was written.
+/// - `Err(SaveSettings)` otherwise.
+pub fn save_settings(settings: &Settings, file_path: &str) -> Result<(), MultiManagerError> {
+    let save_error = |message: String| MultiManagerError::SaveSettings {
+        path: file_path.to_string(),
+        message,
+    };
+
+    let json = serde_json::to_string_pretty(settings).map_err(|e| save_error(e.to_string()))?;
+    fs::write(file_path, json).map_err(|e| save_error(e.to_string()))?;
+    info!("Settings saved to '{}'.", file_path);
+    Ok(())
+}
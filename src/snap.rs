@@ -0,0 +1,94 @@
+use crate::backend::Win32Backend;
+use crate::layout::GridCell;
+use crate::window_browser::list_windows;
+use crate::window_manager::is_window_snapped;
+use windows::Win32::Foundation::HWND;
+
+/// A position offered as a target preset, taken from Windows Snap.
+///
+/// # Fields
+/// - `label`: What the preset is, e.g. "Left Half" or the title of a snapped window.
+/// - `monitor`: The index of the monitor the preset is on, if it is a snap zone.
+/// - `rect`: The position `(x, y, width, height)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapPreset {
+    pub label: String,
+    pub monitor: Option<usize>,
+    pub rect: (i32, i32, i32, i32),
+}
+
+/// The zones of the Windows 11 snap layouts, as `(label, columns, rows, column, row,
+/// column span, row span)` of a grid over the work area.
+const SNAP_ZONES: [(&str, u32, u32, u32, u32, u32, u32); 12] = [
+    ("Left Half", 2, 1, 0, 0, 1, 1),
+    ("Right Half", 2, 1, 1, 0, 1, 1),
+    ("Left Third", 3, 1, 0, 0, 1, 1),
+    ("Center Third", 3, 1, 1, 0, 1, 1),
+    ("Right Third", 3, 1, 2, 0, 1, 1),
+    ("Left Two Thirds", 3, 1, 0, 0, 2, 1),
+    ("Right Two Thirds", 3, 1, 1, 0, 2, 1),
+    ("Top Left Quarter", 2, 2, 0, 0, 1, 1),
+    ("Top Right Quarter", 2, 2, 1, 0, 1, 1),
+    ("Bottom Left Quarter", 2, 2, 0, 1, 1, 1),
+    ("Bottom Right Quarter", 2, 2, 1, 1, 1, 1),
+    ("Full Screen", 1, 1, 0, 0, 1, 1),
+];
+
+/// Computes the zones Windows 11 snap layouts offer on every monitor.
+///
+/// # Arguments
+/// - `work_areas`: The work area of every connected monitor.
+///
+/// # Returns
+/// - Every zone of every monitor, grouped by monitor.
+///
+/// # Example
+/// ```
+/// # use multi_manager::snap::snap_zone_presets;
+/// let presets = snap_zone_presets(&[(0, 0, 1920, 1040)]);
+/// assert_eq!(presets[0].rect, (0, 0, 960, 1040));
+/// ```
+pub fn snap_zone_presets(work_areas: &[(i32, i32, i32, i32)]) -> Vec<SnapPreset> {
+    (0..work_areas.len())
+        .flat_map(|monitor| {
+            SNAP_ZONES.iter().filter_map(
+                move |&(label, columns, rows, column, row, column_span, row_span)| {
+                    let cell = GridCell {
+                        monitor,
+                        columns,
+                        rows,
+                        column,
+                        row,
+                        column_span,
+                        row_span,
+                    };
+                    Some(SnapPreset {
+                        label: label.to_string(),
+                        monitor: Some(monitor),
+                        rect: cell.rect(work_areas)?,
+                    })
+                },
+            )
+        })
+        .collect()
+}
+
+/// Lists the positions of the windows currently snapped by Windows, so a layout arranged with
+/// native snapping can be stored as targets.
+///
+/// The positions are the windows' own rects, which include their invisible resize borders, so
+/// moving a window there looks exactly like the snapped one.
+///
+/// # Returns
+/// - One preset per snapped window, labelled with its title, topmost first.
+pub fn snapped_window_presets() -> Vec<SnapPreset> {
+    list_windows(&Win32Backend)
+        .into_iter()
+        .filter(|window| is_window_snapped(HWND(window.id as *mut std::ffi::c_void)))
+        .map(|window| SnapPreset {
+            label: window.title,
+            monitor: window.monitor,
+            rect: window.rect,
+        })
+        .collect()
+}
@@ -0,0 +1,162 @@
+use log::warn;
+use std::collections::HashMap;
+use windows::Win32::Foundation::{BOOL, HWND, RECT};
+use windows::Win32::Graphics::Dwm::{
+    DwmQueryThumbnailSourceSize, DwmRegisterThumbnail, DwmUnregisterThumbnail,
+    DwmUpdateThumbnailProperties, DWM_THUMBNAIL_PROPERTIES, DWM_TNP_RECTDESTINATION,
+    DWM_TNP_SOURCECLIENTAREAONLY, DWM_TNP_VISIBLE,
+};
+
+/// A registered DWM thumbnail.
+///
+/// # Fields
+/// - `handle`: The thumbnail ID returned by `DwmRegisterThumbnail`.
+/// - `shown`: Whether the thumbnail was placed during the current frame.
+struct Thumbnail {
+    handle: isize,
+    shown: bool,
+}
+
+/// Live previews of other windows drawn by the Desktop Window Manager into the GUI window.
+///
+/// DWM composites the previews itself, so they stay current at no cost to the GUI, but they
+/// are drawn on top of everything in the GUI window. Thumbnails must therefore be placed
+/// again every frame they are visible; `end_frame` removes the ones that were not.
+pub struct Thumbnails {
+    host: usize,
+    entries: HashMap<usize, Thumbnail>,
+}
+
+impl Thumbnails {
+    /// Creates an empty set of thumbnails with no host window yet.
+    pub fn new() -> Self {
+        Thumbnails {
+            host: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Sets the window the thumbnails are drawn into, dropping any drawn into another one.
+    ///
+    /// # Arguments
+    /// - `host`: The HWND of the GUI window.
+    pub fn set_host(&mut self, host: usize) {
+        if self.host != host {
+            self.clear();
+            self.host = host;
+        }
+    }
+
+    /// Shows a live preview of a window for this frame, registering it on first use.
+    ///
+    /// # Arguments
+    /// - `source`: The HWND of the window to preview.
+    /// - `rect`: Where to draw it `(left, top, right, bottom)`, in physical pixels relative to
+    ///   the host window's client area.
+    ///
+    /// # Returns
+    /// - `true` if the preview is shown, `false` if there is no host or DWM refused the window.
+    pub fn show(&mut self, source: usize, rect: (i32, i32, i32, i32)) -> bool {
+        let Some(handle) = self.handle(source) else {
+            return false;
+        };
+
+        let properties = DWM_THUMBNAIL_PROPERTIES {
+            dwFlags: DWM_TNP_RECTDESTINATION | DWM_TNP_VISIBLE | DWM_TNP_SOURCECLIENTAREAONLY,
+            rcDestination: RECT {
+                left: rect.0,
+                top: rect.1,
+                right: rect.2,
+                bottom: rect.3,
+            },
+            fVisible: BOOL(1),
+            fSourceClientAreaOnly: BOOL(0),
+            ..Default::default()
+        };
+        if let Err(e) = unsafe { DwmUpdateThumbnailProperties(handle, &properties) } {
+            warn!("Failed to place thumbnail of HWND {:#x}: {}", source, e);
+            return false;
+        }
+        if let Some(entry) = self.entries.get_mut(&source) {
+            entry.shown = true;
+        }
+        true
+    }
+
+    /// Returns the size of a window as previewed, to keep its aspect ratio.
+    ///
+    /// # Arguments
+    /// - `source`: The HWND of the window to preview.
+    ///
+    /// # Returns
+    /// - `(width, height)` in pixels, or `None` if the window cannot be previewed.
+    pub fn source_size(&mut self, source: usize) -> Option<(i32, i32)> {
+        let handle = self.handle(source)?;
+        let size = unsafe { DwmQueryThumbnailSourceSize(handle) }.ok()?;
+        (size.cx > 0 && size.cy > 0).then_some((size.cx, size.cy))
+    }
+
+    /// Removes the previews that were not shown this frame, e.g. because their workspace
+    /// was collapsed or scrolled out of view. Call once at the end of every frame.
+    pub fn end_frame(&mut self) {
+        self.entries.retain(|_, entry| {
+            if !entry.shown {
+                unsafe {
+                    let _ = DwmUnregisterThumbnail(entry.handle);
+                }
+            }
+            std::mem::take(&mut entry.shown)
+        });
+    }
+
+    /// Returns the thumbnail of a window, registering it if needed.
+    fn handle(&mut self, source: usize) -> Option<isize> {
+        if self.host == 0 {
+            return None;
+        }
+        if let Some(entry) = self.entries.get(&source) {
+            return Some(entry.handle);
+        }
+
+        let host = HWND(self.host as *mut std::ffi::c_void);
+        let window = HWND(source as *mut std::ffi::c_void);
+        match unsafe { DwmRegisterThumbnail(host, window) } {
+            Ok(handle) => {
+                self.entries.insert(
+                    source,
+                    Thumbnail {
+                        handle,
+                        shown: false,
+                    },
+                );
+                Some(handle)
+            }
+            Err(e) => {
+                warn!("Failed to register thumbnail of HWND {:#x}: {}", source, e);
+                None
+            }
+        }
+    }
+
+    /// Unregisters every thumbnail.
+    fn clear(&mut self) {
+        for entry in self.entries.values() {
+            unsafe {
+                let _ = DwmUnregisterThumbnail(entry.handle);
+            }
+        }
+        self.entries.clear();
+    }
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Thumbnails {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
@@ -0,0 +1,468 @@
+//! The notification area (tray) icon, so the application can be reached while its window is
+//! hidden or was never opened.
+//!
+//! The icon lives on a thread of its own with a hidden window that receives its clicks.
+//! Double-clicking it opens the application; right-clicking it shows a menu that the
+//! application builds each time, so it always reflects the current workspaces and settings.
+
+use log::{info, warn};
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleBitmap, CreateCompatibleDC, CreateSolidBrush, DeleteDC, DeleteObject, FillRect,
+    GetDC, ReleaseDC, SelectObject, HBITMAP,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Shell::{
+    Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyMenu, DestroyWindow,
+    DispatchMessageW, GetCursorPos, GetMessageW, GetSystemMetrics, LoadIconW, PostMessageW,
+    PostThreadMessageW, RegisterClassW, RegisterWindowMessageW, SetForegroundWindow,
+    SetMenuItemInfoW, TrackPopupMenu, HICON, HMENU, IDI_APPLICATION, MENUITEMINFOW, MF_CHECKED,
+    MF_GRAYED, MF_POPUP, MF_SEPARATOR, MF_STRING, MIIM_BITMAP, MSG, SM_CXMENUCHECK, SM_CYMENUCHECK,
+    TPM_NONOTIFY, TPM_RETURNCMD, TPM_RIGHTBUTTON, WINDOW_EX_STYLE, WINDOW_STYLE, WM_APP,
+    WM_CONTEXTMENU, WM_LBUTTONDBLCLK, WM_NULL, WM_QUIT, WM_RBUTTONUP, WNDCLASSW,
+};
+
+/// The message the icon's clicks are sent to the hidden window with.
+const WM_TRAY: u32 = WM_APP + 1;
+
+/// The ID of the application's icon resource, embedded by the build script.
+const APP_ICON_ID: usize = 1;
+
+/// An entry of the tray menu.
+///
+/// # Variants
+/// - `Command`: An item that runs `run` on the tray thread when chosen, with a check mark if
+///   `checked`, greyed out unless `enabled`, and a swatch of `color` before the label if set.
+/// - `Submenu`: An item that opens more items; greyed out if it has none.
+/// - `Separator`: A line between groups of items.
+pub enum TrayMenuItem {
+    Command {
+        label: String,
+        checked: bool,
+        enabled: bool,
+        color: Option<[u8; 3]>,
+        run: Box<dyn FnOnce()>,
+    },
+    Submenu {
+        label: String,
+        items: Vec<TrayMenuItem>,
+    },
+    Separator,
+}
+
+impl TrayMenuItem {
+    /// Creates an enabled, unchecked command.
+    ///
+    /// # Arguments
+    /// - `label`: The text of the item.
+    /// - `run`: What choosing the item does.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let exit = TrayMenuItem::command("Exit", move || app.exit());
+    /// ```
+    pub fn command(label: impl Into<String>, run: impl FnOnce() + 'static) -> TrayMenuItem {
+        TrayMenuItem::Command {
+            label: label.into(),
+            checked: false,
+            enabled: true,
+            color: None,
+            run: Box::new(run),
+        }
+    }
+
+    /// Creates a submenu.
+    ///
+    /// # Arguments
+    /// - `label`: The text of the item.
+    /// - `items`: The items it opens.
+    pub fn submenu(label: impl Into<String>, items: Vec<TrayMenuItem>) -> TrayMenuItem {
+        TrayMenuItem::Submenu {
+            label: label.into(),
+            items,
+        }
+    }
+
+    /// Sets the check mark of a command; other items are returned unchanged.
+    pub fn checked(mut self, value: bool) -> TrayMenuItem {
+        if let TrayMenuItem::Command { checked, .. } = &mut self {
+            *checked = value;
+        }
+        self
+    }
+
+    /// Greys out a command that can't be used now; other items are returned unchanged.
+    pub fn enabled(mut self, value: bool) -> TrayMenuItem {
+        if let TrayMenuItem::Command { enabled, .. } = &mut self {
+            *enabled = value;
+        }
+        self
+    }
+
+    /// Shows a swatch of a color before the label of a command, e.g. a workspace's color; other
+    /// items are returned unchanged.
+    pub fn color(mut self, value: Option<[u8; 3]>) -> TrayMenuItem {
+        if let TrayMenuItem::Command { color, .. } = &mut self {
+            *color = value;
+        }
+        self
+    }
+}
+
+/// What the hidden window's procedure needs, kept on the tray thread as it can't capture
+/// anything.
+///
+/// # Fields
+/// - `icon`: The icon as added to the notification area, to add it again or remove it.
+/// - `taskbar_created`: The message Explorer broadcasts when it restarts, which drops icons.
+/// - `on_open`: Called when the icon is double-clicked.
+/// - `menu`: Builds the menu shown when the icon is right-clicked.
+struct TrayState {
+    icon: NOTIFYICONDATAW,
+    taskbar_created: u32,
+    on_open: Box<dyn Fn()>,
+    menu: Box<dyn Fn() -> Vec<TrayMenuItem>>,
+}
+
+thread_local! {
+    static TRAY_STATE: RefCell<Option<TrayState>> = const { RefCell::new(None) };
+}
+
+/// A tray icon. Dropping it removes the icon.
+pub struct TrayIcon {
+    thread_id: u32,
+}
+
+impl TrayIcon {
+    /// Adds the icon to the notification area, on a thread of its own.
+    ///
+    /// # Arguments
+    /// - `tooltip`: The text shown when hovering over the icon.
+    /// - `on_open`: Called on the tray thread when the icon is double-clicked.
+    /// - `menu`: Called on the tray thread each time the icon is right-clicked, to build the
+    ///   menu shown.
+    ///
+    /// # Returns
+    /// - The icon, or `None` if it could not be added.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let tray = TrayIcon::start("Multi Manager", || println!("Open"), || {
+    ///     vec![TrayMenuItem::command("Exit", || std::process::exit(0))]
+    /// });
+    /// ```
+    pub fn start(
+        tooltip: &str,
+        on_open: impl Fn() + Send + 'static,
+        menu: impl Fn() -> Vec<TrayMenuItem> + Send + 'static,
+    ) -> Option<TrayIcon> {
+        let tooltip = tooltip.to_string();
+        let (started, started_receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("Tray Icon".to_string())
+            .spawn(move || run_tray(&tooltip, Box::new(on_open), Box::new(menu), started))
+            .ok()?;
+        match started_receiver.recv() {
+            Ok(Some(thread_id)) => {
+                info!("Tray icon added.");
+                Some(TrayIcon { thread_id })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        info!("Tray icon removed.");
+    }
+}
+
+/// Creates the hidden window, adds the icon, and runs the message loop until `WM_QUIT` is
+/// posted, then removes the icon again.
+fn run_tray(
+    tooltip: &str,
+    on_open: Box<dyn Fn()>,
+    menu: Box<dyn Fn() -> Vec<TrayMenuItem>>,
+    started: Sender<Option<u32>>,
+) {
+    let instance = unsafe { GetModuleHandleW(None) }.unwrap_or_default();
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(tray_proc),
+        hInstance: instance.into(),
+        lpszClassName: w!("MultiManagerTray"),
+        ..Default::default()
+    };
+    unsafe {
+        RegisterClassW(&class);
+    }
+    let hwnd = match unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            class.lpszClassName,
+            w!("Multi Manager Tray"),
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            instance,
+            None,
+        )
+    } {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            warn!("Failed to create the tray icon's window: {}", e);
+            let _ = started.send(None);
+            return;
+        }
+    };
+
+    let icon = unsafe { LoadIconW(instance, PCWSTR(APP_ICON_ID as *const u16)) }
+        .or_else(|_| unsafe { LoadIconW(None, IDI_APPLICATION) })
+        .unwrap_or(HICON::default());
+    let mut data = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: 1,
+        uFlags: NIF_ICON | NIF_MESSAGE | NIF_TIP,
+        uCallbackMessage: WM_TRAY,
+        hIcon: icon,
+        ..Default::default()
+    };
+    // The tooltip is cut to fit, leaving room for the terminating zero
+    let room = data.szTip.len() - 1;
+    for (slot, unit) in data.szTip.iter_mut().zip(tooltip.encode_utf16().take(room)) {
+        *slot = unit;
+    }
+    if !unsafe { Shell_NotifyIconW(NIM_ADD, &data) }.as_bool() {
+        warn!("Failed to add the tray icon.");
+        unsafe {
+            let _ = DestroyWindow(hwnd);
+        }
+        let _ = started.send(None);
+        return;
+    }
+
+    TRAY_STATE.with(|state| {
+        *state.borrow_mut() = Some(TrayState {
+            icon: data,
+            taskbar_created: unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) },
+            on_open,
+            menu,
+        })
+    });
+    let _ = started.send(Some(unsafe { GetCurrentThreadId() }));
+
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {
+        unsafe {
+            DispatchMessageW(&message);
+        }
+    }
+
+    if let Some(state) = TRAY_STATE.with(|state| state.borrow_mut().take()) {
+        unsafe {
+            let _ = Shell_NotifyIconW(NIM_DELETE, &state.icon);
+        }
+    }
+    unsafe {
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+/// The hidden window's procedure: opens the application on a double-click, shows the menu on
+/// a right-click, and adds the icon again when Explorer restarts.
+unsafe extern "system" fn tray_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if message == WM_TRAY {
+        match lparam.0 as u32 & 0xFFFF {
+            WM_LBUTTONDBLCLK => TRAY_STATE.with(|state| {
+                if let Some(state) = state.borrow().as_ref() {
+                    (state.on_open)();
+                }
+            }),
+            WM_RBUTTONUP | WM_CONTEXTMENU => show_menu(hwnd),
+            _ => {}
+        }
+        return LRESULT(0);
+    }
+    let restarted = TRAY_STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .filter(|state| state.taskbar_created == message)
+            .map(|state| state.icon)
+    });
+    if let Some(icon) = restarted {
+        info!("Explorer restarted; adding the tray icon again.");
+        let _ = Shell_NotifyIconW(NIM_ADD, &icon);
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, message, wparam, lparam)
+}
+
+/// Builds the menu, shows it at the pointer, and runs the command chosen, if any.
+fn show_menu(hwnd: HWND) {
+    let Some(items) = TRAY_STATE.with(|state| state.borrow().as_ref().map(|state| (state.menu)()))
+    else {
+        return;
+    };
+    let Ok(menu) = (unsafe { CreatePopupMenu() }) else {
+        return;
+    };
+    let mut commands = Vec::new();
+    let mut swatches = Vec::new();
+    append_items(menu, items, &mut commands, &mut swatches);
+
+    let mut point = POINT::default();
+    let chosen = unsafe {
+        let _ = GetCursorPos(&mut point);
+        // Without this the menu doesn't close when clicking elsewhere
+        let _ = SetForegroundWindow(hwnd);
+        let chosen = TrackPopupMenu(
+            menu,
+            TPM_RETURNCMD | TPM_NONOTIFY | TPM_RIGHTBUTTON,
+            point.x,
+            point.y,
+            0,
+            hwnd,
+            None,
+        );
+        let _ = PostMessageW(hwnd, WM_NULL, WPARAM(0), LPARAM(0));
+        let _ = DestroyMenu(menu);
+        for swatch in swatches {
+            let _ = DeleteObject(swatch);
+        }
+        chosen.0 as usize
+    };
+    // Command IDs start at 1, as 0 means nothing was chosen
+    if let Some(run) = chosen
+        .checked_sub(1)
+        .and_then(|index| commands.get_mut(index))
+    {
+        if let Some(run) = run.take() {
+            run();
+        }
+    }
+}
+
+/// Adds items to a menu, collecting the commands in the order of their IDs and the color
+/// swatches, which must outlive the menu.
+fn append_items(
+    menu: HMENU,
+    items: Vec<TrayMenuItem>,
+    commands: &mut Vec<Option<Box<dyn FnOnce()>>>,
+    swatches: &mut Vec<HBITMAP>,
+) {
+    for item in items {
+        let result = match item {
+            TrayMenuItem::Command {
+                label,
+                checked,
+                enabled,
+                color,
+                run,
+            } => {
+                commands.push(Some(run));
+                let mut flags = MF_STRING;
+                if checked {
+                    flags |= MF_CHECKED;
+                }
+                if !enabled {
+                    flags |= MF_GRAYED;
+                }
+                let id = commands.len();
+                let result = unsafe { AppendMenuW(menu, flags, id, &HSTRING::from(label)) };
+                if let (Ok(()), Some(swatch)) = (&result, color.and_then(color_swatch)) {
+                    swatches.push(swatch);
+                    let info = MENUITEMINFOW {
+                        cbSize: std::mem::size_of::<MENUITEMINFOW>() as u32,
+                        fMask: MIIM_BITMAP,
+                        hbmpItem: swatch,
+                        ..Default::default()
+                    };
+                    let _ = unsafe { SetMenuItemInfoW(menu, id as u32, false, &info) };
+                }
+                result
+            }
+            TrayMenuItem::Submenu { label, items } => {
+                let mut flags = MF_STRING | MF_POPUP;
+                if items.is_empty() {
+                    flags |= MF_GRAYED;
+                }
+                match unsafe { CreatePopupMenu() } {
+                    Ok(submenu) => {
+                        append_items(submenu, items, commands, swatches);
+                        unsafe {
+                            AppendMenuW(menu, flags, submenu.0 as usize, &HSTRING::from(label))
+                        }
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            TrayMenuItem::Separator => unsafe { AppendMenuW(menu, MF_SEPARATOR, 0, None) },
+        };
+        if let Err(e) = result {
+            warn!("Failed to build the tray menu: {}", e);
+        }
+    }
+}
+
+/// Draws a square of a color, the size of a menu check mark.
+///
+/// # Arguments
+/// - `color`: The red, green, and blue components.
+///
+/// # Returns
+/// - The bitmap, to be deleted once the menu is gone, or `None` if it could not be created.
+fn color_swatch([red, green, blue]: [u8; 3]) -> Option<HBITMAP> {
+    unsafe {
+        let width = GetSystemMetrics(SM_CXMENUCHECK);
+        let height = GetSystemMetrics(SM_CYMENUCHECK);
+        let screen = GetDC(None);
+        let dc = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        ReleaseDC(None, screen);
+        if dc.is_invalid() || bitmap.is_invalid() {
+            let _ = DeleteDC(dc);
+            let _ = DeleteObject(bitmap);
+            return None;
+        }
+        let previous = SelectObject(dc, bitmap);
+        let brush = CreateSolidBrush(COLORREF(
+            red as u32 | (green as u32) << 8 | (blue as u32) << 16,
+        ));
+        FillRect(
+            dc,
+            &RECT {
+                left: 0,
+                top: 0,
+                right: width,
+                bottom: height,
+            },
+            brush,
+        );
+        let _ = DeleteObject(brush);
+        SelectObject(dc, previous);
+        let _ = DeleteDC(dc);
+        Some(bitmap)
+    }
+}
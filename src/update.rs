@@ -0,0 +1,259 @@
+use crate::error::MultiManagerError;
+use log::info;
+use serde::Deserialize;
+use windows::core::{HSTRING, PCWSTR};
+use windows::Win32::Networking::WinHttp::*;
+
+/// The host serving the GitHub releases API.
+const RELEASES_HOST: &str = "api.github.com";
+
+/// The API path of the latest published release.
+const LATEST_RELEASE_PATH: &str = "/repos/multiplex55/multi-manager/releases/latest";
+
+/// The version of the running application.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A published release newer than the running version.
+///
+/// # Fields
+/// - `version`: The release's version, without a leading `v`.
+/// - `url`: The release page to download it from.
+#[derive(Clone, Debug)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+}
+
+/// The outcome of an update check: a newer release, nothing newer, or an error.
+pub type UpdateCheckResult = Result<Option<ReleaseInfo>, MultiManagerError>;
+
+/// The fields of the GitHub release response that are used.
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Asks GitHub for the latest release and compares it with the running version.
+///
+/// This blocks on the network, so it should be run on a background thread.
+///
+/// # Returns
+/// - `Ok(Some(release))` if a newer release is available.
+/// - `Ok(None)` if the running version is current.
+/// - `Err(UpdateCheck)` if the request failed or the response could not be understood.
+///
+/// # Example
+/// ```ignore
+/// if let Ok(Some(release)) = check_for_update() {
+///     println!("Version {} is available at {}", release.version, release.url);
+/// }
+/// ```
+pub fn check_for_update() -> UpdateCheckResult {
+    let body = https_get(RELEASES_HOST, LATEST_RELEASE_PATH)?;
+    let release: GitHubRelease =
+        serde_json::from_str(&body).map_err(|e| MultiManagerError::UpdateCheck {
+            message: format!("unexpected response: {}", e),
+        })?;
+
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    info!(
+        "Latest release is {}, running {}.",
+        version, CURRENT_VERSION
+    );
+    if is_newer(&version, CURRENT_VERSION) {
+        Ok(Some(ReleaseInfo {
+            version,
+            url: release.html_url,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Compares two dotted version numbers, ignoring any pre-release or build suffix.
+///
+/// # Arguments
+/// - `candidate`: The version that may be newer (e.g. "0.2.0").
+/// - `current`: The version to compare against.
+///
+/// # Returns
+/// - `true` if `candidate` is strictly newer than `current`.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        let mut parts: Vec<u64> = version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        // "1.2" and "1.2.0" are the same version
+        while parts.last() == Some(&0) {
+            parts.pop();
+        }
+        parts
+    };
+    parse(candidate) > parse(current)
+}
+
+/// A WinHTTP handle that is closed when dropped.
+struct InternetHandle(*mut core::ffi::c_void);
+
+impl InternetHandle {
+    /// Wraps a handle returned by WinHTTP, turning a null handle into the last error.
+    fn new(handle: *mut core::ffi::c_void) -> Result<Self, MultiManagerError> {
+        if handle.is_null() {
+            Err(update_error(&windows::core::Error::from_win32()))
+        } else {
+            Ok(InternetHandle(handle))
+        }
+    }
+}
+
+impl Drop for InternetHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinHttpCloseHandle(self.0);
+        }
+    }
+}
+
+/// Converts a WinHTTP failure into an update check error.
+fn update_error(error: &windows::core::Error) -> MultiManagerError {
+    MultiManagerError::UpdateCheck {
+        message: error.message(),
+    }
+}
+
+/// Performs an HTTPS GET request using WinHTTP, honoring the system proxy settings.
+///
+/// # Arguments
+/// - `host`: The server to connect to.
+/// - `path`: The path and query of the resource.
+///
+/// # Returns
+/// - The response body if the server answered with status 200.
+/// - `Err(UpdateCheck)` otherwise.
+fn https_get(host: &str, path: &str) -> Result<String, MultiManagerError> {
+    let agent = HSTRING::from(format!("multi-manager/{}", CURRENT_VERSION));
+    let headers: Vec<u16> = "Accept: application/vnd.github+json"
+        .encode_utf16()
+        .collect();
+
+    unsafe {
+        let session = InternetHandle::new(WinHttpOpen(
+            &agent,
+            WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            0,
+        ))?;
+        let connection = InternetHandle::new(WinHttpConnect(
+            session.0,
+            &HSTRING::from(host),
+            INTERNET_DEFAULT_HTTPS_PORT,
+            0,
+        ))?;
+        let request = InternetHandle::new(WinHttpOpenRequest(
+            connection.0,
+            &HSTRING::from("GET"),
+            &HSTRING::from(path),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            std::ptr::null(),
+            WINHTTP_FLAG_SECURE,
+        ))?;
+
+        WinHttpSendRequest(request.0, Some(&headers), None, 0, 0, 0)
+            .map_err(|e| update_error(&e))?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut()).map_err(|e| update_error(&e))?;
+
+        let mut status = 0u32;
+        let mut status_size = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some(&mut status as *mut u32 as *mut core::ffi::c_void),
+            &mut status_size,
+            std::ptr::null_mut(),
+        )
+        .map_err(|e| update_error(&e))?;
+        if status != 200 {
+            return Err(MultiManagerError::UpdateCheck {
+                message: format!("server returned HTTP {}", status),
+            });
+        }
+
+        let mut body = Vec::new();
+        let mut buffer = [0u8; 8192];
+        loop {
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                buffer.as_mut_ptr() as *mut core::ffi::c_void,
+                buffer.len() as u32,
+                &mut read,
+            )
+            .map_err(|e| update_error(&e))?;
+            if read == 0 {
+                break;
+            }
+            body.extend_from_slice(&buffer[..read as usize]);
+        }
+
+        String::from_utf8(body).map_err(|e| MultiManagerError::UpdateCheck {
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_versions_are_detected() {
+        for (candidate, current) in [
+            ("0.2.0", "0.1.9"),
+            ("1.10.0", "1.9.0"),
+            ("1.0.0", "0.99"),
+            ("1.2.1", "1.2"),
+            ("2", "1.99.99"),
+        ] {
+            assert!(is_newer(candidate, current), "{} > {}", candidate, current);
+        }
+    }
+
+    #[test]
+    fn same_or_older_versions_are_not_newer() {
+        for (candidate, current) in [
+            ("0.1.0", "0.1.0"),
+            ("1.2", "1.2.0"),
+            ("1.2.0", "1.2"),
+            ("0.1.9", "0.2.0"),
+            ("1.9.0", "1.10.0"),
+        ] {
+            assert!(
+                !is_newer(candidate, current),
+                "{} <= {}",
+                candidate,
+                current
+            );
+        }
+    }
+
+    #[test]
+    fn suffixes_are_ignored() {
+        assert!(!is_newer("0.2.0-beta.1", "0.2.0"));
+        assert!(!is_newer("0.2.0+build.5", "0.2.0"));
+        assert!(is_newer("0.3.0-rc.1", "0.2.0"));
+    }
+
+    #[test]
+    fn unreadable_versions_are_not_newer() {
+        assert!(!is_newer("", "0.1.0"));
+        assert!(!is_newer("latest", "0.1.0"));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::activity::ToggleDirection;
+use crate::error::MultiManagerError;
+use chrono::Local;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// The format of the last-used timestamp. It sorts chronologically as text.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// How often one workspace has been activated.
+///
+/// # Fields
+/// - `to_target`: Toggles that sent the windows to their target positions.
+/// - `to_home`: Toggles that sent the windows home.
+/// - `bring_to_front`: Activations of a workspace in `BringToFront` mode.
+/// - `last_used`: When the workspace was last activated, in local time.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceUsage {
+    pub to_target: u64,
+    pub to_home: u64,
+    pub bring_to_front: u64,
+    pub last_used: Option<String>,
+}
+
+impl WorkspaceUsage {
+    /// Returns the number of activations in every direction.
+    pub fn total(&self) -> u64 {
+        self.to_target + self.to_home + self.bring_to_front
+    }
+}
+
+/// Activation counts for every workspace, kept in their own file so they survive restarts
+/// without touching the workspace file.
+///
+/// Workspaces are identified by name.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    workspaces: HashMap<String, WorkspaceUsage>,
+}
+
+impl UsageStats {
+    /// Counts one activation of a workspace and marks it as used now.
+    ///
+    /// # Arguments
+    /// - `workspace`: The name of the workspace.
+    /// - `direction`: Which way it was toggled.
+    pub fn record(&mut self, workspace: &str, direction: ToggleDirection) {
+        let usage = self.workspaces.entry(workspace.to_string()).or_default();
+        match direction {
+            ToggleDirection::ToTarget => usage.to_target += 1,
+            ToggleDirection::ToHome => usage.to_home += 1,
+            ToggleDirection::BringToFront => usage.bring_to_front += 1,
+        }
+        usage.last_used = Some(Local::now().format(TIMESTAMP_FORMAT).to_string());
+    }
+
+    /// Returns the counts for a workspace, or zeros if it has never been activated.
+    ///
+    /// # Arguments
+    /// - `workspace`: The name of the workspace.
+    pub fn get(&self, workspace: &str) -> WorkspaceUsage {
+        self.workspaces.get(workspace).cloned().unwrap_or_default()
+    }
+
+    /// Moves a workspace's counts to its new name.
+    ///
+    /// # Arguments
+    /// - `old_name`: The name the counts were recorded under.
+    /// - `new_name`: The workspace's new name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(usage) = self.workspaces.remove(old_name) {
+            self.workspaces.insert(new_name.to_string(), usage);
+        }
+    }
+
+    /// Forgets every recorded activation.
+    pub fn clear(&mut self) {
+        self.workspaces.clear();
+    }
+}
+
+/// Loads the usage statistics from a JSON file.
+///
+/// # Arguments
+/// - `file_path`: The path to the statistics file.
+///
+/// # Returns
+/// - The saved statistics, or empty statistics if the file is missing or invalid.
+pub fn load_usage_stats(file_path: &str) -> UsageStats {
+    match fs::read_to_string(file_path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(
+                "Invalid usage statistics in '{}': {}. Starting over.",
+                file_path, e
+            );
+            UsageStats::default()
+        }),
+        Err(_) => UsageStats::default(),
+    }
+}
+
+/// Saves the usage statistics to a JSON file.
+///
+/// # Arguments
+/// - `stats`: The statistics to save.
+/// - `file_path`: The path to the statistics file.
+///
+/// # Returns
+/// - `Ok(())` if the file was written.
+/// - `Err(Save)` otherwise.
+pub fn save_usage_stats(stats: &UsageStats, file_path: &str) -> Result<(), MultiManagerError> {
+    let save_error = |message: String| MultiManagerError::Save {
+        path: file_path.to_string(),
+        message,
+    };
+
+    let json = serde_json::to_string_pretty(stats).map_err(|e| save_error(e.to_string()))?;
+    fs::write(file_path, json).map_err(|e| save_error(e.to_string()))
+}
@@ -1,8 +1,14 @@
+use crate::error::MultiManagerError;
 use std::ptr;
-use windows::core::PCWSTR;
+use std::time::Duration;
+use windows::core::{PCWSTR, PWSTR};
 use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Controls::Dialogs::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
+/// The longest path, in UTF-16 units, the file dialogs can return.
+const FILE_DIALOG_MAX_PATH: usize = 1024;
+
 /// Displays a message box with the specified message and title.
 ///
 /// This function is used to show informational messages to the user.
@@ -12,7 +18,7 @@ use windows::Win32::UI::WindowsAndMessaging::*;
 /// - `title`: The title of the message box.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// show_message_box("Operation successful!", "Info");
 /// ```
 ///
@@ -54,7 +60,7 @@ pub fn show_message_box(message: &str, title: &str) {
 /// - `false` if the user selects "No".
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if show_confirmation_box("Are you sure?", "Confirm Action") {
 ///     println!("User confirmed!");
 /// } else {
@@ -88,3 +94,168 @@ pub fn show_confirmation_box(message: &str, title: &str) -> bool {
         result == windows::Win32::UI::WindowsAndMessaging::MESSAGEBOX_RESULT(6) // IDYES is defined as 6
     }
 }
+
+/// Displays the native "Open" dialog for choosing a workspace file.
+///
+/// # Arguments
+/// - `title`: The title of the dialog.
+/// - `initial_path`: The file initially selected; the dialog opens in its folder.
+///
+/// # Returns
+/// - `Some(path)` of an existing file if the user chose one.
+/// - `None` if the dialog was canceled.
+///
+/// # Example
+/// ```ignore
+/// if let Some(path) = show_open_file_dialog("Open Workspaces", "workspaces.json") {
+///     println!("Opening {}", path);
+/// }
+/// ```
+///
+/// # Platform-Specific Notes
+/// - This function uses the Windows API, so it is only supported on Windows.
+pub fn show_open_file_dialog(title: &str, initial_path: &str) -> Option<String> {
+    show_file_dialog(
+        title,
+        initial_path,
+        OFN_FILEMUSTEXIST | OFN_PATHMUSTEXIST,
+        false,
+    )
+}
+
+/// Displays the native "Save As" dialog for choosing where to save a workspace file.
+///
+/// The user is asked before an existing file is overwritten, and `.json` is appended if no
+/// extension is typed.
+///
+/// # Arguments
+/// - `title`: The title of the dialog.
+/// - `initial_path`: The file initially selected; the dialog opens in its folder.
+///
+/// # Returns
+/// - `Some(path)` if the user chose a file.
+/// - `None` if the dialog was canceled.
+///
+/// # Platform-Specific Notes
+/// - This function uses the Windows API, so it is only supported on Windows.
+pub fn show_save_file_dialog(title: &str, initial_path: &str) -> Option<String> {
+    show_file_dialog(
+        title,
+        initial_path,
+        OFN_OVERWRITEPROMPT | OFN_PATHMUSTEXIST,
+        true,
+    )
+}
+
+/// Shows a common file dialog filtered to JSON files and returns the chosen path.
+fn show_file_dialog(
+    title: &str,
+    initial_path: &str,
+    flags: OPEN_FILENAME_FLAGS,
+    save: bool,
+) -> Option<String> {
+    let filter: Vec<u16> = "Workspace files (*.json)\0*.json\0All files (*.*)\0*.*\0\0"
+        .encode_utf16()
+        .collect();
+    let title: Vec<u16> = title.encode_utf16().chain(Some(0)).collect();
+    let default_extension: Vec<u16> = "json".encode_utf16().chain(Some(0)).collect();
+
+    // The buffer holds the initially selected file on the way in and the choice on the way out
+    let mut file = [0u16; FILE_DIALOG_MAX_PATH];
+    for (slot, unit) in file
+        .iter_mut()
+        .zip(initial_path.encode_utf16().take(FILE_DIALOG_MAX_PATH - 1))
+    {
+        *slot = unit;
+    }
+
+    let mut dialog = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        lpstrFilter: PCWSTR(filter.as_ptr()),
+        lpstrFile: PWSTR(file.as_mut_ptr()),
+        nMaxFile: file.len() as u32,
+        lpstrTitle: PCWSTR(title.as_ptr()),
+        lpstrDefExt: PCWSTR(default_extension.as_ptr()),
+        // Keep the working directory unchanged so relative paths keep their meaning
+        Flags: flags | OFN_EXPLORER | OFN_NOCHANGEDIR,
+        ..Default::default()
+    };
+
+    let accepted = unsafe {
+        if save {
+            GetSaveFileNameW(&mut dialog)
+        } else {
+            GetOpenFileNameW(&mut dialog)
+        }
+    };
+    if !accepted.as_bool() {
+        return None;
+    }
+
+    let len = file
+        .iter()
+        .position(|&unit| unit == 0)
+        .unwrap_or(file.len());
+    Some(String::from_utf16_lossy(&file[..len]))
+}
+
+/// Formats how long ago something happened, in the largest whole unit.
+///
+/// # Arguments
+/// - `elapsed`: The time since it happened.
+///
+/// # Returns
+/// - A short description such as "just now", "42s ago", "5m ago", "3h ago", or "2d ago".
+///
+/// # Example
+/// ```
+/// # use multi_manager::utils::format_ago;
+/// # use std::time::Duration;
+/// assert_eq!(format_ago(Duration::from_secs(300)), "5m ago");
+/// ```
+pub fn format_ago(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    match seconds {
+        0..=4 => "just now".to_string(),
+        5..=59 => format!("{}s ago", seconds),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// Places text on the system clipboard.
+///
+/// # Arguments
+/// - `text`: The text to copy.
+///
+/// # Returns
+/// - `Ok(())` if the clipboard now holds `text`.
+/// - `Err(Clipboard)` if the clipboard could not be opened.
+pub fn set_clipboard_text(text: &str) -> Result<(), MultiManagerError> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| MultiManagerError::Clipboard {
+            message: e.to_string(),
+        })
+}
+
+/// Reads text from the system clipboard.
+///
+/// # Returns
+/// - The clipboard's text.
+/// - `Err(Clipboard)` if the clipboard could not be opened or holds no text.
+///
+/// # Example
+/// ```ignore
+/// if let Ok(text) = get_clipboard_text() {
+///     println!("Clipboard: {}", text);
+/// }
+/// ```
+pub fn get_clipboard_text() -> Result<String, MultiManagerError> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| MultiManagerError::Clipboard {
+            message: e.to_string(),
+        })
+}
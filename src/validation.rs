@@ -0,0 +1,411 @@
+use crate::hotkey::canonical_hotkey;
+use crate::window_manager::{get_window_position, get_window_title, is_rect_on_screen};
+use crate::workspace::{is_valid_key_combo, Window, Workspace};
+use log::info;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+
+/// Identifies which of a window's two stored positions an issue refers to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PositionKind {
+    Home,
+    Target,
+}
+
+/// How many pixels a window may be off a stored position and still count as being there.
+const POSITION_TOLERANCE: i32 = 2;
+
+/// Returns which stored position a window is currently at.
+///
+/// # Arguments
+/// - `rect`: The window's current position `(x, y, width, height)`.
+/// - `home`: The stored home position.
+/// - `target`: The stored target position.
+///
+/// # Returns
+/// - `Some(Home)` or `Some(Target)` if every edge is within a couple of pixels of that
+///   position, checking home first. `None` if the window is elsewhere.
+pub fn matching_position(
+    rect: (i32, i32, i32, i32),
+    home: (i32, i32, i32, i32),
+    target: (i32, i32, i32, i32),
+) -> Option<PositionKind> {
+    let matches = |stored: (i32, i32, i32, i32)| {
+        (rect.0 - stored.0).abs() <= POSITION_TOLERANCE
+            && (rect.1 - stored.1).abs() <= POSITION_TOLERANCE
+            && (rect.2 - stored.2).abs() <= POSITION_TOLERANCE
+            && (rect.3 - stored.3).abs() <= POSITION_TOLERANCE
+    };
+
+    if matches(home) {
+        Some(PositionKind::Home)
+    } else if matches(target) {
+        Some(PositionKind::Target)
+    } else {
+        None
+    }
+}
+
+/// Identifies a workspace's window in a validation issue by its handle and the identity it
+/// was captured with, so the issue still finds it after other windows are added or removed.
+///
+/// # Fields
+/// - `id`: The window's HWND when validation ran.
+/// - `exe`: The executable the window was captured from.
+/// - `class_name`: The window class it was captured with.
+/// - `capture_title`: Its title when it was captured.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WindowKey {
+    pub id: usize,
+    pub exe: String,
+    pub class_name: String,
+    pub capture_title: String,
+}
+
+impl WindowKey {
+    /// Returns the key of a workspace's window.
+    pub fn of(window: &Window) -> Self {
+        WindowKey {
+            id: window.id,
+            exe: window.exe.clone(),
+            class_name: window.class_name.clone(),
+            capture_title: window.capture_title.clone(),
+        }
+    }
+
+    /// Checks whether a workspace's window is the one this key identifies.
+    pub fn matches(&self, window: &Window) -> bool {
+        *self == WindowKey::of(window)
+    }
+}
+
+/// A single problem found while validating the workspaces.
+///
+/// Issues name their workspace and identify their window by `WindowKey` rather than by
+/// position, since the list may change while the report is open. Use `locate` to find
+/// what an issue refers to now, and whether it still applies.
+#[derive(Clone, Debug)]
+pub enum ValidationIssue {
+    /// A window's HWND no longer refers to a live window.
+    InvalidWindow {
+        workspace: String,
+        window: WindowKey,
+        title: String,
+    },
+    /// A workspace's hotkey string is not a valid key combination.
+    InvalidHotkey { workspace: String, hotkey: String },
+    /// A workspace's hotkey is valid but is not currently registered.
+    UnregisteredHotkey { workspace: String, hotkey: String },
+    /// A stored position does not overlap any connected monitor.
+    OffScreenRect {
+        workspace: String,
+        window: WindowKey,
+        title: String,
+        kind: PositionKind,
+        rect: (i32, i32, i32, i32),
+    },
+}
+
+/// Where in the current workspace list a validation issue applies.
+///
+/// # Fields
+/// - `workspace`: The index of the issue's workspace.
+/// - `window`: The index of the issue's window in that workspace, for window issues.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IssueLocation {
+    pub workspace: usize,
+    pub window: Option<usize>,
+}
+
+impl ValidationIssue {
+    /// Returns a human-readable description of the issue for the report dialog.
+    pub fn describe(&self) -> String {
+        match self {
+            ValidationIssue::InvalidWindow {
+                workspace, title, ..
+            } => format!("{}: window '{}' no longer exists", workspace, title),
+            ValidationIssue::InvalidHotkey { workspace, hotkey } => {
+                format!("{}: hotkey '{}' is invalid", workspace, hotkey)
+            }
+            ValidationIssue::UnregisteredHotkey { workspace, hotkey } => {
+                format!("{}: hotkey '{}' is not registered", workspace, hotkey)
+            }
+            ValidationIssue::OffScreenRect {
+                workspace,
+                title,
+                kind,
+                rect,
+                ..
+            } => format!(
+                "{}: {:?} position of '{}' {:?} is off-screen",
+                workspace, kind, title, rect
+            ),
+        }
+    }
+
+    /// Finds what the issue refers to in the current workspace list, checking that the
+    /// workspace and window still hold the values the issue was raised for.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The current workspace list.
+    ///
+    /// # Returns
+    /// - Where the issue applies, or `None` if its workspace or window is gone or has
+    ///   changed since validation, so the issue no longer applies.
+    pub fn locate(&self, workspaces: &[Workspace]) -> Option<IssueLocation> {
+        let find = |name: &str| workspaces.iter().position(|w| w.name == name);
+        let find_window = |name: &str, key: &WindowKey, still: &dyn Fn(&Window) -> bool| {
+            let workspace = find(name)?;
+            let window = workspaces[workspace]
+                .windows
+                .iter()
+                .position(|w| key.matches(w))?;
+            still(&workspaces[workspace].windows[window]).then_some(IssueLocation {
+                workspace,
+                window: Some(window),
+            })
+        };
+
+        match self {
+            ValidationIssue::InvalidHotkey { workspace, hotkey }
+            | ValidationIssue::UnregisteredHotkey { workspace, hotkey } => {
+                let workspace = find(workspace)?;
+                (workspaces[workspace].hotkey.as_ref() == Some(hotkey)).then_some(IssueLocation {
+                    workspace,
+                    window: None,
+                })
+            }
+            ValidationIssue::InvalidWindow {
+                workspace, window, ..
+            } => find_window(workspace, window, &|_| true),
+            ValidationIssue::OffScreenRect {
+                workspace,
+                window,
+                kind,
+                rect,
+                ..
+            } => find_window(workspace, window, &|w| match kind {
+                PositionKind::Home => w.home == *rect,
+                PositionKind::Target => w.target == *rect,
+            }),
+        }
+    }
+}
+
+/// The results of a validate-all pass.
+///
+/// # Fields
+/// - `issues`: Every problem found, in workspace order.
+/// - `workspaces_checked`: The number of workspaces inspected.
+/// - `windows_checked`: The number of windows inspected.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+    pub workspaces_checked: usize,
+    pub windows_checked: usize,
+}
+
+impl ValidationReport {
+    /// Drops every issue that no longer applies to the current workspace list, such as
+    /// issues about a window that has been removed or a hotkey that has been changed.
+    ///
+    /// # Arguments
+    /// - `workspaces`: The current workspace list.
+    pub fn forget_stale(&mut self, workspaces: &[Workspace]) {
+        self.issues
+            .retain(|issue| issue.locate(workspaces).is_some());
+    }
+}
+
+/// Validates every workspace, its hotkey, and each window's HWND and stored positions.
+///
+/// This only reads from its inputs, so it can run on a background thread against a
+/// snapshot of the workspaces.
+///
+/// # Arguments
+/// - `workspaces`: The workspaces to validate.
+/// - `registered_hotkeys`: The hotkeys currently registered with the system, or `None` to skip
+///   the registration check when another process, such as the background agent, holds them.
+///
+/// # Returns
+/// - A `ValidationReport` listing every issue found.
+///
+/// # Example
+/// ```ignore
+/// let report = validate_all(&workspaces, Some(&registered_hotkeys));
+/// println!("{} issues found", report.issues.len());
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(workspaces = workspaces.len())))]
+pub fn validate_all(
+    workspaces: &[Workspace],
+    registered_hotkeys: Option<&HashMap<String, usize>>,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for workspace in workspaces {
+        report.workspaces_checked += 1;
+
+        if let Some(hotkey) = &workspace.hotkey {
+            if !is_valid_key_combo(hotkey) {
+                report.issues.push(ValidationIssue::InvalidHotkey {
+                    workspace: workspace.name.clone(),
+                    hotkey: hotkey.clone(),
+                });
+            } else if !workspace.disabled
+                && registered_hotkeys.is_some_and(|registered| {
+                    !canonical_hotkey(hotkey).is_some_and(|h| registered.contains_key(&h))
+                })
+            {
+                report.issues.push(ValidationIssue::UnregisteredHotkey {
+                    workspace: workspace.name.clone(),
+                    hotkey: hotkey.clone(),
+                });
+            }
+        }
+
+        for window in &workspace.windows {
+            report.windows_checked += 1;
+
+            let hwnd = HWND(window.id as *mut std::ffi::c_void);
+            if unsafe { !IsWindow(hwnd).as_bool() } {
+                report.issues.push(ValidationIssue::InvalidWindow {
+                    workspace: workspace.name.clone(),
+                    window: WindowKey::of(window),
+                    title: window.title.clone(),
+                });
+            }
+
+            for (kind, rect) in [
+                (PositionKind::Home, window.home),
+                (PositionKind::Target, window.target),
+            ] {
+                if !is_rect_on_screen(rect) {
+                    report.issues.push(ValidationIssue::OffScreenRect {
+                        workspace: workspace.name.clone(),
+                        window: WindowKey::of(window),
+                        title: window.title.clone(),
+                        kind,
+                        rect,
+                    });
+                }
+            }
+        }
+    }
+
+    info!(
+        "Validated {} workspaces and {} windows: {} issues found.",
+        report.workspaces_checked,
+        report.windows_checked,
+        report.issues.len()
+    );
+    report
+}
+
+/// How long a cached window state is trusted before the window is queried again.
+pub const WINDOW_CACHE_TTL: Duration = Duration::from_millis(500);
+
+/// The live state of a window as last queried from the system.
+///
+/// # Fields
+/// - `exists`: Whether `IsWindow` reported the handle as valid.
+/// - `title`: The window's title at the time of the check.
+/// - `rect`: The window's position `(x, y, width, height)` at the time of the check, or `None`
+///   if it does not exist or could not be read.
+/// - `checked_at`: When the window was last queried.
+#[derive(Clone, Debug)]
+pub struct CachedWindowState {
+    pub exists: bool,
+    pub title: String,
+    pub rect: Option<(i32, i32, i32, i32)>,
+    pub checked_at: Instant,
+}
+
+/// Caches per-window validity, title, and position so the GUI doesn't call into Win32 for every
+/// window on every frame.
+///
+/// Entries older than the TTL are refreshed on access; entries for handles that are no
+/// longer looked up are pruned periodically.
+pub struct WindowStateCache {
+    entries: HashMap<usize, CachedWindowState>,
+    ttl: Duration,
+    last_pruned: Instant,
+}
+
+impl WindowStateCache {
+    /// Creates an empty cache whose entries expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        WindowStateCache {
+            entries: HashMap::new(),
+            ttl,
+            last_pruned: Instant::now(),
+        }
+    }
+
+    /// Returns the cached state of a window, querying the system if the entry is missing
+    /// or older than the TTL.
+    ///
+    /// # Arguments
+    /// - `id`: The window's HWND as stored in the workspace.
+    pub fn get(&mut self, id: usize) -> &CachedWindowState {
+        let now = Instant::now();
+        if now.duration_since(self.last_pruned) > self.ttl * 20 {
+            let ttl = self.ttl;
+            self.entries
+                .retain(|_, state| now.duration_since(state.checked_at) <= ttl * 20);
+            self.last_pruned = now;
+        }
+
+        let ttl = self.ttl;
+        let stale = self
+            .entries
+            .get(&id)
+            .is_none_or(|state| now.duration_since(state.checked_at) > ttl);
+        if stale {
+            let hwnd = HWND(id as *mut std::ffi::c_void);
+            let exists = unsafe { IsWindow(hwnd).as_bool() };
+            let (title, rect) = if exists {
+                (get_window_title(hwnd), get_window_position(hwnd).ok())
+            } else {
+                (String::new(), None)
+            };
+            self.entries.insert(
+                id,
+                CachedWindowState {
+                    exists,
+                    title,
+                    rect,
+                    checked_at: now,
+                },
+            );
+        }
+        &self.entries[&id]
+    }
+
+    /// Returns whether a window currently exists, using the cached state when fresh.
+    pub fn exists(&mut self, id: usize) -> bool {
+        self.get(id).exists
+    }
+}
+
+/// Checks whether a workspace is usable: it needs a valid hotkey and at least one live window.
+///
+/// # Arguments
+/// - `workspace`: The workspace to check.
+/// - `cache`: The window state cache used to avoid repeated `IsWindow` calls.
+///
+/// # Returns
+/// - `true` if the workspace has a valid hotkey and any of its windows exists.
+pub fn validate_workspace(workspace: &Workspace, cache: &mut WindowStateCache) -> bool {
+    let hotkey_valid = workspace
+        .hotkey
+        .as_ref()
+        .is_some_and(|hotkey| is_valid_key_combo(hotkey));
+
+    hotkey_valid
+        && workspace
+            .windows
+            .iter()
+            .any(|window| cache.exists(window.id))
+}
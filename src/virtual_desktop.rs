@@ -0,0 +1,317 @@
+//! Windows virtual desktops, through the parts Windows makes available to other programs.
+//!
+//! Windows has no public API to list, create, or switch virtual desktops, so the desktops and
+//! their names are read from the registry Explorer keeps them in, and switching and creating
+//! press the same shortcuts a user would (Win+Ctrl+Left/Right and Win+Ctrl+D). Windows are
+//! moved between desktops with `IVirtualDesktopManager` where Windows allows it, which is only
+//! for this program's own windows; other windows are hidden and shown again, which puts them
+//! on the current desktop.
+
+use log::{info, warn};
+use std::thread;
+use std::time::Duration;
+use windows::core::{w, GUID, HSTRING, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::System::Registry::{
+    RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_BINARY, RRF_RT_REG_SZ,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    VIRTUAL_KEY, VK_CONTROL, VK_D, VK_LEFT, VK_LWIN, VK_RIGHT,
+};
+use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_HIDE, SW_SHOWNA};
+
+/// The registry key Explorer keeps the virtual desktops under.
+const DESKTOPS_KEY: PCWSTR =
+    w!(r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops");
+
+/// How long to wait after each desktop switch for Explorer to finish it.
+const SWITCH_DELAY: Duration = Duration::from_millis(150);
+
+/// Lists the virtual desktops in Task View order.
+///
+/// # Returns
+/// - The ID and name of each desktop. Desktops without a name are called "Desktop N", as
+///   Task View shows them. Empty if the desktops cannot be read.
+pub fn virtual_desktops() -> Vec<(GUID, String)> {
+    let Some(ids) = read_binary(DESKTOPS_KEY, w!("VirtualDesktopIDs")) else {
+        return Vec::new();
+    };
+    ids.chunks_exact(16)
+        .enumerate()
+        .map(|(index, bytes)| {
+            let id = guid_from_bytes(bytes);
+            let key = HSTRING::from(format!(
+                r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops\Desktops\{{{:?}}}",
+                id
+            ));
+            let name = read_string(PCWSTR(key.as_ptr()), w!("Name"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Desktop {}", index + 1));
+            (id, name)
+        })
+        .collect()
+}
+
+/// Returns the index of the current virtual desktop in Task View order, if it can be read.
+pub fn current_virtual_desktop() -> Option<usize> {
+    let current = guid_from_bytes(&read_binary(DESKTOPS_KEY, w!("CurrentVirtualDesktop"))?);
+    virtual_desktops().iter().position(|(id, _)| *id == current)
+}
+
+/// Switches to a virtual desktop by pressing Win+Ctrl+Left or Right as many times as needed.
+///
+/// # Arguments
+/// - `index`: The desktop to switch to, in Task View order.
+///
+/// # Returns
+/// - `true` if the current desktop is now `index`.
+pub fn switch_virtual_desktop(index: usize) -> bool {
+    let Some(current) = current_virtual_desktop() else {
+        warn!("Cannot tell which virtual desktop is current; not switching.");
+        return false;
+    };
+    let key = if index > current { VK_RIGHT } else { VK_LEFT };
+    for _ in 0..current.abs_diff(index) {
+        press_win_ctrl(key);
+        thread::sleep(SWITCH_DELAY);
+    }
+    info!(
+        "Switched from virtual desktop {} to {}.",
+        current + 1,
+        index + 1
+    );
+    current_virtual_desktop() == Some(index)
+}
+
+/// Creates a virtual desktop with Win+Ctrl+D, which also switches to it, and names it.
+///
+/// The name is written where Task View keeps it, so it is found again by `virtual_desktops`;
+/// Task View itself may only show it after Explorer restarts.
+///
+/// # Arguments
+/// - `name`: The name to give the desktop.
+///
+/// # Returns
+/// - The index of the new desktop, or `None` if it could not be created.
+pub fn create_virtual_desktop(name: &str) -> Option<usize> {
+    let before = virtual_desktops().len();
+    press_win_ctrl(VK_D);
+    thread::sleep(SWITCH_DELAY);
+
+    let desktops = virtual_desktops();
+    let index = current_virtual_desktop().filter(|_| desktops.len() > before)?;
+    let key = HSTRING::from(format!(
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops\Desktops\{{{:?}}}",
+        desktops[index].0
+    ));
+    let value: Vec<u16> = name.encode_utf16().chain(Some(0)).collect();
+    let result = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            w!("Name"),
+            REG_SZ.0,
+            Some(value.as_ptr() as *const _),
+            (value.len() * 2) as u32,
+        )
+    };
+    if result.is_err() {
+        warn!(
+            "Created a virtual desktop but could not name it '{}'.",
+            name
+        );
+    }
+    info!(
+        "Created virtual desktop '{}' as desktop {}.",
+        name,
+        index + 1
+    );
+    Some(index)
+}
+
+/// Puts a window on the current virtual desktop if it is on another one.
+///
+/// # Arguments
+/// - `hwnd`: The window to move.
+///
+/// # Returns
+/// - `true` if the window is on the current desktop afterwards, or its desktop can't be told.
+pub fn move_to_current_virtual_desktop(hwnd: HWND) -> bool {
+    let Some(manager) = desktop_manager() else {
+        return true;
+    };
+
+    let on_current = || {
+        unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd) }
+            .map(|on| on.as_bool())
+            .unwrap_or(true)
+    };
+    if on_current() {
+        return true;
+    }
+
+    // Windows only lets a program move its own windows; showing a hidden window puts it on
+    // the current desktop, which works for any window
+    let current = read_binary(DESKTOPS_KEY, w!("CurrentVirtualDesktop"));
+    let moved = current.is_some_and(|current| unsafe {
+        manager
+            .MoveWindowToDesktop(hwnd, &guid_from_bytes(&current))
+            .is_ok()
+    });
+    if !moved {
+        unsafe {
+            let _ = ShowWindow(hwnd, SW_HIDE);
+            let _ = ShowWindow(hwnd, SW_SHOWNA);
+        }
+    }
+    on_current()
+}
+
+/// Checks whether a window is on the current virtual desktop.
+///
+/// # Arguments
+/// - `hwnd`: The window to check.
+///
+/// # Returns
+/// - `true` if the window is on the current desktop, is pinned to all desktops, or its
+///   desktop can't be told.
+pub fn is_on_current_virtual_desktop(hwnd: HWND) -> bool {
+    desktop_manager().is_none_or(|manager| {
+        unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd) }
+            .map(|on| on.as_bool())
+            .unwrap_or(true)
+    })
+}
+
+/// Finds the virtual desktop a window is on.
+///
+/// # Arguments
+/// - `hwnd`: The window to look up.
+///
+/// # Returns
+/// - The index of its desktop in Task View order, or `None` if it can't be told.
+pub fn window_virtual_desktop(hwnd: HWND) -> Option<usize> {
+    let id = unsafe { desktop_manager()?.GetWindowDesktopId(hwnd) }.ok()?;
+    virtual_desktops()
+        .iter()
+        .position(|(desktop, _)| *desktop == id)
+}
+
+/// Creates the shell's virtual desktop manager, logging why if it is not available.
+fn desktop_manager() -> Option<IVirtualDesktopManager> {
+    let manager = unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        CoCreateInstance::<_, IVirtualDesktopManager>(&VirtualDesktopManager, None, CLSCTX_ALL)
+    };
+    manager
+        .inspect_err(|e| warn!("Virtual desktops are not available: {}", e))
+        .ok()
+}
+
+/// Presses and releases Win+Ctrl+`key`.
+fn press_win_ctrl(key: VIRTUAL_KEY) {
+    let input = |key: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: key,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let inputs = [
+        input(VK_LWIN, KEYBD_EVENT_FLAGS(0)),
+        input(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        input(key, KEYBD_EVENT_FLAGS(0)),
+        input(key, KEYEVENTF_KEYUP),
+        input(VK_CONTROL, KEYEVENTF_KEYUP),
+        input(VK_LWIN, KEYEVENTF_KEYUP),
+    ];
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
+/// Reads a binary value under HKEY_CURRENT_USER.
+fn read_binary(key: PCWSTR, value: PCWSTR) -> Option<Vec<u8>> {
+    let mut size = 0u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key,
+            value,
+            RRF_RT_REG_BINARY,
+            None,
+            None,
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+        let mut data = vec![0u8; size as usize];
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key,
+            value,
+            RRF_RT_REG_BINARY,
+            None,
+            Some(data.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+        data.truncate(size as usize);
+        Some(data)
+    }
+}
+
+/// Reads a string value under HKEY_CURRENT_USER.
+fn read_string(key: PCWSTR, value: PCWSTR) -> Option<String> {
+    let mut size = 0u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key,
+            value,
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+        let mut data = vec![0u16; (size as usize).div_ceil(2)];
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key,
+            value,
+            RRF_RT_REG_SZ,
+            None,
+            Some(data.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+        let length = data.iter().position(|&c| c == 0).unwrap_or(data.len());
+        Some(String::from_utf16_lossy(&data[..length]))
+    }
+}
+
+/// Converts the 16 bytes of a GUID as stored in the registry.
+fn guid_from_bytes(bytes: &[u8]) -> GUID {
+    let mut raw = [0u8; 16];
+    raw.copy_from_slice(&bytes[..16]);
+    GUID::from_values(
+        u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]),
+        u16::from_le_bytes([raw[4], raw[5]]),
+        u16::from_le_bytes([raw[6], raw[7]]),
+        [
+            raw[8], raw[9], raw[10], raw[11], raw[12], raw[13], raw[14], raw[15],
+        ],
+    )
+}
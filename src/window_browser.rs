@@ -0,0 +1,199 @@
+use crate::backend::{Win32Backend, WindowBackend};
+use crate::workspace::Window;
+use poll_promise::Promise;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// How often the window browser lists the desktop's windows again while it is open.
+pub const WINDOW_BROWSER_REFRESH: Duration = Duration::from_secs(2);
+
+/// The window classes of the desktop and taskbars, which are never worth capturing.
+pub const SHELL_WINDOW_CLASSES: [&str; 4] = [
+    "Progman",
+    "WorkerW",
+    "Shell_TrayWnd",
+    "Shell_SecondaryTrayWnd",
+];
+
+/// Whether shell, tool, and zero-size windows are listed along with the others.
+static SHOW_ALL_WINDOWS: AtomicBool = AtomicBool::new(false);
+
+/// Turns listing of shell, tool, and zero-size windows on or off, for the window browser,
+/// snap presets, and capture rules.
+///
+/// # Arguments
+/// - `enabled`: Whether every visible, titled window is listed.
+pub fn set_show_all_windows(enabled: bool) {
+    SHOW_ALL_WINDOWS.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether shell, tool, and zero-size windows are listed.
+pub fn show_all_windows() -> bool {
+    SHOW_ALL_WINDOWS.load(Ordering::SeqCst)
+}
+
+/// Checks whether a window is one a user would want to capture: visible, titled, and, unless
+/// `show_all_windows` is on, not a tool window, a zero-size window, or part of the shell.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `id`: The window's handle.
+///
+/// # Returns
+/// - `true` if the window should be listed.
+pub fn is_capturable_window(backend: &impl WindowBackend, id: usize) -> bool {
+    if !backend.is_visible(id) || backend.title(id).trim().is_empty() {
+        return false;
+    }
+    if show_all_windows() {
+        return true;
+    }
+
+    let zero_size = !backend.is_minimized(id)
+        && backend
+            .rect(id)
+            .is_none_or(|(_, _, width, height)| width <= 0 || height <= 0);
+    !zero_size && !is_shell_window(backend, id)
+}
+
+/// Checks whether a window is part of the shell, such as the desktop or a taskbar, or a tool
+/// window, none of which are application windows.
+///
+/// # Arguments
+/// - `backend`: The window system to query.
+/// - `id`: The window's handle.
+pub fn is_shell_window(backend: &impl WindowBackend, id: usize) -> bool {
+    backend.is_tool_window(id) || SHELL_WINDOW_CLASSES.contains(&backend.class_name(id).as_str())
+}
+
+/// A top-level window as listed by the window browser.
+///
+/// # Fields
+/// - `id`: The window's HWND.
+/// - `title`: The window's current title.
+/// - `exe`: The file name of the executable that owns the window.
+/// - `class_name`: The window's class name.
+/// - `rect`: The window's position `(x, y, width, height)`.
+/// - `monitor`: The index of the monitor holding the window's center, or `None` if it is
+///   off-screen or minimized.
+#[derive(Clone, Debug)]
+pub struct WindowSummary {
+    pub id: usize,
+    pub title: String,
+    pub exe: String,
+    pub class_name: String,
+    pub rect: (i32, i32, i32, i32),
+    pub monitor: Option<usize>,
+}
+
+impl WindowSummary {
+    /// Returns a workspace entry for this window, with home and target at its current position.
+    pub fn to_window(&self) -> Window {
+        Window {
+            id: self.id,
+            title: self.title.clone(),
+            capture_title: self.title.clone(),
+            exe: self.exe.clone(),
+            class_name: self.class_name.clone(),
+            home: self.rect,
+            target: self.rect,
+            ..Default::default()
+        }
+    }
+}
+
+/// Lists the top-level windows that can be captured; see `is_capturable_window`.
+///
+/// # Arguments
+/// - `backend`: The window system to list.
+///
+/// # Returns
+/// - The windows in z-order, topmost first.
+pub fn list_windows(backend: &impl WindowBackend) -> Vec<WindowSummary> {
+    let monitors = backend.monitors();
+
+    backend
+        .top_level_windows()
+        .into_iter()
+        .filter(|&id| is_capturable_window(backend, id))
+        .map(|id| {
+            let title = backend.title(id);
+            let rect = backend.rect(id).unwrap_or_default();
+            let (center_x, center_y) = (rect.0 + rect.2 / 2, rect.1 + rect.3 / 2);
+            let monitor = if backend.is_minimized(id) {
+                None
+            } else {
+                monitors.iter().position(|&(x, y, width, height)| {
+                    center_x >= x && center_x < x + width && center_y >= y && center_y < y + height
+                })
+            };
+
+            WindowSummary {
+                id,
+                title,
+                exe: backend.exe(id),
+                class_name: backend.class_name(id),
+                rect,
+                monitor,
+            }
+        })
+        .collect()
+}
+
+/// The window list shown by the window browser, refreshed on a background thread so
+/// querying every window never stalls the GUI.
+pub struct WindowBrowser {
+    windows: Vec<WindowSummary>,
+    refreshed_at: Option<Instant>,
+    pending: Option<Promise<Vec<WindowSummary>>>,
+}
+
+impl WindowBrowser {
+    /// Creates an empty browser; the first call to `poll` starts listing windows.
+    pub fn new() -> Self {
+        WindowBrowser {
+            windows: Vec::new(),
+            refreshed_at: None,
+            pending: None,
+        }
+    }
+
+    /// Picks up a finished refresh and starts a new one once the list is older than
+    /// `WINDOW_BROWSER_REFRESH`.
+    ///
+    /// # Returns
+    /// - The most recently listed windows, which stay shown while a refresh runs.
+    pub fn poll(&mut self) -> &[WindowSummary] {
+        if let Some(pending) = self.pending.take() {
+            match pending.try_take() {
+                Ok(windows) => {
+                    self.windows = windows;
+                    self.refreshed_at = Some(Instant::now());
+                }
+                Err(pending) => self.pending = Some(pending),
+            }
+        }
+
+        let stale = self
+            .refreshed_at
+            .is_none_or(|at| at.elapsed() >= WINDOW_BROWSER_REFRESH);
+        if stale && self.pending.is_none() {
+            self.pending = Some(Promise::spawn_thread("Window Browser", || {
+                list_windows(&Win32Backend)
+            }));
+        }
+
+        &self.windows
+    }
+
+    /// Makes the next `poll` list the windows again right away.
+    pub fn refresh(&mut self) {
+        self.refreshed_at = None;
+    }
+}
+
+impl Default for WindowBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
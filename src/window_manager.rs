@@ -1,14 +1,37 @@
-use crate::gui::App;
-use crate::workspace::Workspace;
+use crate::caps_layer::is_layer_key_down;
+use crate::error::MultiManagerError;
+use crate::hotkey::Hotkey;
+use crate::key_hook::is_hooked;
+use crate::workspace::{PositionFlags, SavedWindowStyle};
 use log::{error, info, warn};
-use windows::core::{Result, PCWSTR};
-use windows::Win32::Foundation::{HWND, RECT};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use windows::core::{w, Result, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, POINT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    DeleteObject, EnumDisplayMonitors, GetDC, GetDIBits, GetMonitorInfoW, GetObjectW,
+    MonitorFromRect, MonitorFromWindow, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+    DIB_RGB_COLORS, HDC, HMONITOR, MONITORINFO, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL,
+};
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::System::Threading::{
+    GetProcessTimes, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use windows::Win32::UI::Shell::{
+    SHAppBarMessage, ABM_GETSTATE, ABM_SETSTATE, ABS_ALWAYSONTOP, ABS_AUTOHIDE, APPBARDATA,
+};
 use windows::Win32::UI::WindowsAndMessaging::*;
 
 /// Checks if a hotkey is pressed based on the key sequence string.
 ///
+/// Hotkeys in hook mode are swallowed by the keyboard hook and not seen here; see
+/// `take_hooked_presses`.
+///
 /// # Arguments
 /// - `key_sequence`: The key sequence string (e.g., "Ctrl+Alt+H") to check.
 ///
@@ -17,122 +40,157 @@ use windows::Win32::UI::WindowsAndMessaging::*;
 /// - `false` otherwise.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if is_hotkey_pressed("Ctrl+Shift+P") {
 ///     println!("Hotkey pressed!");
 /// }
 /// ```
 pub fn is_hotkey_pressed(key_sequence: &str) -> bool {
-    let mut modifiers_pressed = true;
-    let mut vk_code: Option<u32> = None;
-
-    for part in key_sequence.split('+') {
-        match part.to_lowercase().as_str() {
-            "ctrl" => unsafe {
-                modifiers_pressed &= GetAsyncKeyState(VK_CONTROL.0 as i32) < 0;
-            },
-            "alt" => unsafe {
-                modifiers_pressed &= GetAsyncKeyState(VK_MENU.0 as i32) < 0;
-            },
-            "shift" => unsafe {
-                modifiers_pressed &= GetAsyncKeyState(VK_SHIFT.0 as i32) < 0;
-            },
-            "win" => unsafe {
-                modifiers_pressed &= GetAsyncKeyState(VK_LWIN.0 as i32) < 0
-                    || GetAsyncKeyState(VK_RWIN.0 as i32) < 0;
-            },
-            _ => vk_code = virtual_key_from_string(part),
-        }
-    }
+    let Some(hotkey) = Hotkey::parse(key_sequence) else {
+        return false;
+    };
+    let down = |vk: VIRTUAL_KEY| unsafe { GetAsyncKeyState(vk.0 as i32) < 0 };
 
-    if let Some(vk) = vk_code {
-        unsafe { modifiers_pressed && GetAsyncKeyState(vk as i32) < 0 }
+    // The CapsLock layer swallows the keys pressed with CapsLock, so it tracks them itself
+    let key_down = if hotkey.caps {
+        is_layer_key_down(hotkey.vk)
     } else {
-        false
-    }
+        down(VIRTUAL_KEY(hotkey.vk as u16))
+    };
+    (!hotkey.ctrl || down(VK_CONTROL))
+        && (!hotkey.alt || down(VK_MENU))
+        && (!hotkey.shift || down(VK_SHIFT))
+        && (!hotkey.win || down(VK_LWIN) || down(VK_RWIN))
+        && key_down
 }
 
 /// Registers a global hotkey for a workspace.
 ///
+/// Hotkeys with the Caps modifier, and hotkeys in hook mode, are only recorded, as they are
+/// recognized by the CapsLock layer or the keyboard hook (see `caps_layer` and `key_hook`),
+/// not by the system.
+///
 /// # Arguments
-/// - `app`: Reference to the `App` struct for tracking registered hotkeys.
+/// - `registered_hotkeys`: The registry of hotkeys already registered, keyed by the canonical
+///   form of their key sequence.
 /// - `id`: The unique identifier for the hotkey.
-/// - `key_sequence`: The key sequence string (e.g., "Ctrl+Alt+H") to register.
+/// - `key_sequence`: The key sequence string (e.g., "Ctrl+Alt+H") to register, in any form
+///   `Hotkey::parse` accepts.
 ///
 /// # Returns
-/// - `true` if the hotkey was successfully registered.
-/// - `false` otherwise.
+/// - `Ok(())` if the hotkey was successfully registered.
+/// - `Err(HotkeyAlreadyRegistered)` if this application already holds the hotkey.
+/// - `Err(InvalidHotkey)` if the key sequence is not a valid hotkey.
+/// - `Err(HotkeyRegistration)` if the system refused the registration.
 ///
 /// # Example
-/// ```
-/// if register_hotkey(1, "Ctrl+Shift+P") {
+/// ```ignore
+/// if register_hotkey(&registered_hotkeys, 1, "Ctrl+Shift+P").is_ok() {
 ///     println!("Hotkey registered!");
 /// }
 /// ```
-pub fn register_hotkey(app: &App, id: i32, key_sequence: &str) -> bool {
+pub fn register_hotkey(
+    registered_hotkeys: &Mutex<HashMap<String, usize>>,
+    id: i32,
+    key_sequence: &str,
+) -> std::result::Result<(), MultiManagerError> {
+    let Some(hotkey) = Hotkey::parse(key_sequence) else {
+        warn!("Invalid hotkey sequence: '{}'.", key_sequence);
+        return Err(MultiManagerError::InvalidHotkey {
+            hotkey: key_sequence.to_string(),
+        });
+    };
+    // Registered under the canonical form, so other spellings of it are recognized too
+    let canonical = hotkey.to_string();
+
     // Check if the hotkey is already registered
-    let registered_hotkeys = app.registered_hotkeys.lock().unwrap();
-    if registered_hotkeys.contains_key(key_sequence) {
+    let registry = registered_hotkeys.lock().unwrap();
+    if registry.contains_key(&canonical) {
         // warn!("Hotkey '{}' is already registered.", key_sequence);
-        return false;
+        return Err(MultiManagerError::HotkeyAlreadyRegistered {
+            hotkey: key_sequence.to_string(),
+        });
     }
-    drop(registered_hotkeys); // Release lock early
-
-    // Proceed with normal registration
-    let mut modifiers: u32 = 0;
-    let mut vk_code: Option<u32> = None;
-
-    for part in key_sequence.split('+') {
-        match part.to_lowercase().as_str() {
-            "ctrl" => modifiers |= MOD_CONTROL.0,
-            "alt" => modifiers |= MOD_ALT.0,
-            "shift" => modifiers |= MOD_SHIFT.0,
-            "win" => modifiers |= MOD_WIN.0,
-            _ => {
-                vk_code = virtual_key_from_string(part);
-            }
-        }
+    drop(registry); // Release lock early
+
+    // The CapsLock layer and the keyboard hook handle these themselves, so only record them
+    if hotkey.caps || is_hooked(&hotkey) {
+        registered_hotkeys
+            .lock()
+            .unwrap()
+            .insert(canonical, id as usize);
+        info!(
+            "Recorded hotkey '{}' with ID {}; it is handled by a keyboard hook.",
+            key_sequence, id
+        );
+        return Ok(());
     }
 
-    if let Some(vk) = vk_code {
-        unsafe {
-            if RegisterHotKey(None, id, HOT_KEY_MODIFIERS(modifiers), vk).is_ok() {
-                // Update the registered hotkeys map
-                let mut registered_hotkeys = app.registered_hotkeys.lock().unwrap();
-                registered_hotkeys.insert(key_sequence.to_string(), id as usize);
+    // Proceed with normal registration
+    let modifiers = [
+        (hotkey.ctrl, MOD_CONTROL),
+        (hotkey.alt, MOD_ALT),
+        (hotkey.shift, MOD_SHIFT),
+        (hotkey.win, MOD_WIN),
+    ]
+    .into_iter()
+    .filter(|(held, _)| *held)
+    .fold(HOT_KEY_MODIFIERS(0), |modifiers, (_, flag)| {
+        modifiers | flag
+    });
 
-                info!("Registered hotkey '{}' with ID {}.", key_sequence, id);
-                return true;
-            } else {
-                error!("Failed to register hotkey: '{}'.", key_sequence);
-            }
+    unsafe {
+        if let Err(e) = RegisterHotKey(None, id, modifiers, hotkey.vk) {
+            error!("Failed to register hotkey: '{}'.", key_sequence);
+            return Err(MultiManagerError::HotkeyRegistration {
+                hotkey: key_sequence.to_string(),
+                code: e.code().0,
+            });
         }
-    } else {
-        warn!("Invalid hotkey sequence: '{}'.", key_sequence);
     }
 
-    false
+    // Update the registered hotkeys map
+    let mut registry = registered_hotkeys.lock().unwrap();
+    registry.insert(canonical, id as usize);
+
+    info!("Registered hotkey '{}' with ID {}.", key_sequence, id);
+    Ok(())
 }
 
 /// Unregisters a global hotkey based on its ID.
 ///
 /// # Arguments
-/// - `app`: Reference to the `App` struct for tracking registered hotkeys.
+/// - `registered_hotkeys`: The registry of hotkeys already registered, keyed by key sequence.
 /// - `id`: The unique identifier of the hotkey to unregister.
 ///
 /// # Example
+/// ```ignore
+/// unregister_hotkey(&registered_hotkeys, 1);
 /// ```
-/// unregister_hotkey(&app, 1);
-/// ```
-pub fn unregister_hotkey(app: &App, id: i32) {
+pub fn unregister_hotkey(registered_hotkeys: &Mutex<HashMap<String, usize>>, id: i32) {
+    // Hotkeys handled by a keyboard hook were never registered with the system, only recorded
+    let hooked_key = registered_hotkeys
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(key, &v)| {
+            v == id as usize && Hotkey::parse(key).is_some_and(|h| h.caps || is_hooked(&h))
+        })
+        .map(|(key, _)| key.clone());
+    if let Some(key) = hooked_key {
+        registered_hotkeys.lock().unwrap().remove(&key);
+        info!("Removed hooked hotkey '{}' from the registry.", key);
+        return;
+    }
+
     unsafe {
         if UnregisterHotKey(None, id).is_ok() {
             info!("Successfully unregistered hotkey with ID {}.", id);
 
             // First, find the key associated with the ID
             let key_to_remove = {
-                let registered_hotkeys = app.registered_hotkeys.lock().unwrap();
-                registered_hotkeys
+                let registry = registered_hotkeys.lock().unwrap();
+                registry
                     .iter()
                     .find(|(_, &v)| v == id as usize)
                     .map(|(key, _)| key.clone()) // Clone the key to avoid borrowing issues
@@ -140,8 +198,8 @@ pub fn unregister_hotkey(app: &App, id: i32) {
 
             // Now remove the key from the map if it exists
             if let Some(key) = key_to_remove {
-                let mut registered_hotkeys = app.registered_hotkeys.lock().unwrap();
-                registered_hotkeys.remove(&key);
+                let mut registry = registered_hotkeys.lock().unwrap();
+                registry.remove(&key);
                 info!("Removed hotkey '{}' from the registry.", key);
             }
         } else {
@@ -150,118 +208,225 @@ pub fn unregister_hotkey(app: &App, id: i32) {
     }
 }
 
-/// Checks if all valid windows in a workspace are at their home positions.
-///
-/// # Arguments
-/// - `workspace`: The workspace whose windows are being checked.
+/// Enumerates all top-level windows.
 ///
 /// # Returns
-/// - `true` if all valid windows are at their home positions.
-/// - `false` otherwise.
-pub fn are_all_windows_at_home(workspace: &Workspace) -> bool {
-    workspace.windows.iter().filter(|w| w.valid).all(|w| {
-        let hwnd = HWND(w.id as *mut std::ffi::c_void);
-        unsafe {
-            IsWindow(hwnd).as_bool()
-                && is_window_at_position(hwnd, w.home.0, w.home.1, w.home.2, w.home.3)
+/// - A list of handles for every top-level window, in z-order.
+pub fn enumerate_top_level_windows() -> Vec<HWND> {
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let handles = &mut *(lparam.0 as *mut Vec<HWND>);
+        handles.push(hwnd);
+        BOOL(1)
+    }
+
+    let mut handles: Vec<HWND> = Vec::new();
+    unsafe {
+        if let Err(e) = EnumWindows(Some(collect), LPARAM(&mut handles as *mut _ as isize)) {
+            warn!("Failed to enumerate windows: {}", e);
         }
-    })
+    }
+    handles
 }
 
-/// Toggles workspace windows between their home and target locations.
+/// Enumerates the connected monitors.
 ///
-/// # Arguments
-/// - `workspace`: The workspace to toggle windows for.
-///
-/// - If all windows are at their home positions, they are moved to their target positions.
-/// - If any window is not at its home or target position, it is moved to its home position.
-///
-/// # Example
-/// ```
-/// toggle_workspace_windows(&mut workspace);
-/// ```
-pub fn toggle_workspace_windows(workspace: &mut Workspace) {
-    let all_at_home = are_all_windows_at_home(workspace);
-    info!("DEBUG all_at_home {}", all_at_home);
-
-    for window in &workspace.windows {
-        let hwnd = HWND(window.id as *mut std::ffi::c_void);
-
-        // Check if the window is valid
-        unsafe {
-            if !IsWindow(hwnd).as_bool() {
-                warn!("Skipping invalid window '{}'.", window.title);
-                continue;
-            }
-        }
+/// # Returns
+/// - The bounds `(x, y, width, height)` of every monitor in virtual-screen coordinates.
+pub fn enumerate_monitors() -> Vec<(i32, i32, i32, i32)> {
+    unsafe extern "system" fn collect(
+        _monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        lparam: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(lparam.0 as *mut Vec<(i32, i32, i32, i32)>);
+        let rect = &*rect;
+        monitors.push((
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+        ));
+        BOOL(1)
+    }
 
-        unsafe {
-            if IsIconic(hwnd).as_bool() {
-                if !ShowWindow(hwnd, SW_RESTORE).as_bool() {
-                    warn!("Failed to restore minimized window '{}'.", window.title);
-                } else {
-                    info!("Restored minimized window '{}'.", window.title);
-                }
-            }
+    let mut monitors: Vec<(i32, i32, i32, i32)> = Vec::new();
+    unsafe {
+        if !EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect),
+            LPARAM(&mut monitors as *mut _ as isize),
+        )
+        .as_bool()
+        {
+            warn!("Failed to enumerate monitors.");
         }
+    }
+    monitors
+}
 
-        let target_position = if all_at_home {
-            window.target
+/// A connected monitor and its scale factor.
+///
+/// # Fields
+/// - `rect`: The monitor's bounds `(x, y, width, height)` in virtual-screen coordinates.
+/// - `scale`: The monitor's effective DPI relative to 96, e.g. `1.5` at 150% scaling.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonitorInfo {
+    pub rect: (i32, i32, i32, i32),
+    pub scale: f32,
+}
+
+/// Enumerates the work areas of the connected monitors, the part not covered by the taskbar
+/// or docked toolbars, in the same order as `enumerate_monitors`.
+///
+/// # Returns
+/// - The work area `(x, y, width, height)` of every monitor, or its full bounds if the work
+///   area cannot be queried.
+pub fn enumerate_work_areas() -> Vec<(i32, i32, i32, i32)> {
+    unsafe extern "system" fn collect(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        lparam: LPARAM,
+    ) -> BOOL {
+        let areas = &mut *(lparam.0 as *mut Vec<(i32, i32, i32, i32)>);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        let work = if GetMonitorInfoW(monitor, &mut info).as_bool() {
+            info.rcWork
         } else {
-            window.home
+            *rect
         };
+        areas.push((
+            work.left,
+            work.top,
+            work.right - work.left,
+            work.bottom - work.top,
+        ));
+        BOOL(1)
+    }
 
-        // Move the window
-        if let Err(e) = move_window(
-            HWND(window.id as *mut std::ffi::c_void),
-            target_position.0,
-            target_position.1,
-            target_position.2,
-            target_position.3,
-        ) {
-            warn!("Failed to move window '{}': {}", window.title, e);
-        } else {
-            info!(
-                "Moved window '{}' to position: {:?}",
-                window.title, target_position
-            );
+    let mut areas: Vec<(i32, i32, i32, i32)> = Vec::new();
+    unsafe {
+        if !EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect),
+            LPARAM(&mut areas as *mut _ as isize),
+        )
+        .as_bool()
+        {
+            warn!("Failed to enumerate monitor work areas.");
         }
+    }
+    areas
+}
 
-        // Activate the window
-        unsafe {
-            let hwnd = HWND(window.id as *mut std::ffi::c_void);
-            if SetForegroundWindow(hwnd).as_bool() {
-                info!("Activated window '{}'", window.title);
-            } else {
-                warn!("Failed to activate window '{}'", window.title);
-            }
+/// Enumerates the connected monitors with their scale factors, in the same order as
+/// `enumerate_monitors`.
+///
+/// # Returns
+/// - One entry per monitor. The scale is `1.0` if the monitor's DPI cannot be queried.
+pub fn enumerate_monitor_info() -> Vec<MonitorInfo> {
+    unsafe extern "system" fn collect(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        lparam: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(lparam.0 as *mut Vec<MonitorInfo>);
+        let rect = &*rect;
+        let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+        let scale = match GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) {
+            Ok(()) if dpi_x > 0 => dpi_x as f32 / 96.0,
+            _ => 1.0,
+        };
+        monitors.push(MonitorInfo {
+            rect: (
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+            ),
+            scale,
+        });
+        BOOL(1)
+    }
+
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+    unsafe {
+        if !EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect),
+            LPARAM(&mut monitors as *mut _ as isize),
+        )
+        .as_bool()
+        {
+            warn!("Failed to enumerate monitors.");
         }
     }
+    monitors
 }
 
-/// Checks if a window is at the specified position.
+/// Finds the monitor a rect is mostly on.
 ///
 /// # Arguments
-/// - `hwnd`: The handle to the window.
-/// - `x`, `y`: The top-left coordinates of the position.
-/// - `w`, `h`: The width and height of the position.
+/// - `monitors`: The connected monitors, from `enumerate_monitor_info`.
+/// - `rect`: The position `(x, y, width, height)` to look up.
 ///
 /// # Returns
-/// - `true` if the window matches the specified position.
-/// - `false` otherwise.
+/// - The index of the monitor sharing the largest area with the rect, or `None` if the rect
+///   lies entirely off-screen.
 ///
 /// # Example
-/// ```
-/// if is_window_at_position(hwnd, 0, 0, 800, 600) {
-///     println!("Window is at the correct position.");
+/// ```ignore
+/// let monitors = enumerate_monitor_info();
+/// match monitor_for_rect(&monitors, window.home) {
+///     Some(index) => println!("Monitor {} at {}x", index + 1, monitors[index].scale),
+///     None => println!("Off-screen"),
 /// }
 /// ```
-fn is_window_at_position(hwnd: HWND, x: i32, y: i32, w: i32, h: i32) -> bool {
-    if let Ok((wx, wy, ww, wh)) = get_window_position(hwnd) {
-        wx == x && wy == y && ww == w && wh == h
-    } else {
-        false
-    }
+pub fn monitor_for_rect(monitors: &[MonitorInfo], rect: (i32, i32, i32, i32)) -> Option<usize> {
+    let overlap = |monitor: &MonitorInfo| {
+        let (mx, my, mw, mh) = monitor.rect;
+        let width = (rect.0 + rect.2).min(mx + mw) - rect.0.max(mx);
+        let height = (rect.1 + rect.3).min(my + mh) - rect.1.max(my);
+        if width > 0 && height > 0 {
+            width as i64 * height as i64
+        } else {
+            0
+        }
+    };
+
+    monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| (index, overlap(monitor)))
+        .filter(|&(_, area)| area > 0)
+        .max_by_key(|&(_, area)| area)
+        .map(|(index, _)| index)
+}
+
+/// Checks whether a rect overlaps any connected monitor.
+///
+/// # Arguments
+/// - `rect`: The position `(x, y, width, height)` to check.
+///
+/// # Returns
+/// - `true` if at least part of the rect is on a monitor.
+/// - `false` if the rect lies entirely off-screen.
+pub fn is_rect_on_screen(rect: (i32, i32, i32, i32)) -> bool {
+    let rect = RECT {
+        left: rect.0,
+        top: rect.1,
+        right: rect.0 + rect.2,
+        bottom: rect.1 + rect.3,
+    };
+    unsafe { !MonitorFromRect(&rect, MONITOR_DEFAULTTONULL).is_invalid() }
 }
 
 /// Retrieves the current position and size of a window.
@@ -274,7 +439,7 @@ fn is_window_at_position(hwnd: HWND, x: i32, y: i32, w: i32, h: i32) -> bool {
 /// - `Err` if the window position cannot be retrieved.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if let Ok((x, y, w, h)) = get_window_position(hwnd) {
 ///     println!("Window position: ({}, {}, {}, {})", x, y, w, h);
 /// }
@@ -295,156 +460,16 @@ pub fn get_window_position(hwnd: HWND) -> Result<(i32, i32, i32, i32)> {
     }
 }
 
-/// Converts a string to a virtual key code.
+/// Checks if a window is snapped (arranged) by Windows, e.g. to half of the screen or to a
+/// zone of a snap layout.
 ///
 /// # Arguments
-/// - `key`: The key string (e.g., "A", "F1", "Ctrl").
+/// - `hwnd`: The handle of the window to check.
 ///
 /// # Returns
-/// - The virtual key code as `Option<u32>`.
-///
-/// # Example
-/// ```
-/// if let Some(vk) = virtual_key_from_string("Ctrl") {
-///     println!("Virtual key code: {}", vk);
-/// }
-/// ```
-fn virtual_key_from_string(key: &str) -> Option<u32> {
-    match key.to_uppercase().as_str() {
-        // Function keys
-        "F1" => Some(0x70),
-        "F2" => Some(0x71),
-        "F3" => Some(0x72),
-        "F4" => Some(0x73),
-        "F5" => Some(0x74),
-        "F6" => Some(0x75),
-        "F7" => Some(0x76),
-        "F8" => Some(0x77),
-        "F9" => Some(0x78),
-        "F10" => Some(0x79),
-        "F11" => Some(0x7A),
-        "F12" => Some(0x7B),
-        "F13" => Some(0x7C),
-        "F14" => Some(0x7D),
-        "F15" => Some(0x7E),
-        "F16" => Some(0x7F),
-        "F17" => Some(0x80),
-        "F18" => Some(0x81),
-        "F19" => Some(0x82),
-        "F20" => Some(0x83),
-        "F21" => Some(0x84),
-        "F22" => Some(0x85),
-        "F23" => Some(0x86),
-        "F24" => Some(0x87),
-
-        // Alphabet keys
-        "A" => Some(0x41),
-        "B" => Some(0x42),
-        "C" => Some(0x43),
-        "D" => Some(0x44),
-        "E" => Some(0x45),
-        "F" => Some(0x46),
-        "G" => Some(0x47),
-        "H" => Some(0x48),
-        "I" => Some(0x49),
-        "J" => Some(0x4A),
-        "K" => Some(0x4B),
-        "L" => Some(0x4C),
-        "M" => Some(0x4D),
-        "N" => Some(0x4E),
-        "O" => Some(0x4F),
-        "P" => Some(0x50),
-        "Q" => Some(0x51),
-        "R" => Some(0x52),
-        "S" => Some(0x53),
-        "T" => Some(0x54),
-        "U" => Some(0x55),
-        "V" => Some(0x56),
-        "W" => Some(0x57),
-        "X" => Some(0x58),
-        "Y" => Some(0x59),
-        "Z" => Some(0x5A),
-
-        // Number keys
-        "0" => Some(0x30),
-        "1" => Some(0x31),
-        "2" => Some(0x32),
-        "3" => Some(0x33),
-        "4" => Some(0x34),
-        "5" => Some(0x35),
-        "6" => Some(0x36),
-        "7" => Some(0x37),
-        "8" => Some(0x38),
-        "9" => Some(0x39),
-
-        // Numpad keys
-        "NUMPAD0" => Some(0x60),
-        "NUMPAD1" => Some(0x61),
-        "NUMPAD2" => Some(0x62),
-        "NUMPAD3" => Some(0x63),
-        "NUMPAD4" => Some(0x64),
-        "NUMPAD5" => Some(0x65),
-        "NUMPAD6" => Some(0x66),
-        "NUMPAD7" => Some(0x67),
-        "NUMPAD8" => Some(0x68),
-        "NUMPAD9" => Some(0x69),
-        "NUMPADMULTIPLY" => Some(0x6A),
-        "NUMPADADD" => Some(0x6B),
-        "NUMPADSEPARATOR" => Some(0x6C),
-        "NUMPADSUBTRACT" => Some(0x6D),
-        "NUMPADDOT" => Some(0x6E),
-        "NUMPADDIVIDE" => Some(0x6F),
-
-        // Arrow keys
-        "UP" => Some(0x26),
-        "DOWN" => Some(0x28),
-        "LEFT" => Some(0x25),
-        "RIGHT" => Some(0x27),
-
-        // Special keys
-        "BACKSPACE" => Some(0x08),
-        "TAB" => Some(0x09),
-        "ENTER" => Some(0x0D),
-        "SHIFT" => Some(0x10),
-        "CTRL" => Some(0x11),
-        "ALT" => Some(0x12),
-        "PAUSE" => Some(0x13),
-        "CAPSLOCK" => Some(0x14),
-        "ESCAPE" => Some(0x1B),
-        "SPACE" => Some(0x20),
-        "PAGEUP" => Some(0x21),
-        "PAGEDOWN" => Some(0x22),
-        "END" => Some(0x23),
-        "HOME" => Some(0x24),
-        "INSERT" => Some(0x2D),
-        "DELETE" => Some(0x2E),
-
-        // Symbols
-        "OEM_PLUS" => Some(0xBB),   // '+' key
-        "OEM_COMMA" => Some(0xBC),  // ',' key
-        "OEM_MINUS" => Some(0xBD),  // '-' key
-        "OEM_PERIOD" => Some(0xBE), // '.' key
-        "OEM_1" => Some(0xBA),      // ';:' key
-        "OEM_2" => Some(0xBF),      // '/?' key
-        "OEM_3" => Some(0xC0),      // '`~' key
-        "OEM_4" => Some(0xDB),      // '[{' key
-        "OEM_5" => Some(0xDC),      // '\|' key
-        "OEM_6" => Some(0xDD),      // ']}' key
-        "OEM_7" => Some(0xDE),      // ''"' key
-
-        // Additional keys
-        "PRINTSCREEN" => Some(0x2C),
-        "SCROLLLOCK" => Some(0x91),
-        "NUMLOCK" => Some(0x90),
-        "LEFTSHIFT" => Some(0xA0),
-        "RIGHTSHIFT" => Some(0xA1),
-        "LEFTCTRL" => Some(0xA2),
-        "RIGHTCTRL" => Some(0xA3),
-        "LEFTALT" => Some(0xA4),
-        "RIGHTALT" => Some(0xA5),
-
-        _ => None,
-    }
+/// - `true` if the window is snapped, `false` if not or if it is maximized or minimized.
+pub fn is_window_snapped(hwnd: HWND) -> bool {
+    unsafe { IsWindowArranged(hwnd).as_bool() }
 }
 
 /// Retrieves the currently active window and its title.
@@ -454,7 +479,7 @@ fn virtual_key_from_string(key: &str) -> Option<u32> {
 /// - `None` if no active window is found.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if let Some((hwnd, title)) = get_active_window() {
 ///     println!("Active window: {} ({:?})", title, hwnd);
 /// }
@@ -466,35 +491,295 @@ pub fn get_active_window() -> Option<(HWND, String)> {
             warn!("No active window detected.");
             None
         } else {
-            let mut buffer = [0u16; 256];
-            let length = GetWindowTextW(hwnd, &mut buffer);
-            let title = String::from_utf16_lossy(&buffer[..length as usize]);
+            let title = get_window_title(hwnd);
             info!("Active window detected: '{}'.", title);
             Some((hwnd, title))
         }
     }
 }
 
-/// Moves a window to a specific position and size.
+/// Retrieves the top-level window under the mouse cursor and its title.
+///
+/// Unlike `get_active_window`, this works for windows that are hard to bring to the
+/// foreground, e.g. ones behind an always-on-top window.
+///
+/// # Returns
+/// - A tuple `(HWND, String)` with the top-level window's handle and title.
+/// - `None` if the cursor position cannot be read or there is no window under it.
+///
+/// # Example
+/// ```ignore
+/// if let Some((hwnd, title)) = get_window_under_cursor() {
+///     println!("Window under cursor: {} ({:?})", title, hwnd);
+/// }
+/// ```
+pub fn get_window_under_cursor() -> Option<(HWND, String)> {
+    unsafe {
+        let mut point = POINT::default();
+        if let Err(e) = GetCursorPos(&mut point) {
+            warn!("Failed to read the cursor position: {}", e);
+            return None;
+        }
+
+        // The point is usually over a child control; capture the window that owns it
+        let hwnd = GetAncestor(WindowFromPoint(point), GA_ROOT);
+        if hwnd.0.is_null() {
+            warn!("No window under the cursor at {:?}.", (point.x, point.y));
+            None
+        } else {
+            let title = get_window_title(hwnd);
+            info!("Window under cursor detected: '{}'.", title);
+            Some((hwnd, title))
+        }
+    }
+}
+
+/// Retrieves the current title of a window.
 ///
 /// # Arguments
 /// - `hwnd`: The handle to the window.
-/// - `x`, `y`: The new top-left position of the window.
-/// - `w`, `h`: The new width and height of the window.
+///
+/// # Returns
+/// - The window's title, or an empty string if it has none.
+///
+/// # Example
+/// ```ignore
+/// println!("Window title: {}", get_window_title(hwnd));
+/// ```
+pub fn get_window_title(hwnd: HWND) -> String {
+    unsafe {
+        let mut buffer = [0u16; 256];
+        let length = GetWindowTextW(hwnd, &mut buffer);
+        String::from_utf16_lossy(&buffer[..length as usize])
+    }
+}
+
+/// Retrieves the class name of a window.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - The window's class name, or an empty string if it cannot be retrieved.
+pub fn get_window_class(hwnd: HWND) -> String {
+    unsafe {
+        let mut buffer = [0u16; 256];
+        let length = GetClassNameW(hwnd, &mut buffer);
+        String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+    }
+}
+
+/// Retrieves the full path of the executable that owns a window.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - The executable path, or `None` if the process cannot be queried.
+pub fn get_process_path(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+        if process_id == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+
+        result
+            .ok()
+            .map(|_| String::from_utf16_lossy(&buffer[..size as usize]))
+    }
+}
+
+/// Retrieves when the process that owns a window was started.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - The start time as a `FILETIME` value (100 ns intervals since 1601), or `None` if the
+///   process cannot be queried.
+pub fn get_process_start_time(hwnd: HWND) -> Option<u64> {
+    unsafe {
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+        if process_id == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let (mut created, mut exited, mut kernel, mut user) = Default::default();
+        let result = GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user);
+        let _ = CloseHandle(process);
+
+        result.ok()?;
+        Some(((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64)
+    }
+}
+
+/// Retrieves the executable file name (e.g. `notepad.exe`) that owns a window.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - The executable file name, or an empty string if it cannot be determined.
+pub fn get_window_exe(hwnd: HWND) -> String {
+    get_process_path(hwnd)
+        .and_then(|path| {
+            std::path::Path::new(&path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default()
+}
+
+/// Retrieves the small icon a window shows in its title bar, as RGBA pixels.
+///
+/// The icon is asked for with a short timeout, so a hung window cannot stall the caller,
+/// falling back to the icon registered for the window's class.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - `(width, height, pixels)` with four bytes per pixel, or `None` if the window has no icon.
+pub fn get_window_icon(hwnd: HWND) -> Option<(usize, usize, Vec<u8>)> {
+    unsafe {
+        let mut icon = 0usize;
+        for kind in [ICON_SMALL2, ICON_SMALL, ICON_BIG] {
+            let mut result = 0usize;
+            let sent = SendMessageTimeoutW(
+                hwnd,
+                WM_GETICON,
+                WPARAM(kind as usize),
+                LPARAM(0),
+                SMTO_ABORTIFHUNG,
+                100,
+                Some(&mut result),
+            );
+            if sent.0 != 0 && result != 0 {
+                icon = result;
+                break;
+            }
+        }
+        if icon == 0 {
+            icon = GetClassLongPtrW(hwnd, GCLP_HICONSM);
+        }
+        if icon == 0 {
+            icon = GetClassLongPtrW(hwnd, GCLP_HICON);
+        }
+        if icon == 0 {
+            return None;
+        }
+
+        let mut info = ICONINFO::default();
+        GetIconInfo(HICON(icon as *mut std::ffi::c_void), &mut info).ok()?;
+        let pixels = icon_pixels(&info);
+
+        // GetIconInfo hands over copies of the bitmaps, which must be freed
+        let _ = DeleteObject(info.hbmColor);
+        let _ = DeleteObject(info.hbmMask);
+        pixels
+    }
+}
+
+/// Reads the color bitmap of an icon as RGBA pixels.
+unsafe fn icon_pixels(info: &ICONINFO) -> Option<(usize, usize, Vec<u8>)> {
+    if info.hbmColor.is_invalid() {
+        // Monochrome icons have no color bitmap; they are rare enough to go without
+        return None;
+    }
+
+    let mut bitmap = BITMAP::default();
+    let size = std::mem::size_of::<BITMAP>() as i32;
+    if GetObjectW(
+        info.hbmColor,
+        size,
+        Some(&mut bitmap as *mut BITMAP as *mut std::ffi::c_void),
+    ) == 0
+    {
+        return None;
+    }
+    let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let mut header = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height, // Top-down rows
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let hdc = GetDC(None);
+    let lines = GetDIBits(
+        hdc,
+        info.hbmColor,
+        0,
+        height as u32,
+        Some(pixels.as_mut_ptr() as *mut std::ffi::c_void),
+        &mut header,
+        DIB_RGB_COLORS,
+    );
+    ReleaseDC(None, hdc);
+    if lines == 0 {
+        return None;
+    }
+
+    // BGRA to RGBA. Icons without an alpha channel come back fully transparent
+    let has_alpha = pixels.chunks_exact(4).any(|pixel| pixel[3] != 0);
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        if !has_alpha {
+            pixel[3] = 255;
+        }
+    }
+    Some((width as usize, height as usize, pixels))
+}
+
+/// Moves a window to a specific position and size using explicit `SetWindowPos` flags.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+/// - `rect`: The new position `(x, y, width, height)` of the window.
+/// - `flags`: The flags passed to `SetWindowPos`.
 ///
 /// # Returns
 /// - `Ok(())` if the window was successfully moved.
 /// - `Err` otherwise.
 ///
 /// # Example
-/// ```
-/// if let Err(e) = move_window(hwnd, 100, 100, 800, 600) {
+/// ```ignore
+/// if let Err(e) = move_window_with_flags(hwnd, (100, 100, 800, 600), SWP_NOZORDER) {
 ///     println!("Failed to move window: {}", e);
 /// }
 /// ```
-pub fn move_window(hwnd: HWND, x: i32, y: i32, w: i32, h: i32) -> Result<()> {
+pub fn move_window_with_flags(
+    hwnd: HWND,
+    rect: (i32, i32, i32, i32),
+    flags: SET_WINDOW_POS_FLAGS,
+) -> Result<()> {
+    let (x, y, w, h) = rect;
     unsafe {
-        SetWindowPos(hwnd, HWND_TOP, x, y, w, h, SWP_NOZORDER)?;
+        SetWindowPos(hwnd, HWND_TOP, x, y, w, h, flags)?;
         info!(
             "Moved window (HWND: {:?}) to position ({}, {}, {}, {}).",
             hwnd.0, x, y, w, h
@@ -503,6 +788,193 @@ pub fn move_window(hwnd: HWND, x: i32, y: i32, w: i32, h: i32) -> Result<()> {
     }
 }
 
+/// Converts a window's position flags into `SetWindowPos` flags.
+///
+/// # Arguments
+/// - `flags`: The window's position flags.
+///
+/// # Returns
+/// - The combined `SET_WINDOW_POS_FLAGS` value.
+pub fn set_window_pos_flags(flags: &PositionFlags) -> SET_WINDOW_POS_FLAGS {
+    let mut result = SET_WINDOW_POS_FLAGS(0);
+    if flags.no_activate {
+        result |= SWP_NOACTIVATE;
+    }
+    if flags.no_size {
+        result |= SWP_NOSIZE;
+    }
+    if flags.no_move {
+        result |= SWP_NOMOVE;
+    }
+    if flags.keep_z_order {
+        result |= SWP_NOZORDER;
+    }
+    result
+}
+
+/// Returns how long it has been since the last keyboard or mouse input in this session.
+///
+/// # Returns
+/// - The time since the last input, or zero if it can't be read.
+///
+/// # Example
+/// ```ignore
+/// if idle_time() > Duration::from_secs(600) {
+///     println!("Nobody has touched the machine for ten minutes.");
+/// }
+/// ```
+pub fn idle_time() -> Duration {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return Duration::ZERO;
+    }
+    // Both are milliseconds since startup that wrap after 49.7 days
+    let now = unsafe { GetTickCount() };
+    Duration::from_millis(now.wrapping_sub(info.dwTime) as u64)
+}
+
+/// Checks whether Windows shows animations, which is off when the user has asked for reduced
+/// motion under Accessibility → Visual effects → Animation effects.
+///
+/// # Returns
+/// - `true` if animations are shown, or if the setting can't be read.
+pub fn system_animations_enabled() -> bool {
+    let mut enabled = BOOL(1);
+    let read = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut enabled as *mut BOOL as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    read.is_err() || enabled.as_bool()
+}
+
+/// Checks whether the taskbar is set to auto-hide.
+pub fn is_taskbar_auto_hide() -> bool {
+    let mut data = APPBARDATA {
+        cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+        ..Default::default()
+    };
+    let state = unsafe { SHAppBarMessage(ABM_GETSTATE, &mut data) } as u32;
+    state & ABS_AUTOHIDE != 0
+}
+
+/// Turns the taskbar's auto-hide setting on or off, as the taskbar settings page does. The
+/// monitors' work areas change accordingly.
+///
+/// # Arguments
+/// - `auto_hide`: Whether the taskbar should hide when not in use.
+///
+/// # Example
+/// ```ignore
+/// let previous = is_taskbar_auto_hide();
+/// set_taskbar_auto_hide(true);
+/// // ... later
+/// set_taskbar_auto_hide(previous);
+/// ```
+pub fn set_taskbar_auto_hide(auto_hide: bool) {
+    let mut data = APPBARDATA {
+        cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+        hWnd: unsafe { FindWindowW(w!("Shell_TrayWnd"), PCWSTR::null()) }.unwrap_or_default(),
+        lParam: LPARAM(if auto_hide {
+            ABS_AUTOHIDE as isize
+        } else {
+            ABS_ALWAYSONTOP as isize
+        }),
+        ..Default::default()
+    };
+    unsafe {
+        SHAppBarMessage(ABM_SETSTATE, &mut data);
+    }
+    info!(
+        "Taskbar auto-hide turned {}.",
+        if auto_hide { "on" } else { "off" }
+    );
+}
+
+/// Strips a window's caption and border styles and sizes it to fill its monitor.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+///
+/// # Returns
+/// - The window's original styles and rect, needed to restore it later.
+/// - `Err` if the monitor or window geometry cannot be retrieved, or the window cannot be resized.
+///
+/// # Example
+/// ```ignore
+/// if let Ok(saved) = make_borderless_fullscreen(hwnd) {
+///     window.saved_style = Some(saved);
+/// }
+/// ```
+pub fn make_borderless_fullscreen(hwnd: HWND) -> Result<SavedWindowStyle> {
+    let rect = get_window_position(hwnd)?;
+    unsafe {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return Err(windows::core::Error::from_win32());
+        }
+
+        let style = GetWindowLongPtrW(hwnd, GWL_STYLE);
+        let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let saved = SavedWindowStyle {
+            style,
+            ex_style,
+            rect,
+        };
+
+        let border_styles = (WS_CAPTION | WS_THICKFRAME | WS_SYSMENU).0 as isize;
+        let border_ex_styles =
+            (WS_EX_DLGMODALFRAME | WS_EX_WINDOWEDGE | WS_EX_CLIENTEDGE | WS_EX_STATICEDGE).0
+                as isize;
+        SetWindowLongPtrW(hwnd, GWL_STYLE, style & !border_styles);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style & !border_ex_styles);
+
+        let monitor_rect = info.rcMonitor;
+        move_window_with_flags(
+            hwnd,
+            (
+                monitor_rect.left,
+                monitor_rect.top,
+                monitor_rect.right - monitor_rect.left,
+                monitor_rect.bottom - monitor_rect.top,
+            ),
+            SWP_FRAMECHANGED | SWP_NOZORDER,
+        )?;
+
+        info!("Made window (HWND: {:?}) borderless fullscreen.", hwnd.0);
+        Ok(saved)
+    }
+}
+
+/// Restores the styles and rect a window had before it was made borderless fullscreen.
+///
+/// # Arguments
+/// - `hwnd`: The handle to the window.
+/// - `saved`: The styles and rect captured by `make_borderless_fullscreen`.
+///
+/// # Returns
+/// - `Ok(())` if the window was restored.
+/// - `Err` if the window could not be moved back to its original rect.
+pub fn restore_window_style(hwnd: HWND, saved: &SavedWindowStyle) -> Result<()> {
+    unsafe {
+        SetWindowLongPtrW(hwnd, GWL_STYLE, saved.style);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, saved.ex_style);
+    }
+    move_window_with_flags(hwnd, saved.rect, SWP_FRAMECHANGED | SWP_NOZORDER)?;
+    info!("Restored window styles (HWND: {:?}).", hwnd.0);
+    Ok(())
+}
+
 /// Listens for key input to confirm or cancel an action.
 ///
 /// # Returns
@@ -510,7 +982,7 @@ pub fn move_window(hwnd: HWND, x: i32, y: i32, w: i32, h: i32) -> Result<()> {
 /// - `"Esc"` if the Escape key is pressed.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if let Some(action) = listen_for_keys_with_dialog() {
 ///     println!("User selected action: {}", action);
 /// }
@@ -0,0 +1,360 @@
+use crate::backend::WindowBackend;
+use crate::engine::{
+    adopt_rule_windows, animate_window, enforce_window_rect, is_window_at_home,
+    move_window_with_retry, move_workspace_windows, toggle_workspace_windows,
+};
+use crate::error::MultiManagerError;
+use crate::layout::clamp_to_work_area;
+use crate::paths::{config_path, USAGE_FILE};
+use crate::session_watch::wait_for_stable_geometry;
+use crate::usage::{save_usage_stats, UsageStats};
+use crate::validation::{validate_all, PositionKind, ValidationReport};
+use crate::workspace::{RuntimeId, Window, Workspace};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A window operation to be performed by the `WindowOps` worker.
+///
+/// Workspaces and windows are named by their `runtime_id`, so an operation still reaches the
+/// right entry if the list was reordered or an entry deleted before the worker got to it.
+pub enum WindowOp {
+    /// Toggles the workspace with the given runtime ID.
+    Toggle(RuntimeId),
+    /// Moves every window of a workspace to its home or target position, whatever the
+    /// current state.
+    MoveAll {
+        workspace: RuntimeId,
+        position: PositionKind,
+    },
+    /// Moves one window of a workspace to its home or target position.
+    MoveWindow {
+        workspace: RuntimeId,
+        window: RuntimeId,
+        position: PositionKind,
+    },
+    /// Toggles one window of a workspace: to its target if it is home, otherwise home.
+    ToggleWindow {
+        workspace: RuntimeId,
+        window: RuntimeId,
+    },
+    /// Runs a full validation pass and sends the report back.
+    Validate {
+        /// Whether to report hotkeys this process has not registered; off while a background
+        /// agent holds them.
+        check_hotkeys: bool,
+        report: poll_promise::Sender<ValidationReport>,
+    },
+}
+
+/// Called by the worker after each operation, e.g. to repaint the GUI.
+type ChangeListener = Box<dyn Fn() + Send>;
+
+/// Handle to the worker thread that performs all window operations.
+///
+/// Operations are queued over a channel and executed in order, so long-running moves
+/// and retries never block the GUI thread.
+#[derive(Clone)]
+pub struct WindowOps {
+    sender: Sender<WindowOp>,
+    on_change: Arc<Mutex<Option<ChangeListener>>>,
+}
+
+impl WindowOps {
+    /// Starts the worker thread.
+    ///
+    /// # Arguments
+    /// - `backend`: The window system the worker operates on.
+    /// - `workspaces`: The shared workspace list the worker operates on.
+    /// - `registered_hotkeys`: The shared hotkey registry, used for validation.
+    /// - `usage`: The shared usage statistics, updated and saved after every toggle or move of
+    ///   a whole workspace.
+    ///
+    /// # Returns
+    /// - A `WindowOps` handle for queueing operations.
+    pub fn spawn(
+        backend: impl WindowBackend + Send + 'static,
+        workspaces: Arc<Mutex<Vec<Workspace>>>,
+        registered_hotkeys: Arc<Mutex<HashMap<String, usize>>>,
+        usage: Arc<Mutex<UsageStats>>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel::<WindowOp>();
+        let on_change: Arc<Mutex<Option<ChangeListener>>> = Arc::new(Mutex::new(None));
+
+        let listener = on_change.clone();
+        thread::Builder::new()
+            .name("Window Ops".to_string())
+            .spawn(move || {
+                for op in receiver {
+                    run_op(&backend, op, &workspaces, &registered_hotkeys, &usage);
+                    if let Some(listener) = listener.lock().unwrap().as_ref() {
+                        listener();
+                    }
+                }
+                info!("Window operations worker stopped.");
+            })
+            .expect("Failed to spawn window operations worker");
+
+        WindowOps { sender, on_change }
+    }
+
+    /// Sets what to call after each operation finishes, replacing any previous listener.
+    ///
+    /// # Arguments
+    /// - `listener`: Called on the worker thread, e.g. to request a repaint of the GUI.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let ctx = cc.egui_ctx.clone();
+    /// window_ops.set_on_change(move || ctx.request_repaint());
+    /// ```
+    pub fn set_on_change(&self, listener: impl Fn() + Send + 'static) {
+        *self.on_change.lock().unwrap() = Some(Box::new(listener));
+    }
+
+    /// Queues an operation for the worker.
+    ///
+    /// # Arguments
+    /// - `op`: The operation to perform.
+    pub fn send(&self, op: WindowOp) {
+        if self.sender.send(op).is_err() {
+            warn!("Window operations worker is not running; operation dropped.");
+        }
+    }
+}
+
+/// Executes a single operation.
+///
+/// The workspace lock is only held long enough to take a snapshot and to write the
+/// results back, never while windows are being moved.
+///
+/// Operations that move windows first wait for the display geometry to settle if it changed,
+/// so windows are not positioned on monitors that are still being reconfigured.
+fn run_op(
+    backend: &impl WindowBackend,
+    op: WindowOp,
+    workspaces: &Arc<Mutex<Vec<Workspace>>>,
+    registered_hotkeys: &Arc<Mutex<HashMap<String, usize>>>,
+    usage: &Arc<Mutex<UsageStats>>,
+) {
+    if !matches!(op, WindowOp::Validate { .. }) {
+        wait_for_stable_geometry(backend);
+    }
+    match op {
+        WindowOp::Toggle(workspace) => {
+            run_on_workspace(backend, workspaces, usage, workspace, |snapshot| {
+                toggle_workspace_windows(backend, snapshot)
+            });
+        }
+        WindowOp::MoveAll {
+            workspace,
+            position,
+        } => {
+            run_on_workspace(backend, workspaces, usage, workspace, |snapshot| {
+                move_workspace_windows(backend, snapshot, position)
+            });
+        }
+        WindowOp::ToggleWindow { workspace, window } => {
+            let snapshot = find_workspace(&workspaces.lock().unwrap(), workspace).cloned();
+            let Some((snapshot, index)) = snapshot.and_then(|snapshot| {
+                let index = snapshot
+                    .windows
+                    .iter()
+                    .position(|entry| entry.runtime_id == window)?;
+                Some((snapshot, index))
+            }) else {
+                warn!(
+                    "Toggle requested for unknown window {:?}/{:?}.",
+                    workspace, window
+                );
+                return;
+            };
+            let position = if is_window_at_home(backend, &snapshot, index) {
+                PositionKind::Target
+            } else {
+                PositionKind::Home
+            };
+            info!(
+                "Toggling window '{}' of '{}' to {:?}.",
+                snapshot.windows[index].title, snapshot.name, position
+            );
+            run_op(
+                backend,
+                WindowOp::MoveWindow {
+                    workspace,
+                    window,
+                    position,
+                },
+                workspaces,
+                registered_hotkeys,
+                usage,
+            );
+        }
+        WindowOp::MoveWindow {
+            workspace,
+            window,
+            position,
+        } => {
+            let entry = {
+                let workspaces = workspaces.lock().unwrap();
+                find_workspace(&workspaces, workspace).and_then(|w| {
+                    find_window(w, window).map(|entry| {
+                        (
+                            entry.clone(),
+                            w.name.clone(),
+                            w.move_retries,
+                            w.retry_backoff_ms,
+                            w.active_profile(&backend.monitors()).map(str::to_string),
+                            w.animation.resolve(),
+                        )
+                    })
+                })
+            };
+            let Some((entry, workspace_name, retries, backoff_ms, profile, animation)) = entry
+            else {
+                warn!(
+                    "Move requested for unknown window {:?}/{:?}.",
+                    workspace, window
+                );
+                return;
+            };
+
+            let work_areas = backend.work_areas();
+            let rect = match position {
+                PositionKind::Home => entry.home_at(profile.as_deref(), &work_areas),
+                PositionKind::Target => entry.target_at(profile.as_deref(), &work_areas),
+            };
+            let rect = if entry.clamp_to_work_area {
+                clamp_to_work_area(rect, &work_areas)
+            } else {
+                rect
+            };
+            animate_window(backend, entry.id, rect, &entry.position_flags, animation);
+            let result = move_window_with_retry(
+                backend,
+                entry.id,
+                rect,
+                &entry.position_flags,
+                retries,
+                backoff_ms,
+            );
+            match &result {
+                Err(e) => warn!("Error moving window '{}': {}", entry.title, e),
+                Ok(()) => {
+                    if let Some(actual) =
+                        enforce_window_rect(backend, entry.id, rect, &entry.position_flags)
+                    {
+                        warn!(
+                            "Window '{}' could not achieve requested rect {:?}; it is at {:?}.",
+                            entry.title, rect, actual
+                        );
+                    }
+                }
+            }
+
+            let mut workspaces = workspaces.lock().unwrap();
+            if let Some(target) = workspaces
+                .iter_mut()
+                .find(|w| w.runtime_id == workspace)
+                .and_then(|w| w.windows.iter_mut().find(|w| w.runtime_id == window))
+                .filter(|w| w.id == entry.id)
+            {
+                target.move_error = result
+                    .err()
+                    .map(|e| MultiManagerError::win32("Move", &workspace_name, &entry.title, &e));
+            }
+        }
+        WindowOp::Validate {
+            check_hotkeys,
+            report,
+        } => {
+            let snapshot = workspaces.lock().unwrap().clone();
+            let registered = registered_hotkeys.lock().unwrap().clone();
+            report.send(validate_all(
+                &snapshot,
+                check_hotkeys.then_some(&registered),
+            ));
+        }
+    }
+}
+
+/// Runs an operation on a snapshot of one workspace, writes the runtime state back, and
+/// counts the activation in the usage statistics.
+///
+/// Windows matching the workspace's auto-capture rules are adopted first, so they are moved
+/// along with the others, and added to the shared workspace as well.
+///
+/// # Arguments
+/// - `backend`: The window system the operation acts on.
+/// - `workspaces`: The shared workspace list.
+/// - `usage`: The shared usage statistics.
+/// - `id`: The runtime ID of the workspace to operate on.
+/// - `operation`: Moves the snapshot's windows.
+fn run_on_workspace(
+    backend: &impl WindowBackend,
+    workspaces: &Arc<Mutex<Vec<Workspace>>>,
+    usage: &Arc<Mutex<UsageStats>>,
+    id: RuntimeId,
+    operation: impl FnOnce(&mut Workspace),
+) {
+    let Some(mut snapshot) = find_workspace(&workspaces.lock().unwrap(), id).cloned() else {
+        warn!("Operation requested for unknown workspace {:?}.", id);
+        return;
+    };
+
+    let adopted = adopt_rule_windows(backend, &mut snapshot);
+    if !adopted.is_empty() {
+        let mut workspaces = workspaces.lock().unwrap();
+        match workspaces
+            .iter_mut()
+            .find(|workspace| workspace.runtime_id == snapshot.runtime_id)
+        {
+            Some(workspace) => workspace.windows.extend(adopted),
+            None => warn!(
+                "Workspace '{}' was removed while adopting windows; they are not kept.",
+                snapshot.name
+            ),
+        }
+    }
+
+    operation(&mut snapshot);
+
+    match workspaces
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .find(|workspace| workspace.runtime_id == snapshot.runtime_id)
+    {
+        Some(workspace) => workspace.merge_runtime_state(&snapshot),
+        None => warn!(
+            "Workspace '{}' was removed while it was being toggled; discarding results.",
+            snapshot.name
+        ),
+    }
+
+    let direction = snapshot.activity.newest_first().next().map(|e| e.direction);
+    if let Some(direction) = direction {
+        let mut usage = usage.lock().unwrap();
+        usage.record(&snapshot.name, direction);
+        if let Err(e) = save_usage_stats(&usage, &config_path(USAGE_FILE)) {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// Finds a workspace by its runtime ID.
+fn find_workspace(workspaces: &[Workspace], id: RuntimeId) -> Option<&Workspace> {
+    workspaces
+        .iter()
+        .find(|workspace| workspace.runtime_id == id)
+}
+
+/// Finds a window of a workspace by its runtime ID.
+fn find_window(workspace: &Workspace, id: RuntimeId) -> Option<&Window> {
+    workspace
+        .windows
+        .iter()
+        .find(|window| window.runtime_id == id)
+}
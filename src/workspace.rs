@@ -1,25 +1,430 @@
-use crate::gui::App;
+use crate::activity::ActivityLog;
+use crate::animation::AnimationOverride;
+use crate::encryption::{decrypt, encrypt, encryption_enabled, is_encrypted};
+use crate::error::MultiManagerError;
+use crate::hotkey::{
+    canonical_hotkey, canonical_hotkey_base, migrate_hotkey, migrate_hotkey_base, Hotkey,
+};
+use crate::key_hook::set_hooked_hotkeys;
+use crate::layout::{AutoArrange, GridCell};
+use crate::schedule::Schedule;
+use crate::utils::format_ago;
 use crate::window_manager::register_hotkey;
+use chrono::Local;
 use log::{error, info, warn};
-use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 /// Represents a workspace, which groups multiple windows and allows toggling between specific positions.
 ///
 /// # Fields
 /// - `name`: The name of the workspace.
-/// - `hotkey`: An optional hotkey assigned to the workspace for activation.
+/// - `hotkey`: An optional hotkey assigned to the workspace for activation, such as
+///   "Ctrl+Alt+H" or "Caps+H", in any spelling `Hotkey::parse` accepts.
+/// - `hotkey_mode`: Whether `hotkey` is registered with the system or caught by the keyboard
+///   hook, for combinations `RegisterHotKey` can't take.
+/// - `home_hotkey`: An optional hotkey that sends every window home, whatever the current state.
+/// - `target_hotkey`: An optional hotkey that sends every window to its target, whatever the
+///   current state.
+/// - `save_hotkey`: An optional hotkey that stores the current position of every window as its
+///   target and saves the workspace file, to iterate on a layout without opening the GUI.
+/// - `window_hotkey_base`: Optional modifiers, e.g. "Ctrl+Alt+Shift", that toggle one of the
+///   workspace's first nine windows when pressed with its number.
+/// - `gesture`: An optional mouse gesture, as strokes such as "DR", that toggles the workspace
+///   when drawn on the desktop with the right button held.
+/// - `controller_buttons`: An optional game controller button combination, such as "LB+RB+A",
+///   that toggles the workspace when pressed on any connected controller.
 /// - `windows`: A list of windows belonging to this workspace.
 /// - `disabled`: A flag indicating whether the workspace is disabled.
-#[derive(Clone, Serialize, Deserialize)]
+/// - `move_retries`: How many additional attempts are made when moving a window fails.
+/// - `retry_backoff_ms`: The delay before the first retry, doubled after each failed attempt.
+/// - `move_delay_ms`: The delay inserted between moving successive windows during a toggle.
+/// - `animation`: Whether the workspace's windows glide to their positions with the global
+///   animation, move instantly, or use an animation of their own.
+/// - `focus_behavior`: Which window, if any, is brought to the foreground after a toggle.
+/// - `match_rule`: How a window is chosen when several candidates match a stale entry.
+/// - `mode`: How the workspace's hotkey acts on its windows.
+/// - `exclusive`: What happens to other visible windows when the workspace is activated.
+/// - `taskbar`: Whether the taskbar is set to auto-hide or always shown when the workspace is
+///   sent to its targets.
+/// - `other_desktop`: What happens to windows that are on another virtual desktop when the
+///   workspace is toggled.
+/// - `on_window_close`: What happens to the other windows when one of the workspace's windows
+///   closes while it is at its targets.
+/// - `profiles`: Display setups with their own window positions, chosen at toggle time by
+///   the connected monitors.
+/// - `color`: An optional color, as RGB, shown as a swatch next to the workspace's name.
+/// - `icon`: An optional emoji or symbol shown before the workspace's name.
+/// - `notes`: Free-form notes on what the layout is for and any app-specific quirks.
+/// - `capture_rules`: Patterns for windows that are added to the workspace automatically
+///   when it is activated, so new instances of an app don't need capturing by hand.
+/// - `auto_arrange`: If set, target positions are computed at toggle time by a layout
+///   algorithm over the windows that exist, instead of using the stored targets.
+/// - `pre_command`: A shell command run before the windows move, waited for up to
+///   `PRE_COMMAND_TIMEOUT`. `%WORKSPACE%` and `%DIRECTION%` are replaced; see
+///   `expand_hook_command`.
+/// - `post_command`: A shell command started after the windows moved, with the same
+///   placeholders.
+/// - `virtual_desktop`: If set, the name of a virtual desktop the workspace switches to (and
+///   creates if needed) when sent to its targets, taking its windows along.
+/// - `schedule`: If set, the times of day the workspace is sent to its targets and home
+///   without a hotkey.
+/// - `suppressed_windows`: Handles of windows minimized or hidden by exclusive mode, restored
+///   on the next toggle. This is runtime state only and is not saved.
+/// - `previous_taskbar_auto_hide`: The taskbar's auto-hide setting before the workspace
+///   changed it, restored when it goes home. This is runtime state only and is not saved.
+/// - `previous_desktop`: The virtual desktop that was current before switching to the
+///   workspace's own, returned to when it goes home. This is runtime state only and is not saved.
+/// - `solo`: What solo mode changed while it is on, put back when it is turned off. This is
+///   runtime state only and is not saved.
+/// - `last_focused`: The handle of the workspace window that had focus when the workspace
+///   last went home, focused again when it is next activated. This is runtime state only and
+///   is not saved.
+/// - `activity`: A bounded history of recent toggles and their per-window results. This is
+///   runtime state only and is not saved.
+/// - `selected`: Whether the workspace is selected in the GUI, e.g. for exporting. This is
+///   runtime state only and is not saved.
+/// - `runtime_id`: Identifies the workspace while the program runs; see `RuntimeId`.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Workspace {
     pub name: String,
     pub hotkey: Option<String>,
+    #[serde(default)]
+    pub hotkey_mode: HotkeyMode,
+    #[serde(default)]
+    pub home_hotkey: Option<String>,
+    #[serde(default)]
+    pub target_hotkey: Option<String>,
+    #[serde(default)]
+    pub save_hotkey: Option<String>,
+    #[serde(default)]
+    pub window_hotkey_base: Option<String>,
+    #[serde(default)]
+    pub gesture: Option<String>,
+    #[serde(default)]
+    pub controller_buttons: Option<String>,
     pub windows: Vec<Window>,
     pub disabled: bool,
     pub valid: bool,
+    #[serde(default = "default_move_retries")]
+    pub move_retries: u32,
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    #[serde(default)]
+    pub move_delay_ms: u64,
+    #[serde(default)]
+    pub animation: AnimationOverride,
+    #[serde(default)]
+    pub focus_behavior: FocusBehavior,
+    #[serde(default)]
+    pub match_rule: MatchRule,
+    #[serde(default)]
+    pub mode: WorkspaceMode,
+    #[serde(default)]
+    pub exclusive: ExclusiveAction,
+    #[serde(default)]
+    pub taskbar: TaskbarAutoHide,
+    #[serde(default)]
+    pub other_desktop: OtherDesktopAction,
+    #[serde(default)]
+    pub on_window_close: CloseReaction,
+    #[serde(default)]
+    pub profiles: Vec<MonitorProfile>,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+    #[serde(default)]
+    pub icon: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub capture_rules: Vec<CaptureRule>,
+    #[serde(default)]
+    pub auto_arrange: Option<AutoArrange>,
+    #[serde(default)]
+    pub pre_command: String,
+    #[serde(default)]
+    pub post_command: String,
+    #[serde(default)]
+    pub virtual_desktop: Option<String>,
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    #[serde(skip)]
+    pub suppressed_windows: Vec<usize>,
+    #[serde(skip)]
+    pub previous_desktop: Option<usize>,
+    #[serde(skip)]
+    pub previous_taskbar_auto_hide: Option<bool>,
+    #[serde(skip)]
+    pub solo: Option<SoloState>,
+    #[serde(skip)]
+    pub last_focused: Option<usize>,
+    #[serde(skip)]
+    pub activity: ActivityLog,
+    #[serde(skip)]
+    pub selected: bool,
+    #[serde(skip)]
+    pub runtime_id: RuntimeId,
+}
+
+/// An identity given to each workspace and window for as long as the program runs and kept
+/// by clones, so results computed on a snapshot can be matched back to the entries they came
+/// from, even if those were renamed, reordered, or recaptured meanwhile. It is not saved.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct RuntimeId(u64);
+
+impl Default for RuntimeId {
+    /// Returns a new identity, different from every other.
+    fn default() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        RuntimeId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A display setup, such as "Docked 3-monitor" or "Laptop only", with its own window positions.
+///
+/// The positions themselves are stored on each window in `Window::profile_positions`.
+///
+/// # Fields
+/// - `name`: The name of the profile, unique within its workspace.
+/// - `monitors`: The bounds `(x, y, width, height)` of every monitor in the setup. The profile
+///   applies when exactly these monitors are connected.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MonitorProfile {
+    pub name: String,
+    pub monitors: Vec<(i32, i32, i32, i32)>,
+}
+
+impl MonitorProfile {
+    /// Checks whether the connected monitors are this profile's setup, in any order.
+    ///
+    /// # Arguments
+    /// - `monitors`: The bounds of the connected monitors.
+    ///
+    /// # Returns
+    /// - `true` if the same monitors, at the same positions and resolutions, are connected.
+    pub fn matches(&self, monitors: &[(i32, i32, i32, i32)]) -> bool {
+        let mut expected = self.monitors.clone();
+        let mut actual = monitors.to_vec();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        expected == actual
+    }
+}
+
+/// A pattern for windows a workspace captures by itself.
+///
+/// Empty fields match any window; a rule with every field empty matches none, so a fresh
+/// rule can't adopt every window on the desktop.
+///
+/// # Fields
+/// - `exe`: The executable file name, e.g. `notepad.exe`, compared case-insensitively.
+/// - `class_name`: The window class name, compared exactly.
+/// - `title`: Text the window title must contain, compared case-insensitively.
+/// - `target`: The target position `(x, y, width, height)` given to adopted windows. Their home
+///   is wherever they were when adopted.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CaptureRule {
+    #[serde(default)]
+    pub exe: String,
+    #[serde(default)]
+    pub class_name: String,
+    #[serde(default)]
+    pub title: String,
+    pub target: (i32, i32, i32, i32),
+}
+
+impl CaptureRule {
+    /// Returns whether the rule has no pattern at all, in which case it matches nothing.
+    pub fn is_empty(&self) -> bool {
+        self.exe.trim().is_empty()
+            && self.class_name.trim().is_empty()
+            && self.title.trim().is_empty()
+    }
+
+    /// Checks whether a window matches the rule.
+    ///
+    /// # Arguments
+    /// - `exe`: The window's executable file name.
+    /// - `class_name`: The window's class name.
+    /// - `title`: The window's current title.
+    ///
+    /// # Example
+    /// ```
+    /// # use multi_manager::workspace::CaptureRule;
+    /// let rule = CaptureRule { exe: "code.exe".into(), ..Default::default() };
+    /// assert!(rule.matches("Code.exe", "Chrome_WidgetWin_1", "main.rs - Visual Studio Code"));
+    /// ```
+    pub fn matches(&self, exe: &str, class_name: &str, title: &str) -> bool {
+        let (pattern_exe, pattern_class, pattern_title) =
+            (self.exe.trim(), self.class_name.trim(), self.title.trim());
+        !self.is_empty()
+            && (pattern_exe.is_empty() || exe.eq_ignore_ascii_case(pattern_exe))
+            && (pattern_class.is_empty() || class_name == pattern_class)
+            && (pattern_title.is_empty()
+                || title.to_lowercase().contains(&pattern_title.to_lowercase()))
+    }
+}
+
+/// A window's home and target positions under one monitor profile.
+///
+/// # Fields
+/// - `profile`: The name of the `MonitorProfile` these positions belong to.
+/// - `home`: The home position `(x, y, width, height)` under that profile.
+/// - `target`: The target position `(x, y, width, height)` under that profile.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProfilePositions {
+    pub profile: String,
+    pub home: (i32, i32, i32, i32),
+    pub target: (i32, i32, i32, i32),
+}
+
+/// Determines what activating a workspace does to its windows.
+///
+/// # Variants
+/// - `Toggle`: Moves windows between their home and target positions.
+/// - `BringToFront`: Restores and raises windows in order without repositioning them.
+/// - `Solo`: Maximizes the workspace's solo window and minimizes its other windows, for
+///   focused work; the next activation puts everything back.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum WorkspaceMode {
+    #[default]
+    Toggle,
+    BringToFront,
+    Solo,
+}
+
+/// What a workspace in solo mode changed, so it can be put back.
+///
+/// # Fields
+/// - `window`: The handle of the window that was maximized.
+/// - `rect`: Where that window was before it was maximized.
+/// - `parked`: The handles of the other windows that were minimized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoloState {
+    pub window: usize,
+    pub rect: (i32, i32, i32, i32),
+    pub parked: Vec<usize>,
+}
+
+/// Controls what exclusive mode does to windows outside the workspace.
+///
+/// # Variants
+/// - `Off`: Other windows are left alone.
+/// - `Minimize`: Other visible windows are minimized on activation.
+/// - `Hide`: Other visible windows are hidden on activation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum ExclusiveAction {
+    #[default]
+    Off,
+    Minimize,
+    Hide,
+}
+
+/// What a workspace does to the taskbar's auto-hide setting when it is sent to its targets.
+///
+/// # Variants
+/// - `Unchanged`: The taskbar is left alone.
+/// - `AutoHide`: The taskbar is set to auto-hide, so full-screen layouts can use its space.
+/// - `AlwaysShow`: Auto-hide is turned off, so the taskbar is always shown.
+///
+/// Either way, the previous setting is restored when the workspace goes home.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum TaskbarAutoHide {
+    #[default]
+    Unchanged,
+    AutoHide,
+    AlwaysShow,
+}
+
+/// What a toggle does with a workspace window that is on another virtual desktop.
+///
+/// # Variants
+/// - `Pull`: The window is brought to the current desktop and moved there.
+/// - `Skip`: The window is left alone on its desktop.
+/// - `SwitchDesktop`: The toggle first switches to the desktop of the workspace's windows;
+///   windows on yet another desktop are brought to it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum OtherDesktopAction {
+    #[default]
+    Pull,
+    Skip,
+    SwitchDesktop,
+}
+
+/// What a workspace at its targets does when one of its windows closes.
+///
+/// # Variants
+/// - `Nothing`: The other windows stay where they are.
+/// - `Rearrange`: An auto-arranging workspace lays the remaining windows out again, so the
+///   closed window leaves no hole.
+/// - `AllHome`: Every window is sent home.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum CloseReaction {
+    #[default]
+    Nothing,
+    Rearrange,
+    AllHome,
+}
+
+/// How the windows matching an entry are ordered to pick one by its instance number.
+///
+/// # Variants
+/// - `ZOrder`: From the topmost window down, as in Alt+Tab.
+/// - `CreationOrder`: By when their process was started, oldest first. Windows of the same
+///   process are ordered by handle, which usually follows the order they were opened in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum InstanceOrder {
+    #[default]
+    ZOrder,
+    CreationOrder,
+}
+
+/// Picks one of several windows matching an entry deterministically, e.g. the second of three
+/// Chrome windows, instead of by the workspace's match rule.
+///
+/// # Fields
+/// - `number`: Which of the matching windows to take, counting from 1.
+/// - `order`: How the matching windows are ordered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct InstanceMatch {
+    pub number: usize,
+    #[serde(default)]
+    pub order: InstanceOrder,
+}
+
+/// Decides between several windows that match a workspace entry whose HWND is no longer valid.
+///
+/// # Variants
+/// - `MostRecentlyActive`: Picks the matching window highest in the z-order.
+/// - `Ask`: Leaves the entry unresolved and lets the user pick from the candidates in the GUI.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum MatchRule {
+    #[default]
+    MostRecentlyActive,
+    Ask,
+}
+
+/// Controls how window focus is handled after a workspace is toggled.
+///
+/// # Variants
+/// - `ActivateAll`: Activates every window in order, leaving the last one focused, or the one
+///   that had focus when the workspace last went home if it still exists.
+/// - `None`: Leaves focus untouched.
+/// - `Window`: Focuses only the window at the given index in the workspace.
+/// - `Preserve`: Restores whichever window was focused before the toggle.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum FocusBehavior {
+    #[default]
+    ActivateAll,
+    None,
+    Window(usize),
+    Preserve,
 }
 
 impl Workspace {
@@ -32,22 +437,235 @@ impl Workspace {
     ///
     /// # Returns
     /// - `Ok(())` if the hotkey is valid and successfully set.
-    /// - `Err` with an error message if the hotkey is invalid.
+    /// - `Err(InvalidHotkey)` if the hotkey is invalid.
     ///
     /// # Example
-    /// ```
+    /// ```ignore
     /// let mut workspace = Workspace::new("Example");
     /// if let Err(e) = workspace.set_hotkey("Ctrl+Shift+P") {
     ///     println!("Failed to set hotkey: {}", e);
     /// }
     /// ```
-    pub fn set_hotkey(&mut self, hotkey: &str) -> Result<(), String> {
-        if is_valid_key_combo(hotkey) {
-            self.hotkey = Some(hotkey.to_string());
-            Ok(())
-        } else {
-            Err(format!("Invalid hotkey: '{}'", hotkey))
+    pub fn set_hotkey(&mut self, hotkey: &str) -> Result<(), MultiManagerError> {
+        match canonical_hotkey(hotkey) {
+            Some(canonical) => {
+                self.hotkey = Some(canonical);
+                Ok(())
+            }
+            None => Err(MultiManagerError::InvalidHotkey {
+                hotkey: hotkey.to_string(),
+            }),
+        }
+    }
+
+    /// Builds the text shown in the workspace's header in the GUI.
+    ///
+    /// If the workspace has been toggled since startup, the direction of the most recent
+    /// toggle and how long ago it happened are appended, so live layouts are easy to spot.
+    ///
+    /// # Returns
+    /// - The name, e.g. "Coding", or with the latest activation, e.g. "Coding  ➡ 5m ago".
+    pub fn get_header_text(&self) -> String {
+        match self.activity.newest_first().next() {
+            Some(entry) => {
+                let elapsed = (Local::now() - entry.triggered_at)
+                    .to_std()
+                    .unwrap_or_default();
+                format!(
+                    "{}  {} {}",
+                    self.name,
+                    entry.direction.arrow(),
+                    format_ago(elapsed)
+                )
+            }
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns the numbered hotkey of one of the workspace's windows: the window hotkey base
+    /// followed by the window's number, e.g. "Ctrl+Alt+Shift+2" for the second window.
+    ///
+    /// # Arguments
+    /// - `index`: The index of the window in the workspace.
+    ///
+    /// # Returns
+    /// - The hotkey, or `None` if the workspace has no valid base or the window is past the
+    ///   ninth.
+    ///
+    /// # Example
+    /// ```
+    /// # let mut workspace: multi_manager::workspace::Workspace = serde_json::from_str(
+    /// #     r#"{"name":"Example","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+    /// # ).unwrap();
+    /// workspace.window_hotkey_base = Some("Ctrl+Alt".to_string());
+    /// assert_eq!(workspace.window_digit_hotkey(0).as_deref(), Some("Ctrl+Alt+1"));
+    /// ```
+    pub fn window_digit_hotkey(&self, index: usize) -> Option<String> {
+        let base = self
+            .window_hotkey_base
+            .as_ref()
+            .filter(|base| is_valid_hotkey_base(base))?;
+        (index < 9).then(|| format!("{}+{}", base, index + 1))
+    }
+
+    /// Finds the monitor profile for the connected displays.
+    ///
+    /// # Arguments
+    /// - `monitors`: The bounds of the connected monitors.
+    ///
+    /// # Returns
+    /// - The name of the first profile whose monitors match, or `None` to use the default
+    ///   positions.
+    pub fn active_profile(&self, monitors: &[(i32, i32, i32, i32)]) -> Option<&str> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.matches(monitors))
+            .map(|profile| profile.name.as_str())
+    }
+
+    /// Saves every window's current home and target positions as a monitor profile.
+    ///
+    /// An existing profile with the same name is replaced, including its monitors.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the profile.
+    /// - `monitors`: The bounds of the monitors the profile applies to, usually the ones
+    ///   connected now.
+    ///
+    /// # Example
+    /// ```ignore
+    /// workspace.save_profile("Docked", Win32Backend.monitors());
+    /// ```
+    pub fn save_profile(&mut self, name: &str, monitors: Vec<(i32, i32, i32, i32)>) {
+        let profile = MonitorProfile {
+            name: name.to_string(),
+            monitors,
+        };
+        match self.profiles.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+
+        for window in &mut self.windows {
+            window.profile_positions.retain(|p| p.profile != name);
+            window.profile_positions.push(ProfilePositions {
+                profile: name.to_string(),
+                home: window.home,
+                target: window.target,
+            });
         }
+        info!(
+            "Saved monitor profile '{}' for workspace '{}'.",
+            name, self.name
+        );
+    }
+
+    /// Copies a monitor profile's positions into the windows' home and target, for editing.
+    ///
+    /// Windows added after the profile was saved keep their current positions.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the profile to load.
+    pub fn load_profile(&mut self, name: &str) {
+        for window in &mut self.windows {
+            if let Some(positions) = window.profile_positions.iter().find(|p| p.profile == name) {
+                window.home = positions.home;
+                window.target = positions.target;
+            }
+        }
+    }
+
+    /// Deletes a monitor profile and the window positions saved for it.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the profile to delete.
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|p| p.name != name);
+        for window in &mut self.windows {
+            window.profile_positions.retain(|p| p.profile != name);
+        }
+    }
+
+    /// Copies runtime state produced by operating on a snapshot of this workspace back into it.
+    ///
+    /// Window operations run on a clone so the workspace list isn't locked while windows move.
+    /// Only state those operations change is copied, so edits made in the GUI meanwhile are
+    /// preserved. The workspace's restore state is always copied, since it is needed to undo
+    /// what the operation did; each window's state is copied onto the entry with the same
+    /// `runtime_id`, so windows added, removed, or reordered meanwhile don't mix up results.
+    ///
+    /// # Arguments
+    /// - `snapshot`: The clone the operation was performed on.
+    pub fn merge_runtime_state(&mut self, snapshot: &Workspace) {
+        self.activity = snapshot.activity.clone();
+        self.suppressed_windows = snapshot.suppressed_windows.clone();
+        self.previous_desktop = snapshot.previous_desktop;
+        self.previous_taskbar_auto_hide = snapshot.previous_taskbar_auto_hide;
+        self.last_focused = snapshot.last_focused;
+        self.solo = snapshot.solo.clone();
+
+        let mut updates: Vec<&Window> = snapshot.windows.iter().collect();
+        for window in &mut self.windows {
+            let Some(position) = updates
+                .iter()
+                .position(|updated| updated.runtime_id == window.runtime_id)
+            else {
+                continue;
+            };
+            let updated = updates.remove(position);
+            window.id = updated.id;
+            window.valid = updated.valid;
+            window.move_error = updated.move_error.clone();
+            window.hidden = updated.hidden;
+            window.candidates = updated.candidates.clone();
+        }
+    }
+
+    /// Removes the windows selected in the GUI, keeping the designated focus window pointing
+    /// at the same entry.
+    ///
+    /// # Returns
+    /// - The removed windows, in order and no longer selected, e.g. to add to another workspace.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let moved = source.take_selected_windows();
+    /// destination.windows.extend(moved);
+    /// ```
+    pub fn take_selected_windows(&mut self) -> Vec<Window> {
+        let mut taken = self.take_windows_where(|w| w.selected);
+        for window in &mut taken {
+            window.selected = false;
+        }
+        taken
+    }
+
+    /// Removes the windows matching a predicate, keeping the designated focus window pointing
+    /// at the same entry.
+    ///
+    /// # Arguments
+    /// - `predicate`: Returns `true` for the windows to remove.
+    ///
+    /// # Returns
+    /// - The removed windows, in order.
+    pub fn take_windows_where(&mut self, predicate: impl Fn(&Window) -> bool) -> Vec<Window> {
+        if let FocusBehavior::Window(focus_index) = self.focus_behavior {
+            let focused_removed = self.windows.get(focus_index).is_some_and(&predicate);
+            let removed_before = self.windows[..focus_index.min(self.windows.len())]
+                .iter()
+                .filter(|w| predicate(w))
+                .count();
+            self.focus_behavior = if focused_removed {
+                FocusBehavior::ActivateAll
+            } else {
+                FocusBehavior::Window(focus_index - removed_before)
+            };
+        }
+
+        let (taken, kept): (Vec<Window>, Vec<Window>) =
+            self.windows.drain(..).partition(|w| predicate(w));
+        self.windows = kept;
+        taken
     }
 }
 
@@ -55,20 +673,254 @@ impl Workspace {
 ///
 /// # Fields
 /// - `id`: The unique identifier (HWND) of the window.
-/// - `title`: The title of the window.
+/// - `title`: The title of the window, refreshed while the window is valid.
+/// - `capture_title`: The title the window had when it was captured, kept for matching.
+/// - `exe`: The file name of the executable that owns the window, used for matching.
+/// - `class_name`: The window class name, used for matching.
 /// - `home`: The home position `(x, y, width, height)` of the window.
 /// - `target`: The target position `(x, y, width, height)` of the window.
-#[derive(Clone, Serialize, Deserialize)]
+/// - `position_flags`: Options controlling how `SetWindowPos` is applied to this window.
+/// - `hide_at`: Which of the two positions, if any, is a hidden pseudo-position.
+/// - `clamp_to_work_area`: Whether positions are fitted inside the work area of their monitor
+///   when moving, so they never overlap the taskbar or docked toolbars.
+/// - `target_action`: Whether the window is moved, minimized, or closed when the workspace is
+///   sent to its targets.
+/// - `instance`: If set, a stale entry is re-resolved to this instance of the matching
+///   windows, whatever their titles, instead of by the workspace's match rule.
+/// - `toggle_hotkey`: An optional hotkey that toggles just this window between its home and
+///   target, leaving the workspace's other windows where they are.
+/// - `solo`: Whether this is the window a workspace in solo mode maximizes. If no window is
+///   marked, the first one is used.
+/// - `settle_ms`: How long to wait after moving this window so the application can apply the new geometry.
+/// - `saved_style`: The original styles and rect of a window made borderless fullscreen, used
+///   to restore it.
+/// - `profile_positions`: The window's positions under each of the workspace's monitor
+///   profiles. `home` and `target` apply when no profile matches.
+/// - `home_cell`: If set, the home position is computed from grid cells on a monitor at
+///   toggle time, in place of `home` and the profile positions.
+/// - `target_cell`: If set, the target position is computed from grid cells likewise.
+/// - `move_error`: The error from the most recent move if it still failed after all retries.
+///   This is runtime state only and is not saved.
+/// - `hidden`: Whether the window is currently hidden by its hidden pseudo-position.
+///   This is runtime state only and is not saved.
+/// - `candidates`: Handles of matching windows awaiting a choice by the user.
+///   This is runtime state only and is not saved.
+/// - `selected`: Whether the window is selected in the GUI for a bulk edit.
+///   This is runtime state only and is not saved.
+/// - `runtime_id`: Identifies the window while the program runs; see `RuntimeId`.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Window {
     pub id: usize,
     pub title: String,
+    #[serde(default)]
+    pub capture_title: String,
+    #[serde(default)]
+    pub exe: String,
+    #[serde(default)]
+    pub class_name: String,
     pub home: (i32, i32, i32, i32),
     pub target: (i32, i32, i32, i32),
     pub valid: bool,
+    #[serde(default)]
+    pub position_flags: PositionFlags,
+    #[serde(default)]
+    pub settle_ms: u64,
+    #[serde(default)]
+    pub hide_at: HidePosition,
+    #[serde(default)]
+    pub clamp_to_work_area: bool,
+    #[serde(default)]
+    pub target_action: WindowAction,
+    #[serde(default)]
+    pub instance: Option<InstanceMatch>,
+    #[serde(default)]
+    pub toggle_hotkey: Option<String>,
+    #[serde(default)]
+    pub solo: bool,
+    #[serde(default)]
+    pub saved_style: Option<SavedWindowStyle>,
+    #[serde(default)]
+    pub profile_positions: Vec<ProfilePositions>,
+    #[serde(default)]
+    pub home_cell: Option<GridCell>,
+    #[serde(default)]
+    pub target_cell: Option<GridCell>,
+    #[serde(skip)]
+    pub move_error: Option<MultiManagerError>,
+    #[serde(skip)]
+    pub hidden: bool,
+    #[serde(skip)]
+    pub candidates: Vec<usize>,
+    #[serde(skip)]
+    pub selected: bool,
+    #[serde(skip)]
+    pub runtime_id: RuntimeId,
+}
+
+impl Window {
+    /// Returns the home position to use under a monitor profile.
+    ///
+    /// # Arguments
+    /// - `profile`: The active monitor profile, if any.
+    ///
+    /// # Returns
+    /// - The profile's home position if this window has positions for it, otherwise `home`.
+    pub fn home_for(&self, profile: Option<&str>) -> (i32, i32, i32, i32) {
+        self.profile_positions_for(profile)
+            .map_or(self.home, |p| p.home)
+    }
+
+    /// Returns the target position to use under a monitor profile.
+    ///
+    /// # Arguments
+    /// - `profile`: The active monitor profile, if any.
+    ///
+    /// # Returns
+    /// - The profile's target position if this window has positions for it, otherwise
+    ///   `target`.
+    pub fn target_for(&self, profile: Option<&str>) -> (i32, i32, i32, i32) {
+        self.profile_positions_for(profile)
+            .map_or(self.target, |p| p.target)
+    }
+
+    /// Returns the home position to move to: from the home grid cell if it has one and its
+    /// monitor is connected, otherwise as `home_for`.
+    ///
+    /// # Arguments
+    /// - `profile`: The active monitor profile, if any.
+    /// - `work_areas`: The work area of every connected monitor.
+    pub fn home_at(
+        &self,
+        profile: Option<&str>,
+        work_areas: &[(i32, i32, i32, i32)],
+    ) -> (i32, i32, i32, i32) {
+        self.home_cell
+            .and_then(|cell| cell.rect(work_areas))
+            .unwrap_or_else(|| self.home_for(profile))
+    }
+
+    /// Returns the target position to move to: from the target grid cell if it has one and
+    /// its monitor is connected, otherwise as `target_for`.
+    ///
+    /// # Arguments
+    /// - `profile`: The active monitor profile, if any.
+    /// - `work_areas`: The work area of every connected monitor.
+    pub fn target_at(
+        &self,
+        profile: Option<&str>,
+        work_areas: &[(i32, i32, i32, i32)],
+    ) -> (i32, i32, i32, i32) {
+        self.target_cell
+            .and_then(|cell| cell.rect(work_areas))
+            .unwrap_or_else(|| self.target_for(profile))
+    }
+
+    /// Finds this window's positions for a monitor profile.
+    fn profile_positions_for(&self, profile: Option<&str>) -> Option<&ProfilePositions> {
+        let profile = profile?;
+        self.profile_positions.iter().find(|p| p.profile == profile)
+    }
+}
+
+/// The window styles and rect captured before a window was made borderless fullscreen.
+///
+/// # Fields
+/// - `style`: The original `GWL_STYLE` value.
+/// - `ex_style`: The original `GWL_EXSTYLE` value.
+/// - `rect`: The original position `(x, y, width, height)` of the window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SavedWindowStyle {
+    pub style: isize,
+    pub ex_style: isize,
+    pub rect: (i32, i32, i32, i32),
+}
+
+/// How a workspace's hotkey is recognized.
+///
+/// # Variants
+/// - `Register`: Registered with the system with `RegisterHotKey`, which fails for combinations
+///   another program holds or Windows reserves.
+/// - `Hook`: Caught by the low-level keyboard hook in `key_hook`, which takes such
+///   combinations from the program holding them, with the trade-offs described there.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum HotkeyMode {
+    #[default]
+    Register,
+    Hook,
+}
+
+/// Selects which of a window's positions hides the window instead of moving it.
+///
+/// # Variants
+/// - `Never`: Both home and target are real positions.
+/// - `Home`: Toggling home hides the window; toggling to target shows it again.
+/// - `Target`: Toggling to target hides the window; toggling home shows it again.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum HidePosition {
+    #[default]
+    Never,
+    Home,
+    Target,
+}
+
+/// What happens to a window when its workspace is sent to its targets, so activating a layout
+/// can also clear away windows that don't belong in it.
+///
+/// # Variants
+/// - `Move`: The window is moved to its target.
+/// - `Minimize`: The window is minimized; toggling home restores it and moves it home.
+/// - `Close`: The window is asked to close, as if its close button was clicked, so the app can
+///   still prompt to save. Toggling home skips it while it is closed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum WindowAction {
+    #[default]
+    Move,
+    Minimize,
+    Close,
+}
+
+/// Per-window options that map onto `SetWindowPos` flags.
+///
+/// # Fields
+/// - `no_activate`: Don't activate the window when moving it (`SWP_NOACTIVATE`).
+/// - `no_size`: Move only, keeping the current size (`SWP_NOSIZE`).
+/// - `no_move`: Resize only, keeping the current position (`SWP_NOMOVE`).
+/// - `keep_z_order`: Keep the current z-order instead of raising the window (`SWP_NOZORDER`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PositionFlags {
+    pub no_activate: bool,
+    pub no_size: bool,
+    pub no_move: bool,
+    pub keep_z_order: bool,
+}
+
+impl Default for PositionFlags {
+    fn default() -> Self {
+        PositionFlags {
+            no_activate: false,
+            no_size: false,
+            no_move: false,
+            keep_z_order: true,
+        }
+    }
+}
+
+/// Default number of retries for a failed window move.
+pub fn default_move_retries() -> u32 {
+    3
+}
+
+/// Default delay in milliseconds before retrying a failed window move.
+pub fn default_retry_backoff_ms() -> u64 {
+    50
 }
 
 /// Validates if a key combination string is in a valid format.
 ///
+/// Any form `Hotkey::parse` accepts is valid, including aliases such as "Control" and
+/// "Return" and modifiers in any order.
+///
 /// # Arguments
 /// - `input`: The key combination string to validate (e.g., "Ctrl+Alt+H").
 ///
@@ -77,7 +929,7 @@ pub struct Window {
 /// - `false` otherwise.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// if is_valid_key_combo("Ctrl+Shift+P") {
 ///     println!("Valid key combo.");
 /// } else {
@@ -85,114 +937,474 @@ pub struct Window {
 /// }
 /// ```
 pub fn is_valid_key_combo(input: &str) -> bool {
-    let pattern = r"^(?:(?:Ctrl|Alt|Shift|Win)\+)?(?:(?:Ctrl|Alt|Shift|Win)\+)?(?:(?:Ctrl|Alt|Shift|Win)\+)?(?:(?:Ctrl|Alt|Shift|Win)\+)?(?:F(?:[1-9]|1[0-2]|1[3-9]|2[0-4])|[A-Z]|[0-9]|NUMPAD[0-9]|NUMPAD(?:MULTIPLY|ADD|SEPARATOR|SUBTRACT|DOT|DIVIDE)|UP|DOWN|LEFT|RIGHT|BACKSPACE|TAB|ENTER|PAUSE|CAPSLOCK|ESCAPE|SPACE|PAGEUP|PAGEDOWN|END|HOME|INSERT|DELETE|OEM_(?:PLUS|COMMA|MINUS|PERIOD|[1-7])|PRINTSCREEN|SCROLLLOCK|NUMLOCK|LEFT(?:SHIFT|CTRL|ALT)|RIGHT(?:SHIFT|CTRL|ALT))$";
-    let re = Regex::new(pattern).unwrap();
-    re.is_match(input)
+    Hotkey::parse(input).is_some()
+}
+
+/// Validates modifiers to combine with a digit, such as a workspace's window hotkey base.
+///
+/// # Arguments
+/// - `base`: The modifiers to validate (e.g., "Ctrl+Alt").
+///
+/// # Returns
+/// - `true` if `base` is one or more of Ctrl, Alt, Shift, Win, and Caps joined by `+`.
+pub fn is_valid_hotkey_base(base: &str) -> bool {
+    canonical_hotkey_base(base).is_some()
 }
 
 /// Saves a list of workspaces to a JSON file.
 ///
+/// If encryption is turned on (see `encryption::set_encryption_enabled`), the JSON is
+/// encrypted before it is written.
+///
 /// # Arguments
 /// - `workspaces`: A reference to the list of workspaces to save.
 /// - `file_path`: The path to the file where the workspaces should be saved.
 ///
+/// # Returns
+/// - `Ok(())` if the file was written.
+/// - `Err(Save)` if the workspaces could not be serialized or the file could not be written.
+///
 /// # Example
+/// ```ignore
+/// if let Err(e) = save_workspaces(&workspaces, "workspaces.json") {
+///     println!("{}", e);
+/// }
 /// ```
-/// save_workspaces(&workspaces, "workspaces.json");
-/// ```/// Saves the current workspaces to a file in JSON format.
-pub fn save_workspaces(workspaces: &[Workspace], file_path: &str) {
-    match serde_json::to_string_pretty(workspaces) {
-        Ok(json) => {
-            if let Err(e) =
-                File::create(file_path).and_then(|mut file| file.write_all(json.as_bytes()))
+pub fn save_workspaces(workspaces: &[Workspace], file_path: &str) -> Result<(), MultiManagerError> {
+    let save_error = |message: String| {
+        error!("Failed to save workspaces to '{}': {}", file_path, message);
+        MultiManagerError::Save {
+            path: file_path.to_string(),
+            message,
+        }
+    };
+
+    let json = serde_json::to_string_pretty(workspaces).map_err(|e| save_error(e.to_string()))?;
+    let data = if encryption_enabled() {
+        encrypt(json.as_bytes()).map_err(|e| save_error(e.to_string()))?
+    } else {
+        json.into_bytes()
+    };
+    File::create(file_path)
+        .and_then(|mut file| file.write_all(&data))
+        .map_err(|e| save_error(e.to_string()))?;
+
+    info!("Workspaces successfully saved to '{}'.", file_path);
+    Ok(())
+}
+
+/// Checks a workspaces file for mistakes, typically after it was edited by hand.
+///
+/// The file is parsed exactly as at startup, then checked for problems that parse but would
+/// misbehave: invalid or duplicate hotkeys, a focus window index past the end of the window
+/// list, and positions with no area. Nothing is registered or moved.
+///
+/// # Arguments
+/// - `file_path`: The workspaces file to check.
+///
+/// # Returns
+/// - A description of each problem found; empty if the file is valid.
+/// - `Err(Load)` if the file cannot be read or is not a valid workspaces file, with the line
+///   and column of the first syntax or type error.
+///
+/// # Example
+/// ```ignore
+/// match validate_workspaces_file("workspaces.json") {
+///     Ok(problems) if problems.is_empty() => println!("OK"),
+///     Ok(problems) => problems.iter().for_each(|p| println!("{}", p)),
+///     Err(e) => println!("{}", e),
+/// }
+/// ```
+pub fn validate_workspaces_file(file_path: &str) -> Result<Vec<String>, MultiManagerError> {
+    let load_error = |message: String| MultiManagerError::Load {
+        path: file_path.to_string(),
+        message,
+    };
+    let data = std::fs::read(file_path).map_err(|e| load_error(e.to_string()))?;
+    let content = decode_workspaces_file(data).map_err(|e| load_error(e.to_string()))?;
+    let workspaces = serde_json::from_str::<Vec<Workspace>>(&content)
+        .map_err(|e| load_error(format!("invalid JSON: {}", e)))?;
+
+    let mut problems = Vec::new();
+    let mut hotkeys: HashMap<String, &str> = HashMap::new();
+    for workspace in &workspaces {
+        let name = &workspace.name;
+        if name.trim().is_empty() {
+            problems.push("A workspace has an empty name.".to_string());
+        }
+
+        if let Some(hotkey) = &workspace.hotkey {
+            if !is_valid_key_combo(hotkey) {
+                problems.push(format!(
+                    "Workspace '{}': hotkey '{}' is not a valid key combination.",
+                    name, hotkey
+                ));
+            } else if let Some(other) =
+                hotkeys.insert(canonical_hotkey(hotkey).unwrap_or_default(), name)
             {
-                error!("Failed to save workspaces to '{}': {}", file_path, e);
-            } else {
-                info!("Workspaces successfully saved to '{}'.", file_path);
+                problems.push(format!(
+                    "Workspace '{}': hotkey '{}' is also used by workspace '{}'.",
+                    name, hotkey, other
+                ));
             }
         }
-        Err(e) => {
-            error!("Failed to serialize workspaces: {}", e);
+
+        if let FocusBehavior::Window(index) = workspace.focus_behavior {
+            if index >= workspace.windows.len() {
+                problems.push(format!(
+                    "Workspace '{}': focus window {} does not exist; it has {} window(s).",
+                    name,
+                    index,
+                    workspace.windows.len()
+                ));
+            }
+        }
+
+        for window in &workspace.windows {
+            if window.position_flags.no_size {
+                continue;
+            }
+            for (label, rect, hidden) in [
+                ("home", window.home, window.hide_at == HidePosition::Home),
+                (
+                    "target",
+                    window.target,
+                    window.hide_at == HidePosition::Target,
+                ),
+            ] {
+                if !hidden && (rect.2 <= 0 || rect.3 <= 0) {
+                    problems.push(format!(
+                        "Workspace '{}', window '{}': {} size {}x{} has no area.",
+                        name, window.title, label, rect.2, rect.3
+                    ));
+                }
+            }
         }
     }
+
+    Ok(problems)
+}
+
+/// Serializes a single workspace as pretty-printed JSON, for sharing via the clipboard.
+///
+/// # Arguments
+/// - `workspace`: The workspace to serialize.
+///
+/// # Returns
+/// - The workspace in the same format as an entry of the workspaces file.
+pub fn workspace_to_json(workspace: &Workspace) -> String {
+    serde_json::to_string_pretty(workspace).unwrap_or_default()
+}
+
+/// Generates the JSON schema of the workspaces file from the workspace types, for editors
+/// to check hand-edited files against. It is checked in as `resources/workspaces.schema.json`.
+///
+/// # Returns
+/// - The schema as pretty-printed JSON.
+///
+/// # Example
+/// ```
+/// # use multi_manager::workspace::workspaces_schema;
+/// let schema: serde_json::Value = serde_json::from_str(&workspaces_schema()).unwrap();
+/// assert_eq!(schema["type"], "array");
+/// ```
+pub fn workspaces_schema() -> String {
+    let mut schema = schemars::schema_for!(Vec<Workspace>);
+    schema.insert(
+        "$id".to_string(),
+        "https://github.com/multiplex55/multi-manager/blob/main/resources/workspaces.schema.json"
+            .into(),
+    );
+    schema.insert("title".to_string(), "Multi Manager workspaces".into());
+    schema.insert(
+        "description".to_string(),
+        "The workspaces file written by Multi Manager (workspaces.json).".into(),
+    );
+    serde_json::to_string_pretty(&schema).unwrap_or_default() + "\n"
+}
+
+/// Parses workspaces pasted as JSON.
+///
+/// Accepts either a single workspace, as produced by `workspace_to_json`, or a list of
+/// workspaces such as the contents of a workspaces file.
+///
+/// # Arguments
+/// - `json`: The pasted text.
+///
+/// # Returns
+/// - The parsed workspaces. Their hotkeys are not registered, and their pre- and post-toggle
+///   commands are removed, so pasting a workspace from elsewhere never runs its commands.
+/// - `Err(ImportWorkspace)` if the text is neither a workspace nor a list of workspaces.
+///
+/// # Example
+/// ```ignore
+/// let json = workspace_to_json(&workspaces[0]);
+/// let copies = workspaces_from_json(&json).unwrap();
+/// assert_eq!(copies.len(), 1);
+/// ```
+pub fn workspaces_from_json(json: &str) -> Result<Vec<Workspace>, MultiManagerError> {
+    let mut workspaces = match serde_json::from_str::<Workspace>(json) {
+        Ok(workspace) => vec![workspace],
+        Err(single_error) => serde_json::from_str::<Vec<Workspace>>(json).map_err(|_| {
+            MultiManagerError::ImportWorkspace {
+                message: single_error.to_string(),
+            }
+        })?,
+    };
+
+    fill_capture_titles(&mut workspaces);
+    migrate_hotkeys(&mut workspaces);
+    for workspace in &mut workspaces {
+        if !workspace.pre_command.is_empty() || !workspace.post_command.is_empty() {
+            warn!(
+                "Removed the commands of pasted workspace '{}'; enter them again to use them.",
+                workspace.name
+            );
+            workspace.pre_command.clear();
+            workspace.post_command.clear();
+        }
+    }
+    Ok(workspaces)
+}
+
+/// Lists the hotkeys of enabled workspaces in hook mode, for the keyboard hook.
+///
+/// # Arguments
+/// - `workspaces`: The workspace list.
+///
+/// # Returns
+/// - The valid hotkeys of enabled workspaces whose hotkey mode is `Hook`.
+///
+/// # Example
+/// ```ignore
+/// set_hooked_hotkeys(hooked_hotkeys(&workspaces));
+/// ```
+pub fn hooked_hotkeys(workspaces: &[Workspace]) -> Vec<Hotkey> {
+    workspaces
+        .iter()
+        .filter(|workspace| !workspace.disabled && workspace.hotkey_mode == HotkeyMode::Hook)
+        .filter_map(|workspace| workspace.hotkey.as_deref().and_then(Hotkey::parse))
+        .collect()
+}
+
+/// Rewrites the hotkeys of workspaces and their windows in their canonical form, for files
+/// written before hotkeys were canonicalized, e.g. "alt+control+h" becomes "Ctrl+Alt+H".
+fn migrate_hotkeys(workspaces: &mut [Workspace]) {
+    for workspace in workspaces.iter_mut() {
+        migrate_hotkey(&mut workspace.hotkey);
+        migrate_hotkey(&mut workspace.home_hotkey);
+        migrate_hotkey(&mut workspace.target_hotkey);
+        migrate_hotkey(&mut workspace.save_hotkey);
+        migrate_hotkey_base(&mut workspace.window_hotkey_base);
+        for window in &mut workspace.windows {
+            migrate_hotkey(&mut window.toggle_hotkey);
+        }
+    }
+}
+
+/// Fills in the capture title of windows saved before capture titles were tracked, which
+/// only have the display title.
+fn fill_capture_titles(workspaces: &mut [Workspace]) {
+    for window in workspaces.iter_mut().flat_map(|w| w.windows.iter_mut()) {
+        if window.capture_title.is_empty() {
+            window.capture_title = window.title.clone();
+        }
+    }
+}
+
+/// Reads a list of workspaces from a JSON file without registering their hotkeys.
+///
+/// Encrypted files are decrypted transparently.
+///
+/// # Arguments
+/// - `file_path`: The path to the file to read.
+///
+/// # Returns
+/// - The workspaces in the file, or an empty vector if the file is missing.
+/// - `Err(Load)` if the file exists but cannot be read or decrypted.
+/// - `Err(Parse)` if the file is not valid workspace JSON, with where the error is.
+pub fn read_workspaces(file_path: &str) -> Result<Vec<Workspace>, MultiManagerError> {
+    let load_error = |message: String| {
+        error!(
+            "Failed to load workspaces from '{}': {}",
+            file_path, message
+        );
+        MultiManagerError::Load {
+            path: file_path.to_string(),
+            message,
+        }
+    };
+
+    let mut file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            warn!(
+                "File '{}' not found: {}. Returning empty workspace list.",
+                file_path, e
+            );
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(load_error(e.to_string())),
+    };
+
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|e| load_error(e.to_string()))?;
+    let content = decode_workspaces_file(data).map_err(|e| load_error(e.to_string()))?;
+    let mut workspaces = serde_json::from_str::<Vec<Workspace>>(&content).map_err(|e| {
+        error!("Failed to parse workspaces in '{}': {}", file_path, e);
+        MultiManagerError::Parse {
+            path: file_path.to_string(),
+            // The position is reported separately
+            message: e
+                .to_string()
+                .split(" at line ")
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            line: e.line(),
+            column: e.column(),
+            snippet: error_snippet(&content, e.line(), e.column()),
+        }
+    })?;
+    info!("Successfully loaded workspaces from '{}'.", file_path);
+
+    fill_capture_titles(&mut workspaces);
+    migrate_hotkeys(&mut workspaces);
+    Ok(workspaces)
+}
+
+/// Quotes the lines leading up to a parse error, numbered, with a caret under the column.
+///
+/// # Arguments
+/// - `content`: The text that failed to parse.
+/// - `line`: The line of the error, counted from 1.
+/// - `column`: The column of the error, counted from 1.
+///
+/// # Returns
+/// - Up to three lines ending at the error line, then the caret line.
+fn error_snippet(content: &str, line: usize, column: usize) -> String {
+    let first = line.saturating_sub(2).max(1);
+    let width = line.to_string().len();
+    let mut snippet = String::new();
+    for (number, text) in content.lines().enumerate().map(|(k, text)| (k + 1, text)) {
+        if number < first {
+            continue;
+        }
+        if number > line {
+            break;
+        }
+        snippet.push_str(&format!("{:>width$} | {}\n", number, text));
+    }
+    snippet.push_str(&format!("{:>width$} | {:>column$}", "", "^"));
+    snippet
+}
+
+/// Moves a workspaces file that could not be parsed out of the way, next to it with the time
+/// appended, so a fresh one can be saved in its place.
+///
+/// # Arguments
+/// - `file_path`: The broken workspaces file.
+///
+/// # Returns
+/// - The path of the backup.
+/// - `Err(Save)` if the file could not be renamed.
+///
+/// # Example
+/// ```ignore
+/// let backup = back_up_broken_file("workspaces.json")?;
+/// // e.g. "workspaces.json.broken-20240521-093000"
+/// ```
+pub fn back_up_broken_file(file_path: &str) -> Result<String, MultiManagerError> {
+    let backup = format!(
+        "{}.broken-{}",
+        file_path,
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    std::fs::rename(file_path, &backup).map_err(|e| MultiManagerError::Save {
+        path: backup.clone(),
+        message: e.to_string(),
+    })?;
+    info!(
+        "Backed up broken workspaces file '{}' to '{}'.",
+        file_path, backup
+    );
+    Ok(backup)
+}
+
+/// Decrypts the contents of a workspaces file if needed and returns them as text.
+fn decode_workspaces_file(data: Vec<u8>) -> Result<String, MultiManagerError> {
+    let data = if is_encrypted(&data) {
+        decrypt(&data)?
+    } else {
+        data
+    };
+    String::from_utf8(data).map_err(|e| MultiManagerError::Encryption {
+        message: format!("the file is not valid UTF-8: {}", e),
+    })
+}
+
+/// Returns when a file was last modified, to notice changes made by other programs.
+///
+/// # Arguments
+/// - `file_path`: The file to check.
+///
+/// # Returns
+/// - The modification time, or `None` if the file does not exist or it cannot be read.
+pub fn file_modified(file_path: &str) -> Option<SystemTime> {
+    std::fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
 }
 
 /// Loads a list of workspaces from a JSON file.
 ///
 /// This function reads a JSON file containing workspace configurations and attempts to register
-/// the hotkeys associated with each workspace using the provided `App` instance. If the file does
-/// not exist or contains invalid data, an empty workspace list is returned. Hotkeys that cannot be
-/// registered are logged as warnings.
+/// the hotkeys associated with each workspace in the provided hotkey registry. If the file does
+/// not exist, an empty workspace list is returned; if it cannot be read or contains invalid data,
+/// an error is returned. Hotkeys that cannot be registered are logged as warnings.
 ///
 /// # Arguments
 /// - `file_path`: The path to the file to load workspaces from.
-/// - `app`: A reference to the `App` instance used to manage registered hotkeys.
+/// - `registered_hotkeys`: The registry that successfully registered hotkeys are recorded in.
 ///
 /// # Returns
 /// - A `Vec<Workspace>` containing the loaded workspaces, with their hotkeys registered if possible.
-/// - An empty vector if the file is missing or invalid.
+/// - An empty vector if the file is missing.
+/// - `Err(Load)` if the file exists but cannot be read or parsed.
 ///
 /// # Behavior
 /// - If a workspace's hotkey is valid and not already registered, it is registered successfully.
 /// - If a hotkey fails to register, a warning is logged but the workspace is still included in the list.
 ///
 /// # Example
-/// ```rust
-/// let app = App {
-///     workspaces: Arc::new(Mutex::new(Vec::new())),
-///     last_hotkey_info: Arc::new(Mutex::new(None)),
-///     hotkey_promise: Arc::new(Mutex::new(None)),
-///     initial_validation_done: Arc::new(Mutex::new(false)),
-///     registered_hotkeys: Arc::new(Mutex::new(HashMap::new())),
-/// };
-///
-/// let workspaces = load_workspaces("workspaces.json", &app);
+/// ```ignore
+/// let registered_hotkeys = Mutex::new(HashMap::new());
+/// let workspaces = load_workspaces("workspaces.json", &registered_hotkeys).unwrap_or_default();
 /// ```
-pub fn load_workspaces(file_path: &str, app: &App) -> Vec<Workspace> {
-    let mut content = String::new();
-    match File::open(file_path) {
-        Ok(mut file) => {
-            if let Err(e) = file.read_to_string(&mut content) {
-                error!("Failed to read file '{}': {}", file_path, e);
-                return Vec::new();
-            }
-            match serde_json::from_str::<Vec<Workspace>>(&content) {
-                Ok(mut workspaces) => {
-                    info!("Successfully loaded workspaces from '{}'.", file_path);
-
-                    for (i, workspace) in workspaces.iter_mut().enumerate() {
-                        if let Some(ref hotkey) = workspace.hotkey {
-                            if !register_hotkey(app, i as i32, hotkey) {
-                                warn!(
-                                    "Failed to register hotkey '{}' for workspace '{}'.",
-                                    hotkey, workspace.name
-                                );
-                            } else {
-                                info!(
-                                    "Registered hotkey '{}' for workspace '{}'.",
-                                    hotkey, workspace.name
-                                );
-                            }
-                        }
-                    }
-
-                    workspaces
-                }
-                Err(e) => {
-                    warn!(
-                        "Failed to parse JSON in '{}': {}. Returning empty workspace list.",
-                        file_path, e
-                    );
-                    Vec::new()
-                }
+pub fn load_workspaces(
+    file_path: &str,
+    registered_hotkeys: &Mutex<HashMap<String, usize>>,
+) -> Result<Vec<Workspace>, MultiManagerError> {
+    let mut workspaces = read_workspaces(file_path)?;
+    // Before registering, so hotkeys in hook mode are only recorded
+    set_hooked_hotkeys(hooked_hotkeys(&workspaces));
+
+    for (i, workspace) in workspaces.iter_mut().enumerate() {
+        if let Some(ref hotkey) = workspace.hotkey {
+            if let Err(e) = register_hotkey(registered_hotkeys, i as i32, hotkey) {
+                warn!(
+                    "Failed to register hotkey for workspace '{}': {}",
+                    workspace.name, e
+                );
+            } else {
+                info!(
+                    "Registered hotkey '{}' for workspace '{}'.",
+                    hotkey, workspace.name
+                );
             }
         }
-        Err(e) => {
-            warn!(
-                "File '{}' not found or cannot be opened: {}. Returning empty workspace list.",
-                file_path, e
-            );
-            Vec::new()
-        }
     }
+
+    Ok(workspaces)
 }
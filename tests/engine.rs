@@ -0,0 +1,463 @@
+//! Tests of the toggling engine against a simulated window system, so home, target, and
+//! toggle behavior is checked without touching real windows.
+
+use multi_manager::backend::{ShowCommand, WindowBackend};
+use multi_manager::engine::{move_workspace_windows, toggle_workspace_windows};
+use multi_manager::validation::PositionKind;
+use multi_manager::workspace::{
+    ExclusiveAction, HidePosition, PositionFlags, Window, WindowAction, Workspace,
+};
+use std::cell::{Cell, RefCell};
+use windows::core::{Error, Result};
+
+const HOME: (i32, i32, i32, i32) = (100, 100, 400, 300);
+const TARGET: (i32, i32, i32, i32) = (960, 0, 960, 1040);
+
+/// A simulated top-level window.
+#[derive(Clone, Debug)]
+struct FakeWindow {
+    id: usize,
+    title: String,
+    exe: String,
+    class_name: String,
+    rect: (i32, i32, i32, i32),
+    visible: bool,
+    minimized: bool,
+    tool_window: bool,
+    process_id: u32,
+}
+
+impl FakeWindow {
+    /// A visible, titled application window at a rect.
+    fn new(id: usize, exe: &str, class_name: &str, rect: (i32, i32, i32, i32)) -> Self {
+        FakeWindow {
+            id,
+            title: format!("{} {}", exe, id),
+            exe: exe.to_string(),
+            class_name: class_name.to_string(),
+            rect,
+            visible: true,
+            minimized: false,
+            tool_window: false,
+            process_id: id as u32,
+        }
+    }
+}
+
+/// A desktop of simulated windows on one 1920x1080 monitor, in z-order, topmost first.
+#[derive(Default)]
+struct FakeBackend {
+    windows: RefCell<Vec<FakeWindow>>,
+    shown: RefCell<Vec<(usize, ShowCommand)>>,
+    foreground: Cell<Option<usize>>,
+    auto_hide: Cell<bool>,
+}
+
+impl FakeBackend {
+    fn with(windows: Vec<FakeWindow>) -> Self {
+        FakeBackend {
+            windows: RefCell::new(windows),
+            ..Default::default()
+        }
+    }
+
+    fn window(&self, id: usize) -> Option<FakeWindow> {
+        self.windows.borrow().iter().find(|w| w.id == id).cloned()
+    }
+
+    fn update(&self, id: usize, change: impl FnOnce(&mut FakeWindow)) -> bool {
+        match self.windows.borrow_mut().iter_mut().find(|w| w.id == id) {
+            Some(window) => {
+                change(window);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn rect_of(&self, id: usize) -> (i32, i32, i32, i32) {
+        self.window(id).expect("No such simulated window").rect
+    }
+}
+
+impl WindowBackend for FakeBackend {
+    fn exists(&self, id: usize) -> bool {
+        self.window(id).is_some()
+    }
+    fn is_visible(&self, id: usize) -> bool {
+        self.window(id).is_some_and(|w| w.visible)
+    }
+    fn is_minimized(&self, id: usize) -> bool {
+        self.window(id).is_some_and(|w| w.minimized)
+    }
+    fn is_tool_window(&self, id: usize) -> bool {
+        self.window(id).is_some_and(|w| w.tool_window)
+    }
+    fn title(&self, id: usize) -> String {
+        self.window(id).map(|w| w.title).unwrap_or_default()
+    }
+    fn exe(&self, id: usize) -> String {
+        self.window(id).map(|w| w.exe).unwrap_or_default()
+    }
+    fn class_name(&self, id: usize) -> String {
+        self.window(id).map(|w| w.class_name).unwrap_or_default()
+    }
+    fn process_id(&self, id: usize) -> u32 {
+        self.window(id).map_or(0, |w| w.process_id)
+    }
+    fn process_start_time(&self, id: usize) -> Option<u64> {
+        self.window(id).map(|w| w.process_id as u64)
+    }
+    fn rect(&self, id: usize) -> Option<(i32, i32, i32, i32)> {
+        self.window(id).map(|w| w.rect)
+    }
+    fn set_rect(&self, id: usize, rect: (i32, i32, i32, i32), flags: &PositionFlags) -> Result<()> {
+        let moved = self.update(id, |window| {
+            if !flags.no_move {
+                (window.rect.0, window.rect.1) = (rect.0, rect.1);
+            }
+            if !flags.no_size {
+                (window.rect.2, window.rect.3) = (rect.2, rect.3);
+            }
+        });
+        if moved {
+            Ok(())
+        } else {
+            Err(Error::empty())
+        }
+    }
+    fn raise(&self, id: usize) -> Result<()> {
+        let mut windows = self.windows.borrow_mut();
+        let index = windows
+            .iter()
+            .position(|w| w.id == id)
+            .ok_or_else(Error::empty)?;
+        let window = windows.remove(index);
+        windows.insert(0, window);
+        Ok(())
+    }
+    fn show(&self, id: usize, command: ShowCommand) -> bool {
+        self.shown.borrow_mut().push((id, command));
+        let was_visible = self.is_visible(id);
+        self.update(id, |window| match command {
+            ShowCommand::Restore | ShowCommand::RestoreNoActivate | ShowCommand::Maximize => {
+                window.visible = true;
+                window.minimized = false;
+            }
+            ShowCommand::ShowNoActivate => window.visible = true,
+            ShowCommand::MinimizeNoActivate => window.minimized = true,
+            ShowCommand::Hide => window.visible = false,
+        });
+        was_visible
+    }
+    fn close(&self, id: usize) -> Result<()> {
+        self.windows.borrow_mut().retain(|w| w.id != id);
+        Ok(())
+    }
+    fn activate(&self, id: usize) -> bool {
+        let exists = self.exists(id);
+        if exists {
+            self.foreground.set(Some(id));
+        }
+        exists
+    }
+    fn foreground(&self) -> Option<usize> {
+        self.foreground.get()
+    }
+    fn top_level_windows(&self) -> Vec<usize> {
+        self.windows.borrow().iter().map(|w| w.id).collect()
+    }
+    fn monitors(&self) -> Vec<(i32, i32, i32, i32)> {
+        vec![(0, 0, 1920, 1080)]
+    }
+    fn work_areas(&self) -> Vec<(i32, i32, i32, i32)> {
+        vec![(0, 0, 1920, 1040)]
+    }
+    fn taskbar_auto_hide(&self) -> bool {
+        self.auto_hide.get()
+    }
+    fn set_taskbar_auto_hide(&self, auto_hide: bool) {
+        self.auto_hide.set(auto_hide);
+    }
+    fn virtual_desktops(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn current_virtual_desktop(&self) -> Option<usize> {
+        None
+    }
+    fn switch_virtual_desktop(&self, _index: usize) -> bool {
+        false
+    }
+    fn create_virtual_desktop(&self, _name: &str) -> Option<usize> {
+        None
+    }
+    fn move_to_current_virtual_desktop(&self, _id: usize) -> bool {
+        true
+    }
+    fn is_on_current_virtual_desktop(&self, _id: usize) -> bool {
+        true
+    }
+    fn window_virtual_desktop(&self, _id: usize) -> Option<usize> {
+        None
+    }
+}
+
+/// Captures a simulated window the way the GUI does, with the given home and target.
+fn capture(
+    window: &FakeWindow,
+    home: (i32, i32, i32, i32),
+    target: (i32, i32, i32, i32),
+) -> Window {
+    Window {
+        id: window.id,
+        title: window.title.clone(),
+        capture_title: window.title.clone(),
+        exe: window.exe.clone(),
+        class_name: window.class_name.clone(),
+        home,
+        target,
+        valid: true,
+        ..Default::default()
+    }
+}
+
+/// Builds a workspace with default settings around the given windows.
+fn workspace_with(windows: Vec<Window>) -> Workspace {
+    let mut workspace: Workspace = serde_json::from_str(
+        r#"{"name":"Test","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+    )
+    .expect("Failed to build test workspace");
+    workspace.windows = windows;
+    workspace
+}
+
+#[test]
+fn toggle_sends_windows_at_home_to_targets_and_back() {
+    let first = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let second = FakeWindow::new(2, "term.exe", "Console", (600, 100, 300, 200));
+    let second_target = (0, 0, 960, 1040);
+    let backend = FakeBackend::with(vec![first.clone(), second.clone()]);
+    let mut workspace = workspace_with(vec![
+        capture(&first, HOME, TARGET),
+        capture(&second, (600, 100, 300, 200), second_target),
+    ]);
+
+    toggle_workspace_windows(&backend, &mut workspace);
+    assert_eq!(backend.rect_of(1), TARGET);
+    assert_eq!(backend.rect_of(2), second_target);
+    assert!(workspace.windows.iter().all(|w| w.move_error.is_none()));
+
+    toggle_workspace_windows(&backend, &mut workspace);
+    assert_eq!(backend.rect_of(1), HOME);
+    assert_eq!(backend.rect_of(2), (600, 100, 300, 200));
+}
+
+#[test]
+fn toggle_sends_windows_home_when_any_is_elsewhere() {
+    let first = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let stray = FakeWindow::new(2, "term.exe", "Console", (300, 300, 250, 250));
+    let backend = FakeBackend::with(vec![first.clone(), stray.clone()]);
+    let mut workspace = workspace_with(vec![
+        capture(&first, HOME, TARGET),
+        capture(&stray, (600, 100, 300, 200), (0, 0, 960, 1040)),
+    ]);
+
+    toggle_workspace_windows(&backend, &mut workspace);
+
+    assert_eq!(backend.rect_of(1), HOME);
+    assert_eq!(backend.rect_of(2), (600, 100, 300, 200));
+}
+
+#[test]
+fn move_to_target_ignores_current_state() {
+    let window = FakeWindow::new(1, "editor.exe", "Editor", (300, 300, 250, 250));
+    let backend = FakeBackend::with(vec![window.clone()]);
+    let mut workspace = workspace_with(vec![capture(&window, HOME, TARGET)]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+    assert_eq!(backend.rect_of(1), TARGET);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+    assert_eq!(backend.rect_of(1), TARGET);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Home);
+    assert_eq!(backend.rect_of(1), HOME);
+}
+
+#[test]
+fn move_restores_minimized_window() {
+    let mut window = FakeWindow::new(1, "editor.exe", "Editor", TARGET);
+    window.minimized = true;
+    let backend = FakeBackend::with(vec![window.clone()]);
+    let mut workspace = workspace_with(vec![capture(&window, HOME, TARGET)]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Home);
+
+    assert!(!backend.is_minimized(1));
+    assert_eq!(backend.rect_of(1), HOME);
+}
+
+#[test]
+fn move_re_resolves_stale_handle_by_exe_and_class() {
+    let restarted = FakeWindow::new(7, "editor.exe", "Editor", (300, 300, 250, 250));
+    let backend = FakeBackend::with(vec![restarted.clone()]);
+    let mut stale = capture(&restarted, HOME, TARGET);
+    stale.id = 3;
+    let mut workspace = workspace_with(vec![stale]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert_eq!(workspace.windows[0].id, 7);
+    assert_eq!(backend.rect_of(7), TARGET);
+}
+
+#[test]
+fn move_re_resolves_stale_handles_to_different_windows() {
+    let first = FakeWindow::new(7, "editor.exe", "Editor", (300, 300, 250, 250));
+    let second = FakeWindow::new(8, "editor.exe", "Editor", (600, 300, 250, 250));
+    let backend = FakeBackend::with(vec![first.clone(), second.clone()]);
+    let mut stale = vec![
+        capture(&first, HOME, TARGET),
+        capture(&second, (500, 100, 400, 300), (0, 0, 960, 1040)),
+    ];
+    for (entry, id) in stale.iter_mut().zip([3, 4]) {
+        entry.id = id;
+        entry.capture_title = format!("untitled {}", id);
+    }
+    let mut workspace = workspace_with(stale);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert_eq!(workspace.windows[0].id, 7);
+    assert_eq!(workspace.windows[1].id, 8);
+    assert_eq!(backend.rect_of(7), TARGET);
+    assert_eq!(backend.rect_of(8), (0, 0, 960, 1040));
+}
+
+#[test]
+fn move_does_not_re_resolve_to_another_entrys_window() {
+    let live = FakeWindow::new(7, "editor.exe", "Editor", HOME);
+    let backend = FakeBackend::with(vec![live.clone()]);
+    let mut stale = capture(&live, (500, 100, 400, 300), (0, 0, 960, 1040));
+    stale.id = 3;
+    stale.capture_title = "untitled".to_string();
+    let mut workspace = workspace_with(vec![stale, capture(&live, HOME, TARGET)]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert!(workspace.windows[0].move_error.is_some());
+    assert_eq!(workspace.windows[1].id, 7);
+    assert_eq!(backend.rect_of(7), TARGET);
+}
+
+#[test]
+fn move_records_missing_window() {
+    let backend = FakeBackend::default();
+    let gone = FakeWindow::new(3, "editor.exe", "Editor", HOME);
+    let mut workspace = workspace_with(vec![capture(&gone, HOME, TARGET)]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert!(workspace.windows[0].move_error.is_some());
+}
+
+#[test]
+fn toggle_hides_and_shows_window_at_hidden_target() {
+    let window = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let backend = FakeBackend::with(vec![window.clone()]);
+    let mut entry = capture(&window, HOME, TARGET);
+    entry.hide_at = HidePosition::Target;
+    let mut workspace = workspace_with(vec![entry]);
+
+    toggle_workspace_windows(&backend, &mut workspace);
+    assert!(!backend.is_visible(1));
+    assert!(workspace.windows[0].hidden);
+
+    toggle_workspace_windows(&backend, &mut workspace);
+    assert!(backend.is_visible(1));
+    assert!(!workspace.windows[0].hidden);
+    assert_eq!(backend.rect_of(1), HOME);
+}
+
+#[test]
+fn target_action_minimizes_instead_of_moving() {
+    let window = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let backend = FakeBackend::with(vec![window.clone()]);
+    let mut entry = capture(&window, HOME, TARGET);
+    entry.target_action = WindowAction::Minimize;
+    let mut workspace = workspace_with(vec![entry]);
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert!(backend.is_minimized(1));
+    assert_eq!(backend.rect_of(1), HOME);
+    assert!(backend
+        .shown
+        .borrow()
+        .contains(&(1, ShowCommand::MinimizeNoActivate)));
+}
+
+#[test]
+fn exclusive_mode_leaves_shell_and_tool_windows_alone() {
+    let window = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let other = FakeWindow::new(2, "browser.exe", "Chrome_WidgetWin_1", (0, 0, 800, 600));
+    let mut desktop = FakeWindow::new(3, "explorer.exe", "Progman", (0, 0, 1920, 1080));
+    desktop.title = "Program Manager".to_string();
+    let taskbar = FakeWindow::new(4, "explorer.exe", "Shell_TrayWnd", (0, 1040, 1920, 40));
+    let second_taskbar = FakeWindow::new(
+        5,
+        "explorer.exe",
+        "Shell_SecondaryTrayWnd",
+        (1920, 1040, 1920, 40),
+    );
+    let mut palette = FakeWindow::new(6, "paint.exe", "Palette", (50, 50, 200, 400));
+    palette.tool_window = true;
+    let backend = FakeBackend::with(vec![
+        window.clone(),
+        other,
+        desktop,
+        taskbar,
+        second_taskbar,
+        palette,
+    ]);
+    let mut workspace = workspace_with(vec![capture(&window, HOME, TARGET)]);
+    workspace.exclusive = ExclusiveAction::Hide;
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Target);
+
+    assert_eq!(workspace.suppressed_windows, vec![2]);
+    assert!(!backend.is_visible(2));
+    assert!((3..=6).all(|id| backend.is_visible(id)));
+    assert!(backend.shown.borrow().iter().all(|&(id, _)| id <= 2));
+
+    move_workspace_windows(&backend, &mut workspace, PositionKind::Home);
+
+    assert!(workspace.suppressed_windows.is_empty());
+    assert!(backend.is_visible(2));
+}
+
+#[test]
+fn results_merge_into_a_workspace_edited_meanwhile() {
+    let window = FakeWindow::new(1, "editor.exe", "Editor", HOME);
+    let other = FakeWindow::new(2, "browser.exe", "Chrome_WidgetWin_1", (0, 0, 800, 600));
+    let gone = FakeWindow::new(3, "viewer.exe", "Viewer", HOME);
+    let backend = FakeBackend::with(vec![window.clone(), other]);
+    let mut workspace = workspace_with(vec![
+        capture(&window, HOME, TARGET),
+        capture(&gone, HOME, TARGET),
+    ]);
+    workspace.exclusive = ExclusiveAction::Hide;
+    let mut snapshot = workspace.clone();
+
+    move_workspace_windows(&backend, &mut snapshot, PositionKind::Target);
+    workspace.name = "Renamed".to_string();
+    let added = FakeWindow::new(9, "notes.exe", "Notes", HOME);
+    workspace.windows.insert(0, capture(&added, HOME, TARGET));
+    workspace.merge_runtime_state(&snapshot);
+
+    assert_eq!(workspace.name, "Renamed");
+    assert_eq!(workspace.suppressed_windows, vec![2]);
+    assert!(workspace.windows[0].move_error.is_none());
+    assert!(workspace.windows[1].move_error.is_none());
+    assert!(workspace.windows[2].move_error.is_some());
+}
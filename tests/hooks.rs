@@ -0,0 +1,42 @@
+//! Tests of workspace commands run through `cmd`.
+
+#![cfg(windows)]
+
+use multi_manager::activity::ToggleDirection;
+use multi_manager::hooks::run_hook;
+use multi_manager::workspace::workspaces_from_json;
+use std::time::Duration;
+
+const TIMEOUT: Option<Duration> = Some(Duration::from_secs(10));
+
+#[test]
+fn workspace_name_is_not_split_into_commands() {
+    let result = run_hook(
+        "echo %WORKSPACE%",
+        "a & exit 7",
+        ToggleDirection::ToTarget,
+        TIMEOUT,
+    );
+
+    assert!(result.is_ok(), "The workspace name ran as a command");
+}
+
+#[test]
+fn placeholders_receive_their_values() {
+    let command = r#"if "%WORKSPACE%|%DIRECTION%"=="a & exit 7|Home" (exit 0) else (exit 1)"#;
+
+    let result = run_hook(command, "a & exit 7", ToggleDirection::ToHome, TIMEOUT);
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn pasted_workspaces_lose_their_commands() {
+    let json = r#"{"name":"Pasted","hotkey":null,"windows":[],"disabled":false,"valid":true,
+        "pre_command":"calc","post_command":"calc"}"#;
+
+    let pasted = workspaces_from_json(json).expect("Failed to parse pasted workspace");
+
+    assert!(pasted[0].pre_command.is_empty());
+    assert!(pasted[0].post_command.is_empty());
+}
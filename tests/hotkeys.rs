@@ -0,0 +1,33 @@
+//! Tests that hotkeys are registered and unregistered under the same registry key, whichever
+//! spelling they were entered in.
+
+#![cfg(windows)]
+
+use multi_manager::window_manager::{register_hotkey, unregister_hotkey};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[test]
+fn aliased_caps_hotkey_is_recorded_and_removed_by_its_canonical_form() {
+    let registry = Mutex::new(HashMap::new());
+
+    register_hotkey(&registry, 41, "hyper + alt + return").expect("Failed to record hotkey");
+    assert_eq!(registry.lock().unwrap().get("Alt+Caps+ENTER"), Some(&41));
+    assert!(register_hotkey(&registry, 42, "Caps+Alt+Enter").is_err());
+
+    unregister_hotkey(&registry, 41);
+    assert!(registry.lock().unwrap().is_empty());
+}
+
+#[test]
+#[ignore = "registers a system-wide hotkey"]
+fn aliased_hotkey_is_registered_and_unregistered_by_its_canonical_form() {
+    let registry = Mutex::new(HashMap::new());
+
+    register_hotkey(&registry, 43, "shift + control + f24").expect("Failed to register hotkey");
+    assert_eq!(registry.lock().unwrap().get("Ctrl+Shift+F24"), Some(&43));
+    assert!(register_hotkey(&registry, 44, "Ctrl+Shift+F24").is_err());
+
+    unregister_hotkey(&registry, 43);
+    assert!(registry.lock().unwrap().is_empty());
+}
@@ -0,0 +1,27 @@
+//! Checks that the JSON schema checked in for the workspaces file matches the workspace types.
+
+use multi_manager::workspace::workspaces_schema;
+use serde_json::Value;
+use std::fs;
+
+const SCHEMA_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/resources/workspaces.schema.json"
+);
+
+#[test]
+fn checked_in_schema_matches_workspace_types() {
+    let generated = workspaces_schema();
+    if std::env::var_os("UPDATE_SCHEMA").is_some() {
+        fs::write(SCHEMA_PATH, &generated).expect("Failed to write the schema");
+        return;
+    }
+
+    let checked_in = fs::read_to_string(SCHEMA_PATH).expect("Failed to read the schema");
+    let parse = |json: &str| serde_json::from_str::<Value>(json).expect("Invalid schema JSON");
+    assert!(
+        parse(&checked_in) == parse(&generated),
+        "resources/workspaces.schema.json is out of date; regenerate it with \
+         `UPDATE_SCHEMA=1 cargo test --test schema`."
+    );
+}
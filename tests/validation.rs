@@ -0,0 +1,105 @@
+//! Tests that validation issues keep pointing at the workspace and window they were raised
+//! for while the workspace list changes under the report.
+
+use multi_manager::validation::{
+    IssueLocation, PositionKind, ValidationIssue, ValidationReport, WindowKey,
+};
+use multi_manager::workspace::{Window, Workspace};
+
+/// Builds a workspace with default settings around the given windows.
+fn workspace(name: &str, hotkey: Option<&str>, windows: Vec<Window>) -> Workspace {
+    let mut workspace: Workspace = serde_json::from_str(
+        r#"{"name":"Test","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+    )
+    .expect("Failed to build test workspace");
+    workspace.name = name.to_string();
+    workspace.hotkey = hotkey.map(str::to_string);
+    workspace.windows = windows;
+    workspace
+}
+
+fn window(id: usize, title: &str) -> Window {
+    Window {
+        id,
+        title: title.to_string(),
+        capture_title: title.to_string(),
+        exe: "editor.exe".to_string(),
+        class_name: "Editor".to_string(),
+        home: (-5000, 0, 400, 300),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn issues_follow_their_window_when_others_are_removed() {
+    let mut workspaces = vec![
+        workspace("Other", None, Vec::new()),
+        workspace("Code", None, vec![window(1, "a"), window(2, "b")]),
+    ];
+    let issue = ValidationIssue::OffScreenRect {
+        workspace: "Code".to_string(),
+        window: WindowKey::of(&workspaces[1].windows[1]),
+        title: "b".to_string(),
+        kind: PositionKind::Home,
+        rect: (-5000, 0, 400, 300),
+    };
+
+    workspaces.remove(0);
+    workspaces[0].windows.remove(0);
+
+    assert_eq!(
+        issue.locate(&workspaces),
+        Some(IssueLocation {
+            workspace: 0,
+            window: Some(0),
+        })
+    );
+}
+
+#[test]
+fn issues_no_longer_apply_once_their_subject_changes() {
+    let mut workspaces = vec![workspace("Code", Some("Ctrl+Q+"), vec![window(1, "a")])];
+    let mut report = ValidationReport {
+        issues: vec![
+            ValidationIssue::InvalidHotkey {
+                workspace: "Code".to_string(),
+                hotkey: "Ctrl+Q+".to_string(),
+            },
+            ValidationIssue::InvalidWindow {
+                workspace: "Code".to_string(),
+                window: WindowKey::of(&workspaces[0].windows[0]),
+                title: "a".to_string(),
+            },
+            ValidationIssue::OffScreenRect {
+                workspace: "Code".to_string(),
+                window: WindowKey::of(&workspaces[0].windows[0]),
+                title: "a".to_string(),
+                kind: PositionKind::Home,
+                rect: (-5000, 0, 400, 300),
+            },
+        ],
+        ..Default::default()
+    };
+
+    report.forget_stale(&workspaces);
+    assert_eq!(report.issues.len(), 3);
+
+    workspaces[0].hotkey = Some("Ctrl+Q".to_string());
+    workspaces[0].windows[0].home = (0, 0, 400, 300);
+    workspaces[0].windows.push(window(3, "a"));
+    workspaces[0].windows.swap(0, 1);
+    report.forget_stale(&workspaces);
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(
+        report.issues[0].locate(&workspaces),
+        Some(IssueLocation {
+            workspace: 0,
+            window: Some(1),
+        })
+    );
+
+    workspaces[0].name = "Renamed".to_string();
+    report.forget_stale(&workspaces);
+    assert!(report.issues.is_empty());
+}
@@ -0,0 +1,234 @@
+//! End-to-end tests that create real Win32 windows and drive them through capture, move
+//! and toggle, asserting the final window rects.
+//!
+//! These put windows on the desktop and can be disturbed by user input, so they are ignored
+//! by default. Run them with `cargo test --test window_integration -- --ignored --test-threads=1`.
+#![cfg(windows)]
+
+use multi_manager::backend::{Win32Backend, WindowBackend};
+use multi_manager::engine::{
+    adopt_rule_windows, capture_workspace_positions, move_window_with_retry,
+    toggle_workspace_windows,
+};
+use multi_manager::validation::PositionKind;
+use multi_manager::window_manager::{
+    get_window_class, get_window_exe, get_window_position, get_window_title,
+};
+use multi_manager::workspace::{CaptureRule, HidePosition, PositionFlags, Window, Workspace};
+use windows::core::w;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+const HOME: (i32, i32, i32, i32) = (100, 100, 400, 300);
+const TARGET: (i32, i32, i32, i32) = (200, 150, 500, 350);
+
+/// A visible top-level test window that is destroyed when dropped.
+struct TestWindow(HWND);
+
+impl TestWindow {
+    /// Creates a titled, visible window at the given rect.
+    fn create(rect: (i32, i32, i32, i32)) -> Self {
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                w!("STATIC"),
+                w!("Multi Manager Test Window"),
+                WS_OVERLAPPEDWINDOW | WS_VISIBLE,
+                rect.0,
+                rect.1,
+                rect.2,
+                rect.3,
+                None,
+                None,
+                None,
+                None,
+            )
+        }
+        .expect("Failed to create test window");
+        TestWindow(hwnd)
+    }
+
+    fn id(&self) -> usize {
+        self.0 .0 as usize
+    }
+
+    fn rect(&self) -> (i32, i32, i32, i32) {
+        get_window_position(self.0).expect("Failed to read test window rect")
+    }
+}
+
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = DestroyWindow(self.0);
+        }
+    }
+}
+
+/// Captures a test window the way the GUI does, with the given home and target positions.
+fn capture(
+    window: &TestWindow,
+    home: (i32, i32, i32, i32),
+    target: (i32, i32, i32, i32),
+) -> Window {
+    let title = get_window_title(window.0);
+    Window {
+        id: window.id(),
+        title: title.clone(),
+        capture_title: title,
+        exe: get_window_exe(window.0),
+        class_name: get_window_class(window.0),
+        home,
+        target,
+        valid: true,
+        ..Default::default()
+    }
+}
+
+/// Builds a workspace with default settings around the given windows.
+fn workspace_with(windows: Vec<Window>) -> Workspace {
+    let mut workspace: Workspace = serde_json::from_str(
+        r#"{"name":"Test","hotkey":null,"windows":[],"disabled":false,"valid":true}"#,
+    )
+    .expect("Failed to build test workspace");
+    workspace.windows = windows;
+    workspace
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn capture_records_window_identity() {
+    let window = TestWindow::create(HOME);
+    let captured = capture(&window, HOME, TARGET);
+
+    assert_eq!(captured.title, "Multi Manager Test Window");
+    assert!(captured.class_name.eq_ignore_ascii_case("Static"));
+    assert!(captured.exe.to_lowercase().ends_with(".exe"));
+    assert_eq!(window.rect(), HOME);
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn move_places_window_at_exact_rect() {
+    let window = TestWindow::create(HOME);
+
+    move_window_with_retry(
+        &Win32Backend,
+        window.id(),
+        TARGET,
+        &PositionFlags::default(),
+        0,
+        0,
+    )
+    .expect("Failed to move test window");
+
+    assert_eq!(window.rect(), TARGET);
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn move_respects_no_size_flag() {
+    let window = TestWindow::create(HOME);
+    let flags = PositionFlags {
+        no_size: true,
+        ..PositionFlags::default()
+    };
+
+    move_window_with_retry(&Win32Backend, window.id(), TARGET, &flags, 0, 0)
+        .expect("Failed to move test window");
+
+    assert_eq!(window.rect(), (TARGET.0, TARGET.1, HOME.2, HOME.3));
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn toggle_moves_between_home_and_target() {
+    let first = TestWindow::create(HOME);
+    let second = TestWindow::create((600, 100, 300, 200));
+    let second_target = (650, 200, 320, 240);
+    let mut workspace = workspace_with(vec![
+        capture(&first, HOME, TARGET),
+        capture(&second, (600, 100, 300, 200), second_target),
+    ]);
+
+    toggle_workspace_windows(&Win32Backend, &mut workspace);
+    assert_eq!(first.rect(), TARGET);
+    assert_eq!(second.rect(), second_target);
+    assert!(workspace.windows.iter().all(|w| w.move_error.is_none()));
+
+    toggle_workspace_windows(&Win32Backend, &mut workspace);
+    assert_eq!(first.rect(), HOME);
+    assert_eq!(second.rect(), (600, 100, 300, 200));
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn toggle_moves_window_home_when_elsewhere() {
+    let window = TestWindow::create((300, 300, 250, 250));
+    let mut workspace = workspace_with(vec![capture(&window, HOME, TARGET)]);
+
+    toggle_workspace_windows(&Win32Backend, &mut workspace);
+
+    assert_eq!(window.rect(), HOME);
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn toggle_hides_and_shows_window_at_hidden_position() {
+    let window = TestWindow::create(HOME);
+    let mut captured = capture(&window, HOME, TARGET);
+    captured.hide_at = HidePosition::Target;
+    let mut workspace = workspace_with(vec![captured]);
+
+    toggle_workspace_windows(&Win32Backend, &mut workspace);
+    assert!(!Win32Backend.is_visible(window.id()));
+    assert!(workspace.windows[0].hidden);
+
+    toggle_workspace_windows(&Win32Backend, &mut workspace);
+    assert!(Win32Backend.is_visible(window.id()));
+    assert_eq!(window.rect(), HOME);
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn capture_rule_adopts_new_window_once() {
+    let window = TestWindow::create(HOME);
+    let mut workspace = workspace_with(Vec::new());
+    workspace.capture_rules.push(CaptureRule {
+        title: "multi manager test".to_string(),
+        target: TARGET,
+        ..Default::default()
+    });
+
+    let adopted = adopt_rule_windows(&Win32Backend, &mut workspace);
+    assert!(adopted.iter().any(|w| w.id == window.id()));
+    assert_eq!(workspace.windows.len(), adopted.len());
+
+    let entry = workspace
+        .windows
+        .iter()
+        .find(|w| w.id == window.id())
+        .unwrap();
+    assert_eq!(entry.home, HOME);
+    assert_eq!(entry.target, TARGET);
+
+    assert!(adopt_rule_windows(&Win32Backend, &mut workspace).is_empty());
+}
+
+#[test]
+#[ignore = "creates real windows on the desktop"]
+fn capture_all_stores_current_positions() {
+    let first = TestWindow::create(HOME);
+    let second = TestWindow::create(TARGET);
+    let mut workspace = workspace_with(vec![
+        capture(&first, (0, 0, 100, 100), TARGET),
+        capture(&second, (0, 0, 100, 100), TARGET),
+    ]);
+
+    let captured = capture_workspace_positions(&Win32Backend, &mut workspace, PositionKind::Home);
+
+    assert_eq!(captured, 2);
+    assert_eq!(workspace.windows[0].home, HOME);
+    assert_eq!(workspace.windows[1].home, TARGET);
+    assert_eq!(workspace.windows[0].target, TARGET);
+}